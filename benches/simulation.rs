@@ -0,0 +1,82 @@
+//! Timings of the simulation's hot loop: the update pass, rendering and
+//! collision checks, at several population sizes
+//!
+//! Run with `cargo bench`. Under `cargo test` each case runs once, to
+//! check it still works.
+
+use std::hint::black_box;
+
+use asciiquarium_rs::entities::Fish;
+use asciiquarium_rs::entity::EntityManager;
+use asciiquarium_rs::render::RenderOptions;
+use asciiquarium_rs::simulation::Simulation;
+use asciiquarium_rs::spawning;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+
+/// Fish added to the filled tank
+const POPULATIONS: [usize; 3] = [50, 200, 800];
+
+/// A filled 300x100 tank with `fish` more fish, the same on every run
+fn tank(fish: usize) -> Simulation {
+    let (width, height) = (300, 100);
+    let mut entity_manager = EntityManager::new();
+    entity_manager.seed(1);
+    let mut simulation = Simulation::empty(entity_manager, width, height);
+    let bounds = simulation.world().bounds();
+    let entity_manager = simulation.entity_manager_mut();
+    spawning::initialize_aquarium(entity_manager, bounds);
+    let mut rng = entity_manager.rng();
+    for _ in 0..fish {
+        entity_manager.spawn(|id| Fish::new_random(id, bounds, false, &mut rng));
+    }
+    // Let bubbles and currents get going
+    simulation.run(60);
+    simulation
+}
+
+fn update_all(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_all");
+    for fish in POPULATIONS {
+        let mut simulation = tank(fish);
+        group.bench_function(BenchmarkId::from_parameter(fish), |b| {
+            b.iter(|| simulation.step())
+        });
+    }
+    group.finish();
+}
+
+fn render_all(c: &mut Criterion) {
+    let screen = Rect::new(0, 0, 300, 100);
+    let options = RenderOptions::default();
+    let mut group = c.benchmark_group("render_all");
+    for fish in POPULATIONS {
+        let simulation = tank(fish);
+        let mut buffer = Buffer::empty(screen);
+        group.bench_function(BenchmarkId::from_parameter(fish), |b| {
+            b.iter(|| {
+                buffer.reset();
+                simulation
+                    .entity_manager()
+                    .render_all(&mut buffer, screen, &options);
+                black_box(&buffer);
+            })
+        });
+    }
+    group.finish();
+}
+
+fn check_collisions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("check_collisions");
+    for fish in POPULATIONS {
+        let simulation = tank(fish);
+        group.bench_function(BenchmarkId::from_parameter(fish), |b| {
+            b.iter(|| black_box(simulation.entity_manager().check_collisions()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, update_all, render_all, check_collisions);
+criterion_main!(benches);
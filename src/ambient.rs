@@ -0,0 +1,89 @@
+//! Ambient happenings that liven up the tank between large creatures
+//!
+//! In the original only the death of a large creature ever brings something
+//! new on screen. The ambient scheduler fires small, self-contained events
+//! every so often on its own clock: a burst of bubbles from the floor, a
+//! school of fish darting across, a glint of treasure, an anchor dropped
+//! from a passing boat. The [`EntityManager`] owns the scheduler and, when
+//! an event is due, picks one of the ambient spawners in its
+//! [`SpawnRegistry`].
+//!
+//! [`EntityManager`]: crate::entity::EntityManager
+//! [`SpawnRegistry`]: crate::registry::SpawnRegistry
+
+use crate::rng::RngHandle;
+use rand::Rng;
+use std::time::Duration;
+
+/// Average number of ambient events per minute unless configured otherwise
+pub const DEFAULT_EVENTS_PER_MINUTE: f32 = 1.0;
+
+/// Decides when the next ambient event happens
+#[derive(Debug, Clone)]
+pub struct AmbientScheduler {
+    events_per_minute: f32,
+    time_until_next: f32,
+}
+
+impl AmbientScheduler {
+    /// Schedule roughly `events_per_minute` events (must be positive)
+    pub fn new(events_per_minute: f32, rng: &mut RngHandle) -> Self {
+        let mut scheduler = Self {
+            events_per_minute,
+            time_until_next: 0.0,
+        };
+        scheduler.schedule_next(rng);
+        scheduler
+    }
+
+    pub fn events_per_minute(&self) -> f32 {
+        self.events_per_minute
+    }
+
+    /// Advance the clock, returning whether an event is due
+    pub fn update(&mut self, delta_time: Duration, rng: &mut RngHandle) -> bool {
+        self.time_until_next -= delta_time.as_secs_f32();
+        if self.time_until_next > 0.0 {
+            return false;
+        }
+
+        self.schedule_next(rng);
+        true
+    }
+
+    /// Random gap around the mean so events don't tick like a metronome
+    fn schedule_next(&mut self, rng: &mut RngHandle) {
+        let mean_gap = 60.0 / self.events_per_minute;
+        self.time_until_next = rng.gen_range(0.5..1.5) * mean_gap;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_event_before_gap() {
+        let mut rng = RngHandle::default();
+        let mut scheduler = AmbientScheduler::new(1.0, &mut rng);
+        // The shortest possible gap is half the mean (30 seconds)
+        assert!(!scheduler.update(Duration::from_secs(29), &mut rng));
+    }
+
+    #[test]
+    fn test_frequency_controls_event_rate() {
+        let mut rng = RngHandle::default();
+        let mut frequent = AmbientScheduler::new(60.0, &mut rng);
+        let mut rare = AmbientScheduler::new(0.5, &mut rng);
+        let (mut frequent_count, mut rare_count) = (0, 0);
+
+        // Ten simulated minutes in one-second steps
+        for _ in 0..600 {
+            frequent_count += frequent.update(Duration::from_secs(1), &mut rng) as usize;
+            rare_count += rare.update(Duration::from_secs(1), &mut rng) as usize;
+        }
+
+        assert!(frequent_count > 300, "got {frequent_count}");
+        assert!(rare_count <= 10, "got {rare_count}");
+    }
+}
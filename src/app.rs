@@ -1,14 +1,74 @@
-use crate::entity::EntityManager;
-use crate::event::{AppEvent, Event, EventHandler};
+use crate::chat::{ChatConfig, ChatFeed};
+use crate::cli::Options;
+#[cfg(unix)]
+use crate::control::ControlSocket;
+use crate::control::{Command, ControlRequest};
+use crate::dirty::TankCache;
+use crate::entities::{Clock, Fish, Food, Pomodoro, PomodoroTimes};
+use crate::entity::{Entity, EntityId, EntityKind, EntityManager};
+use crate::event::{AppEvent, Event, EventHandler, FramePacer};
+use crate::event_log::{self, EventLog};
+use crate::gif;
+use crate::hooks::AquariumEvent;
+use crate::idle::IdleDetector;
+use crate::inspector;
+use crate::metrics::{self, MetricsServer};
+use crate::mouse::{self, MouseAction, Tooltip};
+use crate::palette;
+use crate::perf::PerfCounter;
+use crate::prompt::{Prompt, PromptAction};
+use crate::render::RenderOptions;
+use crate::screenshot;
+#[cfg(feature = "scripting")]
+use crate::scripting::Scripts;
+use crate::simulation::{buffer_to_ansi, buffer_to_color_mask, buffer_to_text, Simulation};
+use crate::sound::SoundEffects;
 use crate::spawning;
+use crate::stats::SessionStats;
+use crate::status::StatusBar;
+use crate::stream::{self, StreamRenderer};
+use crate::stress::StressTest;
+use crate::strip::Strip;
+#[cfg(feature = "sysmon")]
+use crate::sysmon::HostMonitor;
+use crate::timestep::{self, FixedTimestep};
+use crate::title::TitleScreen;
+use crate::toast::{self, Toasts};
+use crate::ui::AquariumWidget;
+use crate::world::WorldContext;
+use color_eyre::eyre::WrapErr;
+use ratatui::{buffer::Buffer, widgets::Widget};
 use ratatui::{
-    DefaultTerminal,
-    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    crossterm::event::{self as terminal_event, KeyCode, KeyEvent, KeyModifiers},
     layout::Rect,
+    Terminal,
 };
-use std::time::Instant;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-/// Application with simplified architecture using death callbacks
+/// Columns and rows one press of an arrow key (or hjkl) pans the camera
+const PAN_STEP: (i32, i32) = (4, 2);
+
+/// What the camera keeps in the middle of a `--world` tank
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Follow {
+    /// Stays where it was panned to
+    #[default]
+    Nothing,
+    /// The shark, whenever there is one
+    Shark,
+    /// The creature picked with Tab
+    Selection,
+}
+
+/// Application with simplified architecture using respawn policies
 pub struct App {
     /// Is the application running?
     pub running: bool,
@@ -18,32 +78,146 @@ pub struct App {
     pub events: EventHandler,
     /// Last update time for delta calculations
     pub last_update: Instant,
+    /// Frame time not yet simulated
+    pub timestep: FixedTimestep,
     /// Pause state
     pub paused: bool,
     /// Current screen bounds
     pub screen_bounds: Rect,
+    /// World extents and visible viewport used by the simulation
+    pub world: WorldContext,
     /// Whether aquarium has been initialized
     pub initialized: bool,
     /// Previous screen size for detecting resizes
     pub previous_size: (u16, u16),
     /// Classic mode flag (disables new fish/monsters, like -c flag in original)
     pub classic_mode: bool,
+    /// Ecology mode flag (fish breed and juveniles grow up)
+    pub ecology_mode: bool,
+    /// Chaos-monkey stress test driver (hidden `--stress` flag)
+    pub stress: Option<StressTest>,
+    /// Counts gathered this session (fed by the event hooks)
+    pub stats: Rc<RefCell<SessionStats>>,
+    /// Whether the stats overlay is shown
+    pub show_stats: bool,
+    /// Content, colours and row of the status line
+    pub status_bar: StatusBar,
+    /// Visual options passed to every entity when rendering
+    pub render_options: RenderOptions,
+    /// Frame and update timings
+    pub perf: PerfCounter,
+    /// Whether the status bar shows the performance readout
+    pub show_perf: bool,
+    /// Whether entity bounding boxes and collisions are outlined
+    pub show_debug: bool,
+    /// Title splash, while it is on screen
+    pub title: Option<TitleScreen>,
+    /// Last frame of the tank, so only what changed is drawn again
+    pub tank_cache: RefCell<TankCache>,
+    /// Picks a low-power tick rate while nothing changes on screen
+    pub idle: IdleDetector,
+    /// Socket commands come in on, if listening
+    #[cfg(unix)]
+    pub control: Option<ControlSocket>,
+    /// HTTP endpoint serving metrics, if listening
+    pub metrics: Option<MetricsServer>,
+    /// Screensaver mode: any key quits
+    pub screensaver: bool,
+    /// Set from other threads (signals, stdin closing) to end the app
+    pub stop: Arc<AtomicBool>,
+    /// The thin strip drawn instead of the tank (`--strip`)
+    pub strip: Option<Strip>,
+    /// Tank size for snapshots, dumps and recordings; the terminal's if
+    /// not set
+    pub offscreen_size: Option<(u16, u16)>,
+    /// Size of a virtual tank larger than the terminal, panned with the
+    /// arrow keys, hjkl or by dragging
+    pub world_size: Option<(u16, u16)>,
+    /// What the camera follows, if anything
+    pub follow: Follow,
+    /// Creature being inspected
+    pub selected: Option<EntityId>,
+    /// Tooltip for the last fish clicked, while it is up
+    pub tooltip: Option<Tooltip>,
+    /// Notices of changed settings and arrivals, while they are up
+    pub toasts: Rc<RefCell<Toasts>>,
+    /// The `:` command line, while it is open
+    pub prompt: Option<Prompt>,
+    /// Screen cell of the crosshair in feed mode, where Enter drops food
+    pub feed_cursor: Option<(u16, u16)>,
+    /// Chat whose viewers can spawn fish, if joined
+    pub chat: Option<ChatFeed>,
+    /// Show the clock overlay in the sky
+    pub clock: bool,
+    /// Pomodoro interval lengths and when the timer started, if running
+    pub pomodoro: Option<(PomodoroTimes, Instant)>,
+    /// Host sampler driving the tank, in `--sysmon` mode
+    #[cfg(feature = "sysmon")]
+    pub sysmon: Option<HostMonitor>,
+    /// Rhai scripts driving the tank's entities
+    #[cfg(feature = "scripting")]
+    pub scripts: Option<Scripts>,
 }
 
 impl Default for App {
     fn default() -> Self {
         let classic_mode = false; // Default to modern mode (with new fish)
-        Self {
+        let mut app = Self {
             running: true,
             entity_manager: EntityManager::new(),
             events: EventHandler::new(),
             last_update: Instant::now(),
+            timestep: FixedTimestep::new(),
             paused: false,
             screen_bounds: Rect::new(0, 0, 80, 24), // Default size
+            world: WorldContext::new(Rect::new(0, 0, 80, 24)),
             initialized: false,
             previous_size: (80, 24),
             classic_mode,
-        }
+            ecology_mode: false,
+            stress: None,
+            stats: Rc::new(RefCell::new(SessionStats::new())),
+            show_stats: false,
+            status_bar: StatusBar::default(),
+            render_options: RenderOptions::default(),
+            perf: PerfCounter::new(),
+            show_perf: false,
+            show_debug: false,
+            title: None,
+            tank_cache: RefCell::new(TankCache::new()),
+            idle: IdleDetector::new(),
+            #[cfg(unix)]
+            control: None,
+            metrics: None,
+            screensaver: false,
+            stop: Arc::new(AtomicBool::new(false)),
+            strip: None,
+            offscreen_size: None,
+            world_size: None,
+            follow: Follow::Nothing,
+            selected: None,
+            tooltip: None,
+            toasts: Rc::new(RefCell::new(Toasts::new())),
+            prompt: None,
+            feed_cursor: None,
+            chat: None,
+            clock: false,
+            pomodoro: None,
+            #[cfg(feature = "sysmon")]
+            sysmon: None,
+            #[cfg(feature = "scripting")]
+            scripts: None,
+        };
+
+        let stats = Rc::clone(&app.stats);
+        app.on_event(move |event| stats.borrow_mut().record(event));
+        let toasts = Rc::clone(&app.toasts);
+        app.on_event(move |event| {
+            if let Some(text) = toast::announce(event) {
+                toasts.borrow_mut().push(text, Instant::now());
+            }
+        });
+        app
     }
 }
 
@@ -55,16 +229,76 @@ impl App {
 
     /// Constructs a new instance of [`App`] with classic mode enabled.
     pub fn new_classic() -> Self {
-        Self {
+        // Keep the default entity manager: it already feeds the stats
+        let mut app = Self {
             classic_mode: true,
-            entity_manager: EntityManager::new_classic(),
             ..Default::default()
+        };
+        app.entity_manager.set_classic_mode(true);
+        app
+    }
+
+    /// Constructs a new instance of [`App`] from command line options.
+    pub fn with_options(options: &Options) -> Self {
+        let mut app = if options.classic_mode {
+            Self::new_classic()
+        } else {
+            Self::new()
+        };
+        if let Some(seed) = options.seed {
+            app.entity_manager.seed(seed);
         }
+        app.ecology_mode = options.ecology_mode;
+        app.entity_manager.set_background(options.background);
+        app.entity_manager.set_ecology_mode(options.ecology_mode);
+        if options.still_water {
+            app.entity_manager.set_water_scroll(false);
+        }
+        app.entity_manager
+            .set_shark_aggressiveness(options.shark_aggressiveness);
+        app.entity_manager
+            .set_ambient_frequency(options.ambient_frequency);
+        app.entity_manager.set_time_scale(options.time_scale);
+        app.render_options.depth_dimming = options.depth_dimming;
+        app.render_options.water_gradient = options.water_gradient;
+        app.render_options.night_mode = options.night_mode;
+        app.render_options.half_block = options.half_block;
+        app.render_options.reflections = options.reflections;
+        app.status_bar = options.status_bar.clone();
+        if options.strip {
+            app.strip = Some(Strip::new(80, 1, app.entity_manager.rng()));
+        } else if options.title {
+            app.title = Some(TitleScreen::new(Instant::now()));
+        }
+        if options.stress_mode {
+            app.stress = Some(StressTest::new());
+        }
+        app.idle.set_enabled(options.idle);
+        app.screensaver = options.screensaver;
+        app.offscreen_size = options.size;
+        app.world_size = options.world_size;
+        app.clock = options.clock;
+        app.pomodoro = options.pomodoro.map(|times| (times, Instant::now()));
+        app
+    }
+
+    /// Run the application's main loop on a full-screen `terminal`.
+    pub fn run<B: ratatui::backend::Backend>(
+        mut self,
+        mut terminal: Terminal<B>,
+    ) -> color_eyre::Result<()> {
+        // Mouse reports become clicks and drags
+        crossterm::execute!(io::stdout(), terminal_event::EnableMouseCapture)?;
+        let result = self.run_loop(&mut terminal);
+        crossterm::execute!(io::stdout(), terminal_event::DisableMouseCapture)?;
+        result
     }
 
-    /// Run the application's main loop.
-    pub fn run(mut self, mut terminal: DefaultTerminal) -> color_eyre::Result<()> {
-        while self.running {
+    fn run_loop<B: ratatui::backend::Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+    ) -> color_eyre::Result<()> {
+        while self.running && !self.stop.load(Ordering::Relaxed) {
             // Get terminal size and check for resize
             let size = terminal.size()?;
             let current_size = (size.width, size.height);
@@ -78,25 +312,348 @@ impl App {
 
             // Initialize aquarium if needed (like original's redraw)
             if !self.initialized {
+                self.world = self.world_for(self.screen_bounds);
                 self.initialize_aquarium();
             }
 
-            terminal.draw(|frame| frame.render_widget(&self, frame.area()))?;
+            let started = Instant::now();
+            terminal.draw(|frame| frame.render_widget(&*self, frame.area()))?;
+            self.idle.frame(self.frame_changed());
+            self.perf.record_frame(Instant::now(), started.elapsed());
+            self.perf.record_dropped(self.events.dropped_ticks());
+            self.record_metrics(started.elapsed(), self.events.dropped_ticks());
             self.handle_events()?;
+            self.events.set_tick_rate(self.idle.tick_fps());
+            self.timestep.set_frame_interval(self.tick_interval());
+        }
+        Ok(())
+    }
+
+    /// Run the main loop in stream mode, writing frames to `out`.
+    ///
+    /// No input is read and no alternate screen is used; the loop ends when
+    /// the output is closed (e.g. the reading end of a pipe goes away).
+    pub fn run_stream<W: Write>(self, out: &mut W) -> color_eyre::Result<()> {
+        self.run_stream_with(StreamRenderer::new(), out)
+    }
+
+    /// Run the main loop in stream mode, writing frames to `out` with
+    /// `renderer`.
+    pub fn run_stream_with<W: Write>(
+        mut self,
+        mut renderer: StreamRenderer,
+        out: &mut W,
+    ) -> color_eyre::Result<()> {
+        let mut pacer = FramePacer::new(self.tick_interval(), Instant::now());
+        // Plain stream output carries no colours, so half blocks would show
+        // up as solid glyphs
+        if !renderer.has_colors() {
+            self.render_options.half_block = false;
+        }
+
+        while self.running && !self.stop.load(Ordering::Relaxed) {
+            let (width, height) = stream::stream_size();
+            if (width, height) != self.previous_size {
+                self.on_resize((width, height));
+                renderer.invalidate();
+            }
+
+            self.screen_bounds = Rect::new(0, 0, width, height);
+            if !self.initialized {
+                self.world = self.world_for(self.screen_bounds);
+                self.initialize_aquarium();
+            }
+
+            let started = Instant::now();
+            let mut buffer = Buffer::empty(self.screen_bounds);
+            (&self).render(self.screen_bounds, &mut buffer);
+            match renderer.render_changes(&buffer, out) {
+                Err(err) if err.kind() == io::ErrorKind::BrokenPipe => break,
+                result => result?,
+            }
+            self.perf.record_frame(Instant::now(), started.elapsed());
+            self.idle.frame(self.frame_changed());
+
+            pacer.set_interval(self.tick_interval(), Instant::now());
+            self.timestep.set_frame_interval(self.tick_interval());
+            std::thread::sleep(pacer.timeout(Instant::now()));
+            pacer.poll(Instant::now());
+            self.perf.record_dropped(pacer.dropped());
+            self.record_metrics(started.elapsed(), pacer.dropped());
+            self.handle_stream_events();
+            self.tick();
+        }
+        Ok(())
+    }
+
+    /// Answer the control commands that came in during a stream frame
+    ///
+    /// The stream paces its own ticks and reads no terminal input, so only
+    /// app events are handled and the rest are dropped.
+    fn handle_stream_events(&mut self) {
+        while let Some(event) = self.events.try_next() {
+            match event {
+                Event::App(AppEvent::Quit) => self.quit(),
+                Event::App(AppEvent::Control(request)) => self.answer(request),
+                _ => {}
+            }
+        }
+    }
+
+    /// Record `seconds` of the tank to a GIF at `path` instead of showing it.
+    ///
+    /// The tank is the size of the terminal, as in stream mode, unless a
+    /// size was given, and runs on simulated time, so the recording takes
+    /// as long as it takes to draw.
+    pub fn export_gif(self, path: &Path, seconds: f32) -> color_eyre::Result<()> {
+        let mut simulation = self.into_simulation();
+        let file =
+            File::create(path).wrap_err_with(|| format!("failed to create {}", path.display()))?;
+        gif::record(&mut simulation, seconds, BufWriter::new(file))
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Print one frame of the tank after `ticks` simulation steps to `out`,
+    /// with ANSI colours.
+    pub fn snapshot<W: Write>(self, ticks: u64, out: &mut W) -> color_eyre::Result<()> {
+        writeln!(out, "{}", buffer_to_ansi(&self.render_offscreen(ticks)))?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Write one frame of the tank after `ticks` simulation steps to `out`
+    /// as plain text, and its colour mask to `colors` if given.
+    ///
+    /// With a seed and a size (`--seed`, `--size`) the same frame comes
+    /// out every time, for golden files.
+    pub fn dump<W: Write>(
+        self,
+        ticks: u64,
+        out: &mut W,
+        colors: Option<&Path>,
+    ) -> color_eyre::Result<()> {
+        let frame = self.render_offscreen(ticks);
+        if let Some(path) = colors {
+            std::fs::write(path, buffer_to_color_mask(&frame) + "\n")
+                .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        }
+        writeln!(out, "{}", buffer_to_text(&frame))?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// One frame after `ticks` simulation steps, drawn off screen
+    fn render_offscreen(self, ticks: u64) -> Buffer {
+        if let Some(mut strip) = self.strip {
+            let (width, height) = self.offscreen_size.unwrap_or_else(stream::stream_size);
+            strip.resize(width, height);
+            for _ in 0..ticks {
+                strip.update(timestep::STEP);
+            }
+            let area = Rect::new(0, 0, width, strip.rows());
+            let mut buffer = Buffer::empty(area);
+            strip.render(area, &mut buffer);
+            return buffer;
         }
+
+        let mut simulation = self.into_simulation();
+        simulation.run(ticks);
+        simulation.render()
+    }
+
+    /// A filled headless tank with this app's entities and render options,
+    /// the size of the terminal (as in stream mode) unless a size was given
+    fn into_simulation(self) -> Simulation {
+        let (width, height) = self.offscreen_size.unwrap_or_else(stream::stream_size);
+        let widget = AquariumWidget::new().render_options(self.render_options);
+        let mut simulation =
+            Simulation::empty(self.entity_manager, width, height).with_widget(widget);
+        let bounds = simulation.world().bounds();
+        spawning::initialize_aquarium(simulation.entity_manager_mut(), bounds);
+        simulation
+    }
+
+    /// Write aquarium events to `target` as JSON lines (see
+    /// [`crate::event_log`]).
+    pub fn log_events(&mut self, target: &event_log::Target) -> io::Result<()> {
+        let mut log = EventLog::new(target.open()?);
+        self.on_event(move |event| log.record(event));
+        Ok(())
+    }
+
+    /// Take commands from a Unix socket at `path` for as long as the app
+    /// runs (see [`crate::control`]).
+    #[cfg(unix)]
+    pub fn listen_for_control(&mut self, path: &Path) -> io::Result<()> {
+        self.control = Some(ControlSocket::bind(path, self.events.sender())?);
         Ok(())
     }
 
+    /// Serve metrics over HTTP at `address` for as long as the app runs
+    /// (see [`crate::metrics`]).
+    pub fn serve_metrics(&mut self, address: &str) -> io::Result<SocketAddr> {
+        let server = MetricsServer::bind(address)?;
+        let shared = Arc::clone(server.metrics());
+        self.on_event(move |event| metrics::lock(&shared).record(event));
+        let address = server.address();
+        self.metrics = Some(server);
+        Ok(address)
+    }
+
+    /// Play sound effects for aquarium events (see [`crate::sound`]).
+    pub fn play_sounds(&mut self, mut effects: SoundEffects) {
+        self.on_event(move |event| effects.record(event));
+    }
+
+    /// Let the host's CPU, network and load drive the tank (see
+    /// [`crate::sysmon`]).
+    #[cfg(feature = "sysmon")]
+    pub fn monitor_host(&mut self) {
+        self.sysmon = Some(HostMonitor::new());
+    }
+
+    /// Let Rhai scripts drive the tank's entities every step (see
+    /// [`crate::scripting`]).
+    #[cfg(feature = "scripting")]
+    pub fn run_scripts(&mut self, scripts: Scripts) {
+        scripts.listen(&mut self.entity_manager);
+        self.scripts = Some(scripts);
+    }
+
+    /// Let viewers in the chat spawn fish (see [`crate::chat`]).
+    pub fn join_chat(&mut self, config: ChatConfig) {
+        self.chat = Some(ChatFeed::connect(config));
+    }
+
+    /// Add a labelled fish for each chat request that passes the limits
+    fn spawn_chat_fish(&mut self) {
+        let Some(chat) = &mut self.chat else {
+            return;
+        };
+        let manager = &mut self.entity_manager;
+        let requests = chat.poll(Instant::now(), |id| manager.get_entity(id).is_some());
+        for request in requests {
+            let bounds = self.world.bounds();
+            let classic_mode = manager.classic_mode();
+            let waterline = manager.environment().waterline() as u16;
+            let color = palette::terminal_color(request.rgb());
+            let mut rng = manager.rng();
+            let id = manager.spawn(|id| {
+                let name = &request.name;
+                Fish::new_labelled(id, bounds, classic_mode, waterline, name, color, &mut rng)
+            });
+            chat.spawned(id);
+        }
+    }
+
+    /// Update the served metrics after a frame
+    fn record_metrics(&self, frame_time: Duration, dropped_ticks: u64) {
+        if let Some(server) = &self.metrics {
+            let mut metrics = metrics::lock(server.metrics());
+            metrics.record_frame(frame_time, dropped_ticks);
+            metrics.record_tank(&self.entity_manager, self.paused);
+        }
+    }
+
+    /// Run a line typed at the `:` prompt, showing any reply but a plain
+    /// `ok`
+    fn run_command(&mut self, line: &str) {
+        if line.trim().is_empty() {
+            return;
+        }
+        let reply = match Command::parse(line) {
+            Ok(command) => self.control(command),
+            Err(error) => format!("error: {error}"),
+        };
+        if reply != "ok" {
+            self.notify(reply);
+        }
+    }
+
+    /// Carry out a command from the control socket, returning the reply.
+    pub fn control(&mut self, command: Command) -> String {
+        match command {
+            Command::Spawn(name) => {
+                let bounds = self.world.bounds();
+                let spawn = self
+                    .entity_manager
+                    .registry()
+                    .get(&name)
+                    .map(|entry| entry.spawn.clone());
+                match (spawn, name.as_str()) {
+                    (Some(spawn), _) => spawn(&mut self.entity_manager, bounds),
+                    (None, "fish") => spawning::add_fish(&mut self.entity_manager, bounds),
+                    (None, _) => return format!("error: no creature called {name}"),
+                }
+            }
+            Command::Pause if !self.paused => self.toggle_pause(),
+            Command::Resume if self.paused => self.toggle_pause(),
+            Command::Pause | Command::Resume => {}
+            Command::SetSpeed(speed) => {
+                self.entity_manager.set_time_scale(speed);
+                self.show_speed();
+            }
+            Command::Stats => {
+                let manager = &self.entity_manager;
+                return format!(
+                    "ok entities={} fish={} speed={} paused={}",
+                    manager.entity_count(),
+                    manager.get_entities_of_kind(EntityKind::Fish).len(),
+                    manager.time_scale(),
+                    self.paused
+                );
+            }
+            Command::Theme(theme) => {
+                theme.apply(&mut self.render_options);
+                self.notify_setting("theme", format!("theme {}", theme.name()));
+            }
+            Command::Seed(seed) => {
+                self.redraw();
+                self.entity_manager.seed(seed);
+                self.notify(format!("seed {seed}"));
+            }
+            Command::Redraw => self.redraw(),
+            Command::Quit => self.quit(),
+        }
+        "ok".to_string()
+    }
+
+    /// Whether the frame just drawn changed the tank; the strip is always
+    /// moving
+    fn frame_changed(&self) -> bool {
+        self.strip.is_some() || self.tank_cache.borrow().changed()
+    }
+
+    /// Time between ticks at the current idle state
+    fn tick_interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.idle.tick_fps())
+    }
+
+    /// Run a control socket command and send back its reply
+    fn answer(&mut self, request: ControlRequest) {
+        self.idle.wake();
+        let reply = self.control(request.command);
+        let _ = request.reply.send(reply);
+    }
+
     pub fn handle_events(&mut self) -> color_eyre::Result<()> {
         match self.events.next()? {
             Event::Tick => self.tick(),
-            Event::Crossterm(event) => {
-                if let crossterm::event::Event::Key(key_event) = event {
+            Event::Crossterm(event) => match event {
+                terminal_event::Event::Key(key_event) => {
+                    self.idle.wake();
                     self.handle_key_event(key_event)?;
                 }
-            }
+                _ => self.idle.wake(),
+            },
             Event::App(app_event) => match app_event {
                 AppEvent::Quit => self.quit(),
+                AppEvent::Control(request) => self.answer(request),
+                AppEvent::Mouse(action) => {
+                    self.idle.wake();
+                    self.handle_mouse(action);
+                }
             },
         }
         Ok(())
@@ -104,18 +661,284 @@ impl App {
 
     /// Handles the key events and updates the state of [`App`].
     pub fn handle_key_event(&mut self, key_event: KeyEvent) -> color_eyre::Result<()> {
+        if self.screensaver {
+            self.quit();
+            return Ok(());
+        }
+
+        // Any key cuts the title short
+        if let Some(title) = self.title.as_mut() {
+            let now = Instant::now();
+            if title.is_holding(now) {
+                title.skip(now);
+                return Ok(());
+            }
+        }
+
+        if let Some(prompt) = self.prompt.as_mut() {
+            match prompt.handle_key(key_event) {
+                PromptAction::Editing => {}
+                PromptAction::Submit(line) => {
+                    self.prompt = None;
+                    self.run_command(&line);
+                }
+                PromptAction::Cancel => self.prompt = None,
+            }
+            return Ok(());
+        }
+
+        if self.feed_cursor.is_some() && self.handle_feed_key(key_event) {
+            return Ok(());
+        }
+
         match key_event.code {
             KeyCode::Esc | KeyCode::Char('q') => self.events.send(AppEvent::Quit),
+            KeyCode::Char(':') => self.prompt = Some(Prompt::new()),
             KeyCode::Char('c' | 'C') if key_event.modifiers == KeyModifiers::CONTROL => {
                 self.events.send(AppEvent::Quit)
             }
+            KeyCode::Char('s') if key_event.modifiers == KeyModifiers::CONTROL => self.screenshot(),
+            KeyCode::F(12) => self.screenshot(),
             KeyCode::Char('p' | 'P') => self.toggle_pause(),
             KeyCode::Char('r' | 'R') => self.redraw(),
+            KeyCode::Char('s') => self.toggle_stats(),
+            KeyCode::Char('n' | 'N') => self.toggle_night(),
+            KeyCode::Char('f') => self.toggle_perf(),
+            KeyCode::Char('F') => self.toggle_feed_mode(),
+            KeyCode::Char('+' | '=') => self.change_speed(timestep::TIME_SCALE_STEP),
+            KeyCode::Char('-' | '_') => self.change_speed(-timestep::TIME_SCALE_STEP),
+            KeyCode::Char(']') => self.change_density(spawning::FISH_DENSITY_STEP),
+            KeyCode::Char('[') => self.change_density(-spawning::FISH_DENSITY_STEP),
+            KeyCode::Left | KeyCode::Char('h') => self.pan_by_key(-PAN_STEP.0, 0),
+            KeyCode::Right | KeyCode::Char('l') => self.pan_by_key(PAN_STEP.0, 0),
+            KeyCode::Up | KeyCode::Char('k') => self.pan_by_key(0, -PAN_STEP.1),
+            KeyCode::Down | KeyCode::Char('j') => self.pan_by_key(0, PAN_STEP.1),
+            KeyCode::Char('c') => self.toggle_follow_shark(),
+            KeyCode::Tab => self.select_next(false),
+            KeyCode::BackTab => self.select_next(true),
+            KeyCode::F(3) => self.toggle_debug(),
+            KeyCode::Char('S') => self.summon(spawning::add_shark),
+            KeyCode::Char('W') => self.summon(spawning::add_whale),
+            KeyCode::Char('M') => self.summon(spawning::add_sea_monster),
+            KeyCode::Char('B') => self.summon(spawning::add_big_fish),
+            KeyCode::Char('H') => self.summon(spawning::add_hook),
+            KeyCode::Char('D') => self.summon(spawning::add_ducks),
+            KeyCode::Char('X') => self.rescue_fish(),
             _ => {}
         }
         Ok(())
     }
 
+    /// Handles a click or drag on the tank
+    pub fn handle_mouse(&mut self, action: MouseAction) {
+        if self.screensaver {
+            // Only a deliberate click or drag ends the screensaver
+            if !matches!(action, MouseAction::Move { .. }) {
+                self.quit();
+            }
+            return;
+        }
+        match action {
+            MouseAction::Click { column, row } if self.feed_cursor.is_some() => {
+                self.feed_cursor = Some((column, row));
+                self.drop_pinch();
+            }
+            MouseAction::Click { column, row } => self.click(column, row),
+            // The tank follows the pointer, so the camera moves the other way
+            MouseAction::Drag { dx, dy } => self.pan_by_key(-dx, -dy),
+            MouseAction::Move { column, row } => {
+                if self.feed_cursor.is_some() {
+                    self.feed_cursor = Some((column, row));
+                }
+            }
+        }
+    }
+
+    /// Show the fish under a screen cell, or drop food there if it is open
+    /// water
+    fn click(&mut self, column: u16, row: u16) {
+        if self.strip.is_some() || !self.initialized {
+            return;
+        }
+        let viewport = self.world.viewport();
+        let (x, y) = (viewport.x + column, viewport.y + row);
+        if let Some(fish) = self.fish_at(x, y) {
+            let text = mouse::describe_fish(fish);
+            self.tooltip = Some(Tooltip::new(text, column, row, Instant::now()));
+            return;
+        }
+        self.drop_food(x, y);
+    }
+
+    /// Add a food flake at a world cell, if it is open water
+    fn drop_food(&mut self, x: u16, y: u16) {
+        let waterline = self.entity_manager.environment().waterline();
+        let floor = self.world.bounds().bottom().saturating_sub(1);
+        if y as f32 > waterline && y < floor {
+            self.entity_manager
+                .spawn(|id| Food::new(id, x as f32, y as f32));
+        }
+    }
+
+    /// Start or stop feed mode, with its crosshair in the middle of the
+    /// screen
+    pub fn toggle_feed_mode(&mut self) {
+        if self.feed_cursor.take().is_some() {
+            self.notify_setting("feed", "feeding done");
+            return;
+        }
+        if self.strip.is_some() {
+            return;
+        }
+        let viewport = self.world.viewport();
+        self.feed_cursor = Some((viewport.width / 2, viewport.height / 2));
+        self.notify_setting("feed", "feed: arrows move, Enter drops food, Esc ends");
+    }
+
+    /// Move the crosshair or drop food in feed mode; false for keys that
+    /// should do what they normally do
+    fn handle_feed_key(&mut self, key_event: KeyEvent) -> bool {
+        let (dx, dy) = match key_event.code {
+            KeyCode::Left | KeyCode::Char('h') => (-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => (1, 0),
+            KeyCode::Up | KeyCode::Char('k') => (0, -1),
+            KeyCode::Down | KeyCode::Char('j') => (0, 1),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                self.drop_pinch();
+                return true;
+            }
+            KeyCode::Esc => {
+                self.toggle_feed_mode();
+                return true;
+            }
+            _ => return false,
+        };
+        if let Some((column, row)) = self.feed_cursor {
+            let viewport = self.world.viewport();
+            let column = (column as i32 + dx).clamp(0, viewport.width as i32 - 1);
+            let row = (row as i32 + dy).clamp(0, viewport.height as i32 - 1);
+            self.feed_cursor = Some((column as u16, row as u16));
+        }
+        true
+    }
+
+    /// Drop a pinch of food, three flakes side by side, at the crosshair
+    fn drop_pinch(&mut self) {
+        let Some((column, row)) = self.feed_cursor else {
+            return;
+        };
+        if !self.initialized {
+            return;
+        }
+        let viewport = self.world.viewport();
+        let (x, y) = (viewport.x + column, viewport.y + row);
+        for x in x.saturating_sub(1)..=x + 1 {
+            self.drop_food(x, y);
+        }
+    }
+
+    /// The frontmost fish showing at a world cell
+    fn fish_at(&self, x: u16, y: u16) -> Option<&Fish> {
+        let manager = &self.entity_manager;
+        manager
+            .entities_in_rect(Rect::new(x, y, 1, 1))
+            .into_iter()
+            .filter_map(|id| manager.get_as::<Fish>(id))
+            .filter(|fish| {
+                let position = fish.position();
+                let column = x as i32 - position.x as i32;
+                let row = y as i32 - position.y as i32;
+                column >= 0
+                    && row >= 0
+                    && fish
+                        .get_current_sprite()
+                        .is_opaque(column as u16, row as u16)
+            })
+            .min_by_key(|fish| fish.depth())
+    }
+
+    /// Move the camera over a virtual tank by `dx`, `dy` cells, stopping
+    /// at its edges
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        self.world.pan(dx, dy);
+    }
+
+    /// Pan by hand, which stops the camera following anything
+    fn pan_by_key(&mut self, dx: i32, dy: i32) {
+        self.follow = Follow::Nothing;
+        self.pan(dx, dy);
+    }
+
+    /// Save the frame on screen to the working directory, and say where
+    pub fn screenshot(&mut self) {
+        let mut frame = Buffer::empty(self.screen_bounds);
+        (&*self).render(self.screen_bounds, &mut frame);
+        let text = match screenshot::save(&frame, Path::new(".")) {
+            Ok(path) => format!("saved {}", path.display()),
+            Err(err) => format!("screenshot failed: {err}"),
+        };
+        self.notify(text);
+    }
+
+    /// Start or stop keeping the camera on the shark
+    pub fn toggle_follow_shark(&mut self) {
+        let (follow, text) = match self.follow {
+            Follow::Shark => (Follow::Nothing, "camera free"),
+            _ => (Follow::Shark, "following the shark"),
+        };
+        self.follow = follow;
+        self.notify_setting("camera", text);
+    }
+
+    /// Inspect the next creature (or the previous one, `backwards`), and
+    /// have the camera follow it
+    pub fn select_next(&mut self, backwards: bool) {
+        if self.strip.is_some() {
+            return;
+        }
+        self.selected = inspector::cycle(&self.entity_manager, self.selected, backwards);
+        if self.selected.is_some() {
+            self.follow = Follow::Selection;
+        } else if self.follow == Follow::Selection {
+            self.follow = Follow::Nothing;
+        }
+    }
+
+    /// Centre the camera on whatever it follows, if that is in the tank
+    fn track_camera(&mut self) {
+        let target = match self.follow {
+            Follow::Nothing => None,
+            Follow::Shark => self
+                .entity_manager
+                .get_entities_of_kind(EntityKind::Shark)
+                .into_iter()
+                .next(),
+            Follow::Selection => self
+                .selected
+                .and_then(|id| self.entity_manager.get_entity(id)),
+        };
+        let Some(target) = target else {
+            return;
+        };
+        let position = target.position();
+        let (width, height) = target.get_current_sprite().get_bounding_box();
+        let x = position.x + width as f32 / 2.0;
+        let y = position.y + height as f32 / 2.0;
+        self.world.center_on(x, y);
+    }
+
+    /// The world for a screen of `screen` bounds: the screen itself, or a
+    /// virtual tank seen through it
+    fn world_for(&self, screen: Rect) -> WorldContext {
+        match self.world_size {
+            Some((width, height)) => {
+                let bounds = Rect::new(0, 0, width.max(screen.width), height.max(screen.height));
+                WorldContext::with_viewport(bounds, screen)
+            }
+            None => WorldContext::new(screen),
+        }
+    }
+
     /// Handles the tick event - simplified to just update entities
     pub fn tick(&mut self) {
         if self.paused {
@@ -126,10 +949,82 @@ impl App {
         let delta_time = now.duration_since(self.last_update);
         self.last_update = now;
 
-        // Simple: just update all entities
-        // Death callbacks will handle all spawning automatically
-        self.entity_manager
-            .update_all(delta_time, self.screen_bounds);
+        if self
+            .tooltip
+            .as_ref()
+            .is_some_and(|tooltip| tooltip.is_expired(now))
+        {
+            self.tooltip = None;
+        }
+        self.toasts.borrow_mut().expire(now);
+
+        // The tank stays still behind the title until it starts dissolving
+        match &self.title {
+            Some(title) if title.is_done(now) => self.title = None,
+            Some(title) if title.is_holding(now) => return,
+            _ => {}
+        }
+
+        // Stress mode shakes things up before the regular update
+        if let Some(stress) = self.stress.as_mut() {
+            stress.step(&mut self.entity_manager, &mut self.world);
+        }
+
+        // Simulate in fixed steps; respawn policies handle all spawning
+        let steps = self.timestep.advance(delta_time);
+        for _ in 0..steps {
+            match &mut self.strip {
+                Some(strip) => {
+                    strip.update(timestep::STEP.mul_f32(self.entity_manager.time_scale()))
+                }
+                None => {
+                    self.entity_manager.update_all(timestep::STEP, &self.world);
+                    #[cfg(feature = "scripting")]
+                    self.step_scripts(now);
+                }
+            }
+        }
+        // A creature that has left the tank can't be inspected
+        if let Some(id) = self.selected {
+            if self.entity_manager.get_entity(id).is_none() {
+                self.selected = None;
+                if self.follow == Follow::Selection {
+                    self.follow = Follow::Nothing;
+                }
+            }
+        }
+        if self.strip.is_none() {
+            self.track_camera();
+        }
+        if let Some(server) = &self.metrics {
+            metrics::lock(server.metrics()).record_ticks(steps as u64);
+        }
+        self.spawn_chat_fish();
+        #[cfg(feature = "sysmon")]
+        if let Some(sample) = self.sysmon.as_mut().and_then(|monitor| monitor.poll(now)) {
+            sample.apply(&mut self.entity_manager, self.world.bounds());
+        }
+        self.perf
+            .record_update(now.elapsed(), self.entity_manager.take_collision_time());
+
+        if self.stress.is_some() {
+            if let Err(violation) = self.entity_manager.check_invariants() {
+                panic!("stress test invariant violated: {violation}");
+            }
+        }
+    }
+
+    /// Run the scripts for one step, announcing any that stopped
+    #[cfg(feature = "scripting")]
+    fn step_scripts(&mut self, now: Instant) {
+        let Some(scripts) = self.scripts.as_mut() else {
+            return;
+        };
+        let bounds = self.world.bounds();
+        for error in scripts.run(&mut self.entity_manager, timestep::STEP, bounds) {
+            let text = format!("Script stopped: {error}");
+            self.toasts.borrow_mut().push(text, now);
+        }
     }
 
     /// Set running to false to quit the application.
@@ -140,40 +1035,181 @@ impl App {
     /// Toggle pause state
     pub fn toggle_pause(&mut self) {
         self.paused = !self.paused;
+        // Resume where we left off instead of catching up on the pause
+        if !self.paused {
+            self.last_update = Instant::now();
+            self.timestep.reset();
+        }
+        let text = if self.paused { "paused" } else { "resumed" };
+        self.notify_setting("pause", text);
     }
 
-    /// Handle screen resize by reinitializing aquarium with new entity counts
-    fn on_resize(&mut self, new_size: (u16, u16)) {
-        self.previous_size = new_size;
-        // Preserve classic_mode setting when reinitializing
-        let classic_mode = self.entity_manager.classic_mode();
-        self.entity_manager = if classic_mode {
-            EntityManager::new_classic()
+    /// Show `text` as a toast
+    pub fn notify(&self, text: impl Into<String>) {
+        self.toasts.borrow_mut().push(text, Instant::now());
+    }
+
+    /// Show the new value of setting `key` as a toast, in place of one
+    /// still up for its old value
+    fn notify_setting(&self, key: &'static str, text: impl Into<String>) {
+        self.toasts.borrow_mut().set(key, text, Instant::now());
+    }
+
+    /// Bring on a large creature now with `spawn`, in place of the oldest
+    /// one if every slot is taken
+    pub fn summon(&mut self, spawn: fn(&mut EntityManager, Rect)) {
+        if self.strip.is_some() || !self.initialized {
+            return;
+        }
+        self.entity_manager.make_room_for_large();
+        spawn(&mut self.entity_manager, self.world.bounds());
+    }
+
+    /// Take the shark or big fish out before it eats anyone, and bring on
+    /// something gentler
+    pub fn rescue_fish(&mut self) {
+        if self.strip.is_some() || !self.initialized {
+            return;
+        }
+        if !spawning::rescue_fish(&mut self.entity_manager, self.world.bounds()) {
+            self.notify("no predators about");
+        }
+    }
+
+    /// Show or hide the stats overlay
+    pub fn toggle_stats(&mut self) {
+        self.show_stats = !self.show_stats;
+    }
+
+    /// Switch night lighting on or off
+    pub fn toggle_night(&mut self) {
+        self.render_options.night_mode = !self.render_options.night_mode;
+        let text = if self.render_options.night_mode {
+            "night"
         } else {
-            EntityManager::new()
+            "day"
         };
-        self.initialized = false;
+        self.notify_setting("theme", text);
+    }
+
+    /// Speed the tank up (or slow it down, for negative `step`)
+    pub fn change_speed(&mut self, step: f32) {
+        let scale = self.entity_manager.time_scale() + step;
+        self.entity_manager.set_time_scale(scale);
+        self.show_speed();
+    }
+
+    fn show_speed(&self) {
+        let scale = self.entity_manager.time_scale();
+        self.notify_setting("speed", format!("speed x{scale}"));
+    }
+
+    /// Keep more fish (or fewer, for negative `step`), adding or retiring
+    /// them straight away
+    pub fn change_density(&mut self, step: f32) {
+        let manager = &mut self.entity_manager;
+        manager.set_fish_density(manager.fish_density() + step);
+        if self.initialized && self.strip.is_none() {
+            spawning::fit_fish(manager, self.world.bounds());
+        }
+        let text = format!(
+            "fish x{} ({})",
+            manager.fish_density(),
+            spawning::fish_target(manager, self.world.bounds())
+        );
+        self.notify_setting("fish", text);
+    }
+
+    /// Show or hide the performance readout in the status bar
+    pub fn toggle_perf(&mut self) {
+        self.show_perf = !self.show_perf;
+    }
+
+    /// Show or hide the debug overlay
+    pub fn toggle_debug(&mut self) {
+        self.show_debug = !self.show_debug;
+    }
+
+    /// Handle screen resize, keeping the creatures of a filled tank
+    ///
+    /// Everything is moved to fit the new size and fish and seaweed are
+    /// topped up or thinned out to the new entity counts.
+    fn on_resize(&mut self, new_size: (u16, u16)) {
+        self.previous_size = new_size;
+        if !self.initialized {
+            return;
+        }
+
+        if let Some(strip) = &mut self.strip {
+            strip.resize(new_size.0, new_size.1);
+            return;
+        }
+        let new_bounds = Rect::new(0, 0, new_size.0, new_size.1);
+        let mut world = self.world_for(new_bounds);
+        if world.bounds() != self.world.bounds() {
+            spawning::resize_aquarium(
+                &mut self.entity_manager,
+                self.world.bounds(),
+                world.bounds(),
+            );
+        }
+        // A virtual tank keeps the camera where it was, as far as it fits
+        let viewport = self.world.viewport();
+        world.set_viewport(Rect {
+            x: viewport.x,
+            y: viewport.y,
+            ..new_bounds
+        });
+        self.world = world;
+        self.pan(0, 0);
     }
 
     /// Redraw by clearing all entities and reinitializing
     pub fn redraw(&mut self) {
-        // Preserve classic_mode setting when reinitializing
-        let classic_mode = self.entity_manager.classic_mode();
-        self.entity_manager = if classic_mode {
-            EntityManager::new_classic()
-        } else {
-            EntityManager::new()
-        };
+        self.entity_manager = self.fresh_entity_manager();
         self.initialized = false;
+        self.tank_cache.borrow_mut().invalidate();
+    }
+
+    /// Create an empty entity manager that keeps the current mode flags
+    /// and registered event callbacks
+    fn fresh_entity_manager(&mut self) -> EntityManager {
+        self.entity_manager.fresh()
     }
 
     /// Initialize the aquarium using the simplified spawning system
     fn initialize_aquarium(&mut self) {
+        if let Some(strip) = &mut self.strip {
+            let bounds = self.world.bounds();
+            *strip = Strip::new(bounds.width, bounds.height, self.entity_manager.rng());
+            self.initialized = true;
+            return;
+        }
         // Use the simple initialization function that matches original Perl
-        spawning::initialize_aquarium(&mut self.entity_manager, self.screen_bounds);
+        spawning::initialize_aquarium(&mut self.entity_manager, self.world.bounds());
+        self.spawn_overlays();
         self.initialized = true;
     }
 
+    /// Add the clock and pomodoro timer, if enabled
+    fn spawn_overlays(&mut self) {
+        let bounds = self.world.bounds();
+        if self.clock {
+            self.entity_manager.spawn(|id| Clock::new(id, bounds));
+        }
+        if let Some((times, started)) = self.pomodoro {
+            self.entity_manager
+                .spawn(|id| Pomodoro::new(id, bounds, times, started));
+        }
+    }
+
+    /// Register a callback for aquarium events (spawns, kills, weather).
+    ///
+    /// Callbacks survive resizes and redraws.
+    pub fn on_event(&mut self, callback: impl FnMut(&AquariumEvent) + 'static) {
+        self.entity_manager.on_event(callback);
+    }
+
     /// Get entity manager reference for rendering
     pub fn entity_manager(&self) -> &EntityManager {
         &self.entity_manager
@@ -0,0 +1,508 @@
+//! Creatures defined in sprite asset files
+//!
+//! Every `*.sprite` file in `~/.config/asciiquarium-rs/sprites/` (or the
+//! directory given with `--sprites`) describes one creature. Settings come
+//! first as `key = value` lines, then the art, one `--- frame` section per
+//! animation frame, each optionally followed by a `--- mask` section with
+//! its colour mask. Frames facing left can be given as `--- frame left`;
+//! without them the creature looks the same both ways.
+//!
+//! ```text
+//! # A jellyfish drifting through the tank
+//! name = jellyfish
+//! category = ambient
+//! weight = 2
+//! speed = 1.5
+//! depth = 12
+//! frame_delay = 0.4
+//! direction = random
+//! rows = water
+//!
+//! --- frame
+//!  .-.
+//! (   )
+//!  )))
+//! --- mask
+//!  mmm
+//! m   m
+//!  ccc
+//! ```
+//!
+//! Each definition is registered under its name in the tank's
+//! [`SpawnRegistry`], so a file can also replace a built-in creature.
+//! Loaded creatures are drawn as [`ScriptedCreature`]s.
+//!
+//! Sprites written for the original Perl program can be dropped in the
+//! same directory as `*.pl` files; see [`perl_sprites`].
+//!
+//! [`ScriptedCreature`]: crate::entities::ScriptedCreature
+//! [`perl_sprites`]: crate::perl_sprites
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use ratatui::layout::Rect;
+
+use crate::entities::ScriptedCreature;
+use crate::entity::{EntityManager, Sprite};
+use crate::perl_sprites;
+use crate::registry::{Category, SpawnRegistry};
+
+/// File extension of sprite definitions
+pub const EXTENSION: &str = "sprite";
+
+/// Shortest `frame_delay` a sprite file may ask for, in seconds
+pub const MIN_FRAME_DELAY: f32 = 0.01;
+
+/// Longest `frame_delay` a sprite file may ask for, in seconds
+pub const MAX_FRAME_DELAY: f32 = 60.0;
+
+/// Which way a creature swims
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heading {
+    Left,
+    Right,
+    Random,
+}
+
+/// Where in the tank a creature appears
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rows {
+    /// Anywhere between the waterline and the floor
+    Water,
+    /// Floating on the water surface
+    Surface,
+    /// Resting on the bottom
+    Floor,
+}
+
+/// One frame of art with its optional colour mask
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub art: String,
+    pub mask: Option<String>,
+}
+
+/// A creature read from a sprite file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatureDef {
+    pub name: String,
+    pub category: Category,
+    pub weight: f32,
+    /// Columns per second; zero keeps the creature in place
+    pub speed: f32,
+    pub depth: u8,
+    /// Seconds each frame is shown, [`MIN_FRAME_DELAY`] to [`MAX_FRAME_DELAY`]
+    pub frame_delay: f32,
+    pub heading: Heading,
+    pub rows: Rows,
+    /// Frames facing right
+    pub frames: Vec<Frame>,
+    /// Frames facing left (empty to reuse `frames`)
+    pub left_frames: Vec<Frame>,
+}
+
+/// Why a sprite file couldn't be read
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetError {
+    pub path: PathBuf,
+    /// Line the problem is on (1-based), 0 for the whole file
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "{}:{}: {}", self.path.display(), self.line, self.message)
+        } else {
+            write!(f, "{}: {}", self.path.display(), self.message)
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+/// Section of the art a line belongs to
+enum Section {
+    Settings,
+    Frame { left: bool },
+    Mask { left: bool },
+}
+
+impl CreatureDef {
+    /// A definition with the default settings and no frames
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            category: Category::Ambient,
+            weight: 1.0,
+            speed: 2.0,
+            depth: crate::depth::FISH_START,
+            frame_delay: 0.5,
+            heading: Heading::Random,
+            rows: Rows::Water,
+            frames: Vec::new(),
+            left_frames: Vec::new(),
+        }
+    }
+
+    /// Columns and rows of the largest frame, either way round
+    pub fn size(&self) -> (u16, u16) {
+        self.frames
+            .iter()
+            .chain(&self.left_frames)
+            .map(|frame| Sprite::from_ascii_art(&frame.art, None).get_bounding_box())
+            .fold((0, 0), |(width, height), (w, h)| {
+                (width.max(w), height.max(h))
+            })
+    }
+
+    /// Parse a sprite definition; errors carry the line number, and an
+    /// empty path for the caller to fill in
+    pub fn parse(text: &str) -> Result<Self, AssetError> {
+        let error = |line: usize, message: String| AssetError {
+            path: PathBuf::new(),
+            line,
+            message,
+        };
+
+        let mut def = CreatureDef::new("");
+        let mut section = Section::Settings;
+
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+
+            if let Some(header) = line.strip_prefix("---") {
+                let words: Vec<&str> = header.split_whitespace().collect();
+                let left = words.get(1) == Some(&"left");
+                let frames = if left {
+                    &mut def.left_frames
+                } else {
+                    &mut def.frames
+                };
+                section = match words.first().copied() {
+                    Some("frame") => {
+                        frames.push(Frame {
+                            art: String::new(),
+                            mask: None,
+                        });
+                        Section::Frame { left }
+                    }
+                    Some("mask") => match (&section, frames.last_mut()) {
+                        (Section::Frame { left: frame_left }, Some(frame))
+                            if *frame_left == left =>
+                        {
+                            frame.mask = Some(String::new());
+                            Section::Mask { left }
+                        }
+                        _ => return Err(error(number, "mask without a frame".to_string())),
+                    },
+                    _ => {
+                        return Err(error(
+                            number,
+                            format!("unknown section {:?}", header.trim()),
+                        ))
+                    }
+                };
+                continue;
+            }
+
+            match section {
+                Section::Settings => {
+                    let line = line.trim();
+                    if line.is_empty() || line.starts_with('#') {
+                        continue;
+                    }
+                    let Some((key, value)) = line.split_once('=') else {
+                        return Err(error(
+                            number,
+                            format!("expected `key = value`, got {line:?}"),
+                        ));
+                    };
+                    def.set(key.trim(), value.trim())
+                        .map_err(|message| error(number, message))?;
+                }
+                Section::Frame { left } | Section::Mask { left } => {
+                    let frames = if left {
+                        &mut def.left_frames
+                    } else {
+                        &mut def.frames
+                    };
+                    let frame = frames.last_mut().expect("section opened with a frame");
+                    let text = match &mut frame.mask {
+                        Some(mask) if matches!(section, Section::Mask { .. }) => mask,
+                        _ => &mut frame.art,
+                    };
+                    if !text.is_empty() {
+                        text.push('\n');
+                    }
+                    text.push_str(line);
+                }
+            }
+        }
+
+        for frame in def.frames.iter_mut().chain(def.left_frames.iter_mut()) {
+            trim_trailing_blank_lines(&mut frame.art);
+            if let Some(mask) = &mut frame.mask {
+                trim_trailing_blank_lines(mask);
+            }
+        }
+
+        if def.name.is_empty() {
+            return Err(error(0, "missing `name`".to_string()));
+        }
+        if def.frames.iter().all(|frame| frame.art.trim().is_empty()) {
+            return Err(error(0, "no `--- frame` with any art".to_string()));
+        }
+        if !def.left_frames.is_empty() && def.left_frames.len() != def.frames.len() {
+            return Err(error(
+                0,
+                "needs as many left frames as right frames".to_string(),
+            ));
+        }
+        Ok(def)
+    }
+
+    /// Apply one `key = value` setting
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let number = |value: &str| {
+            value
+                .parse::<f32>()
+                .ok()
+                .filter(|number| number.is_finite() && *number >= 0.0)
+                .ok_or_else(|| format!("`{key}` needs a number, got {value:?}"))
+        };
+
+        match key {
+            "name" => self.name = value.to_string(),
+            "weight" => self.weight = number(value)?,
+            "speed" => self.speed = number(value)?,
+            "frame_delay" => {
+                let delay = number(value)?;
+                if delay > MAX_FRAME_DELAY {
+                    return Err(format!(
+                        "`frame_delay` needs at most {MAX_FRAME_DELAY} seconds, got {value:?}"
+                    ));
+                }
+                self.frame_delay = delay.max(MIN_FRAME_DELAY);
+            }
+            "depth" => {
+                self.depth = value
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|depth| *depth <= crate::depth::HIDING)
+                    .ok_or_else(|| {
+                        format!("`depth` needs 0 to {}, got {value:?}", crate::depth::HIDING)
+                    })?
+            }
+            "category" => {
+                self.category = match value {
+                    "ambient" => Category::Ambient,
+                    "large" => Category::Large,
+                    "decoration" => Category::Decoration,
+                    _ => return Err(format!("unknown category {value:?}")),
+                }
+            }
+            "direction" => {
+                self.heading = match value {
+                    "left" => Heading::Left,
+                    "right" => Heading::Right,
+                    "random" => Heading::Random,
+                    _ => return Err(format!("unknown direction {value:?}")),
+                }
+            }
+            "rows" => {
+                self.rows = match value {
+                    "water" => Rows::Water,
+                    "surface" => Rows::Surface,
+                    "floor" => Rows::Floor,
+                    _ => return Err(format!("unknown rows {value:?}")),
+                }
+            }
+            _ => return Err(format!("unknown setting {key:?}")),
+        }
+        Ok(())
+    }
+}
+
+fn trim_trailing_blank_lines(text: &mut String) {
+    while text.ends_with('\n')
+        || text
+            .lines()
+            .last()
+            .is_some_and(|line| line.trim().is_empty())
+    {
+        match text.rfind('\n') {
+            Some(end) => text.truncate(end),
+            None => text.clear(),
+        }
+    }
+}
+
+/// `~/.config/asciiquarium-rs`, honouring `XDG_CONFIG_HOME`
+pub fn config_dir() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config.join("asciiquarium-rs"))
+}
+
+/// `~/.config/asciiquarium-rs/sprites`, honouring `XDG_CONFIG_HOME`
+pub fn default_dir() -> Option<PathBuf> {
+    Some(config_dir()?.join("sprites"))
+}
+
+/// Read every sprite file in `dir`, in file name order
+///
+/// A missing directory simply has no creatures. Files that can't be read
+/// or parsed are reported and skipped; a Perl file yields a creature for
+/// each image it defines.
+pub fn load_dir(dir: &Path) -> (Vec<CreatureDef>, Vec<AssetError>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (Vec::new(), Vec::new());
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == EXTENSION || ext == perl_sprites::EXTENSION)
+        })
+        .collect();
+    paths.sort();
+
+    let mut defs = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) => {
+                errors.push(AssetError {
+                    path,
+                    line: 0,
+                    message: err.to_string(),
+                });
+                continue;
+            }
+        };
+
+        if path
+            .extension()
+            .is_some_and(|ext| ext == perl_sprites::EXTENSION)
+        {
+            let (sprites, perl_errors) = perl_sprites::parse(&text);
+            defs.extend(sprites.iter().map(|sprite| sprite.to_creature()));
+            errors.extend(perl_errors.into_iter().map(|err| AssetError {
+                path: path.clone(),
+                ..err
+            }));
+            continue;
+        }
+        match CreatureDef::parse(&text) {
+            Ok(def) => defs.push(def),
+            Err(err) => errors.push(AssetError { path, ..err }),
+        }
+    }
+    (defs, errors)
+}
+
+/// Register a spawner for each definition
+pub fn register_all(registry: &mut SpawnRegistry, defs: Vec<CreatureDef>) {
+    for def in defs {
+        // Definitions are loaded once at startup; their names live as long
+        // as the built-in ones, as entity types and in event hooks
+        let name: &'static str = Box::leak(def.name.clone().into_boxed_str());
+        let (weight, category, size) = (def.weight, def.category, def.size());
+        registry
+            .register(name, weight, category, move |manager, bounds| {
+                spawn(manager, &def, name, bounds)
+            })
+            .size = Some(size);
+    }
+}
+
+/// Add one creature from `def` to the tank
+fn spawn(manager: &mut EntityManager, def: &CreatureDef, name: &'static str, bounds: Rect) {
+    let large = def.category == Category::Large;
+    if large && manager.large_creatures_full() {
+        return;
+    }
+
+    let waterline = manager.environment().waterline();
+    let mut rng = manager.rng();
+    let creature = |id| ScriptedCreature::new(id, def, name, bounds, waterline, &mut rng);
+    if large {
+        manager.spawn_large(creature);
+    } else {
+        manager.spawn(creature);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JELLYFISH: &str = "# A jellyfish\nname = jellyfish\ncategory = large\nspeed = 1.5\nrows = water\n\n--- frame\n .-.\n(   )\n\n--- mask\n mmm\nm   m\n--- frame\n .-.\n{   }\n";
+
+    #[test]
+    fn test_parse() {
+        let def = CreatureDef::parse(JELLYFISH).unwrap();
+        assert_eq!(def.name, "jellyfish");
+        assert_eq!(def.category, Category::Large);
+        assert_eq!(def.speed, 1.5);
+        assert_eq!(def.frames.len(), 2);
+        assert_eq!(def.frames[0].art, " .-.\n(   )");
+        assert_eq!(def.frames[0].mask.as_deref(), Some(" mmm\nm   m"));
+        assert_eq!(def.frames[1].mask, None);
+        assert!(def.left_frames.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = CreatureDef::parse("name = x\nspeed = fast\n--- frame\no").unwrap_err();
+        assert_eq!(err.line, 2);
+
+        let err = CreatureDef::parse("name = x\n--- mask\nm").unwrap_err();
+        assert_eq!(err.line, 2);
+
+        assert!(CreatureDef::parse("--- frame\no").is_err());
+        assert!(CreatureDef::parse("name = x").is_err());
+        assert!(CreatureDef::parse("name = x\nframe_delay = 1e30\n--- frame\no").is_err());
+        assert!(
+            CreatureDef::parse("name = x\n--- frame\no\n--- frame left\no\n--- frame\no").is_err()
+        );
+    }
+
+    #[test]
+    fn test_registered_creature_spawns() {
+        let def = CreatureDef::parse(JELLYFISH).unwrap();
+        let mut manager = EntityManager::new();
+        register_all(manager.registry_mut(), vec![def]);
+
+        let entry = manager.registry().get("jellyfish").cloned().unwrap();
+        assert_eq!(entry.size, Some((5, 2)));
+        (entry.spawn)(&mut manager, Rect::new(0, 0, 80, 24));
+        assert_eq!(manager.get_entities_by_type("jellyfish").len(), 1);
+        assert!(manager.large_creatures_full());
+    }
+
+    #[test]
+    fn test_load_dir_skips_bad_files() {
+        let dir = std::env::temp_dir().join(format!("asciiquarium-assets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.sprite"), JELLYFISH).unwrap();
+        std::fs::write(dir.join("b.sprite"), "name = broken").unwrap();
+        std::fs::write(dir.join("c.pl"), "my @eel_image = ('~~>', '<~~');").unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a sprite").unwrap();
+
+        let (defs, errors) = load_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(defs.len(), 2);
+        assert_eq!(defs[1].name, "perl_eel");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].path.ends_with("b.sprite"));
+        assert!(load_dir(&dir).0.is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+//! Terminal backends
+//!
+//! How the app gets its frames onto the screen is chosen with
+//! `--backend`:
+//!
+//! | Backend | Output |
+//! |---|---|
+//! | `crossterm` | Full-screen ratatui with keyboard input, the default on a TTY |
+//! | `termion` | Full screen drawn through termion instead (unix, `termion` feature) |
+//! | `stream` | Plain cursor-addressed text (see [`crate::stream`]), the default otherwise |
+//! | `dumb` | Like `stream`, with ANSI colours, for piping into tools that keep them |
+//!
+//! Each backend sets the terminal up, runs the app and puts the terminal
+//! back, so adding one only touches [`Backend::run`].
+
+/// Where and how the aquarium is drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// Crossterm on a TTY, stream otherwise
+    #[default]
+    Auto,
+    Crossterm,
+    /// Full screen through termion; keys and mouse still come in through
+    /// the shared event handler
+    Termion,
+    Stream,
+    Dumb,
+}
+
+impl Backend {
+    /// The `--backend` values, for listing in errors and help
+    pub const NAMES: [&str; 5] = ["auto", "crossterm", "termion", "stream", "dumb"];
+
+    /// Parse a `--backend` value
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "crossterm" => Some(Self::Crossterm),
+            "termion" => Some(Self::Termion),
+            "stream" | "plain" => Some(Self::Stream),
+            "dumb" | "ansi" => Some(Self::Dumb),
+            _ => None,
+        }
+    }
+
+    /// The backend to use when stdout is (or isn't) a terminal
+    pub fn resolve(self, is_terminal: bool) -> Self {
+        match self {
+            Self::Auto if is_terminal => Self::Crossterm,
+            Self::Auto => Self::Stream,
+            backend => backend,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Backend {
+    /// Run `app` on stdout until it quits
+    pub fn run(self, app: crate::App) -> color_eyre::Result<()> {
+        use crate::stream::StreamRenderer;
+        use std::io::IsTerminal;
+
+        let stdout = std::io::stdout();
+        match self.resolve(stdout.is_terminal()) {
+            Self::Auto | Self::Crossterm => {
+                let terminal = ratatui::init();
+                let result = app.run(terminal);
+                ratatui::restore();
+                result
+            }
+            #[cfg(all(unix, feature = "termion"))]
+            Self::Termion => {
+                use ratatui::backend::TermionBackend;
+                use termion::raw::IntoRawMode;
+                use termion::screen::IntoAlternateScreen;
+
+                // Raw mode and the alternate screen end when the terminal
+                // is dropped at the end of the run
+                let stdout = stdout.into_raw_mode()?.into_alternate_screen()?;
+                let mut terminal = ratatui::Terminal::new(TermionBackend::new(stdout))?;
+                terminal.hide_cursor()?;
+                app.run(terminal)
+            }
+            #[cfg(not(all(unix, feature = "termion")))]
+            Self::Termion => Err(color_eyre::eyre::eyre!(
+                "this build has no termion backend; enable the termion feature"
+            )),
+            Self::Stream => app.run_stream(&mut stdout.lock()),
+            Self::Dumb => app.run_stream_with(StreamRenderer::with_colors(), &mut stdout.lock()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_resolve() {
+        assert_eq!(Backend::parse("Dumb"), Some(Backend::Dumb));
+        assert_eq!(Backend::parse("termion"), Some(Backend::Termion));
+        assert_eq!(Backend::parse("curses"), None);
+        for name in Backend::NAMES {
+            assert!(Backend::parse(name).is_some(), "{name}");
+        }
+
+        assert_eq!(Backend::Auto.resolve(true), Backend::Crossterm);
+        assert_eq!(Backend::Auto.resolve(false), Backend::Stream);
+        assert_eq!(Backend::Dumb.resolve(true), Backend::Dumb);
+        assert_eq!(Backend::Termion.resolve(false), Backend::Termion);
+    }
+}
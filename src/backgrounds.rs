@@ -0,0 +1,161 @@
+//! Background scenes for the bottom-right corner of the tank
+//!
+//! The original always draws its castle there. `--background` picks one of
+//! a few alternatives instead, or nothing at all. Whichever is chosen is
+//! placed by the spawner like the castle: in the bottom-right corner, or
+//! at the right edge of each segment on ultrawide screens, using the
+//! scene's own width.
+
+use crate::entities::Castle;
+use crate::entity::Sprite;
+use crate::sprite;
+
+/// Decoration drawn at the bottom of the tank
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Background {
+    /// The original castle
+    #[default]
+    Castle,
+    /// Branching corals around a brain coral
+    Reef,
+    /// A sunken ship with a broken mast
+    Shipwreck,
+    /// Broken columns of a temple
+    Atlantis,
+    /// Bare sand
+    None,
+}
+
+const REEF_IMAGE: &str = r#"   \|/    Y    \ | /
+  --O--  \|/    \|/    ,-.
+   /|\  --+--  --+--  (   )
+    |     |      |     `-'
+ .--+--.  |  .---+---.  |
+(   o   )_|_(  o   o  )_|_
+ `-----'     `-------'"#;
+
+const REEF_MASK: &str = r#"   mmm    r    m m m
+  mmMmm  rrr    mmm    yyy
+   mmm  rrRrr  mmMmm  y   y
+    m     r      m     yyy
+ yyyMyyy  r  yyyyMyyyy  g
+y   Y   ygggy  Y   Y  yggg
+ yyyyyyy     yyyyyyyyy"#;
+
+const SHIPWRECK_IMAGE: &str = r#"        \
+         \  |
+          \ |   ___
+       ____\|__/   |
+   ___/ o   o   o  |___
+   \   ___________    /
+    \_/    ~~~    \__/"#;
+
+const SHIPWRECK_MASK: &str = r#"        y
+         y  y
+          y y   yyy
+       yyyyyyyyy   y
+   yyyy w   w   w  yyyy
+   y   yyyyyyyyyyy    y
+    yyy    ccc    yyyy"#;
+
+const ATLANTIS_IMAGE: &str = r#"      _____________
+     /_____________\
+      | |   | |   |
+      | |   | |   |_
+      | |   |_|   | |
+   ___| |__/___\__| |___
+  |_____________________|"#;
+
+const ATLANTIS_MASK: &str = r#"      CCCCCCCCCCCCC
+     CCCCCCCCCCCCCCC
+      c c   c c   c
+      c c   c c   cc
+      c c   ccc   c c
+   cccc ccccccccccc cccc
+  ccccccccccccccccccccccc"#;
+
+impl Background {
+    /// Parse a `--background` value
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "castle" => Some(Self::Castle),
+            "reef" | "coral" => Some(Self::Reef),
+            "shipwreck" | "wreck" => Some(Self::Shipwreck),
+            "atlantis" | "ruins" => Some(Self::Atlantis),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    /// The scene's sprite; `None` for bare sand
+    pub fn sprite(self) -> Option<Sprite> {
+        match self {
+            Self::Castle => Some(Castle::sprite()),
+            Self::Reef => Some(sprite!(REEF_IMAGE, REEF_MASK)),
+            Self::Shipwreck => Some(sprite!(SHIPWRECK_IMAGE, SHIPWRECK_MASK)),
+            Self::Atlantis => Some(sprite!(ATLANTIS_IMAGE, ATLANTIS_MASK)),
+            Self::None => None,
+        }
+    }
+
+    /// Columns the scene takes, including the castle's gap to the right
+    /// edge of the screen
+    pub fn width(self) -> u16 {
+        match self {
+            Self::Castle => Castle::width(),
+            Self::None => 0,
+            _ => self
+                .sprite()
+                .map_or(0, |sprite| sprite.get_bounding_box().0 + 1),
+        }
+    }
+
+    /// Rows from the top of the scene to the bottom of the screen
+    ///
+    /// Like the castle, every scene stands one row above the bottom line.
+    pub fn height(self) -> u16 {
+        match self {
+            Self::Castle => Castle::height(),
+            Self::None => 0,
+            _ => self
+                .sprite()
+                .map_or(0, |sprite| sprite.get_bounding_box().1 + 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Background::parse("Reef"), Some(Background::Reef));
+        assert_eq!(Background::parse("none"), Some(Background::None));
+        assert_eq!(Background::parse("volcano"), None);
+    }
+
+    #[test]
+    fn test_masks_fit_their_art() {
+        for (image, mask) in [
+            (REEF_IMAGE, REEF_MASK),
+            (SHIPWRECK_IMAGE, SHIPWRECK_MASK),
+            (ATLANTIS_IMAGE, ATLANTIS_MASK),
+        ] {
+            assert_eq!(image.lines().count(), mask.lines().count());
+            for (line, mask_line) in image.lines().zip(mask.lines()) {
+                assert!(mask_line.len() <= line.len(), "{mask_line:?} / {line:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_scene_sizes() {
+        assert_eq!(Background::Castle.width(), 32);
+        assert_eq!(Background::Castle.height(), 13);
+        assert_eq!(Background::Reef.height(), 8);
+        assert!(Background::Atlantis.width() > 20);
+        assert!(Background::None.sprite().is_none());
+        assert_eq!(Background::None.width(), 0);
+    }
+}
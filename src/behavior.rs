@@ -0,0 +1,231 @@
+//! Per-creature behaviour state machines
+//!
+//! Fish in the original swim at a constant speed from edge to edge. Here
+//! they slowly tire, and a tired fish that passes seaweed slows down and
+//! hovers beside it for a few seconds before swimming on. Speed changes are
+//! eased so fish drift to a stop and pick up again rather than snapping.
+//!
+//! Fish also keep an eye out for sharks: one passing within a few rows
+//! sends them ducking behind the seaweed and castle until it has gone.
+//! Food dropped in nearby draws them to it.
+
+use crate::entity::Position;
+use crate::rng::RngHandle;
+use rand::Rng;
+use std::time::Duration;
+
+/// How close (rows, centre to centre) a shark must come to scare a fish
+pub const SCARE_ROWS: f32 = 6.0;
+
+/// How close (columns) a fish must be to seaweed to rest beside it
+pub const REST_DISTANCE: f32 = 4.0;
+
+/// How far (columns, and rows) a fish notices food, centre to centre
+pub const FEED_DISTANCE: (f32, f32) = (24.0, 8.0);
+
+/// Energy below which a fish will stop at the next seaweed it passes
+const TIRED_ENERGY: f32 = 0.3;
+
+/// Energy drained per second while swimming (randomised per fish)
+const DRAIN_PER_SEC: std::ops::Range<f32> = 0.01..0.03;
+
+/// How long a fish hovers once it stops
+const REST_SECS: std::ops::Range<f32> = 3.0..6.0;
+
+/// Fraction of normal speed kept while hovering
+const RESTING_SPEED: f32 = 0.1;
+
+/// Fraction of the remaining speed difference closed per second
+const SPEED_EASING_PER_SEC: f32 = 2.0;
+
+/// What a fish is currently doing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Activity {
+    /// Cruising; energy slowly drains
+    Swimming,
+    /// Hovering beside seaweed; resumes when `remaining` runs out
+    Resting { remaining: f32 },
+}
+
+/// Energy and rest state of a single fish
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stamina {
+    activity: Activity,
+    energy: f32,
+    drain_per_sec: f32,
+    speed_factor: f32,
+}
+
+impl Stamina {
+    /// Fully rested, with a random drain rate so fish tire at different times
+    pub fn new(rng: &mut RngHandle) -> Self {
+        Self::with_drain(rng.gen_range(DRAIN_PER_SEC))
+    }
+
+    /// Fully rested with a fixed drain rate
+    pub fn with_drain(drain_per_sec: f32) -> Self {
+        Self {
+            activity: Activity::Swimming,
+            energy: 1.0,
+            drain_per_sec,
+            speed_factor: 1.0,
+        }
+    }
+
+    pub fn activity(&self) -> Activity {
+        self.activity
+    }
+
+    pub fn is_resting(&self) -> bool {
+        matches!(self.activity, Activity::Resting { .. })
+    }
+
+    /// Whether the fish would stop at seaweed right now
+    pub fn is_tired(&self) -> bool {
+        self.activity == Activity::Swimming && self.energy < TIRED_ENERGY
+    }
+
+    /// Multiplier to apply to the fish's normal speed
+    pub fn speed_factor(&self) -> f32 {
+        self.speed_factor
+    }
+
+    /// A tired fish near seaweed settles down for a rest
+    pub fn consider_rest(&mut self, near_shelter: bool, rng: &mut RngHandle) {
+        if near_shelter && self.is_tired() {
+            self.activity = Activity::Resting {
+                remaining: rng.gen_range(REST_SECS),
+            };
+        }
+    }
+
+    /// Advance the state machine and ease the speed towards its target
+    pub fn update(&mut self, delta_time: Duration) {
+        let dt = delta_time.as_secs_f32();
+
+        let target_speed = match &mut self.activity {
+            Activity::Swimming => {
+                self.energy = (self.energy - self.drain_per_sec * dt).max(0.0);
+                1.0
+            }
+            Activity::Resting { remaining } => {
+                *remaining -= dt;
+                if *remaining <= 0.0 {
+                    self.activity = Activity::Swimming;
+                    self.energy = 1.0;
+                }
+                RESTING_SPEED
+            }
+        };
+
+        let blend = (SPEED_EASING_PER_SEC * dt).min(1.0);
+        self.speed_factor += (target_speed - self.speed_factor) * blend;
+    }
+}
+
+/// Whether any shelter centre lies within [`REST_DISTANCE`] columns of `x`
+pub fn near_shelter(x: f32, shelters: &[f32]) -> bool {
+    shelters
+        .iter()
+        .any(|shelter| (shelter - x).abs() <= REST_DISTANCE)
+}
+
+/// Whether any threat centre is within [`SCARE_ROWS`] rows of row `y`
+pub fn is_threatened(y: f32, threats: &[f32]) -> bool {
+    threats
+        .iter()
+        .any(|threat| (threat - y).abs() <= SCARE_ROWS)
+}
+
+/// The closest food within [`FEED_DISTANCE`] of `centre`, if any
+pub fn nearest_food(centre: Position, food: &[Position]) -> Option<Position> {
+    food.iter()
+        .copied()
+        .filter(|flake| {
+            (flake.x - centre.x).abs() <= FEED_DISTANCE.0
+                && (flake.y - centre.y).abs() <= FEED_DISTANCE.1
+        })
+        .min_by(|a, b| {
+            let distance = |flake: &Position| (flake.x - centre.x).hypot(flake.y - centre.y);
+            distance(a).total_cmp(&distance(b))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tire(stamina: &mut Stamina) {
+        while !stamina.is_tired() {
+            stamina.update(Duration::from_secs(1));
+        }
+    }
+
+    #[test]
+    fn test_fresh_fish_does_not_rest() {
+        let mut stamina = Stamina::with_drain(0.02);
+        stamina.consider_rest(true, &mut RngHandle::default());
+        assert_eq!(stamina.activity(), Activity::Swimming);
+        assert_eq!(stamina.speed_factor(), 1.0);
+    }
+
+    #[test]
+    fn test_tired_fish_rests_only_near_shelter() {
+        let mut stamina = Stamina::with_drain(0.1);
+        tire(&mut stamina);
+
+        stamina.consider_rest(false, &mut RngHandle::default());
+        assert!(!stamina.is_resting());
+        stamina.consider_rest(true, &mut RngHandle::default());
+        assert!(stamina.is_resting());
+    }
+
+    #[test]
+    fn test_rest_slows_then_resumes() {
+        let mut stamina = Stamina::with_drain(0.1);
+        tire(&mut stamina);
+        stamina.consider_rest(true, &mut RngHandle::default());
+
+        // Eases down rather than stopping dead
+        stamina.update(Duration::from_millis(100));
+        assert!(stamina.speed_factor() < 1.0);
+        assert!(stamina.speed_factor() > RESTING_SPEED);
+
+        for _ in 0..60 {
+            stamina.update(Duration::from_millis(100));
+        }
+        assert_eq!(stamina.activity(), Activity::Swimming);
+        assert!(!stamina.is_tired());
+
+        for _ in 0..30 {
+            stamina.update(Duration::from_millis(100));
+        }
+        assert!(stamina.speed_factor() > 0.9);
+    }
+
+    #[test]
+    fn test_is_threatened() {
+        assert!(is_threatened(10.0, &[15.0]));
+        assert!(!is_threatened(10.0, &[17.0]));
+        assert!(!is_threatened(10.0, &[]));
+    }
+
+    #[test]
+    fn test_nearest_food() {
+        let fish = Position::new(10.0, 10.0, 5);
+        let flake = |x, y| Position::new(x, y, 3);
+        assert_eq!(
+            nearest_food(fish, &[flake(30.0, 10.0), flake(4.0, 12.0)]),
+            Some(flake(4.0, 12.0))
+        );
+        assert_eq!(nearest_food(fish, &[flake(40.0, 10.0)]), None);
+        assert_eq!(nearest_food(fish, &[flake(10.0, 19.0)]), None);
+    }
+
+    #[test]
+    fn test_near_shelter() {
+        assert!(near_shelter(10.0, &[30.0, 13.0]));
+        assert!(!near_shelter(10.0, &[30.0, 15.0]));
+        assert!(!near_shelter(10.0, &[]));
+    }
+}
@@ -0,0 +1,86 @@
+//! Character set selection for sprites
+//!
+//! The default set is the original's plain ASCII. `--charset unicode`
+//! swaps in Unicode glyphs where they read better: box drawing for the
+//! castle, braille for the seaweed and emoji for bubbles and the smallest
+//! fish. Each entity keeps its own substitution table next to its art, so
+//! colour masks still line up one mask character per art character.
+//!
+//! Like truecolor detection, the choice is global: it is made once at
+//! startup, before any sprites are built.
+
+use std::sync::OnceLock;
+
+/// Which glyphs sprites are drawn with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Charset {
+    /// Plain ASCII, as in the original
+    #[default]
+    Ascii,
+    /// Box drawing, braille and emoji
+    Unicode,
+}
+
+impl Charset {
+    /// Parse a `--charset` value
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "ascii" => Some(Self::Ascii),
+            "unicode" | "utf8" | "utf-8" => Some(Self::Unicode),
+            _ => None,
+        }
+    }
+}
+
+static CHARSET: OnceLock<Charset> = OnceLock::new();
+
+/// Choose the character set; only the first call has any effect
+pub fn set(charset: Charset) {
+    let _ = CHARSET.set(charset);
+}
+
+/// The character set chosen at startup (ASCII if none was chosen)
+pub fn current() -> Charset {
+    CHARSET.get().copied().unwrap_or_default()
+}
+
+/// `art` with each character in `glyphs` replaced by its Unicode
+/// counterpart when the current character set is Unicode
+pub fn substitute(art: &str, glyphs: &[(char, char)]) -> String {
+    substitute_for(current(), art, glyphs)
+}
+
+/// [`substitute`] for an explicit character set
+pub fn substitute_for(charset: Charset, art: &str, glyphs: &[(char, char)]) -> String {
+    match charset {
+        Charset::Ascii => art.to_string(),
+        Charset::Unicode => art
+            .chars()
+            .map(|ch| {
+                glyphs
+                    .iter()
+                    .find(|(ascii, _)| *ascii == ch)
+                    .map_or(ch, |(_, glyph)| *glyph)
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Charset::parse("unicode"), Some(Charset::Unicode));
+        assert_eq!(Charset::parse("ASCII"), Some(Charset::Ascii));
+        assert_eq!(Charset::parse("ebcdic"), None);
+    }
+
+    #[test]
+    fn test_substitute_keeps_ascii_art_by_default() {
+        let glyphs = [('(', '⢎'), (')', '⡱')];
+        assert_eq!(substitute_for(Charset::Ascii, "( )", &glyphs), "( )");
+        assert_eq!(substitute_for(Charset::Unicode, "( )", &glyphs), "⢎ ⡱");
+    }
+}
@@ -0,0 +1,296 @@
+//! Chat viewers spawning fish
+//!
+//! With `--chat CHANNEL` the app joins an IRC channel (Twitch chat by
+//! default) and every `!fish` message, or channel point redemption, adds a
+//! fish with the chatter's name over it in their chat colour. Viewers
+//! without a colour get one picked from their name.
+//!
+//! A stream's chat can be busy, so chat fish are rate limited: one every
+//! [`SPAWN_GAP`] at most, one per viewer per [`VIEWER_COOLDOWN`] and no
+//! more than [`MAX_CHAT_FISH`] in the tank at once. Requests over the
+//! limits are dropped rather than queued.
+//!
+//! Twitch lets anyone read chat without logging in; to use an account set
+//! `ASCIIQUARIUM_CHAT_NICK` and `ASCIIQUARIUM_CHAT_TOKEN` (an OAuth token).
+//! `--chat-server HOST:PORT` points at another plain-text IRC server.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::entity::EntityId;
+use crate::palette;
+
+/// Twitch's plain-text IRC endpoint
+pub const DEFAULT_SERVER: &str = "irc.chat.twitch.tv:6667";
+
+/// The chat command that asks for a fish
+pub const SPAWN_COMMAND: &str = "!fish";
+
+/// Most chat fish in the tank at once
+pub const MAX_CHAT_FISH: usize = 8;
+
+/// Shortest time between two chat fish
+pub const SPAWN_GAP: Duration = Duration::from_secs(2);
+
+/// Shortest time between two fish from the same viewer
+pub const VIEWER_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Longest name shown over a fish, in characters
+const MAX_LABEL: usize = 16;
+
+/// Wait before connecting again after the server hung up
+const RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+/// Where to chat and as whom
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatConfig {
+    pub server: String,
+    /// Channel name without the leading `#`
+    pub channel: String,
+    pub nick: Option<String>,
+    pub token: Option<String>,
+}
+
+impl ChatConfig {
+    /// Join `channel` on `server` (Twitch if `None`), logging in with the
+    /// account in the environment if there is one
+    pub fn new(channel: &str, server: Option<&str>) -> Self {
+        Self {
+            server: server.unwrap_or(DEFAULT_SERVER).to_string(),
+            channel: channel.trim_start_matches('#').to_ascii_lowercase(),
+            nick: std::env::var("ASCIIQUARIUM_CHAT_NICK").ok(),
+            token: std::env::var("ASCIIQUARIUM_CHAT_TOKEN").ok(),
+        }
+    }
+}
+
+/// A viewer asking for a fish
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FishRequest {
+    /// Name to show over the fish
+    pub name: String,
+    /// Chat colour, if the viewer has one
+    pub color: Option<(u8, u8, u8)>,
+}
+
+impl FishRequest {
+    /// The request in a raw IRC line, if it is a `!fish` message or a
+    /// channel point redemption
+    pub fn parse(line: &str) -> Option<Self> {
+        let (tags, rest) = match line.strip_prefix('@') {
+            Some(tagged) => tagged.split_once(' ')?,
+            None => ("", line),
+        };
+        let rest = rest.strip_prefix(':')?;
+        let (prefix, rest) = rest.split_once(' ')?;
+        let (params, text) = rest.strip_prefix("PRIVMSG ")?.split_once(" :")?;
+        if !params.starts_with('#') {
+            return None;
+        }
+
+        let tags: HashMap<&str, &str> = tags
+            .split(';')
+            .filter_map(|tag| tag.split_once('='))
+            .collect();
+        let redeemed = tags.contains_key("custom-reward-id");
+        if !redeemed && text.split_whitespace().next() != Some(SPAWN_COMMAND) {
+            return None;
+        }
+
+        let nick = prefix.split('!').next().unwrap_or(prefix);
+        let name = tags
+            .get("display-name")
+            .filter(|name| !name.is_empty())
+            .copied()
+            .unwrap_or(nick);
+        Some(Self {
+            name: name
+                .chars()
+                .filter(|ch| !ch.is_control())
+                .take(MAX_LABEL)
+                .collect(),
+            color: tags.get("color").and_then(|color| parse_hex(color)),
+        })
+    }
+
+    /// The viewer's chat colour, or one that always comes out the same for
+    /// their name
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        self.color.unwrap_or_else(|| {
+            let hash = self
+                .name
+                .to_lowercase()
+                .bytes()
+                .fold(2166136261u32, |hash, byte| {
+                    (hash ^ byte as u32).wrapping_mul(16777619)
+                });
+            let color = palette::hsl_to_rgb((hash % 360) as f32, 0.7, 0.6);
+            palette::to_rgb(color).unwrap_or((255, 255, 255))
+        })
+    }
+}
+
+/// `#RRGGBB` as RGB
+fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    let channel = |at: usize| u8::from_str_radix(hex.get(at..at + 2)?, 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Chat requests coming in from a background connection, and the limits
+/// applied to them
+pub struct ChatFeed {
+    requests: mpsc::Receiver<FishRequest>,
+    last_spawn: Option<Instant>,
+    viewers: HashMap<String, Instant>,
+    /// Chat fish that may still be in the tank
+    fish: Vec<EntityId>,
+}
+
+impl ChatFeed {
+    /// Feed from requests sent to `requests` (see [`ChatFeed::connect`])
+    pub fn new(requests: mpsc::Receiver<FishRequest>) -> Self {
+        Self {
+            requests,
+            last_spawn: None,
+            viewers: HashMap::new(),
+            fish: Vec::new(),
+        }
+    }
+
+    /// Join the chat in `config` on a background thread, reconnecting when
+    /// the connection drops, for as long as the feed lives
+    pub fn connect(config: ChatConfig) -> Self {
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || loop {
+            if let Ok(false) = read_chat(&config, &sender) {
+                return;
+            }
+            thread::sleep(RECONNECT_DELAY);
+        });
+        Self::new(requests)
+    }
+
+    /// Requests that pass the limits at `now`; `alive` tells which earlier
+    /// chat fish are still in the tank
+    pub fn poll(&mut self, now: Instant, alive: impl Fn(EntityId) -> bool) -> Vec<FishRequest> {
+        self.fish.retain(|&id| alive(id));
+        let mut allowed = Vec::new();
+        while let Ok(request) = self.requests.try_recv() {
+            let key = request.name.to_lowercase();
+            let ready = |since: Option<&Instant>, wait| {
+                since.is_none_or(|&since| now.duration_since(since) >= wait)
+            };
+            if self.fish.len() + allowed.len() < MAX_CHAT_FISH
+                && ready(self.last_spawn.as_ref(), SPAWN_GAP)
+                && ready(self.viewers.get(&key), VIEWER_COOLDOWN)
+            {
+                self.last_spawn = Some(now);
+                self.viewers.insert(key, now);
+                allowed.push(request);
+            }
+        }
+        allowed
+    }
+
+    /// Count `id` against the cap on chat fish
+    pub fn spawned(&mut self, id: EntityId) {
+        self.fish.push(id);
+    }
+}
+
+/// Read the chat in `config` until the connection drops, sending fish
+/// requests to `requests`; `Ok(false)` once nobody is listening any more
+fn read_chat(config: &ChatConfig, requests: &mpsc::Sender<FishRequest>) -> io::Result<bool> {
+    let stream = TcpStream::connect(&config.server)?;
+    let mut writer = stream.try_clone()?;
+    // Twitch takes any nick starting with "justinfan" as a reader
+    let nick = config.nick.as_deref().unwrap_or("justinfan31337");
+    if let Some(token) = &config.token {
+        write!(
+            writer,
+            "PASS oauth:{}\r\n",
+            token.trim_start_matches("oauth:")
+        )?;
+    }
+    write!(
+        writer,
+        "NICK {nick}\r\nCAP REQ :twitch.tv/tags\r\nJOIN #{}\r\n",
+        config.channel
+    )?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+        if let Some(token) = line.strip_prefix("PING ") {
+            write!(writer, "PONG {token}\r\n")?;
+        } else if let Some(request) = FishRequest::parse(line) {
+            if requests.send(request).is_err() {
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_requests() {
+        let line = "@color=#1E90FF;display-name=Alice;mod=0 \
+                    :alice!alice@alice.tmi.twitch.tv PRIVMSG #stream :!fish please";
+        assert_eq!(
+            FishRequest::parse(line),
+            Some(FishRequest {
+                name: "Alice".to_string(),
+                color: Some((0x1e, 0x90, 0xff)),
+            })
+        );
+
+        let redeemed = "@color=;custom-reward-id=abc :bob!bob@host PRIVMSG #stream :hi";
+        let request = FishRequest::parse(redeemed).unwrap();
+        assert_eq!((request.name.as_str(), request.color), ("bob", None));
+        assert_eq!(request.rgb(), FishRequest::parse(redeemed).unwrap().rgb());
+
+        assert_eq!(
+            FishRequest::parse(":carol!c@host PRIVMSG #stream :fish?"),
+            None
+        );
+        assert_eq!(FishRequest::parse("PING :tmi.twitch.tv"), None);
+    }
+
+    #[test]
+    fn test_poll_applies_limits() {
+        let (sender, requests) = mpsc::channel();
+        let mut feed = ChatFeed::new(requests);
+        let request = |name: &str| FishRequest {
+            name: name.to_string(),
+            color: None,
+        };
+        let start = Instant::now();
+
+        sender.send(request("alice")).unwrap();
+        sender.send(request("bob")).unwrap();
+        assert_eq!(feed.poll(start, |_| true), vec![request("alice")]);
+        feed.spawned(1);
+
+        // Alice is cooling down, bob is let through after the gap
+        let later = start + SPAWN_GAP;
+        sender.send(request("Alice")).unwrap();
+        sender.send(request("bob")).unwrap();
+        assert_eq!(feed.poll(later, |_| true), vec![request("bob")]);
+
+        // The cap counts only fish still in the tank
+        feed.fish = (0..MAX_CHAT_FISH as EntityId).collect();
+        sender.send(request("carol")).unwrap();
+        assert!(feed.poll(later + SPAWN_GAP, |_| true).is_empty());
+        sender.send(request("carol")).unwrap();
+        assert_eq!(feed.poll(later + SPAWN_GAP, |id| id > 0).len(), 1);
+    }
+}
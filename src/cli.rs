@@ -0,0 +1,798 @@
+//! Command line option parsing
+//!
+//! Mirrors the tiny flag set of the original Perl script (`-c` for classic
+//! mode) and adds switches for the optional Rust-only features.
+
+use crate::backend::Backend;
+use crate::backgrounds::Background;
+use crate::charset::Charset;
+use crate::entities::PomodoroTimes;
+use crate::event_log;
+use crate::palette::Palette;
+use crate::render::WaterGradient;
+use crate::scheduler::RespawnPolicy;
+use crate::sound;
+use crate::status::{self, StatusBar, StatusPosition};
+use std::path::PathBuf;
+
+/// Simulation steps before a snapshot when `--ticks` isn't given: two
+/// seconds, enough for fish to swim in and bubbles to rise
+pub const DEFAULT_SNAPSHOT_TICKS: u64 = 2 * crate::timestep::TICK_RATE as u64;
+
+/// What `--help` prints
+pub const HELP: &str = "\
+Usage: asciiquarium-rs [COMMAND] [OPTIONS]
+
+Commands:
+  snapshot                 Print one frame with ANSI colours and exit
+  dump                     Print one frame as plain text and exit
+  serve --ssh ADDR         Give everyone who connects over SSH a tank (ssh feature)
+  screensaver-config       Print screensaver configuration and exit
+
+Tank:
+  -c, --classic            Only the original fish and monsters
+  -e, --ecology            Fish breed, and juveniles grow up
+      --seed N             Seed the random numbers, to repeat a run exactly
+      --speed X            Simulation speed as a multiple of real time (0-4)
+      --shark-aggression X How eagerly sharks chase fish (0-1, default 0.5)
+      --ambient N          Ambient events per minute (default 1, 0 for none)
+      --weight NAME=W,...  Spawn weights for registered creatures
+      --respawn KIND=P,... Respawn policy per kind: none, same, random_large,
+                           splat or splat_respawn
+      --background NAME    castle, reef, shipwreck, atlantis or none
+      --world WxH          Virtual tank larger than the terminal, panned around
+      --sprites DIR        Load creatures from sprite files in DIR
+      --scripts DIR        Run the Rhai scripts in DIR (scripting feature)
+      --still-water        Keep the water surface still, as in the original
+      --no-idle            Keep ticking at full rate while nothing changes
+
+Look:
+      --charset NAME       ascii or unicode
+      --palette NAME       standard, deuteranopia, protanopia or tritanopia
+      --gradient[=RRGGBB,RRGGBB]
+                           Shade the water from surface to floor
+      --depth-dim          Draw creatures further back dimmer
+      --half-block         Draw the water gradient with half blocks
+      --reflections        Mirror what swims under the waterline
+      --night              Start in night mode
+      --title              Show the title splash first
+      --clock              Show a large clock in the sky
+      --pomodoro WORK[/BREAK]
+                           Pomodoro timer, in minutes
+      --strip              A one- to three-row strip instead of the full tank
+      --status-format TEXT Status line template with {name} placeholders
+      --status-fg COLOR    Status line text colour
+      --status-bg COLOR    Status line background colour
+      --status-position P  top or bottom
+
+Output:
+      --backend NAME       auto, crossterm, termion (termion feature), stream
+                           or dumb
+  -s, --stream             Same as --backend stream
+      --screensaver        Any key quits, as screensaver frameworks expect
+      --ticks N            Steps before a snapshot or dump (default 120)
+      --size WxH           Tank size for snapshots, dumps and recordings
+      --colors FILE        Where dump writes the colour mask
+      --export-gif FILE    Record the tank to a GIF instead of showing it
+      --export-seconds S   Length of the recording (default 5)
+
+Outside world:
+      --sysmon             Host CPU, network and load drive the tank
+      --events-json PATH|FD
+                           Write aquarium events as JSON lines
+      --control PATH       Take commands on a Unix socket
+      --metrics ADDR       Serve Prometheus metrics on host:port or a port
+      --chat CHANNEL       Let a Twitch channel's viewers spawn fish
+      --chat-server HOST:PORT
+                           IRC server for --chat, if not Twitch
+      --sound bell|COMMAND Play sound effects
+      --sound-events LIST  Which effects: all, shark, spout, bubble
+      --stress             Chaos mode for reproducing crashes and slowdowns
+
+  -h, --help               Print this help
+";
+
+/// Options selected on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    /// Classic mode: only old fish and monsters (original `-c` flag)
+    pub classic_mode: bool,
+    /// Ecology mode: fish breed and juveniles grow up
+    pub ecology_mode: bool,
+    /// How frames reach the screen (`-s` picks plain stream output for
+    /// dumb terminals, also used automatically when stdout is not a TTY)
+    pub backend: Backend,
+    /// How eagerly sharks chase fish (0.0 = never, 1.0 = relentless)
+    pub shark_aggressiveness: f32,
+    /// Hidden chaos-monkey mode for reproducing crashes and slowdowns
+    pub stress_mode: bool,
+    /// Average ambient events per minute (0.0 disables them)
+    pub ambient_frequency: f32,
+    /// Simulation speed as a multiple of real time (0.0-4.0)
+    pub time_scale: f32,
+    /// Draw entities further back in dimmer colours
+    pub depth_dimming: bool,
+    /// Background gradient for the water, if any
+    pub water_gradient: Option<WaterGradient>,
+    /// Start in night mode
+    pub night_mode: bool,
+    /// Glyphs used for sprites
+    pub charset: Charset,
+    /// Colours used for sprite masks
+    pub palette: Palette,
+    /// Scene in the bottom-right corner of the tank
+    pub background: Background,
+    /// Draw the water gradient and particles with half blocks
+    pub half_block: bool,
+    /// Keep the water surface still, as in the original
+    pub still_water: bool,
+    /// Show the title splash before the tank fills
+    pub title: bool,
+    /// Draw a one- to three-row strip instead of the full tank
+    pub strip: bool,
+    /// Mirror what swims under the waterline onto the surface
+    pub reflections: bool,
+    /// Status line template, colours and row
+    pub status_bar: StatusBar,
+    /// Directory of sprite files to load creatures from (the default
+    /// config directory when `None`)
+    pub sprite_dir: Option<PathBuf>,
+    /// Directory of Rhai scripts to run (the default config directory when
+    /// `None`; needs the scripting feature)
+    pub script_dir: Option<PathBuf>,
+    /// Spawn weights for registered creatures by name, in the order given
+    pub creature_weights: Vec<(String, f32)>,
+    /// Respawn policies by entity kind name, in place of the kinds' own
+    pub respawn_policies: Vec<(String, RespawnPolicy)>,
+    /// Seed for the random number generator, to repeat a run exactly
+    pub seed: Option<u64>,
+    /// Tick slowly while nothing in the tank changes
+    pub idle: bool,
+    /// Print a single frame with ANSI colours and exit (`snapshot`)
+    pub snapshot: bool,
+    /// Behave as screensaver frameworks expect (any key quits)
+    pub screensaver: bool,
+    /// Print screensaver configuration and exit (`screensaver-config`)
+    pub screensaver_config: bool,
+    /// Write a single frame as plain text and exit (`dump`)
+    pub dump: bool,
+    /// Print the usage and exit (`--help`)
+    pub help: bool,
+    /// Serve tanks to remote clients instead of showing one (`serve`)
+    pub serve: bool,
+    /// Where `serve` listens for SSH clients (`--ssh :2222`)
+    pub ssh_address: Option<String>,
+    /// Where `dump` writes the frame's colour mask
+    pub dump_colors: Option<PathBuf>,
+    /// Simulation steps to run before the snapshot or dump is taken
+    pub snapshot_ticks: u64,
+    /// Tank size for snapshots, dumps and recordings instead of the
+    /// terminal's
+    pub size: Option<(u16, u16)>,
+    /// Virtual tank size, larger than the terminal and panned with the
+    /// arrow keys, hjkl or by dragging with the mouse
+    pub world_size: Option<(u16, u16)>,
+    /// Where to write aquarium events as JSON lines
+    pub events_json: Option<event_log::Target>,
+    /// Unix socket to take commands from while running
+    pub control_socket: Option<PathBuf>,
+    /// Address to serve Prometheus metrics on (`host:port` or a port)
+    pub metrics_address: Option<String>,
+    /// Chat channel whose viewers can spawn fish
+    pub chat_channel: Option<String>,
+    /// IRC server for the chat channel, if not Twitch
+    pub chat_server: Option<String>,
+    /// Show a large clock in the sky
+    pub clock: bool,
+    /// Run a pomodoro timer with these intervals
+    pub pomodoro: Option<PomodoroTimes>,
+    /// Let host CPU, network and load drive the tank
+    pub sysmon: bool,
+    /// How to play sound effects, if at all
+    pub sound: Option<sound::Player>,
+    /// Which sound effects are played
+    pub sound_events: Vec<sound::Sound>,
+    /// Record the tank to this GIF instead of showing it
+    pub export_gif: Option<PathBuf>,
+    /// Length of the recording in seconds
+    pub export_seconds: f32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            classic_mode: false,
+            ecology_mode: false,
+            backend: Backend::Auto,
+            shark_aggressiveness: crate::entities::shark::DEFAULT_AGGRESSIVENESS,
+            stress_mode: false,
+            ambient_frequency: crate::ambient::DEFAULT_EVENTS_PER_MINUTE,
+            time_scale: 1.0,
+            depth_dimming: false,
+            water_gradient: None,
+            night_mode: false,
+            charset: Charset::Ascii,
+            palette: Palette::Standard,
+            background: Background::Castle,
+            half_block: false,
+            still_water: false,
+            title: false,
+            strip: false,
+            reflections: false,
+            status_bar: StatusBar::default(),
+            sprite_dir: None,
+            script_dir: None,
+            creature_weights: Vec::new(),
+            respawn_policies: Vec::new(),
+            seed: None,
+            idle: true,
+            snapshot: false,
+            screensaver: false,
+            screensaver_config: false,
+            dump: false,
+            help: false,
+            serve: false,
+            ssh_address: None,
+            dump_colors: None,
+            snapshot_ticks: DEFAULT_SNAPSHOT_TICKS,
+            size: None,
+            world_size: None,
+            events_json: None,
+            control_socket: None,
+            metrics_address: None,
+            chat_channel: None,
+            chat_server: None,
+            clock: false,
+            pomodoro: None,
+            sysmon: false,
+            sound: None,
+            sound_events: sound::Sound::DEFAULT.to_vec(),
+            export_gif: None,
+            export_seconds: crate::gif::DEFAULT_SECONDS,
+        }
+    }
+}
+
+impl Options {
+    /// Parse options from an argument list (without the program name)
+    ///
+    /// Unknown arguments, and a value that names none of a flag's choices,
+    /// are errors.
+    pub fn parse<I, S>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut options = Self::default();
+        let mut args = args.into_iter().map(|arg| arg.as_ref().to_string());
+
+        while let Some(arg) = args.next() {
+            // Accept both `--flag value` and `--flag=value`
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = || inline_value.clone().or_else(|| args.next());
+
+            match flag.as_str() {
+                "-h" | "--help" => options.help = true,
+                "-c" | "--classic" => options.classic_mode = true,
+                "-e" | "--ecology" => options.ecology_mode = true,
+                "-s" | "--stream" => options.backend = Backend::Stream,
+                "--stress" => options.stress_mode = true,
+                "--depth-dim" => options.depth_dimming = true,
+                "--night" => options.night_mode = true,
+                "--half-block" => options.half_block = true,
+                "--still-water" => options.still_water = true,
+                "--title" => options.title = true,
+                "--strip" => options.strip = true,
+                "--sysmon" => options.sysmon = true,
+                "--clock" => options.clock = true,
+                "--reflections" => options.reflections = true,
+                "--no-idle" => options.idle = false,
+                "snapshot" => options.snapshot = true,
+                "dump" => options.dump = true,
+                "serve" => options.serve = true,
+                "--ssh" => options.ssh_address = value(),
+                "--colors" => {
+                    if let Some(path) = value() {
+                        options.dump_colors = Some(PathBuf::from(path));
+                    }
+                }
+                "--size" => {
+                    if let Some(size) = value().and_then(|v| parse_size(&v)) {
+                        options.size = Some(size);
+                    }
+                }
+                "--world" => {
+                    if let Some(size) = value().and_then(|v| parse_size(&v)) {
+                        options.world_size = Some(size);
+                    }
+                }
+                "--screensaver" => options.screensaver = true,
+                "screensaver-config" => options.screensaver_config = true,
+                "--backend" => {
+                    let name = value().unwrap_or_default();
+                    options.backend = Backend::parse(&name).ok_or_else(|| {
+                        format!(
+                            "no backend called {name:?}; choose one of {}",
+                            Backend::NAMES.join(", ")
+                        )
+                    })?;
+                }
+                "--charset" => {
+                    if let Some(charset) = value().and_then(|v| Charset::parse(&v)) {
+                        options.charset = charset;
+                    }
+                }
+                "--background" => {
+                    if let Some(background) = value().and_then(|v| Background::parse(&v)) {
+                        options.background = background;
+                    }
+                }
+                "--status-format" => {
+                    if let Some(format) = value() {
+                        options.status_bar.format = format;
+                    }
+                }
+                "--status-fg" => {
+                    if let Some(color) = value().and_then(|v| status::parse_color(&v)) {
+                        options.status_bar.fg = color;
+                    }
+                }
+                "--status-bg" => {
+                    if let Some(color) = value().and_then(|v| status::parse_color(&v)) {
+                        options.status_bar.bg = color;
+                    }
+                }
+                "--status-position" => {
+                    if let Some(position) = value().and_then(|v| StatusPosition::parse(&v)) {
+                        options.status_bar.position = position;
+                    }
+                }
+                "--sprites" => {
+                    if let Some(dir) = value() {
+                        options.sprite_dir = Some(PathBuf::from(dir));
+                    }
+                }
+                "--scripts" => {
+                    if let Some(dir) = value() {
+                        options.script_dir = Some(PathBuf::from(dir));
+                    }
+                }
+                "--palette" => {
+                    if let Some(palette) = value().and_then(|v| Palette::parse(&v)) {
+                        options.palette = palette;
+                    }
+                }
+                // Bare `--gradient` uses the default colours; a value must be
+                // given inline so the next flag isn't taken as colours
+                "--gradient" => {
+                    options.water_gradient = match &inline_value {
+                        Some(spec) => WaterGradient::parse(spec).or(options.water_gradient),
+                        None => Some(WaterGradient::default()),
+                    }
+                }
+                "--shark-aggression" => {
+                    if let Some(level) = value().and_then(|v| v.parse::<f32>().ok()) {
+                        options.shark_aggressiveness = level.clamp(0.0, 1.0);
+                    }
+                }
+                "--ambient" => {
+                    if let Some(rate) = value().and_then(|v| v.parse::<f32>().ok()) {
+                        options.ambient_frequency = rate.max(0.0);
+                    }
+                }
+                "--speed" => {
+                    if let Some(scale) = value().and_then(|v| v.parse::<f32>().ok()) {
+                        options.time_scale = scale.clamp(0.0, crate::timestep::MAX_TIME_SCALE);
+                    }
+                }
+                // `name=weight`, comma separated or repeated; later ones win
+                "--weight" => {
+                    for pair in value().iter().flat_map(|v| v.split(',')) {
+                        let weight = pair.split_once('=').and_then(|(name, weight)| {
+                            Some((name.trim().to_string(), weight.trim().parse::<f32>().ok()?))
+                        });
+                        if let Some((name, weight)) = weight {
+                            options.creature_weights.push((name, weight.max(0.0)));
+                        }
+                    }
+                }
+                // `kind=policy`, comma separated or repeated; later ones win
+                "--respawn" => {
+                    for pair in value().iter().flat_map(|v| v.split(',')) {
+                        let policy = pair.split_once('=').and_then(|(kind, policy)| {
+                            Some((
+                                kind.trim().to_string(),
+                                RespawnPolicy::parse(policy.trim())?,
+                            ))
+                        });
+                        options.respawn_policies.extend(policy);
+                    }
+                }
+                "--ticks" => {
+                    if let Some(ticks) = value().and_then(|v| v.parse::<u64>().ok()) {
+                        options.snapshot_ticks = ticks;
+                    }
+                }
+                "--events-json" => {
+                    if let Some(target) = value() {
+                        options.events_json = Some(event_log::Target::parse(&target));
+                    }
+                }
+                "--control" => {
+                    if let Some(path) = value() {
+                        options.control_socket = Some(PathBuf::from(path));
+                    }
+                }
+                "--metrics" => options.metrics_address = value(),
+                // `WORK[/BREAK]` in minutes
+                "--pomodoro" => {
+                    options.pomodoro = value().and_then(|v| PomodoroTimes::parse(&v));
+                }
+                "--chat" => options.chat_channel = value(),
+                "--chat-server" => options.chat_server = value(),
+                "--sound" => options.sound = value().map(|v| sound::Player::parse(&v)),
+                "--sound-events" => {
+                    if let Some(list) = value() {
+                        options.sound_events = sound::Sound::parse_list(&list);
+                    }
+                }
+                "--export-gif" => {
+                    if let Some(path) = value() {
+                        options.export_gif = Some(PathBuf::from(path));
+                    }
+                }
+                "--export-seconds" => {
+                    if let Some(seconds) = value().and_then(|v| v.parse::<f32>().ok()) {
+                        if seconds.is_finite() && seconds > 0.0 {
+                            options.export_seconds = seconds;
+                        }
+                    }
+                }
+                "--seed" => {
+                    if let Some(seed) = value().and_then(|v| v.parse::<u64>().ok()) {
+                        options.seed = Some(seed);
+                    }
+                }
+                _ => return Err(format!("unknown option {flag}; see --help")),
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Parse options from the process arguments
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(std::env::args().skip(1))
+    }
+}
+
+/// Parse a `--size` value such as `80x24`
+fn parse_size(value: &str) -> Option<(u16, u16)> {
+    let (width, height) = value.split_once('x')?;
+    let size = (width.parse().ok()?, height.parse().ok()?);
+    (size.0 > 0 && size.1 > 0).then_some(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    #[test]
+    fn test_default_options() {
+        let options = Options::parse(Vec::<String>::new()).unwrap();
+        assert!(!options.classic_mode);
+        assert!(!options.ecology_mode);
+        assert!(!options.depth_dimming);
+    }
+
+    #[test]
+    fn test_parse_flags() {
+        let options = Options::parse([
+            "-c",
+            "--ecology",
+            "-s",
+            "--stress",
+            "--depth-dim",
+            "--night",
+            "--half-block",
+            "--still-water",
+            "--title",
+            "--strip",
+            "--sysmon",
+            "--clock",
+            "--reflections",
+            "--no-idle",
+        ])
+        .unwrap();
+        assert!(options.classic_mode);
+        assert!(options.depth_dimming);
+        assert!(options.night_mode);
+        assert!(options.half_block);
+        assert!(options.still_water);
+        assert!(options.title);
+        assert!(options.strip);
+        assert!(options.sysmon);
+        assert!(options.clock);
+        assert!(options.reflections);
+        assert!(!options.idle);
+        assert!(options.ecology_mode);
+        assert_eq!(options.backend, Backend::Stream);
+        assert!(options.stress_mode);
+    }
+
+    #[test]
+    fn test_parse_backend() {
+        assert_eq!(
+            Options::parse(["--backend", "dumb"]).unwrap().backend,
+            Backend::Dumb
+        );
+        let error = Options::parse(["--backend=vt52"]).unwrap_err();
+        assert!(error.contains("vt52") && error.contains("crossterm, termion"));
+    }
+
+    #[test]
+    fn test_parse_shark_aggression() {
+        let options = Options::parse(["--shark-aggression", "0.9"]).unwrap();
+        assert_eq!(options.shark_aggressiveness, 0.9);
+
+        let options = Options::parse(["--shark-aggression=0"]).unwrap();
+        assert_eq!(options.shark_aggressiveness, 0.0);
+
+        // Out of range values are clamped, garbage is ignored
+        let options = Options::parse(["--shark-aggression=7"]).unwrap();
+        assert_eq!(options.shark_aggressiveness, 1.0);
+        let options = Options::parse(["--shark-aggression", "lots"]).unwrap();
+        assert_eq!(
+            options.shark_aggressiveness,
+            crate::entities::shark::DEFAULT_AGGRESSIVENESS
+        );
+    }
+
+    #[test]
+    fn test_parse_ambient_frequency() {
+        let options = Options::parse(["--ambient", "3"]).unwrap();
+        assert_eq!(options.ambient_frequency, 3.0);
+
+        let options = Options::parse(["--ambient=0"]).unwrap();
+        assert_eq!(options.ambient_frequency, 0.0);
+
+        let options = Options::parse(["--ambient=-2"]).unwrap();
+        assert_eq!(options.ambient_frequency, 0.0);
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let options =
+            Options::parse(["--weight", "shark=0,whale=3", "--weight=ship=x,eel=-1"]).unwrap();
+        assert_eq!(
+            options.creature_weights,
+            vec![
+                ("shark".to_string(), 0.0),
+                ("whale".to_string(), 3.0),
+                ("eel".to_string(), 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_respawn_policies() {
+        let options = Options::parse([
+            "--respawn",
+            "shark=splat_respawn,whale=none",
+            "--respawn=fish=sometimes,ship=same",
+        ])
+        .unwrap();
+        assert_eq!(
+            options.respawn_policies,
+            vec![
+                (
+                    "shark".to_string(),
+                    RespawnPolicy::SpawnSplat { respawn: true }
+                ),
+                ("whale".to_string(), RespawnPolicy::None),
+                ("ship".to_string(), RespawnPolicy::RespawnSame),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_seed() {
+        assert_eq!(Options::parse(["--seed", "42"]).unwrap().seed, Some(42));
+        assert_eq!(Options::parse(["--seed=-1"]).unwrap().seed, None);
+    }
+
+    #[test]
+    fn test_parse_snapshot() {
+        let options = Options::parse(["snapshot", "--ticks", "600"]).unwrap();
+        assert!(options.snapshot);
+        assert_eq!(options.snapshot_ticks, 600);
+        assert!(!Options::parse(["--ticks=1"]).unwrap().snapshot);
+    }
+
+    #[test]
+    fn test_parse_serve() {
+        let options = Options::parse(["serve", "--ssh", ":2222", "-c"]).unwrap();
+        assert!(options.serve && options.classic_mode);
+        assert_eq!(options.ssh_address.as_deref(), Some(":2222"));
+        assert!(!Options::parse(["-e"]).unwrap().serve);
+    }
+
+    #[test]
+    fn test_parse_dump() {
+        let options = Options::parse(["dump", "--size", "100x30", "--colors=tank.colors"]).unwrap();
+        assert!(options.dump && !options.snapshot);
+        assert_eq!(options.size, Some((100, 30)));
+        assert_eq!(options.dump_colors, Some(PathBuf::from("tank.colors")));
+        assert_eq!(Options::parse(["--size=0x30"]).unwrap().size, None);
+        assert_eq!(Options::parse(["--size=big"]).unwrap().size, None);
+    }
+
+    #[test]
+    fn test_parse_screensaver() {
+        let options = Options::parse(["--screensaver"]).unwrap();
+        assert!(options.screensaver && !options.screensaver_config);
+        assert!(
+            Options::parse(["screensaver-config"])
+                .unwrap()
+                .screensaver_config
+        );
+    }
+
+    #[test]
+    fn test_parse_events_json() {
+        let options = Options::parse(["--events-json=3"]).unwrap();
+        assert_eq!(options.events_json, Some(event_log::Target::Fd(3)));
+    }
+
+    #[test]
+    fn test_parse_metrics_address() {
+        let options = Options::parse(["--metrics", "9100"]).unwrap();
+        assert_eq!(options.metrics_address.as_deref(), Some("9100"));
+        assert_eq!(Options::parse(["-e"]).unwrap().metrics_address, None);
+    }
+
+    #[test]
+    fn test_parse_chat() {
+        let options =
+            Options::parse(["--chat", "mychannel", "--chat-server=irc.example:6667"]).unwrap();
+        assert_eq!(options.chat_channel.as_deref(), Some("mychannel"));
+        assert_eq!(options.chat_server.as_deref(), Some("irc.example:6667"));
+    }
+
+    #[test]
+    fn test_parse_world_size() {
+        assert_eq!(
+            Options::parse(["--world", "240x60"]).unwrap().world_size,
+            Some((240, 60))
+        );
+        assert_eq!(Options::parse(["--world=wide"]).unwrap().world_size, None);
+    }
+
+    #[test]
+    fn test_parse_pomodoro() {
+        let options = Options::parse(["--pomodoro", "50/10"]).unwrap();
+        assert_eq!(options.pomodoro, PomodoroTimes::parse("50/10"));
+        assert_eq!(Options::parse(["--pomodoro=lunch"]).unwrap().pomodoro, None);
+    }
+
+    #[test]
+    fn test_parse_sound() {
+        let options = Options::parse(["--sound", "bell", "--sound-events=bubble"]).unwrap();
+        assert_eq!(options.sound, Some(sound::Player::Bell));
+        assert_eq!(options.sound_events, vec![sound::Sound::BubblePop]);
+        assert_eq!(
+            Options::parse(["-c"]).unwrap().sound_events,
+            sound::Sound::DEFAULT
+        );
+    }
+
+    #[test]
+    fn test_parse_control_socket() {
+        let options = Options::parse(["--control", "/tmp/aquarium.sock"]).unwrap();
+        assert_eq!(
+            options.control_socket,
+            Some(PathBuf::from("/tmp/aquarium.sock"))
+        );
+    }
+
+    #[test]
+    fn test_parse_export() {
+        let options = Options::parse(["--export-gif", "tank.gif", "--export-seconds=2.5"]).unwrap();
+        assert_eq!(options.export_gif, Some(PathBuf::from("tank.gif")));
+        assert_eq!(options.export_seconds, 2.5);
+        let options = Options::parse(["--export-seconds", "-1"]).unwrap();
+        assert_eq!(options.export_seconds, crate::gif::DEFAULT_SECONDS);
+    }
+
+    #[test]
+    fn test_parse_speed() {
+        let options = Options::parse(["--speed", "0.5"]).unwrap();
+        assert_eq!(options.time_scale, 0.5);
+
+        // Out of range values are clamped, garbage is ignored
+        let options = Options::parse(["--speed=9"]).unwrap();
+        assert_eq!(options.time_scale, crate::timestep::MAX_TIME_SCALE);
+        let options = Options::parse(["--speed", "warp"]).unwrap();
+        assert_eq!(options.time_scale, 1.0);
+    }
+
+    #[test]
+    fn test_parse_charset() {
+        let options = Options::parse(["--charset", "unicode"]).unwrap();
+        assert_eq!(options.charset, Charset::Unicode);
+
+        let options = Options::parse(["--charset=klingon"]).unwrap();
+        assert_eq!(options.charset, Charset::Ascii);
+    }
+
+    #[test]
+    fn test_parse_palette() {
+        let options = Options::parse(["--palette", "protanopia"]).unwrap();
+        assert_eq!(options.palette, Palette::Protanopia);
+
+        let options = Options::parse(["--palette=mauve"]).unwrap();
+        assert_eq!(options.palette, Palette::Standard);
+    }
+
+    #[test]
+    fn test_parse_background() {
+        let options = Options::parse(["--background", "shipwreck"]).unwrap();
+        assert_eq!(options.background, Background::Shipwreck);
+
+        let options = Options::parse(["--background=none"]).unwrap();
+        assert_eq!(options.background, Background::None);
+
+        let options = Options::parse(["--background=moon"]).unwrap();
+        assert_eq!(options.background, Background::Castle);
+    }
+
+    #[test]
+    fn test_parse_status_bar() {
+        let options = Options::parse([
+            "--status-format={fish} fish | {fps} fps",
+            "--status-fg",
+            "yellow",
+            "--status-bg=#102030",
+            "--status-position=top",
+        ])
+        .unwrap();
+        assert_eq!(options.status_bar.format, "{fish} fish | {fps} fps");
+        assert_eq!(options.status_bar.fg, Color::Yellow);
+        assert_eq!(options.status_bar.bg, Color::Rgb(0x10, 0x20, 0x30));
+        assert_eq!(options.status_bar.position, StatusPosition::Top);
+
+        let options = Options::parse(["--status-fg=glitter"]).unwrap();
+        assert_eq!(options.status_bar, StatusBar::default());
+    }
+
+    #[test]
+    fn test_parse_sprite_dir() {
+        let options = Options::parse(["--sprites", "/tmp/creatures"]).unwrap();
+        assert_eq!(options.sprite_dir, Some(PathBuf::from("/tmp/creatures")));
+        assert_eq!(Options::parse(["-c"]).unwrap().sprite_dir, None);
+
+        let options = Options::parse(["--scripts=/tmp/scripts"]).unwrap();
+        assert_eq!(options.script_dir, Some(PathBuf::from("/tmp/scripts")));
+    }
+
+    #[test]
+    fn test_unknown_flags_rejected() {
+        let error = Options::parse(["-e", "--bogus"]).unwrap_err();
+        assert!(error.contains("--bogus") && error.contains("--help"));
+        // `snapshot` is a command, not a flag
+        assert!(Options::parse(["--snapshot"]).is_err());
+        assert!(Options::parse(["fishies"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_help() {
+        assert!(Options::parse(["-h"]).unwrap().help);
+        assert!(Options::parse(["-c", "--help"]).unwrap().help);
+        assert!(!Options::parse(["-c"]).unwrap().help);
+        assert!(HELP.contains("--shark-aggression") && HELP.contains("serve --ssh"));
+    }
+}
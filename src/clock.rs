@@ -0,0 +1,177 @@
+//! Monotonic time that also works in the browser
+//!
+//! `std::time::Instant` panics on `wasm32-unknown-unknown`, where the
+//! standard library has no clock. Everything in the simulation core that
+//! reads the time uses [`Instant`] from here instead: on native targets it
+//! is the standard one, and on wasm32 a stand-in that counts milliseconds
+//! from `aquarium_now`, a function the page imports into the module (the
+//! web frontend passes `performance.now()`).
+//!
+//! [`local_time`] reads the wall clock for the clock overlay, and
+//! [`local_timestamp`] for naming screenshots.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+pub use web::Instant;
+
+/// The local time of day as hours and minutes; UTC where the time zone
+/// can't be looked up, and `None` in the browser build
+#[cfg(unix)]
+pub fn local_time() -> Option<(u8, u8)> {
+    let tm = local_tm()?;
+    Some((tm.tm_hour as u8, tm.tm_min as u8))
+}
+
+/// The local date and time as `YYYYMMDD-HHMMSS`, for file names; UTC
+/// where the time zone can't be looked up, and `None` in the browser build
+#[cfg(unix)]
+pub fn local_timestamp() -> Option<String> {
+    let tm = local_tm()?;
+    Some(format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    ))
+}
+
+#[cfg(unix)]
+fn local_tm() -> Option<libc::tm> {
+    // SAFETY: `time` accepts a null pointer, and `localtime_r` only writes
+    // to the `tm` it is handed
+    unsafe {
+        let now = libc::time(std::ptr::null_mut());
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&now, &mut tm).is_null() {
+            return None;
+        }
+        Some(tm)
+    }
+}
+
+/// The local time of day as hours and minutes; UTC where the time zone
+/// can't be looked up, and `None` in the browser build
+#[cfg(not(unix))]
+pub fn local_time() -> Option<(u8, u8)> {
+    if cfg!(target_arch = "wasm32") {
+        return None;
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    let minutes = now.as_secs() / 60 % (24 * 60);
+    Some(((minutes / 60) as u8, (minutes % 60) as u8))
+}
+
+/// The local date and time as `YYYYMMDD-HHMMSS`, for file names; UTC
+/// where the time zone can't be looked up, and `None` in the browser build
+#[cfg(not(unix))]
+pub fn local_timestamp() -> Option<String> {
+    if cfg!(target_arch = "wasm32") {
+        return None;
+    }
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    // Days to a civil date, after Howard Hinnant's `civil_from_days`
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    let time = secs % 86_400;
+    Some(format!(
+        "{year:04}{month:02}{day:02}-{:02}{:02}{:02}",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    ))
+}
+
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use std::ops::{Add, AddAssign, Sub};
+    use std::time::Duration;
+
+    extern "C" {
+        /// Milliseconds since some fixed point, never going backwards
+        fn aquarium_now() -> f64;
+    }
+
+    /// A moment read from the page's clock
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Instant(Duration);
+
+    impl Instant {
+        pub fn now() -> Self {
+            // SAFETY: the import takes nothing and returns a plain number
+            let millis = unsafe { aquarium_now() };
+            Self(Duration::from_secs_f64(millis.max(0.0) / 1000.0))
+        }
+
+        pub fn duration_since(&self, earlier: Instant) -> Duration {
+            self.0.saturating_sub(earlier.0)
+        }
+
+        pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+            self.duration_since(earlier)
+        }
+
+        pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+            self.0.checked_sub(earlier.0)
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Self::now().duration_since(*self)
+        }
+
+        pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+            self.0.checked_add(duration).map(Self)
+        }
+
+        pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+            self.0.checked_sub(duration).map(Self)
+        }
+    }
+
+    impl Add<Duration> for Instant {
+        type Output = Instant;
+
+        fn add(self, duration: Duration) -> Instant {
+            Self(self.0 + duration)
+        }
+    }
+
+    impl AddAssign<Duration> for Instant {
+        fn add_assign(&mut self, duration: Duration) {
+            self.0 += duration;
+        }
+    }
+
+    impl Sub<Duration> for Instant {
+        type Output = Instant;
+
+        fn sub(self, duration: Duration) -> Instant {
+            Self(self.0 - duration)
+        }
+    }
+
+    impl Sub<Instant> for Instant {
+        type Output = Duration;
+
+        fn sub(self, earlier: Instant) -> Duration {
+            self.duration_since(earlier)
+        }
+    }
+}
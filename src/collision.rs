@@ -0,0 +1,212 @@
+//! Which entities can touch which, and what happens when they do
+//!
+//! Every entity sits on some [`CollisionLayers`] (what it is) and has a
+//! mask of the layers it reacts to (what it hits). The [`EntityManager`]
+//! only compares an entity against neighbours on a layer in its mask, so
+//! shark teeth are tested against fish but two passing fish never are.
+//! Each pair that starts touching becomes a typed [`Contact`] handed to
+//! both entities through [`Entity::on_contact`], and reported to event
+//! callbacks as a collision.
+//!
+//! Checks run in two phases. The manager's [`SpatialIndex`] finds the few
+//! entities near each masked one, and those on a masked layer whose boxes
+//! overlap its own are kept; the cells of those pairs are then compared on
+//! their sprites' opaque-cell bitmaps, over the overlap only.
+//!
+//! ```
+//! use asciiquarium_rs::collision::{CollisionLayers, ContactKind};
+//!
+//! let fishing = CollisionLayers::HOOK | CollisionLayers::FOOD;
+//! assert!(fishing.intersects(CollisionLayers::FOOD));
+//! assert_eq!(ContactKind::of(CollisionLayers::TEETH), Some(ContactKind::Bite));
+//! ```
+//!
+//! [`EntityManager`]: crate::entity::EntityManager
+//! [`SpatialIndex`]: crate::spatial::SpatialIndex
+
+use std::ops::BitOr;
+
+use crate::entity::{Entity, EntityId};
+
+/// A set of collision layers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CollisionLayers(u8);
+
+impl CollisionLayers {
+    pub const NONE: Self = Self(0);
+    /// Fish that can be bitten, hooked or fed
+    pub const FISH: Self = Self(1);
+    /// A shark's bite
+    pub const TEETH: Self = Self(1 << 1);
+    /// A fishing hook
+    pub const HOOK: Self = Self(1 << 2);
+    /// Food sinking through the water
+    pub const FOOD: Self = Self(1 << 3);
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for CollisionLayers {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+/// What a contact means, from the layer of the entity that made it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContactKind {
+    /// Teeth closed on the target
+    Bite,
+    /// A hook caught the target
+    Hooked,
+    /// The target reached some food
+    Fed,
+    /// Anything else an embedder's masks let meet
+    Touch,
+}
+
+impl ContactKind {
+    /// Lowercase name, as in event logs
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bite => "bite",
+            Self::Hooked => "hooked",
+            Self::Fed => "fed",
+            Self::Touch => "touch",
+        }
+    }
+
+    /// The kind of contact made by an entity on `layers`, if it makes any
+    pub fn of(layers: CollisionLayers) -> Option<Self> {
+        if layers.is_empty() {
+            None
+        } else if layers.intersects(CollisionLayers::TEETH) {
+            Some(ContactKind::Bite)
+        } else if layers.intersects(CollisionLayers::HOOK) {
+            Some(ContactKind::Hooked)
+        } else if layers.intersects(CollisionLayers::FOOD) {
+            Some(ContactKind::Fed)
+        } else {
+            Some(ContactKind::Touch)
+        }
+    }
+}
+
+/// Two entities that started touching: `source`, whose mask took in
+/// `target`'s layers, and `target`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Contact {
+    pub kind: ContactKind,
+    pub source: EntityId,
+    pub target: EntityId,
+}
+
+/// The contact `source` makes with `target`, if its mask takes in the
+/// target's layers
+pub fn contact_between(source: &dyn Entity, target: &dyn Entity) -> Option<Contact> {
+    if !source
+        .collision_mask()
+        .intersects(target.collision_layers())
+    {
+        return None;
+    }
+    Some(Contact {
+        kind: ContactKind::of(source.collision_layers())?,
+        source: source.id(),
+        target: target.id(),
+    })
+}
+
+/// A box of screen cells, right and bottom edges excluded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aabb {
+    pub left: u16,
+    pub top: u16,
+    pub right: u16,
+    pub bottom: u16,
+}
+
+impl Aabb {
+    /// The cells an entity's current sprite covers
+    pub fn of(entity: &dyn Entity) -> Self {
+        let (left, top) = entity.position().to_screen_coords();
+        let (width, height) = entity.get_current_sprite().get_bounding_box();
+        Self {
+            left,
+            top,
+            right: left.saturating_add(width),
+            bottom: top.saturating_add(height),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left >= self.right || self.top >= self.bottom
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.left < other.right
+            && other.left < self.right
+            && self.top < other.bottom
+            && other.top < self.bottom
+    }
+}
+
+/// Whether the visible cells of two entities overlap
+pub fn sprites_overlap(a: &dyn Entity, b: &dyn Entity) -> bool {
+    let (a_box, b_box) = (Aabb::of(a), Aabb::of(b));
+    if !a_box.intersects(&b_box) {
+        return false;
+    }
+
+    // Only the cells both boxes cover can show both sprites
+    let (a_sprite, b_sprite) = (a.get_current_sprite(), b.get_current_sprite());
+    let (left, right) = (a_box.left.max(b_box.left), a_box.right.min(b_box.right));
+    let (top, bottom) = (a_box.top.max(b_box.top), a_box.bottom.min(b_box.bottom));
+    (top..bottom).any(|y| {
+        (left..right).any(|x| {
+            a_sprite.is_opaque(x - a_box.left, y - a_box.top)
+                && b_sprite.is_opaque(x - b_box.left, y - b_box.top)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Fish, SharkTeeth};
+    use crate::entity::{Position, Velocity};
+    use crate::rng::RngHandle;
+    use ratatui::layout::Rect;
+
+    #[test]
+    fn test_only_masked_pairs_make_contact() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let teeth = SharkTeeth::new(1, Position::new(5.0, 5.0, 2), Velocity::zero(), 9);
+        let fish = Fish::new_random(2, bounds, false, &mut RngHandle::default());
+        let other_fish = Fish::new_random(3, bounds, false, &mut RngHandle::default());
+
+        assert_eq!(
+            contact_between(&teeth, &fish),
+            Some(Contact {
+                kind: ContactKind::Bite,
+                source: 1,
+                target: 2,
+            })
+        );
+        assert_eq!(contact_between(&fish, &teeth), None);
+        assert_eq!(contact_between(&fish, &other_fish), None);
+        assert_eq!(ContactKind::of(CollisionLayers::NONE), None);
+    }
+}
@@ -0,0 +1,264 @@
+//! Driving a running aquarium from outside
+//!
+//! With `--control PATH` the app listens on a Unix socket at `PATH` for
+//! one command per line and answers each with one line, so scripts,
+//! window-manager keybindings and stream overlays can steer it:
+//!
+//! ```text
+//! $ echo 'spawn shark' | nc -U /tmp/aquarium.sock
+//! ok
+//! ```
+//!
+//! | Command | Effect |
+//! |---|---|
+//! | `spawn NAME` | Run the registered spawner `NAME` (`shark`, `whale`, ...) or add a `fish` |
+//! | `pause`, `resume` | Stop or restart the simulation |
+//! | `set speed N` | Simulation speed as a multiple of real time |
+//! | `theme NAME` | Switch the water to `day`, `ocean` or `night` |
+//! | `seed N` | Refill the tank from random seed `N`, as `--seed` would |
+//! | `stats` | Counts and state on one line |
+//! | `redraw` | Clear and refill the tank |
+//! | `quit` (or `q`) | Close the aquarium |
+//!
+//! Replies start with `ok` or `error:`. Commands are carried to the app's
+//! event loop as [`AppEvent::Control`] events and handled between frames.
+//! The same commands can be typed into the app after a `:`.
+//!
+//! [`AppEvent::Control`]: crate::event::AppEvent::Control
+
+use std::fmt;
+use std::sync::mpsc;
+
+use crate::render::Theme;
+
+/// A command read from the control socket
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Spawn(String),
+    Pause,
+    Resume,
+    SetSpeed(f32),
+    Theme(Theme),
+    Seed(u64),
+    Stats,
+    Redraw,
+    Quit,
+}
+
+impl Command {
+    /// Parse one line of input
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["spawn", name] => Ok(Self::Spawn(name.to_string())),
+            ["pause"] => Ok(Self::Pause),
+            ["resume"] => Ok(Self::Resume),
+            ["set", "speed", speed] => speed
+                .parse::<f32>()
+                .ok()
+                .filter(|speed| speed.is_finite() && *speed >= 0.0)
+                .map(Self::SetSpeed)
+                .ok_or_else(|| format!("bad speed {speed}")),
+            ["theme", name] => Theme::parse(name)
+                .map(Self::Theme)
+                .ok_or_else(|| format!("unknown theme {name}")),
+            ["seed", seed] => seed
+                .parse::<u64>()
+                .map(Self::Seed)
+                .map_err(|_| format!("bad seed {seed}")),
+            ["stats"] => Ok(Self::Stats),
+            ["redraw"] => Ok(Self::Redraw),
+            ["quit" | "q"] => Ok(Self::Quit),
+            [] => Err("empty command".to_string()),
+            _ => Err(format!("unknown command {}", line.trim())),
+        }
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Spawn(name) => write!(f, "spawn {name}"),
+            Self::Pause => f.write_str("pause"),
+            Self::Resume => f.write_str("resume"),
+            Self::SetSpeed(speed) => write!(f, "set speed {speed}"),
+            Self::Theme(theme) => write!(f, "theme {}", theme.name()),
+            Self::Seed(seed) => write!(f, "seed {seed}"),
+            Self::Stats => f.write_str("stats"),
+            Self::Redraw => f.write_str("redraw"),
+            Self::Quit => f.write_str("quit"),
+        }
+    }
+}
+
+/// A command on its way to the app, with where to send the reply
+#[derive(Debug, Clone)]
+pub struct ControlRequest {
+    pub command: Command,
+    pub reply: mpsc::Sender<String>,
+}
+
+#[cfg(unix)]
+pub use socket::ControlSocket;
+
+#[cfg(unix)]
+mod socket {
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::{Command, ControlRequest};
+    use crate::event::{AppEvent, Event};
+
+    /// How long a client waits for the app, which may be busy drawing
+    const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// A listening control socket; the socket file is removed when this is
+    /// dropped
+    #[derive(Debug)]
+    pub struct ControlSocket {
+        path: PathBuf,
+    }
+
+    impl ControlSocket {
+        /// Listen at `path`, sending commands into the event loop through
+        /// `events`
+        ///
+        /// A socket file left behind by an aquarium that didn't shut down
+        /// cleanly is replaced; one another aquarium is listening on is an
+        /// error.
+        pub fn bind(path: &Path, events: mpsc::Sender<Event>) -> io::Result<Self> {
+            if path.exists() {
+                if UnixStream::connect(path).is_ok() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AddrInUse,
+                        format!("{} is in use", path.display()),
+                    ));
+                }
+                std::fs::remove_file(path)?;
+            }
+            let listener = UnixListener::bind(path)?;
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let events = events.clone();
+                    thread::spawn(move || serve(stream, &events));
+                }
+            });
+            Ok(Self {
+                path: path.to_path_buf(),
+            })
+        }
+
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+    }
+
+    impl Drop for ControlSocket {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    /// Answer to one line of input: the app's reply if the line parses and
+    /// the app answers in time, an error otherwise
+    fn answer(line: &str, events: &mpsc::Sender<Event>) -> String {
+        let command = match Command::parse(line) {
+            Ok(command) => command,
+            Err(error) => return format!("error: {error}"),
+        };
+        let (reply, replies) = mpsc::channel();
+        let request = ControlRequest { command, reply };
+        if events.send(Event::App(AppEvent::Control(request))).is_err() {
+            return "error: aquarium is closing".to_string();
+        }
+        replies
+            .recv_timeout(REPLY_TIMEOUT)
+            .unwrap_or_else(|_| "error: no reply".to_string())
+    }
+
+    /// Answer one client's commands until it hangs up
+    fn serve(stream: UnixStream, events: &mpsc::Sender<Event>) {
+        let Ok(mut writer) = stream.try_clone() else {
+            return;
+        };
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                return;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            if writeln!(writer, "{}", answer(&line, events)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            Command::parse("spawn shark"),
+            Ok(Command::Spawn("shark".to_string()))
+        );
+        assert_eq!(Command::parse("  set speed 2 "), Ok(Command::SetSpeed(2.0)));
+        assert_eq!(Command::parse("pause"), Ok(Command::Pause));
+        assert!(Command::parse("set speed fast").is_err());
+        assert!(Command::parse("spawn").is_err());
+        assert!(Command::parse("").is_err());
+        assert_eq!(Command::parse("q"), Ok(Command::Quit));
+        assert!(Command::parse("theme neon").is_err());
+        for command in [
+            Command::Stats,
+            Command::SetSpeed(0.5),
+            Command::Redraw,
+            Command::Theme(Theme::Night),
+            Command::Seed(42),
+        ] {
+            assert_eq!(Command::parse(&command.to_string()), Ok(command));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_socket_round_trip() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixStream;
+
+        use crate::event::{AppEvent, Event};
+
+        let path = std::env::temp_dir().join(format!("aquarium-test-{}.sock", std::process::id()));
+        let (events, received) = mpsc::channel();
+        let socket = ControlSocket::bind(&path, events).unwrap();
+
+        // Play the app: answer the first request
+        let app = std::thread::spawn(move || match received.recv().unwrap() {
+            Event::App(AppEvent::Control(request)) => request
+                .reply
+                .send(format!("ok {}", request.command))
+                .unwrap(),
+            event => panic!("unexpected {event:?}"),
+        });
+
+        let mut client = UnixStream::connect(socket.path()).unwrap();
+        writeln!(client, "bogus\nset speed 2").unwrap();
+        let mut lines = BufReader::new(client).lines();
+        assert_eq!(
+            lines.next().unwrap().unwrap(),
+            "error: unknown command bogus"
+        );
+        assert_eq!(lines.next().unwrap().unwrap(), "ok set speed 2");
+        app.join().unwrap();
+
+        drop(socket);
+        assert!(!path.exists());
+    }
+}
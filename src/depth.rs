@@ -18,6 +18,12 @@ pub const FISH_END: u8 = 20;
 pub const SEAWEED: u8 = 21;
 pub const CASTLE: u8 = 22;
 
+// Frightened fish duck behind the seaweed and castle
+pub const HIDING: u8 = 23;
+
+// Sand along the tank floor, behind everything else
+pub const SAND: u8 = 24;
+
 // Water surface layers (animated waves)
 pub const WATER_LINE3: u8 = 2;
 pub const WATER_GAP3: u8 = 3;
@@ -29,9 +35,8 @@ pub const WATER_LINE0: u8 = 8;
 pub const WATER_GAP0: u8 = 9;
 
 /// Get a random fish depth between FISH_START and FISH_END
-pub fn random_fish_depth() -> u8 {
+pub fn random_fish_depth(rng: &mut crate::rng::RngHandle) -> u8 {
     use rand::Rng;
-    let mut rng = rand::thread_rng();
     rng.gen_range(FISH_START..=FISH_END)
 }
 
@@ -84,18 +89,19 @@ mod tests {
     #[test]
     fn test_depth_ordering() {
         // GUI should be in front of everything
-        assert!(GUI_TEXT < GUI);
-        assert!(GUI < SHARK);
+        const { assert!(GUI_TEXT < GUI) };
+        const { assert!(GUI < SHARK) };
 
         // Sharks should be in front of fish
-        assert!(SHARK < FISH_START);
+        const { assert!(SHARK < FISH_START) };
 
         // Fish should be in front of environment
-        assert!(FISH_END < SEAWEED);
-        assert!(SEAWEED < CASTLE);
+        const { assert!(FISH_END < SEAWEED) };
+        const { assert!(SEAWEED < CASTLE) };
+        const { assert!(HIDING < SAND) };
 
         // Water surface should be mixed with other elements
-        assert!(WATER_LINE3 == SHARK);
+        const { assert!(WATER_LINE3 == SHARK) };
     }
 
     #[test]
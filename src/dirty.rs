@@ -0,0 +1,364 @@
+//! Partial redraws of the tank
+//!
+//! Most of the tank is the same from one frame to the next: the castle,
+//! the sand and the seaweed between sways. [`TankCache`] keeps the last
+//! frame and a fingerprint of every entity's footprint, and only repaints
+//! the regions an entity left or now covers. Anything that changes the
+//! whole tank (a new size, different render options, the tide moving the
+//! waterline) falls back to a full redraw.
+//!
+//! Under the entities that move lies the static layer: the water and the
+//! static entities drawn behind everything else (see
+//! [`Entity::is_static`]). It is drawn once into its own buffer and each
+//! repaint starts from a copy of it, until one of its entities changes or
+//! something that moves goes behind it.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use ratatui::{buffer::Buffer, layout::Rect};
+
+use crate::entity::{Entity, EntityId, EntityManager};
+use crate::render::RenderOptions;
+
+/// Above this share of dirty cells a full redraw is cheaper
+const FULL_REDRAW_SHARE: f32 = 0.5;
+
+/// Screen cells `entity` may draw on, clipped to `screen`
+///
+/// Includes one extra column on the left, where drawing over the right
+/// half of a wide glyph blanks it.
+pub fn footprint_area(entity: &dyn Entity, screen: Rect) -> Rect {
+    let position = entity.position();
+    let (width, height) = entity.get_current_sprite().get_bounding_box();
+    // Truncated like the renderer does
+    let left = (position.x as i32 - 1).max(0);
+    let top = (position.y as i32).max(0);
+    let right = (position.x as i32 + width as i32).min(screen.width as i32);
+    let bottom = (position.y as i32 + height as i32).min(screen.height as i32);
+    if right <= left || bottom <= top {
+        return Rect::default();
+    }
+    Rect::new(
+        left as u16,
+        top as u16,
+        (right - left) as u16,
+        (bottom - top) as u16,
+    )
+}
+
+/// Where an entity was drawn and what it looked like
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Footprint {
+    area: Rect,
+    fingerprint: u64,
+}
+
+impl Footprint {
+    fn of(entity: &dyn Entity, screen: Rect) -> Self {
+        let position = entity.position();
+        let sprite = entity.get_current_sprite();
+        let mut hasher = DefaultHasher::new();
+        (position.x as i32).hash(&mut hasher);
+        // Half blocks draw particles at double vertical resolution
+        ((position.y * 2.0).floor() as i32).hash(&mut hasher);
+        entity.entity_type().hash(&mut hasher);
+        entity.depth().hash(&mut hasher);
+        sprite.lines().hash(&mut hasher);
+        sprite.color_mask().hash(&mut hasher);
+        sprite.background_mask().hash(&mut hasher);
+        sprite.attribute_mask().hash(&mut hasher);
+
+        Self {
+            area: footprint_area(entity, screen),
+            fingerprint: hasher.finish(),
+        }
+    }
+}
+
+/// Everything besides the entities that shapes the whole tank
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scene {
+    size: (u16, u16),
+    options: RenderOptions,
+    waterline: u16,
+}
+
+/// The last frame of the tank, for partial redraws
+#[derive(Debug, Default)]
+pub struct TankCache {
+    tank: Option<Buffer>,
+    scene: Option<Scene>,
+    footprints: HashMap<EntityId, Footprint>,
+    static_layer: Option<Buffer>,
+    /// Fingerprint of the entities in the static layer
+    static_key: u64,
+    /// Whether the last update drew anything
+    changed: bool,
+}
+
+impl TankCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the last frame so the next one is drawn in full
+    pub fn invalidate(&mut self) {
+        self.tank = None;
+    }
+
+    /// Whether the last [`TankCache::update`] changed the tank
+    pub fn changed(&self) -> bool {
+        self.changed
+    }
+
+    /// Bring the cached tank up to date and return it
+    ///
+    /// `draw_static` renders the static layer (background and
+    /// [`EntityManager::render_static_layer`]) into a buffer covering the
+    /// whole tank. `draw` renders the moving entities over it into the
+    /// buffer it is given, which covers either the whole tank or one dirty
+    /// region.
+    pub fn update(
+        &mut self,
+        entity_manager: &EntityManager,
+        options: RenderOptions,
+        size: (u16, u16),
+        mut draw_static: impl FnMut(&mut Buffer),
+        mut draw: impl FnMut(&mut Buffer),
+    ) -> &Buffer {
+        let screen = Rect::new(0, 0, size.0, size.1);
+        let scene = Scene {
+            size,
+            options,
+            waterline: entity_manager.environment().waterline().max(0.0) as u16,
+        };
+        let footprints: HashMap<EntityId, Footprint> = entity_manager
+            .entities()
+            .map(|entity| (entity.id(), Footprint::of(entity, screen)))
+            .collect();
+
+        let mut hasher = DefaultHasher::new();
+        for entity in entity_manager.static_layer() {
+            (entity.id(), Footprint::of(entity, screen)).hash(&mut hasher);
+        }
+        let static_key = hasher.finish();
+        if self.static_layer.is_none() || self.scene != Some(scene) || self.static_key != static_key
+        {
+            let mut layer = Buffer::empty(screen);
+            draw_static(&mut layer);
+            self.static_layer = Some(layer);
+            self.static_key = static_key;
+            self.tank = None;
+        }
+        let static_layer = self.static_layer.as_ref().unwrap();
+
+        let regions = if self.tank.is_some() && self.scene == Some(scene) {
+            let regions = self.dirty_regions(&footprints);
+            let dirty: u32 = regions.iter().map(|region| region.area()).sum();
+            ((dirty as f32) < screen.area() as f32 * FULL_REDRAW_SHARE).then_some(regions)
+        } else {
+            None
+        };
+
+        match (regions, self.tank.as_mut()) {
+            (Some(regions), Some(tank)) => {
+                self.changed = !regions.is_empty();
+                for region in regions {
+                    let mut patch = Buffer::empty(region);
+                    for (x, y) in region.positions().map(|at| (at.x, at.y)) {
+                        patch[(x, y)] = static_layer[(x, y)].clone();
+                    }
+                    draw(&mut patch);
+                    tank.merge(&patch);
+                }
+            }
+            _ => {
+                let mut tank = static_layer.clone();
+                draw(&mut tank);
+                self.tank = Some(tank);
+                self.changed = true;
+            }
+        }
+
+        self.scene = Some(scene);
+        self.footprints = footprints;
+        self.tank.as_ref().unwrap()
+    }
+
+    /// Regions to repaint: the old and new footprints of every entity that
+    /// moved, changed, appeared or went away, merged where they overlap
+    fn dirty_regions(&self, footprints: &HashMap<EntityId, Footprint>) -> Vec<Rect> {
+        let mut regions: Vec<Rect> = Vec::new();
+        let appeared = footprints
+            .keys()
+            .filter(|id| !self.footprints.contains_key(id));
+        for id in self.footprints.keys().chain(appeared) {
+            let (before, after) = (self.footprints.get(id), footprints.get(id));
+            if before == after {
+                continue;
+            }
+            for footprint in [before, after].into_iter().flatten() {
+                if !footprint.area.is_empty() {
+                    add_region(&mut regions, footprint.area);
+                }
+            }
+        }
+        regions
+    }
+}
+
+/// Add `area` to `regions`, merging it with any region it overlaps
+fn add_region(regions: &mut Vec<Rect>, mut area: Rect) {
+    while let Some(index) = regions.iter().position(|region| region.intersects(area)) {
+        area = area.union(regions.swap_remove(index));
+    }
+    regions.push(area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Glint;
+    use crate::entity::Position;
+
+    const SCREEN: Rect = Rect::new(0, 0, 20, 10);
+
+    fn draw_static(entity_manager: &EntityManager, buffer: &mut Buffer) {
+        entity_manager.render_static_layer(buffer, SCREEN, &RenderOptions::default());
+    }
+
+    fn draw_moving(entity_manager: &EntityManager, buffer: &mut Buffer) {
+        entity_manager.render_moving(buffer, SCREEN, &RenderOptions::default());
+    }
+
+    #[test]
+    fn test_partial_redraw_matches_full_redraw() {
+        let mut entity_manager = EntityManager::new();
+        entity_manager.spawn(|id| Glint::new(id, 2.0, 2.0));
+        let moving = entity_manager.spawn(|id| Glint::new(id, 5.0, 5.0));
+
+        let mut cache = TankCache::new();
+        let mut draws = Vec::new();
+        cache.update(
+            &entity_manager,
+            RenderOptions::default(),
+            (20, 10),
+            |buf| draw_static(&entity_manager, buf),
+            |buf| {
+                draws.push(buf.area);
+                draw_moving(&entity_manager, buf)
+            },
+        );
+        assert_eq!(draws, vec![Rect::new(0, 0, 20, 10)]);
+
+        let glint = entity_manager.get_entity_mut(moving).unwrap();
+        glint.set_position(Position::new(9.0, 7.0, glint.depth()));
+
+        let mut draws = Vec::new();
+        let tank = cache.update(
+            &entity_manager,
+            RenderOptions::default(),
+            (20, 10),
+            |buf| draw_static(&entity_manager, buf),
+            |buf| {
+                draws.push(buf.area);
+                draw_moving(&entity_manager, buf)
+            },
+        );
+        // Only the cells the glint left and moved onto are repainted
+        assert_eq!(draws.len(), 2);
+        assert!(draws.iter().all(|area| area.area() <= 2));
+
+        let mut full = Buffer::empty(SCREEN);
+        entity_manager.render_all(&mut full, SCREEN, &RenderOptions::default());
+        assert_eq!(tank, &full);
+        assert!(cache.changed());
+
+        // Nothing moved, so nothing is drawn
+        cache.update(
+            &entity_manager,
+            RenderOptions::default(),
+            (20, 10),
+            |buf| draw_static(&entity_manager, buf),
+            |buf| draw_moving(&entity_manager, buf),
+        );
+        assert!(!cache.changed());
+    }
+
+    #[test]
+    fn test_static_layer_is_drawn_once() {
+        use crate::entities::Castle;
+
+        let mut entity_manager = EntityManager::new();
+        entity_manager.spawn(|id| Castle::new_at_position(id, 0.0, 0.0));
+        let glint = entity_manager.spawn(|id| Glint::new(id, 3.0, 3.0));
+        assert_eq!(entity_manager.static_layer().count(), 1);
+
+        let mut cache = TankCache::new();
+        let mut static_draws = 0;
+        for x in [3.0, 4.0, 5.0] {
+            let entity = entity_manager.get_entity_mut(glint).unwrap();
+            entity.set_position(Position::new(x, 3.0, entity.depth()));
+            let tank = cache.update(
+                &entity_manager,
+                RenderOptions::default(),
+                (20, 10),
+                |buf| {
+                    static_draws += 1;
+                    draw_static(&entity_manager, buf)
+                },
+                |buf| draw_moving(&entity_manager, buf),
+            );
+            let mut full = Buffer::empty(SCREEN);
+            entity_manager.render_all(&mut full, SCREEN, &RenderOptions::default());
+            assert_eq!(tank, &full);
+        }
+        assert_eq!(static_draws, 1);
+
+        // Something moving behind the castle takes it out of the layer
+        entity_manager.spawn(|id| {
+            let mut behind = Glint::new(id, 5.0, 3.0);
+            behind.set_position(Position::new(5.0, 3.0, crate::depth::SAND));
+            behind
+        });
+        assert_eq!(entity_manager.static_layer().count(), 0);
+    }
+
+    #[test]
+    fn test_new_options_redraw_everything() {
+        let entity_manager = EntityManager::new();
+        let mut cache = TankCache::new();
+        cache.update(
+            &entity_manager,
+            RenderOptions::default(),
+            (20, 10),
+            |_| {},
+            |_| {},
+        );
+
+        let options = RenderOptions {
+            depth_dimming: true,
+            ..RenderOptions::default()
+        };
+        let mut draws = Vec::new();
+        cache.update(
+            &entity_manager,
+            options,
+            (20, 10),
+            |_| {},
+            |buf| draws.push(buf.area),
+        );
+        assert_eq!(draws, vec![Rect::new(0, 0, 20, 10)]);
+    }
+
+    #[test]
+    fn test_overlapping_regions_merge() {
+        let mut regions = Vec::new();
+        add_region(&mut regions, Rect::new(0, 0, 4, 1));
+        add_region(&mut regions, Rect::new(10, 0, 2, 1));
+        add_region(&mut regions, Rect::new(3, 0, 8, 1));
+        assert_eq!(regions, vec![Rect::new(0, 0, 12, 1)]);
+    }
+}
@@ -0,0 +1,219 @@
+//! Optional ecology mode: fish breeding and population dynamics
+//!
+//! Two adult fish of the same species that swim close together for a while
+//! produce a juvenile. Juveniles start as tiny fry and grow through size
+//! stages (see [`LifeStage`](crate::entities::fish::LifeStage)). Births stop
+//! once the fish population reaches a cap derived from the screen area.
+
+use crate::entities::FishSpecies;
+use crate::entity::{EntityId, Position};
+use ratatui::layout::Rect;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How long (seconds) two fish must stay close before breeding
+pub const LINGER_SECONDS: f32 = 3.0;
+
+/// How long (seconds) a parent waits before it can breed again
+pub const BREEDING_COOLDOWN_SECONDS: f32 = 30.0;
+
+/// Maximum horizontal distance between fish centres to count as "near"
+pub const MATE_DISTANCE_X: f32 = 10.0;
+
+/// Maximum vertical distance between fish centres to count as "near"
+pub const MATE_DISTANCE_Y: f32 = 3.0;
+
+/// Screen cells per fish at the population cap.
+///
+/// The normal population uses one fish per 350 cells (original formula), so
+/// the cap allows breeding to roughly double it.
+const CELLS_PER_FISH_AT_CAP: usize = 175;
+
+/// Maximum fish population for the given screen with the water surface at
+/// `waterline` (same area as the original fish formula: everything below the
+/// water surface)
+pub fn population_cap(screen_bounds: Rect, waterline: f32) -> usize {
+    let water_rows = screen_bounds
+        .height
+        .saturating_sub(waterline.max(0.0) as u16);
+    let area = water_rows as usize * screen_bounds.width as usize;
+    (area / CELLS_PER_FISH_AT_CAP).max(1)
+}
+
+/// A fish that is able to breed this frame
+#[derive(Debug, Clone, Copy)]
+pub struct Mate {
+    pub id: EntityId,
+    pub species: FishSpecies,
+    pub position: Position,
+    pub size: (u16, u16),
+}
+
+impl Mate {
+    fn center(&self) -> (f32, f32) {
+        (
+            self.position.x + self.size.0 as f32 / 2.0,
+            self.position.y + self.size.1 as f32 / 2.0,
+        )
+    }
+
+    fn is_near(&self, other: &Mate) -> bool {
+        let (ax, ay) = self.center();
+        let (bx, by) = other.center();
+        (ax - bx).abs() <= MATE_DISTANCE_X && (ay - by).abs() <= MATE_DISTANCE_Y
+    }
+}
+
+/// A juvenile that should be spawned
+#[derive(Debug, Clone, Copy)]
+pub struct Birth {
+    pub species: FishSpecies,
+    pub position: Position,
+    /// Parent whose heading the juvenile inherits
+    pub parent_id: EntityId,
+}
+
+/// Tracks how long pairs of fish have lingered together
+#[derive(Debug, Default)]
+pub struct Ecology {
+    linger: HashMap<(EntityId, EntityId), f32>,
+    cooldowns: HashMap<EntityId, f32>,
+}
+
+impl Ecology {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance breeding timers and return any juveniles that should be born
+    ///
+    /// `fish_count` is the current fish population (adults and juveniles)
+    /// and is compared against `cap`, usually [`population_cap`].
+    pub fn update(
+        &mut self,
+        mates: &[Mate],
+        fish_count: usize,
+        delta_time: Duration,
+        cap: usize,
+    ) -> Vec<Birth> {
+        let dt = delta_time.as_secs_f32();
+
+        // Tick down cooldowns and forget parents that have left the tank
+        self.cooldowns.retain(|id, remaining| {
+            *remaining -= dt;
+            *remaining > 0.0 && mates.iter().any(|m| m.id == *id)
+        });
+
+        let mut births = Vec::new();
+        let mut population = fish_count;
+        let mut lingering = HashMap::new();
+
+        for (i, a) in mates.iter().enumerate() {
+            for b in &mates[i + 1..] {
+                if a.species != b.species || !a.is_near(b) {
+                    continue;
+                }
+                if self.cooldowns.contains_key(&a.id) || self.cooldowns.contains_key(&b.id) {
+                    continue;
+                }
+
+                let key = (a.id.min(b.id), a.id.max(b.id));
+                let time = self.linger.get(&key).copied().unwrap_or(0.0) + dt;
+
+                if time >= LINGER_SECONDS && population < cap {
+                    let (ax, ay) = a.center();
+                    let (bx, by) = b.center();
+                    births.push(Birth {
+                        species: a.species,
+                        position: Position::new((ax + bx) / 2.0, (ay + by) / 2.0, a.position.depth),
+                        parent_id: a.id,
+                    });
+                    population += 1;
+                    self.cooldowns.insert(a.id, BREEDING_COOLDOWN_SECONDS);
+                    self.cooldowns.insert(b.id, BREEDING_COOLDOWN_SECONDS);
+                } else {
+                    lingering.insert(key, time);
+                }
+            }
+        }
+
+        // Pairs that drifted apart lose their accumulated time
+        self.linger = lingering;
+        births
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::WATERLINE_Y;
+
+    fn mate(id: EntityId, species: FishSpecies, x: f32) -> Mate {
+        Mate {
+            id,
+            species,
+            position: Position::new(x, 12.0, 10),
+            size: (5, 3),
+        }
+    }
+
+    #[test]
+    fn test_population_cap_scales_with_area() {
+        assert_eq!(population_cap(Rect::new(0, 0, 80, 24), WATERLINE_Y), 6);
+        assert!(population_cap(Rect::new(0, 0, 300, 80), WATERLINE_Y) > 100);
+        assert_eq!(population_cap(Rect::new(0, 0, 10, 5), WATERLINE_Y), 1);
+    }
+
+    #[test]
+    fn test_population_cap_follows_the_tide() {
+        let bounds = Rect::new(0, 0, 175, 24);
+        assert_eq!(population_cap(bounds, WATERLINE_Y), 15);
+        assert_eq!(population_cap(bounds, WATERLINE_Y + 3.0), 12);
+    }
+
+    #[test]
+    fn test_lingering_pair_breeds() {
+        let mut ecology = Ecology::new();
+        let cap = population_cap(Rect::new(0, 0, 80, 24), WATERLINE_Y);
+        let mates = [
+            mate(1, FishSpecies::OldTiny, 10.0),
+            mate(2, FishSpecies::OldTiny, 14.0),
+        ];
+
+        let births = ecology.update(&mates, 2, Duration::from_secs(2), cap);
+        assert!(births.is_empty());
+
+        let births = ecology.update(&mates, 2, Duration::from_secs(2), cap);
+        assert_eq!(births.len(), 1);
+        assert_eq!(births[0].species, FishSpecies::OldTiny);
+
+        // Parents are on cooldown afterwards
+        let births = ecology.update(&mates, 3, Duration::from_secs(5), cap);
+        assert!(births.is_empty());
+    }
+
+    #[test]
+    fn test_different_species_do_not_breed() {
+        let mut ecology = Ecology::new();
+        let mates = [
+            mate(1, FishSpecies::OldTiny, 10.0),
+            mate(2, FishSpecies::OldRounded, 12.0),
+        ];
+
+        let births = ecology.update(&mates, 2, Duration::from_secs(10), 6);
+        assert!(births.is_empty());
+    }
+
+    #[test]
+    fn test_population_cap_blocks_births() {
+        let mut ecology = Ecology::new();
+        let cap = population_cap(Rect::new(0, 0, 80, 24), WATERLINE_Y);
+        let mates = [
+            mate(1, FishSpecies::OldTiny, 10.0),
+            mate(2, FishSpecies::OldTiny, 12.0),
+        ];
+
+        let births = ecology.update(&mates, cap, Duration::from_secs(10), cap);
+        assert!(births.is_empty());
+    }
+}
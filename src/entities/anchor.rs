@@ -0,0 +1,164 @@
+use crate::depth;
+use crate::entity::{Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::sprite;
+use crate::timestep;
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// Sinking / hauling speed (rows per tick)
+const SINK_SPEED: f32 = 0.15;
+const HAUL_SPEED: f32 = 0.25;
+
+/// How long the anchor lies on the bottom before it is hauled up
+const REST_SECS: f32 = 5.0;
+
+const ANCHOR_IMAGE: &str = r#"  O
+--+--
+  |
+\_|_/"#;
+
+const ANCHOR_MASK: &str = r#"  W
+WWWWW
+  W
+WWWWW"#;
+
+/// Where the anchor is in its drop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnchorState {
+    Sinking,
+    Resting { remaining: f32 },
+    Hauling,
+}
+
+/// An anchor dropped from an unseen boat (ambient event)
+///
+/// It sinks from the waterline to the floor, lies there a moment and is
+/// hauled back out of the water.
+#[derive(Debug, Clone)]
+pub struct Anchor {
+    id: EntityId,
+    position: Position,
+    sprite: Sprite,
+    state: AnchorState,
+    alive: bool,
+}
+
+impl Anchor {
+    /// Drop an anchor at column `x`, starting at row `y`
+    pub fn new(id: EntityId, x: f32, y: f32) -> Self {
+        Self {
+            id,
+            position: Position::new(x, y, depth::SEAWEED - 1),
+            sprite: sprite!(ANCHOR_IMAGE, ANCHOR_MASK),
+            state: AnchorState::Sinking,
+            alive: true,
+        }
+    }
+
+    pub fn state(&self) -> AnchorState {
+        self.state
+    }
+}
+
+impl Entity for Anchor {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        match self.state {
+            AnchorState::Sinking => Velocity::new(0.0, SINK_SPEED),
+            AnchorState::Resting { .. } => Velocity::zero(),
+            AnchorState::Hauling => Velocity::new(0.0, -HAUL_SPEED),
+        }
+    }
+
+    fn set_velocity(&mut self, _velocity: Velocity) {
+        // The anchor only moves on its rope
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
+        let ticks = timestep::ticks(delta_time);
+        let floor = world_bounds.bottom() as f32 - self.sprite.get_bounding_box().1 as f32;
+
+        match &mut self.state {
+            AnchorState::Sinking => {
+                self.position.y += SINK_SPEED * ticks;
+                if self.position.y >= floor {
+                    self.position.y = floor;
+                    self.state = AnchorState::Resting {
+                        remaining: REST_SECS,
+                    };
+                }
+            }
+            AnchorState::Resting { remaining } => {
+                *remaining -= delta_time.as_secs_f32();
+                if *remaining <= 0.0 {
+                    self.state = AnchorState::Hauling;
+                }
+            }
+            AnchorState::Hauling => {
+                self.position.y -= HAUL_SPEED * ticks;
+                let height = self.sprite.get_bounding_box().1 as f32;
+                if self.position.y + height <= world_bounds.y as f32 {
+                    self.alive = false;
+                }
+            }
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Anchor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_drop_cycle() {
+        let bounds = Rect::new(0, 0, 80, 30);
+        let mut anchor = Anchor::new(1, 20.0, 9.0);
+        let step = Duration::from_millis(100);
+
+        while anchor.state() == AnchorState::Sinking {
+            anchor.update(step, bounds);
+        }
+        assert_eq!(anchor.position().y, 26.0); // Resting on the bottom
+
+        for _ in 0..60 {
+            anchor.update(step, bounds);
+        }
+        assert_eq!(anchor.state(), AnchorState::Hauling);
+
+        for _ in 0..100 {
+            anchor.update(step, bounds);
+        }
+        assert!(!anchor.is_alive());
+    }
+}
@@ -8,7 +8,8 @@
 //! In classic mode, only BigFish1 appears.
 
 use crate::depth::SHARK;
-use crate::entity::{DeathCallback, Direction, Entity, EntityId, Position, Sprite, Velocity};
+use crate::entity::{Direction, Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::rng::RngHandle;
 use rand::Rng;
 use ratatui::layout::Rect;
 use std::time::Duration;
@@ -35,10 +36,15 @@ pub struct BigFish {
 }
 
 impl BigFish {
-    /// Create a new big fish with random variant selection
-    pub fn new(id: EntityId, screen_bounds: Rect, classic_mode: bool) -> Self {
-        let mut rng = rand::thread_rng();
-
+    /// Create a new big fish with random variant selection, swimming below
+    /// `waterline`
+    pub fn new(
+        id: EntityId,
+        screen_bounds: Rect,
+        classic_mode: bool,
+        waterline: f32,
+        rng: &mut RngHandle,
+    ) -> Self {
         // Select variant based on mode
         let variant = if classic_mode {
             BigFishVariant::Variant1
@@ -52,12 +58,17 @@ impl BigFish {
             }
         };
 
-        Self::new_variant(id, screen_bounds, variant)
+        Self::new_variant(id, screen_bounds, variant, waterline, rng)
     }
 
-    /// Create a new big fish with specific variant
-    pub fn new_variant(id: EntityId, screen_bounds: Rect, variant: BigFishVariant) -> Self {
-        let mut rng = rand::thread_rng();
+    /// Create a new big fish with specific variant, swimming below `waterline`
+    pub fn new_variant(
+        id: EntityId,
+        screen_bounds: Rect,
+        variant: BigFishVariant,
+        waterline: f32,
+        rng: &mut RngHandle,
+    ) -> Self {
         let direction = if rng.gen_bool(0.5) {
             Direction::Right
         } else {
@@ -65,8 +76,8 @@ impl BigFish {
         };
 
         let (sprite, speed) = match variant {
-            BigFishVariant::Variant1 => (create_big_fish_1_sprite(direction), 3.0),
-            BigFishVariant::Variant2 => (create_big_fish_2_sprite(direction), 2.5),
+            BigFishVariant::Variant1 => (create_big_fish_1_sprite(direction, rng), 3.0),
+            BigFishVariant::Variant2 => (create_big_fish_2_sprite(direction, rng), 2.5),
         };
 
         // Match original Perl spawn positions:
@@ -81,7 +92,7 @@ impl BigFish {
         };
 
         // Y position varies by variant due to different sprite heights
-        let max_height = 9;
+        let max_height = waterline.max(0.0) as u16;
         let height_offset = match variant {
             BigFishVariant::Variant1 => 15, // Original: height - 15
             BigFishVariant::Variant2 => 14, // Original: height - 14
@@ -161,20 +172,20 @@ impl Entity for BigFish {
         self.alive = false;
     }
 
+    fn kind(&self) -> EntityKind {
+        EntityKind::BigFish
+    }
+
     fn entity_type(&self) -> &'static str {
         match self.variant {
             BigFishVariant::Variant1 => "big_fish_1",
             BigFishVariant::Variant2 => "big_fish_2",
         }
     }
-
-    fn death_callback(&self) -> Option<DeathCallback> {
-        Some(crate::spawning::random_object)
-    }
 }
 
 /// Create sprite for big fish variant 1 (traditional)
-fn create_big_fish_1_sprite(direction: Direction) -> Sprite {
+fn create_big_fish_1_sprite(direction: Direction, rng: &mut RngHandle) -> Sprite {
     let (image, mask) = match direction {
         Direction::Right => (
             r#" ______
@@ -238,11 +249,11 @@ fn create_big_fish_1_sprite(direction: Direction) -> Sprite {
         ),
     };
 
-    Sprite::from_ascii_art_with_random_colors(image, Some(mask))
+    Sprite::from_ascii_art_with_random_colors(image, Some(mask), rng)
 }
 
 /// Create sprite for big fish variant 2 (stylized)
-fn create_big_fish_2_sprite(direction: Direction) -> Sprite {
+fn create_big_fish_2_sprite(direction: Direction, rng: &mut RngHandle) -> Sprite {
     let (image, mask) = match direction {
         Direction::Right => (
             r#"                _ _ _
@@ -302,17 +313,18 @@ fn create_big_fish_2_sprite(direction: Direction) -> Sprite {
         ),
     };
 
-    Sprite::from_ascii_art_with_random_colors(image, Some(mask))
+    Sprite::from_ascii_art_with_random_colors(image, Some(mask), rng)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::environment::WATERLINE_Y;
 
     #[test]
     fn test_big_fish_creation() {
         let bounds = Rect::new(0, 0, 80, 24);
-        let fish = BigFish::new(1, bounds, false);
+        let fish = BigFish::new(1, bounds, false, WATERLINE_Y, &mut RngHandle::default());
         assert_eq!(fish.id, 1);
         // Large creature is tracked by EntityManager, not a trait method
         assert!(fish.alive);
@@ -322,11 +334,23 @@ mod tests {
     fn test_big_fish_variants() {
         let bounds = Rect::new(0, 0, 80, 24);
 
-        let fish1 = BigFish::new_variant(1, bounds, BigFishVariant::Variant1);
+        let fish1 = BigFish::new_variant(
+            1,
+            bounds,
+            BigFishVariant::Variant1,
+            WATERLINE_Y,
+            &mut RngHandle::default(),
+        );
         assert_eq!(fish1.entity_type(), "big_fish_1");
         assert_eq!(fish1.sprite.get_bounding_box().1, 14);
 
-        let fish2 = BigFish::new_variant(2, bounds, BigFishVariant::Variant2);
+        let fish2 = BigFish::new_variant(
+            2,
+            bounds,
+            BigFishVariant::Variant2,
+            WATERLINE_Y,
+            &mut RngHandle::default(),
+        );
         assert_eq!(fish2.entity_type(), "big_fish_2");
         assert_eq!(fish2.sprite.get_bounding_box().1, 13);
     }
@@ -337,7 +361,7 @@ mod tests {
 
         // Classic mode should only create Variant1
         for _ in 0..10 {
-            let fish = BigFish::new(1, bounds, true);
+            let fish = BigFish::new(1, bounds, true, WATERLINE_Y, &mut RngHandle::default());
             assert_eq!(fish.variant, BigFishVariant::Variant1);
         }
     }
@@ -347,7 +371,13 @@ mod tests {
         let bounds = Rect::new(0, 0, 80, 24);
 
         // Test Variant1 positions (matches original Perl)
-        let fish1 = BigFish::new_variant(1, bounds, BigFishVariant::Variant1);
+        let fish1 = BigFish::new_variant(
+            1,
+            bounds,
+            BigFishVariant::Variant1,
+            WATERLINE_Y,
+            &mut RngHandle::default(),
+        );
         match fish1.direction {
             Direction::Right => {
                 assert_eq!(fish1.position.x, -34.0); // Original Perl: x = -34
@@ -358,7 +388,13 @@ mod tests {
         }
 
         // Test Variant2 positions (matches original Perl)
-        let fish2 = BigFish::new_variant(2, bounds, BigFishVariant::Variant2);
+        let fish2 = BigFish::new_variant(
+            2,
+            bounds,
+            BigFishVariant::Variant2,
+            WATERLINE_Y,
+            &mut RngHandle::default(),
+        );
         match fish2.direction {
             Direction::Right => {
                 assert_eq!(fish2.position.x, -33.0); // Original Perl: x = -33
@@ -372,7 +408,13 @@ mod tests {
     #[test]
     fn test_big_fish_movement() {
         let bounds = Rect::new(0, 0, 80, 24);
-        let mut fish = BigFish::new_variant(1, bounds, BigFishVariant::Variant1);
+        let mut fish = BigFish::new_variant(
+            1,
+            bounds,
+            BigFishVariant::Variant1,
+            WATERLINE_Y,
+            &mut RngHandle::default(),
+        );
         let initial_x = fish.position.x;
 
         fish.update(Duration::from_secs(1), bounds);
@@ -383,22 +425,27 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_big_fish_death_callback() {
-        let bounds = Rect::new(0, 0, 80, 24);
-        let fish = BigFish::new_variant(1, bounds, BigFishVariant::Variant1);
-        assert!(fish.death_callback().is_some());
-    }
-
     #[test]
     fn test_big_fish_speeds() {
         let bounds = Rect::new(0, 0, 80, 24);
 
-        let fish1 = BigFish::new_variant(1, bounds, BigFishVariant::Variant1);
+        let fish1 = BigFish::new_variant(
+            1,
+            bounds,
+            BigFishVariant::Variant1,
+            WATERLINE_Y,
+            &mut RngHandle::default(),
+        );
         let speed1 = fish1.velocity.dx.abs();
         assert!((speed1 - 3.0).abs() < 0.01);
 
-        let fish2 = BigFish::new_variant(2, bounds, BigFishVariant::Variant2);
+        let fish2 = BigFish::new_variant(
+            2,
+            bounds,
+            BigFishVariant::Variant2,
+            WATERLINE_Y,
+            &mut RngHandle::default(),
+        );
         let speed2 = fish2.velocity.dx.abs();
         assert!((speed2 - 2.5).abs() < 0.01);
     }
@@ -409,7 +456,13 @@ mod tests {
 
         // Test Variant1 Y range (height - 15)
         for _ in 0..10 {
-            let fish1 = BigFish::new_variant(1, bounds, BigFishVariant::Variant1);
+            let fish1 = BigFish::new_variant(
+                1,
+                bounds,
+                BigFishVariant::Variant1,
+                WATERLINE_Y,
+                &mut RngHandle::default(),
+            );
             // Y should be between 9 and (24 - 15) = 9, so exactly 9 for small screen
             assert!(fish1.position.y >= 9.0);
             assert!(fish1.position.y < (bounds.height.saturating_sub(15).max(10)) as f32);
@@ -417,20 +470,41 @@ mod tests {
 
         // Test Variant2 Y range (height - 14)
         for _ in 0..10 {
-            let fish2 = BigFish::new_variant(2, bounds, BigFishVariant::Variant2);
+            let fish2 = BigFish::new_variant(
+                2,
+                bounds,
+                BigFishVariant::Variant2,
+                WATERLINE_Y,
+                &mut RngHandle::default(),
+            );
             // Y should be between 9 and (24 - 14) = 10
             assert!(fish2.position.y >= 9.0);
             assert!(fish2.position.y < (bounds.height.saturating_sub(14).max(10)) as f32);
         }
     }
 
+    #[test]
+    fn test_big_fish_swims_below_a_high_tide() {
+        let bounds = Rect::new(0, 0, 80, 40);
+        for _ in 0..10 {
+            let fish = BigFish::new_variant(
+                1,
+                bounds,
+                BigFishVariant::Variant2,
+                13.0,
+                &mut RngHandle::default(),
+            );
+            assert!(fish.position.y >= 13.0);
+        }
+    }
+
     #[test]
     fn test_big_fish_variant_selection() {
         let bounds = Rect::new(0, 0, 80, 24);
 
         // Classic mode should always use Variant1
         for _ in 0..10 {
-            let fish = BigFish::new(1, bounds, true); // classic_mode = true
+            let fish = BigFish::new(1, bounds, true, WATERLINE_Y, &mut RngHandle::default()); // classic_mode = true
             assert_eq!(fish.variant, BigFishVariant::Variant1);
         }
 
@@ -438,7 +512,7 @@ mod tests {
         let mut has_variant1 = false;
         let mut has_variant2 = false;
         for _ in 0..30 {
-            let fish = BigFish::new(1, bounds, false); // classic_mode = false
+            let fish = BigFish::new(1, bounds, false, WATERLINE_Y, &mut RngHandle::default()); // classic_mode = false
             match fish.variant {
                 BigFishVariant::Variant1 => has_variant1 = true,
                 BigFishVariant::Variant2 => has_variant2 = true,
@@ -454,11 +528,23 @@ mod tests {
         let bounds = Rect::new(0, 0, 80, 24);
 
         // Both variants should use SHARK depth (2), not FISH_START (3)
-        let fish1 = BigFish::new_variant(1, bounds, BigFishVariant::Variant1);
+        let fish1 = BigFish::new_variant(
+            1,
+            bounds,
+            BigFishVariant::Variant1,
+            WATERLINE_Y,
+            &mut RngHandle::default(),
+        );
         assert_eq!(fish1.depth(), SHARK);
         assert_eq!(fish1.depth(), 2);
 
-        let fish2 = BigFish::new_variant(2, bounds, BigFishVariant::Variant2);
+        let fish2 = BigFish::new_variant(
+            2,
+            bounds,
+            BigFishVariant::Variant2,
+            WATERLINE_Y,
+            &mut RngHandle::default(),
+        );
         assert_eq!(fish2.depth(), SHARK);
         assert_eq!(fish2.depth(), 2);
     }
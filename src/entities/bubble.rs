@@ -1,6 +1,14 @@
-use crate::entity::{Animation, Entity, EntityId, Position, Sprite, Velocity};
+use crate::charset;
+use crate::entity::{Animation, Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::environment::WATERLINE_Y;
+use crate::pool::Recycle;
+use crate::rng::RngHandle;
+use crate::timestep;
 use ratatui::layout::Rect;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+
+/// Replacements for the bubble frames with `--charset unicode`
+const UNICODE_GLYPHS: &[(char, char)] = &[('.', '∘'), ('o', '○'), ('O', '🫧')];
 
 /// A bubble entity that rises from fish to the water surface
 #[derive(Debug)]
@@ -10,20 +18,27 @@ pub struct Bubble {
     velocity: Velocity,
     animation: Animation,
     alive: bool,
-    created_at: Instant,
+    popped_at_surface: bool,
+    pop_y: f32,
+    age: Duration,
 }
 
 impl Bubble {
+    /// One cyan animation frame
+    fn frame(art: &str) -> Sprite {
+        Sprite::from_ascii_art(&charset::substitute(art, UNICODE_GLYPHS), Some("C"))
+    }
+
     /// Create a new bubble at the specified position
-    pub fn new(id: EntityId, position: Position) -> Self {
+    pub fn new(id: EntityId, position: Position, rng: &mut RngHandle) -> Self {
         // Create the 5-frame bubble animation: '.', 'o', 'O', 'O', 'O'
         // Use cyan color mask for all frames
         let frames = vec![
-            Sprite::from_ascii_art(".", Some("C")),
-            Sprite::from_ascii_art("o", Some("C")),
-            Sprite::from_ascii_art("O", Some("C")),
-            Sprite::from_ascii_art("O", Some("C")),
-            Sprite::from_ascii_art("O", Some("C")),
+            Self::frame("."),
+            Self::frame("o"),
+            Self::frame("O"),
+            Self::frame("O"),
+            Self::frame("O"),
         ];
 
         let animation = Animation::new(
@@ -32,28 +47,32 @@ impl Bubble {
             false,                      // Don't loop - bubble grows then stays at max size
         );
 
-        // Bubbles rise with slight random variation
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        let horizontal_drift = rng.gen_range(-0.1..0.1);
-        let velocity = Velocity::new(horizontal_drift, -1.0); // Rise upward with slight horizontal drift
-
         Self {
             id,
             position,
-            velocity,
+            velocity: Self::rising(rng),
             animation,
             alive: true,
-            created_at: Instant::now(),
+            popped_at_surface: false,
+            pop_y: WATERLINE_Y,
+            age: Duration::ZERO,
         }
     }
 
+    /// Rise upward with slight random horizontal drift
+    fn rising(rng: &mut RngHandle) -> Velocity {
+        use rand::Rng;
+        let horizontal_drift = rng.gen_range(-0.1..0.1);
+        Velocity::new(horizontal_drift, -1.0)
+    }
+
     /// Create a bubble from a fish position with direction awareness
     pub fn from_fish_position(
         id: EntityId,
         fish_pos: Position,
         fish_sprite_width: u16,
         fish_moving_right: bool,
+        rng: &mut RngHandle,
     ) -> Self {
         // Position bubble at fish's mouth/front based on direction
         let bubble_x = if fish_moving_right {
@@ -68,27 +87,46 @@ impl Bubble {
         let bubble_depth = fish_pos.depth.saturating_sub(1);
 
         let bubble_position = Position::new(bubble_x, bubble_y, bubble_depth);
-        Self::new(id, bubble_position)
+        Self::new(id, bubble_position, rng)
     }
 
     /// Check if bubble has reached water surface and should pop
     fn check_surface_collision(&mut self) {
-        // Water surface is around Y=5-9 based on original code
-        let water_surface_y = 9.0;
-        if self.position.y <= water_surface_y {
+        // Pops on the waterline (row 9 at mid tide, like the original)
+        if self.position.y <= self.pop_y {
             self.alive = false;
+            self.popped_at_surface = true;
         }
     }
 
     /// Check if bubble is too old and should disappear
     fn check_age_limit(&mut self) {
         // Bubbles live for maximum 30 seconds (very generous)
-        if self.created_at.elapsed() > Duration::from_secs(30) {
+        if self.age > Duration::from_secs(30) {
             self.alive = false;
         }
     }
 }
 
+impl Recycle for Bubble {
+    type Start = Position;
+
+    fn create(id: EntityId, position: Position, rng: &mut RngHandle) -> Self {
+        Self::new(id, position, rng)
+    }
+
+    fn restart(&mut self, id: EntityId, position: Position, rng: &mut RngHandle) {
+        self.id = id;
+        self.position = position;
+        self.velocity = Self::rising(rng);
+        self.animation.reset();
+        self.alive = true;
+        self.popped_at_surface = false;
+        self.pop_y = WATERLINE_Y;
+        self.age = Duration::ZERO;
+    }
+}
+
 impl Entity for Bubble {
     fn id(&self) -> EntityId {
         self.id
@@ -123,16 +161,18 @@ impl Entity for Bubble {
             return;
         }
 
+        self.age += delta_time;
+
         // Update animation
-        self.animation.update();
+        self.animation.update(delta_time);
 
         // Update position based on velocity
-        let speed_multiplier = 60.0; // Scale for 60 FPS
-        self.position.x += self.velocity.dx * delta_time.as_secs_f32() * speed_multiplier;
-        self.position.y += self.velocity.dy * delta_time.as_secs_f32() * speed_multiplier;
+        let ticks = timestep::ticks(delta_time);
+        self.position.x += self.velocity.dx * ticks;
+        self.position.y += self.velocity.dy * ticks;
 
         // Add slight buoyancy effect - bubbles accelerate upward slightly
-        self.velocity.dy -= 0.01; // Small upward acceleration
+        self.velocity.dy -= 0.01 * ticks; // Small upward acceleration
 
         // Limit maximum rise speed
         self.velocity.dy = self.velocity.dy.max(-2.0);
@@ -156,8 +196,21 @@ impl Entity for Bubble {
         self.alive = false;
     }
 
-    fn entity_type(&self) -> &'static str {
-        "bubble"
+    fn kind(&self) -> EntityKind {
+        EntityKind::Bubble
+    }
+
+    fn ride_tide(&mut self, rows: f32) {
+        self.pop_y += rows;
+    }
+
+    fn surface_pop(&self) -> Option<Position> {
+        self.popped_at_surface.then_some(self.position)
+    }
+
+    fn current_response(&self) -> f32 {
+        // Bubbles have no will of their own and drift with the full current
+        1.0
     }
 }
 
@@ -168,8 +221,12 @@ mod tests {
 
     #[test]
     fn test_bubble_creation() {
-        let position = Position::new(10.0, 15.0, depth::random_fish_depth());
-        let bubble = Bubble::new(1, position);
+        let position = Position::new(
+            10.0,
+            15.0,
+            depth::random_fish_depth(&mut RngHandle::default()),
+        );
+        let bubble = Bubble::new(1, position, &mut RngHandle::default());
 
         assert!(bubble.is_alive());
         assert_eq!(bubble.entity_type(), "bubble");
@@ -181,8 +238,12 @@ mod tests {
     #[test]
     fn test_bubble_from_fish() {
         use crate::depth;
-        let fish_pos = Position::new(20.0, 12.0, depth::random_fish_depth());
-        let bubble = Bubble::from_fish_position(1, fish_pos, 6, true); // Fish moving right
+        let fish_pos = Position::new(
+            20.0,
+            12.0,
+            depth::random_fish_depth(&mut RngHandle::default()),
+        );
+        let bubble = Bubble::from_fish_position(1, fish_pos, 6, true, &mut RngHandle::default()); // Fish moving right
 
         // Bubble should be positioned at fish's mouth area
         assert_eq!(bubble.position().x, 26.0); // 20 + 6 (right side)
@@ -193,32 +254,45 @@ mod tests {
     #[test]
     fn test_bubble_animation() {
         use crate::depth;
-        let position = Position::new(10.0, 15.0, depth::random_fish_depth());
-        let bubble = Bubble::new(1, position);
+        let position = Position::new(
+            10.0,
+            15.0,
+            depth::random_fish_depth(&mut RngHandle::default()),
+        );
+        let bubble = Bubble::new(1, position, &mut RngHandle::default());
 
         let initial_sprite = bubble.get_current_sprite();
-        assert_eq!(initial_sprite.lines[0], "."); // Should start with small bubble
+        assert_eq!(initial_sprite.lines()[0], "."); // Should start with small bubble
     }
 
     #[test]
     fn test_bubble_surface_collision() {
         use crate::depth;
-        let position = Position::new(10.0, 8.0, depth::random_fish_depth()); // Near surface
-        let mut bubble = Bubble::new(1, position);
+        let position = Position::new(
+            10.0,
+            8.0,
+            depth::random_fish_depth(&mut RngHandle::default()),
+        ); // Near surface
+        let mut bubble = Bubble::new(1, position, &mut RngHandle::default());
 
         bubble.update(Duration::from_millis(100), Rect::new(0, 0, 80, 24));
 
         // Bubble should die when it reaches the surface
         if bubble.position().y <= 9.0 {
             assert!(!bubble.is_alive());
+            assert!(bubble.surface_pop().is_some());
         }
     }
 
     #[test]
     fn test_bubble_movement() {
         use crate::depth;
-        let position = Position::new(10.0, 15.0, depth::random_fish_depth());
-        let mut bubble = Bubble::new(1, position);
+        let position = Position::new(
+            10.0,
+            15.0,
+            depth::random_fish_depth(&mut RngHandle::default()),
+        );
+        let mut bubble = Bubble::new(1, position, &mut RngHandle::default());
 
         let initial_y = bubble.position().y;
         bubble.update(Duration::from_millis(16), Rect::new(0, 0, 80, 24)); // ~60 FPS
@@ -1,6 +1,18 @@
-use crate::entity::{Entity, EntityId, Position, Sprite, Velocity};
+use crate::charset;
+use crate::clock::Instant;
+use crate::entity::{Entity, EntityId, EntityKind, Position, Sprite, Velocity};
 use ratatui::layout::Rect;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+
+/// Box drawing replacements for the castle art with `--charset unicode`
+const UNICODE_GLYPHS: &[(char, char)] = &[
+    ('|', '│'),
+    ('/', '╱'),
+    ('\\', '╲'),
+    ('^', '▲'),
+    ('=', '═'),
+    ('~', '≈'),
+];
 
 /// A castle entity that serves as background decoration
 #[derive(Debug, Clone)]
@@ -15,7 +27,7 @@ pub struct Castle {
 impl Castle {
     /// Create a new castle positioned at bottom-right of screen
     pub fn new(id: EntityId, screen_bounds: Rect) -> Self {
-        let castle_sprite = Self::create_castle_sprite();
+        let castle_sprite = Self::sprite();
 
         // Position at bottom-right (original: width-32, height-13)
         let x = screen_bounds.width.saturating_sub(32) as f32;
@@ -34,20 +46,25 @@ impl Castle {
 
     /// Create a new castle with specific position
     pub fn new_at_position(id: EntityId, x: f32, y: f32) -> Self {
-        let castle_sprite = Self::create_castle_sprite();
+        Self::new_with_sprite(id, x, y, Self::sprite())
+    }
+
+    /// Create a castle at a specific position that looks like `sprite`
+    /// (one of the other background scenes)
+    pub fn new_with_sprite(id: EntityId, x: f32, y: f32, sprite: Sprite) -> Self {
         let position = Position::new(x, y, crate::depth::CASTLE);
 
         Self {
             id,
             position,
-            sprite: castle_sprite,
+            sprite,
             alive: true,
             _created_at: Instant::now(),
         }
     }
 
     /// Create the castle sprite with ASCII art and color mask
-    fn create_castle_sprite() -> Sprite {
+    pub fn sprite() -> Sprite {
         // Castle ASCII art from original Perl implementation
         let castle_image = r#"               T~~
                |
@@ -71,13 +88,15 @@ impl Castle {
            y       y
 
 
-
               yyy
              yy yy
             y y y y
             yyyyyyy"#;
 
-        Sprite::from_ascii_art(castle_image, Some(castle_mask))
+        Sprite::from_ascii_art(
+            &charset::substitute(castle_image, UNICODE_GLYPHS),
+            Some(castle_mask),
+        )
     }
 
     /// Get the castle width (for positioning calculations)
@@ -139,7 +158,16 @@ impl Entity for Castle {
 
     fn update(&mut self, _delta_time: Duration, _screen_bounds: Rect) {
         // Castle is static and doesn't need updates
-        // (Screen repositioning is handled externally by the app)
+    }
+
+    fn on_resize(&mut self, _old: Rect, new: Rect) {
+        // Back to the bottom-right corner; ultrawide layouts are spread
+        // out again by the spawner
+        self.reposition_for_screen(new);
+    }
+
+    fn is_static(&self) -> bool {
+        true
     }
 
     fn is_alive(&self) -> bool {
@@ -150,8 +178,8 @@ impl Entity for Castle {
         self.alive = false;
     }
 
-    fn entity_type(&self) -> &'static str {
-        "castle"
+    fn kind(&self) -> EntityKind {
+        EntityKind::Castle
     }
 }
 
@@ -178,14 +206,14 @@ mod tests {
         let castle = Castle::new(1, Rect::new(0, 0, 80, 24));
         let sprite = castle.get_current_sprite();
 
-        assert!(!sprite.lines.is_empty());
-        assert_eq!(sprite.lines.len(), 12); // Castle should have 12 lines
+        assert!(!sprite.lines().is_empty());
+        assert_eq!(sprite.lines().len(), 12); // Castle should have 12 lines
 
         // Check that first line contains the castle top
-        assert!(sprite.lines[0].contains("T~~"));
+        assert!(sprite.lines()[0].contains("T~~"));
 
         // Check that last line contains the castle base
-        assert!(sprite.lines.last().unwrap().contains("_______"));
+        assert!(sprite.lines().last().unwrap().contains("_______"));
     }
 
     #[test]
@@ -0,0 +1,172 @@
+use crate::clock;
+use crate::depth;
+use crate::entity::{Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// Row the clock's top sits on, in the sky above the water
+const CLOCK_Y: f32 = 1.0;
+
+/// Seven-segment digits, three rows of three columns each
+const DIGITS: [[&str; 3]; 10] = [
+    [" _ ", "| |", "|_|"],
+    ["   ", "  |", "  |"],
+    [" _ ", " _|", "|_ "],
+    [" _ ", " _|", " _|"],
+    ["   ", "|_|", "  |"],
+    [" _ ", "|_ ", " _|"],
+    [" _ ", "|_ ", "|_|"],
+    [" _ ", "  |", "  |"],
+    [" _ ", "|_|", "|_|"],
+    [" _ ", "|_|", " _|"],
+];
+
+/// Shown for each digit when the time can't be read
+const UNKNOWN: [&str; 3] = ["   ", " _ ", "   "];
+
+/// A large digital clock floating in the sky band, showing the local time
+#[derive(Debug, Clone)]
+pub struct Clock {
+    id: EntityId,
+    position: Position,
+    time: Option<(u8, u8)>,
+    sprite: Sprite,
+    alive: bool,
+}
+
+impl Clock {
+    /// A clock centred over a screen of `screen_bounds`
+    pub fn new(id: EntityId, screen_bounds: Rect) -> Self {
+        let mut clock = Self {
+            id,
+            position: Position::new(0.0, CLOCK_Y, depth::GUI),
+            time: None,
+            sprite: Self::sprite(None),
+            alive: true,
+        };
+        clock.set_time(clock::local_time());
+        clock.centre_in(screen_bounds);
+        clock
+    }
+
+    /// Show `time` (hours, minutes), or dashes for `None`
+    pub fn set_time(&mut self, time: Option<(u8, u8)>) {
+        if time != self.time {
+            self.time = time;
+            self.sprite = Self::sprite(time);
+        }
+    }
+
+    fn centre_in(&mut self, screen_bounds: Rect) {
+        let width = self.sprite.get_bounding_box().0;
+        self.position.x = (screen_bounds.width.saturating_sub(width) / 2) as f32;
+    }
+
+    /// `HH:MM` in seven-segment digits
+    fn sprite(time: Option<(u8, u8)>) -> Sprite {
+        let glyphs: Vec<[&str; 3]> = match time {
+            Some((hours, minutes)) => [hours / 10, hours % 10, minutes / 10, minutes % 10]
+                .map(|digit| DIGITS[digit as usize % 10])
+                .to_vec(),
+            None => vec![UNKNOWN; 4],
+        };
+        let colon = [" ", ".", "."];
+        let art: Vec<String> = (0..3)
+            .map(|row| {
+                let digit = |i: usize| glyphs[i][row];
+                format!(
+                    "{} {} {} {} {}",
+                    digit(0),
+                    digit(1),
+                    colon[row],
+                    digit(2),
+                    digit(3)
+                )
+            })
+            .collect();
+        let art = art.join("\n");
+        let mask: String = art
+            .chars()
+            .map(|ch| if ch == ' ' || ch == '\n' { ch } else { 'W' })
+            .collect();
+        Sprite::from_ascii_art(&art, Some(&mask))
+    }
+}
+
+impl Entity for Clock {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        Velocity::zero()
+    }
+
+    fn set_velocity(&mut self, _velocity: Velocity) {
+        // The clock hangs in the sky
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    fn update(&mut self, _delta_time: Duration, _world_bounds: Rect) {
+        self.set_time(clock::local_time());
+    }
+
+    fn on_resize(&mut self, _old: Rect, new: Rect) {
+        self.centre_in(new);
+    }
+
+    fn glows(&self) -> bool {
+        true
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Clock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_shows_time_centred() {
+        let mut clock = Clock::new(1, Rect::new(0, 0, 80, 24));
+        clock.set_time(Some((13, 57)));
+        assert_eq!(
+            clock.get_current_sprite().lines(),
+            vec![
+                "     _     _   _ ",
+                "  |  _| . |_    |",
+                "  |  _| .  _|   |"
+            ]
+        );
+        assert_eq!(clock.position().y, CLOCK_Y);
+        assert_eq!(clock.depth(), depth::GUI);
+
+        clock.on_resize(Rect::new(0, 0, 80, 24), Rect::new(0, 0, 41, 24));
+        assert_eq!(clock.position().x, 12.0);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::depth;
+use crate::entity::{
+    Animation, Direction, Entity, EntityId, EntityKind, Position, Sprite, Velocity,
+};
+use crate::timestep;
+use ratatui::layout::Rect;
+use std::f32::consts::PI;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Columns per tick
+const SPEED: f32 = 0.5;
+
+/// Columns covered by one leap out of the water and back
+const LEAP_LENGTH: f32 = 24.0;
+
+/// Rows the dolphin rises at the top of a leap
+const LEAP_HEIGHT: f32 = 4.0;
+
+/// Rows of dolphin above the waterline while swimming at the surface
+const ABOVE_WATER: f32 = 2.0;
+
+/// How long each tail frame is shown
+const FRAME_DURATION: Duration = Duration::from_millis(250);
+
+/// Facing right, tail down and tail up
+const DOLPHIN_ART: [&str; 2] = [
+    "        ,\n      __)\\_\n(\\_.-'    a`-.\n(/~~````(/~^^`",
+    "        ,\n(\\__  __)\\_\n(/~.''    a`-.\n    ````\\)~^^`",
+];
+
+/// A dolphin leaping along the water surface (a pomodoro celebration)
+#[derive(Debug, Clone)]
+pub struct Dolphin {
+    id: EntityId,
+    position: Position,
+    velocity: Velocity,
+    animation: Animation,
+    /// Row the dolphin swims at between leaps
+    surface_y: f32,
+    /// Columns travelled since it set off, leaps included
+    travelled: f32,
+    alive: bool,
+}
+
+impl Dolphin {
+    /// A dolphin heading across `screen_bounds`, `lag` columns behind the
+    /// edge it comes in from, swimming at `waterline`
+    pub fn new(
+        id: EntityId,
+        screen_bounds: Rect,
+        direction: Direction,
+        waterline: f32,
+        lag: f32,
+    ) -> Self {
+        let frames = Self::frames(&direction);
+        let width = frames[0].get_bounding_box().0 as f32;
+        let (x, dx) = match direction {
+            Direction::Right => (-width - lag, SPEED),
+            Direction::Left => (screen_bounds.width as f32 + lag, -SPEED),
+        };
+        let surface_y = waterline - ABOVE_WATER;
+        Self {
+            id,
+            position: Position::new(x, surface_y, depth::WATER_GAP2),
+            velocity: Velocity::new(dx, 0.0),
+            animation: Animation::new(frames, FRAME_DURATION, true),
+            surface_y,
+            travelled: 0.0,
+            alive: true,
+        }
+    }
+
+    /// Both tail frames for a facing, composed the first time they're
+    /// needed
+    fn frames(direction: &Direction) -> Vec<Sprite> {
+        static FRAMES: OnceLock<[Vec<Sprite>; 2]> = OnceLock::new();
+        let [right, left] = FRAMES.get_or_init(|| {
+            let right: Vec<Sprite> = DOLPHIN_ART
+                .iter()
+                .map(|art| {
+                    let mask: String = art
+                        .chars()
+                        .map(|ch| match ch {
+                            ' ' | '\n' => ch,
+                            'a' => 'W',
+                            _ => 'B',
+                        })
+                        .collect();
+                    Sprite::from_ascii_art(art, Some(&mask))
+                })
+                .collect();
+            let left = right.iter().map(Sprite::mirrored).collect();
+            [right, left]
+        });
+        match direction {
+            Direction::Right => right.clone(),
+            Direction::Left => left.clone(),
+        }
+    }
+
+    /// Rows above the surface row at this point of the leaps
+    fn height(&self) -> f32 {
+        (self.travelled / LEAP_LENGTH * PI).sin().abs() * LEAP_HEIGHT
+    }
+}
+
+impl Entity for Dolphin {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        self.velocity
+    }
+
+    fn set_velocity(&mut self, velocity: Velocity) {
+        self.velocity = velocity;
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        self.animation.get_current_sprite()
+    }
+
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
+        self.animation.update(delta_time);
+        let step = self.velocity.dx * timestep::ticks(delta_time);
+        self.position.x += step;
+        self.travelled += step.abs();
+        self.position.y = (self.surface_y - self.height()).round();
+
+        // Gone once past the far edge
+        let width = self.get_current_sprite().get_bounding_box().0 as f32;
+        let gone = if self.velocity.dx > 0.0 {
+            self.position.x > world_bounds.width as f32
+        } else {
+            self.position.x < -width
+        };
+        if gone {
+            self.alive = false;
+        }
+    }
+
+    fn ride_tide(&mut self, rows: f32) {
+        self.surface_y += rows;
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Dolphin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dolphin_leaps_across_and_leaves() {
+        let bounds = Rect::new(0, 0, 40, 24);
+        let mut dolphin = Dolphin::new(1, bounds, Direction::Left, 9.0, 0.0);
+        assert_eq!(dolphin.position().x, 40.0);
+        assert_eq!(dolphin.get_current_sprite().lines()[0].trim(), ",");
+
+        // Half a leap along it is at the top of the arc
+        let ticks = (LEAP_LENGTH / 2.0 / SPEED) as u32;
+        dolphin.update(timestep::STEP * ticks, bounds);
+        assert_eq!(dolphin.position().y, 9.0 - ABOVE_WATER - LEAP_HEIGHT);
+
+        dolphin.update(timestep::STEP * ticks, bounds);
+        assert_eq!(dolphin.position().y, 9.0 - ABOVE_WATER);
+        assert!(dolphin.is_alive());
+
+        dolphin.update(Duration::from_secs(5), bounds);
+        assert!(!dolphin.is_alive());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::entity::{Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::pool::Recycle;
+use crate::rng::RngHandle;
+use crate::sprite;
+use crate::timestep;
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// Top row of the water surface at mid tide, where falling droplets land
+pub const SURFACE_Y: f32 = 5.0;
+
+/// Downward acceleration (rows per tick, per tick)
+const GRAVITY: f32 = 0.02;
+
+/// Droplets never outlive this many seconds, even if they miss the water
+const MAX_LIFETIME_SECS: f32 = 4.0;
+
+/// A water droplet thrown out of a whale spout that arcs back to the surface
+#[derive(Debug, Clone)]
+pub struct Droplet {
+    id: EntityId,
+    position: Position,
+    velocity: Velocity,
+    sprite: Sprite,
+    surface_y: f32,
+    age: f32,
+    alive: bool,
+}
+
+impl Droplet {
+    /// Create a droplet with an initial throw velocity
+    pub fn new(id: EntityId, position: Position, velocity: Velocity) -> Self {
+        Self {
+            id,
+            position,
+            velocity,
+            sprite: sprite!(".", "C"),
+            surface_y: SURFACE_Y,
+            age: 0.0,
+            alive: true,
+        }
+    }
+}
+
+impl Recycle for Droplet {
+    type Start = (Position, Velocity);
+
+    fn create(id: EntityId, (position, velocity): Self::Start, _rng: &mut RngHandle) -> Self {
+        Self::new(id, position, velocity)
+    }
+
+    fn restart(&mut self, id: EntityId, (position, velocity): Self::Start, _rng: &mut RngHandle) {
+        self.id = id;
+        self.position = position;
+        self.velocity = velocity;
+        self.surface_y = SURFACE_Y;
+        self.age = 0.0;
+        self.alive = true;
+    }
+}
+
+impl Entity for Droplet {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        self.velocity
+    }
+
+    fn set_velocity(&mut self, velocity: Velocity) {
+        self.velocity = velocity;
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    fn is_particle(&self) -> bool {
+        true
+    }
+
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
+        if !self.alive {
+            return;
+        }
+
+        let ticks = timestep::ticks(delta_time);
+        self.position.x += self.velocity.dx * ticks;
+        self.position.y += self.velocity.dy * ticks;
+        self.velocity.dy += GRAVITY * ticks;
+        self.age += delta_time.as_secs_f32();
+
+        // Gone once it falls back into the water (or wanders off)
+        let landed = self.velocity.dy > 0.0 && self.position.y >= self.surface_y;
+        let outside = self.position.x < world_bounds.x as f32
+            || self.position.x >= world_bounds.right() as f32;
+        if landed || outside || self.age >= MAX_LIFETIME_SECS {
+            self.alive = false;
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn ride_tide(&mut self, rows: f32) {
+        self.surface_y += rows;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Droplet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_droplet_arcs_back_to_surface() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut droplet = Droplet::new(1, Position::new(20.0, 2.0, 5), Velocity::new(0.3, -0.4));
+
+        let mut highest = droplet.position().y;
+        let mut steps = 0;
+        while droplet.is_alive() && steps < 1000 {
+            droplet.update(Duration::from_millis(33), bounds);
+            highest = highest.min(droplet.position().y);
+            steps += 1;
+        }
+
+        assert!(!droplet.is_alive());
+        assert!(highest < 2.0, "droplet should rise before falling");
+        assert!(droplet.position().y >= SURFACE_Y);
+        assert!(droplet.position().x > 20.0);
+    }
+
+    #[test]
+    fn test_droplet_dies_off_world() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut droplet = Droplet::new(1, Position::new(79.5, 2.0, 5), Velocity::new(1.0, -0.4));
+        droplet.update(Duration::from_millis(33), bounds);
+        assert!(!droplet.is_alive());
+    }
+}
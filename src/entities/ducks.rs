@@ -0,0 +1,156 @@
+use crate::depth;
+use crate::entity::{
+    Animation, Direction, Entity, EntityId, EntityKind, Position, Sprite, Velocity,
+};
+use crate::rng::RngHandle;
+use crate::timestep;
+use rand::Rng;
+use ratatui::layout::Rect;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Columns per tick
+const SPEED: f32 = 1.0;
+
+/// How long each frame is shown
+const FRAME_DURATION: Duration = Duration::from_millis(250);
+
+/// Three ducks facing right, each frame with a different one quacking
+const DUCKS_ART: [&str; 3] = [
+    "\n      _??????????_??????????_\n,____(')=??,____(')=??,____(')<\n \\~~= ')????\\~~= ')????\\~~= ')",
+    "\n      _??????????_??????????_\n,____(')=??,____(')<??,____(')=\n \\~~= ')????\\~~= ')????\\~~= ')",
+    "\n      _??????????_??????????_\n,____(')<??,____(')=??,____(')=\n \\~~= ')????\\~~= ')????\\~~= ')",
+];
+
+const DUCKS_MASK: &str =
+    "\n      g          g          g\nwwwwwgcgy  wwwwwgcgy  wwwwwgcgy\n wwww Ww    wwww Ww    wwww Ww";
+
+/// A row of ducks paddling along the water surface (as in the original)
+#[derive(Debug, Clone)]
+pub struct Ducks {
+    id: EntityId,
+    position: Position,
+    velocity: Velocity,
+    animation: Animation,
+    alive: bool,
+}
+
+impl Ducks {
+    /// Ducks setting off from either side of `screen_bounds`
+    pub fn new(id: EntityId, screen_bounds: Rect, rng: &mut RngHandle) -> Self {
+        let direction = if rng.gen_bool(0.5) {
+            Direction::Right
+        } else {
+            Direction::Left
+        };
+        let frames = Self::frames(&direction);
+        let width = frames[0].get_bounding_box().0 as f32;
+        let (x, dx) = match direction {
+            Direction::Right => (-width, SPEED),
+            Direction::Left => (screen_bounds.width as f32 - 2.0, -SPEED),
+        };
+        Self {
+            id,
+            position: Position::new(x, 5.0, depth::WATER_GAP3),
+            velocity: Velocity::new(dx, 0.0),
+            animation: Animation::new(frames, FRAME_DURATION, true),
+            alive: true,
+        }
+    }
+
+    /// The frames for a facing, composed the first time they're needed
+    fn frames(direction: &Direction) -> Vec<Sprite> {
+        static FRAMES: OnceLock<[Vec<Sprite>; 2]> = OnceLock::new();
+        let [right, left] = FRAMES.get_or_init(|| {
+            let right: Vec<Sprite> = DUCKS_ART
+                .iter()
+                .map(|art| Sprite::from_ascii_art(art, Some(DUCKS_MASK)))
+                .collect();
+            let left = right.iter().map(Sprite::mirrored).collect();
+            [right, left]
+        });
+        match direction {
+            Direction::Right => right.clone(),
+            Direction::Left => left.clone(),
+        }
+    }
+}
+
+impl Entity for Ducks {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        self.velocity
+    }
+
+    fn set_velocity(&mut self, velocity: Velocity) {
+        self.velocity = velocity;
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        self.animation.get_current_sprite()
+    }
+
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
+        self.animation.update(delta_time);
+        self.position.x += self.velocity.dx * timestep::ticks(delta_time);
+
+        let width = self.get_current_sprite().get_bounding_box().0 as f32;
+        if self.position.x > world_bounds.right() as f32 || self.position.x + width < 0.0 {
+            self.alive = false;
+        }
+    }
+
+    fn ride_tide(&mut self, rows: f32) {
+        self.position.y += rows;
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Ducks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ducks_paddle_across_and_leave() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut ducks = Ducks::new(1, bounds, &mut RngHandle::default());
+        assert_eq!(ducks.position().y, 5.0);
+        assert_eq!(ducks.get_current_sprite().lines().len(), 4);
+        let facing_right = ducks.velocity().dx > 0.0;
+        assert_eq!(
+            ducks.get_current_sprite().lines()[2].ends_with('<'),
+            facing_right
+        );
+
+        ducks.update(timestep::STEP * 10, bounds);
+        assert!(ducks.is_alive());
+        ducks.update(Duration::from_secs(3), bounds);
+        assert!(!ducks.is_alive());
+    }
+}
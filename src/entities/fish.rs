@@ -1,8 +1,48 @@
+use crate::behavior::{self, Stamina};
+use crate::charset::{self, Charset};
+use crate::collision::{CollisionLayers, Contact, ContactKind};
 use crate::depth;
-use crate::entity::{DeathCallback, Direction, Entity, EntityId, Position, Sprite, Velocity};
+use crate::entities::hook;
+use crate::entity::{Direction, Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::rng::RngHandle;
+use crate::scheduler::{Population, RespawnPolicy};
+use crate::timestep;
+use crate::world;
+use rand::seq::SliceRandom;
 use rand::Rng;
 use ratatui::{layout::Rect, style::Color};
-use std::time::{Duration, Instant};
+use std::time::Duration;
+
+/// Build a fish sprite with `label` written over it, the label and the
+/// body in `color` and the eye white, both centred on the wider of the two
+fn labelled_sprite((art, mask): (&str, &str), label: &str, color: Color) -> Sprite {
+    use unicode_segmentation::UnicodeSegmentation;
+    use unicode_width::UnicodeWidthStr;
+
+    let art_width = art.lines().map(UnicodeWidthStr::width).max().unwrap_or(0);
+    let label_width = label.width();
+    let width = art_width.max(label_width);
+    let indent = |used: usize| " ".repeat((width - used) / 2);
+
+    let (label_indent, art_indent) = (indent(label_width), indent(art_width));
+    let mut lines = vec![format!("{label_indent}{label}")];
+    lines.extend(art.lines().map(|line| format!("{art_indent}{line}")));
+    let mut masks = vec![format!(
+        "{label_indent}{}",
+        "1".repeat(label.graphemes(true).count())
+    )];
+    masks.extend(
+        mask.replace('4', "W")
+            .lines()
+            .map(|line| format!("{art_indent}{line}")),
+    );
+
+    Sprite::from_ascii_art_with_palette(
+        &lines.join("\n"),
+        Some(&masks.join("\n")),
+        [color; crate::palette::PALETTE_SIZE],
+    )
+}
 
 /// Fish species category (new vs old from original Perl)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -11,8 +51,39 @@ pub enum FishCategory {
     Old,
 }
 
-/// Fish species with their ASCII art and colors
-/// Matches all 12 species from original asciiquarium.pl
+/// Build a randomly coloured fish sprite; the eye (mask digit 4) is
+/// always bold white, as in the original add_fish
+fn fish_sprite(art: &str, mask: Option<&str>, rng: &mut RngHandle) -> Sprite {
+    let mask = mask.map(|mask| mask.replace('4', "W"));
+    Sprite::from_ascii_art_with_random_colors(art, mask.as_deref(), rng)
+}
+
+/// How a species swims: its speed range and preferred part of the water
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeciesTraits {
+    /// Cruising speed range (columns per tick)
+    pub speed: (f32, f32),
+    /// Preferred depth band as fractions of the water column
+    /// (0.0 = just below the waterline, 1.0 = the floor)
+    pub band: (f32, f32),
+}
+
+impl SpeciesTraits {
+    const fn new(speed: (f32, f32), band: (f32, f32)) -> Self {
+        Self { speed, band }
+    }
+
+    /// Row for a fish at position `t` (0.0-1.0) through its band, given
+    /// the first and last rows it may occupy
+    pub fn band_row(&self, t: f32, top: u16, bottom: u16) -> f32 {
+        let column = bottom.saturating_sub(top) as f32;
+        let fraction = self.band.0 + (self.band.1 - self.band.0) * t;
+        (top as f32 + column * fraction).round()
+    }
+}
+
+/// Fish species, matching all 12 species from original asciiquarium.pl;
+/// their art, colours and habits are rows of [`SPECIES`]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FishSpecies {
     // NEW FISH (4 species) - Added in asciiquarium 1.1
@@ -32,431 +103,617 @@ pub enum FishSpecies {
     OldRounded,    // Rounded small fish with diagonal body (\/ o\)
 }
 
-impl FishSpecies {
-    /// Get category of this fish species
-    pub fn category(&self) -> FishCategory {
-        match self {
-            FishSpecies::NewSmall1
-            | FishSpecies::NewSmall2
-            | FishSpecies::NewMedium1
-            | FishSpecies::NewMedium2 => FishCategory::New,
-
-            FishSpecies::OldFancy
-            | FishSpecies::OldSimple
-            | FishSpecies::OldWavy
-            | FishSpecies::OldTiny
-            | FishSpecies::OldCommaLarge
-            | FishSpecies::OldAngledFin
-            | FishSpecies::OldCommaSmall
-            | FishSpecies::OldRounded => FishCategory::Old,
-        }
-    }
-
-    /// Get all new fish species
-    pub fn new_species() -> &'static [FishSpecies] {
-        &[
-            FishSpecies::NewSmall1,
-            FishSpecies::NewSmall2,
-            FishSpecies::NewMedium1,
-            FishSpecies::NewMedium2,
-        ]
-    }
-
-    /// Get all old fish species
-    pub fn old_species() -> &'static [FishSpecies] {
-        &[
-            FishSpecies::OldFancy,
-            FishSpecies::OldSimple,
-            FishSpecies::OldWavy,
-            FishSpecies::OldTiny,
-            FishSpecies::OldCommaLarge,
-            FishSpecies::OldAngledFin,
-            FishSpecies::OldCommaSmall,
-            FishSpecies::OldRounded,
-        ]
-    }
-
-    /// Get a random fish species following original logic:
-    /// - 25% chance for new fish (int(rand(12)) > 8, meaning 9,10,11 out of 0-11)
-    /// - 75% chance for old fish
-    /// - classic_mode flag disables new fish
-    pub fn random(classic_mode: bool) -> Self {
-        let mut rng = rand::thread_rng();
+/// Rough size of a species
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeClass {
+    /// Three rows or fewer
+    Tiny,
+    Small,
+    /// Wide or tall enough to stand out
+    Medium,
+}
 
-        if classic_mode {
-            // Classic mode: only old fish
-            let old = Self::old_species();
-            old[rng.gen_range(0..old.len())]
-        } else {
-            // Modern mode: 25% new, 75% old (matching original int(rand(12)) > 8)
-            if rng.gen_range(0..12) > 8 {
-                // New fish (9, 10, 11 = 3 out of 12 = 25%)
-                let new = Self::new_species();
-                new[rng.gen_range(0..new.len())]
-            } else {
-                // Old fish (0-8 = 9 out of 12 = 75%)
-                let old = Self::old_species();
-                old[rng.gen_range(0..old.len())]
-            }
-        }
-    }
+/// Everything that sets a species apart; one row of [`SPECIES`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FishSpeciesInfo {
+    pub species: FishSpecies,
+    pub category: FishCategory,
+    pub size: SizeClass,
+    /// Art and colour mask facing right
+    pub right: (&'static str, &'static str),
+    /// Art and colour mask facing left
+    pub left: (&'static str, &'static str),
+    pub traits: SpeciesTraits,
+    /// Range of seconds between two bubbles
+    pub bubble_secs: (f32, f32),
+    /// Chance of being picked relative to the rest of its category
+    pub rarity: f32,
+    /// Colour the species is known by (sprite colours are randomized, as
+    /// in the original)
+    pub base_color: Color,
+    /// Emoji drawn instead of the art with `--charset unicode` (only the
+    /// smallest fish, whose art is about emoji-sized anyway)
+    pub emoji: Option<&'static str>,
+}
 
-    /// Get the sprites for this fish species (right-facing, left-facing)
-    pub fn get_sprites(&self) -> (Sprite, Sprite) {
-        match self {
-            // NEW FISH
-            FishSpecies::NewSmall1 => {
-                let right_art = r#"   \
+/// Seconds between bubbles for every species in the original
+const BUBBLE_SECS: (f32, f32) = (3.0, 10.0);
+
+/// Rows per tick a fish rises or sinks towards food
+const FEED_SWIM: f32 = 0.1;
+
+/// Columns food must be ahead of or behind a fish's middle before it turns
+/// to face it
+const FEED_TURN: f32 = 2.0;
+
+/// Every species, in [`FishSpecies`] order: small darting fish stay near
+/// the top, big slow ones keep to the bottom
+pub const SPECIES: [FishSpeciesInfo; 12] = [
+    FishSpeciesInfo {
+        species: FishSpecies::NewSmall1,
+        category: FishCategory::New,
+        size: SizeClass::Small,
+        right: (
+            r#"   \
   / \
 >=_('>
   \_/
-   /"#;
-                let right_mask = r#"   1
+   /"#,
+            r#"   1
   1 1
 663745
   111
-   3"#;
-
-                let left_art = r#"  /
+   3"#,
+        ),
+        left: (
+            r#"  /
  / \
 <')_=<
  \_/
-  \"#;
-                let left_mask = r#"  2
+  \"#,
+            r#"  2
  111
 547366
  111
-  3"#;
-
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
-            }
-            FishSpecies::NewSmall2 => {
-                let right_art = r#"     ,
+  3"#,
+        ),
+        traits: SpeciesTraits::new((1.0, 2.2), (0.0, 0.5)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Yellow,
+        emoji: None,
+    },
+    FishSpeciesInfo {
+        species: FishSpecies::NewSmall2,
+        category: FishCategory::New,
+        size: SizeClass::Medium,
+        right: (
+            r#"     ,
      \}\
 \  .'  `\
 \}\<   ( 6>
 /  `,  .'
      \}/
-     '"#;
-                let right_mask = r#"     2
+     '"#,
+            r#"     2
      22
 6  11  11
 661   7 45
 6  11  11
      33
-     3"#;
-
-                let left_art = r#"    ,
+     3"#,
+        ),
+        left: (
+            r#"    ,
    /\{
  /'  `.  /
 <6 )   >\{\{
  `.  ,'  \
    \{
-    `"#;
-                let left_mask = r#"    2
+    `"#,
+            r#"    2
    22
  11  11  6
 54 7   166
  11  11  6
    33
-    3"#;
-
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
-            }
-            FishSpecies::NewMedium1 => {
-                let right_art = r#"            \\'`.
+    3"#,
+        ),
+        traits: SpeciesTraits::new((0.8, 1.8), (0.1, 0.6)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Cyan,
+        emoji: None,
+    },
+    FishSpeciesInfo {
+        species: FishSpecies::NewMedium1,
+        category: FishCategory::New,
+        size: SizeClass::Medium,
+        right: (
+            r#"            \\'`.
              )  \
 (`.??????_.-`' ' '`-.
  \ `.??.`        (o) \_
   >  ><     (((       (
  / .`??`._      /_|  /'
 (.`???????`-. _  _.-`
-            /__/'"#;
-                let right_mask = r#"            1111
+            /__/'"#,
+            r#"            1111
              1  1
 111      11111 1 1111
  1 11  11        141 11
   1  11     777       5
  1 11  111      333  11
 111       111 1  1111
-            11111"#;
-
-                let left_art = r#"       .'`/
+            11111"#,
+        ),
+        left: (
+            r#"       .'`/
       /  (
   .-'` ` `'-._??????.')
 _/ (o)        '.??.' /
 )       )))     ><  <
 `\  |_\      _.'??'. \
   '-._  _ .-'???????'.)
-      `\__\"#;
-                let left_mask = r#"       1111
+      `\__\"#,
+            r#"       1111
       1  1
   1111 1 11111      111
 11 141        11  11 1
 5       777     11  1
 11  333      111  11 1
   1111  1 111       111
-      11111"#;
-
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
-            }
-            FishSpecies::NewMedium2 => {
-                let right_art = r#"       ,--,_
+      11111"#,
+        ),
+        traits: SpeciesTraits::new((0.5, 1.2), (0.4, 1.0)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Green,
+        emoji: None,
+    },
+    FishSpeciesInfo {
+        species: FishSpecies::NewMedium2,
+        category: FishCategory::New,
+        size: SizeClass::Medium,
+        right: (
+            r#"       ,--,_
 __    _\.---'-.
 \ '.-"     // o\
 /_.'-._    \\  /
-       `"--(/"`"#;
-                let right_mask = r#"       22222
+       `"--(/"`"#,
+            r#"       22222
 66    121111211
 6 6111     77 41
 6661111    77  1
-       11113311"#;
-
-                let left_art = r#"    _,--,
+       11113311"#,
+        ),
+        left: (
+            r#"    _,--,
  .-'---./_    __
 /o \\     "-.' /
 \  //    _.-'._\
- `"\)--"`"#;
-                let left_mask = r#"    22222
+ `"\)--"`"#,
+            r#"    22222
  112111121    66
 14 77     1116 6
 1  77    1111666
- 11331111"#;
-
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
-            }
-
-            // OLD FISH
-            FishSpecies::OldFancy => {
-                let right_art = r#"       \
+ 11331111"#,
+        ),
+        traits: SpeciesTraits::new((0.4, 1.0), (0.5, 1.0)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Magenta,
+        emoji: None,
+    },
+    FishSpeciesInfo {
+        species: FishSpecies::OldFancy,
+        category: FishCategory::Old,
+        size: SizeClass::Medium,
+        right: (
+            r#"       \
      ...\..,
 \  /'       \
  >=     (  ' >
 /  \      / /
-    `"'"'/'"#;
-                let right_mask = r#"       2
+    `"'"'/''"#,
+            r#"       2
      1112111
 6  11       1
  66     7  4 5
 6  1      3 1
-    11111311"#;
-
-                let left_art = r#"      /
+    11111311"#,
+        ),
+        left: (
+            r#"      /
   ,../...
  /       '\  /
 < '  )     =<
  \ \      /  \
-  `'\'"'"'"#;
-                let left_mask = r#"      2
+  `'\'"'"'"#,
+            r#"      2
   1112111
  1       11  6
 5 4  7     66
  1 3      1  6
-  11311111"#;
-
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
-            }
-            FishSpecies::OldSimple => {
-                let right_art = r#"    \
+  11311111"#,
+        ),
+        traits: SpeciesTraits::new((0.5, 1.2), (0.5, 1.0)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Blue,
+        emoji: None,
+    },
+    FishSpeciesInfo {
+        species: FishSpecies::OldSimple,
+        category: FishCategory::Old,
+        size: SizeClass::Small,
+        right: (
+            r#"    \
 \ /--\
 >=  (o>
 / \__/
-    /"#;
-                let right_mask = r#"    2
+    /"#,
+            r#"    2
 6 1111
 66  745
 6 1111
-    3"#;
-
-                let left_art = r#"  /
+    3"#,
+        ),
+        left: (
+            r#"  /
  /--\ /
 <o)  =<
  \__/ \
-  \"#;
-                let left_mask = r#"  2
+  \"#,
+            r#"  2
  1111 6
 547  66
  1111 6
-  3"#;
-
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
-            }
-            FishSpecies::OldWavy => {
-                let right_art = r#"       \:.
+  3"#,
+        ),
+        traits: SpeciesTraits::new((0.7, 1.6), (0.2, 0.8)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Red,
+        emoji: None,
+    },
+    FishSpeciesInfo {
+        species: FishSpecies::OldWavy,
+        category: FishCategory::Old,
+        size: SizeClass::Medium,
+        right: (
+            r#"       \:.
 \;,   ,;\\\\\,,
   \\\\\;;:::::::o
   ///;;::::::::<
- /;` ``/////``"#;
-                let right_mask = r#"       222
+ /;` ``/////``"#,
+            r#"       222
 666   1122211
   6661111111114
   66611111111115
- 666 113333311"#;
-
-                let left_art = r#"      .:/
+ 666 113333311"#,
+        ),
+        left: (
+            r#"      .:/
    ,,///;,   ,;/
  o:::::::;;///
 >::::::::;;\\\\\
-  ''\\\\\\\\\'' ';\\"#;
-                let left_mask = r#"      222
+  ''\\\\\\\\\'' ';\\"#,
+            r#"      222
    1122211   666
  4111111111666
 51111111111666
-  113333311 666"#;
-
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
-            }
-            FishSpecies::OldTiny => {
-                let right_art = r#"  __
+  113333311 666"#,
+        ),
+        traits: SpeciesTraits::new((0.6, 1.4), (0.3, 0.9)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Green,
+        emoji: None,
+    },
+    FishSpeciesInfo {
+        species: FishSpecies::OldTiny,
+        category: FishCategory::Old,
+        size: SizeClass::Tiny,
+        right: (
+            r#"  __
 ><_'>
-   '"#;
-                let right_mask = r#"  11
+   '"#,
+            r#"  11
 61145
-   3"#;
-
-                let left_art = r#" __
+   3"#,
+        ),
+        left: (
+            r#" __
 <'_><
- `"#;
-                let left_mask = r#" 11
+ `"#,
+            r#" 11
 54116
- 3"#;
-
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
-            }
-            FishSpecies::OldCommaLarge => {
-                let right_art = r#"   ..\,
+ 3"#,
+        ),
+        traits: SpeciesTraits::new((1.2, 2.4), (0.0, 0.4)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Yellow,
+        emoji: Some("🐟"),
+    },
+    FishSpeciesInfo {
+        species: FishSpecies::OldCommaLarge,
+        category: FishCategory::Old,
+        size: SizeClass::Tiny,
+        right: (
+            r#"   ..\,
 >='   ('>
-  '''/'"#;
-                let right_mask = r#"   1121
+  '''/''"#,
+            r#"   1121
 661   745
-  111311"#;
-
-                let left_art = r#"  ,/..
+  111311"#,
+        ),
+        left: (
+            r#"  ,/..
 <')   `=<
- ``\```"#;
-                let left_mask = r#"  1211
+ ``\```"#,
+            r#"  1211
 547   166
- 113111"#;
-
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
-            }
-            FishSpecies::OldAngledFin => {
-                // Same art as NewSmall1 (appears in both new and old arrays in original)
-                let right_art = r#"   \
+ 113111"#,
+        ),
+        traits: SpeciesTraits::new((0.9, 1.9), (0.0, 0.6)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Cyan,
+        emoji: None,
+    },
+    // Same art as NewSmall1 (appears in both new and old arrays in original)
+    FishSpeciesInfo {
+        species: FishSpecies::OldAngledFin,
+        category: FishCategory::Old,
+        size: SizeClass::Small,
+        right: (
+            r#"   \
   / \
 >=_('>
   \_/
-   /"#;
-                let right_mask = r#"   2
+   /"#,
+            r#"   2
   1 1
 661745
   111
-   3"#;
-
-                let left_art = r#"  /
+   3"#,
+        ),
+        left: (
+            r#"  /
  / \
 <')_=<
  \_/
-  \"#;
-                let left_mask = r#"  2
+  \"#,
+            r#"  2
  1 1
 547166
  111
-  3"#;
-
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
-            }
-            FishSpecies::OldCommaSmall => {
-                let right_art = r#"  ,\
+  3"#,
+        ),
+        traits: SpeciesTraits::new((1.0, 2.0), (0.1, 0.6)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Magenta,
+        emoji: None,
+    },
+    FishSpeciesInfo {
+        species: FishSpecies::OldCommaSmall,
+        category: FishCategory::Old,
+        size: SizeClass::Tiny,
+        right: (
+            r#"  ,\
 >=('>
-  '/"#;
-                let right_mask = r#"  12
+  '/"#,
+            r#"  12
 66745
-  13"#;
-
-                let left_art = r#" /,
+  13"#,
+        ),
+        left: (
+            r#" /,
 <')=<
- \`"#;
-                let left_mask = r#" 21
+ \`"#,
+            r#" 21
 54766
- 31"#;
-
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
-            }
-            FishSpecies::OldRounded => {
-                let right_art = r#"  __
+ 31"#,
+        ),
+        traits: SpeciesTraits::new((1.3, 2.5), (0.0, 0.3)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Blue,
+        emoji: Some("🐠"),
+    },
+    FishSpeciesInfo {
+        species: FishSpecies::OldRounded,
+        category: FishCategory::Old,
+        size: SizeClass::Tiny,
+        right: (
+            r#"  __
 \/ o\
-/\__/"#;
-                let right_mask = r#"  11
+/\__/"#,
+            r#"  11
 61 41
-61111"#;
-
-                let left_art = r#" __
+61111"#,
+        ),
+        left: (
+            r#" __
 /o \/
-\__/\"#;
-                let left_mask = r#" 11
+\__/\"#,
+            r#" 11
 14 16
-11116"#;
+11116"#,
+        ),
+        traits: SpeciesTraits::new((0.8, 1.8), (0.2, 0.7)),
+        bubble_secs: BUBBLE_SECS,
+        rarity: 1.0,
+        base_color: Color::Red,
+        emoji: None,
+    },
+];
+
+impl FishSpecies {
+    /// This species' row of [`SPECIES`]
+    pub fn info(&self) -> &'static FishSpeciesInfo {
+        &SPECIES[*self as usize]
+    }
+
+    /// Get category of this fish species
+    pub fn category(&self) -> FishCategory {
+        self.info().category
+    }
+
+    /// Speed and depth preferences
+    pub fn traits(&self) -> SpeciesTraits {
+        self.info().traits
+    }
+
+    /// Species of `category`, in table order
+    pub fn of_category(category: FishCategory) -> Vec<FishSpecies> {
+        SPECIES
+            .iter()
+            .filter(|info| info.category == category)
+            .map(|info| info.species)
+            .collect()
+    }
+
+    /// Get all new fish species
+    pub fn new_species() -> Vec<FishSpecies> {
+        Self::of_category(FishCategory::New)
+    }
+
+    /// Get all old fish species
+    pub fn old_species() -> Vec<FishSpecies> {
+        Self::of_category(FishCategory::Old)
+    }
+
+    /// Get a random fish species following original logic:
+    /// - 25% chance for new fish (int(rand(12)) > 8, meaning 9,10,11 out of 0-11)
+    /// - 75% chance for old fish
+    /// - classic_mode flag disables new fish
+    ///
+    /// Within the category each species is weighted by its rarity.
+    pub fn random(classic_mode: bool, rng: &mut RngHandle) -> Self {
+        // New fish are 9, 10, 11 = 3 out of 12 = 25%, never in classic mode
+        let category = if !classic_mode && rng.gen_range(0..12) > 8 {
+            FishCategory::New
+        } else {
+            FishCategory::Old
+        };
+        let choices: Vec<&FishSpeciesInfo> = SPECIES
+            .iter()
+            .filter(|info| info.category == category)
+            .collect();
+        choices
+            .choose_weighted(rng, |info| info.rarity)
+            .map_or(FishSpecies::OldTiny, |info| info.species)
+    }
 
-                (
-                    Sprite::from_ascii_art_with_random_colors(right_art, Some(right_mask)),
-                    Sprite::from_ascii_art_with_random_colors(left_art, Some(left_mask)),
-                )
+    /// What the species is called, for people
+    pub fn name(&self) -> &'static str {
+        match self {
+            FishSpecies::NewSmall1 => "finned fish",
+            FishSpecies::NewSmall2 => "bracket fish",
+            FishSpecies::NewMedium1 => "question fish",
+            FishSpecies::NewMedium2 => "bulgy fish",
+            FishSpecies::OldFancy => "fancy fish",
+            FishSpecies::OldSimple => "simple fish",
+            FishSpecies::OldWavy => "wavy fish",
+            FishSpecies::OldTiny => "tiny fish",
+            FishSpecies::OldCommaLarge => "comma fish",
+            FishSpecies::OldAngledFin => "angled fish",
+            FishSpecies::OldCommaSmall => "little comma fish",
+            FishSpecies::OldRounded => "round fish",
+        }
+    }
+
+    /// Emoji drawn instead of the ASCII art with `--charset unicode`
+    pub fn emoji(&self) -> Option<&'static str> {
+        self.info().emoji
+    }
+
+    /// Get the sprites for this fish species (right-facing, left-facing)
+    pub fn get_sprites(&self, rng: &mut RngHandle) -> (Sprite, Sprite) {
+        let info = self.info();
+        if charset::current() == Charset::Unicode {
+            if let Some(emoji) = info.emoji {
+                // Emoji can't be mirrored, so both directions share it
+                let sprite = fish_sprite(emoji, Some("1"), rng);
+                return (sprite.clone(), sprite);
             }
         }
+
+        (
+            fish_sprite(info.right.0, Some(info.right.1), rng),
+            fish_sprite(info.left.0, Some(info.left.1), rng),
+        )
     }
 
     /// Get the base color for this fish species
     pub fn get_base_color(&self) -> Color {
-        // Colors are randomized in the original, we just provide a base
+        self.info().base_color
+    }
+}
+
+/// Growth stage of a fish (only juveniles born in ecology mode pass through
+/// the early stages; regular fish spawn as adults)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifeStage {
+    /// Freshly hatched, three-character sprite
+    Fry,
+    /// Half-grown, four-character sprite
+    Juvenile,
+    /// Fully grown, uses the species sprite
+    Adult,
+}
+
+impl LifeStage {
+    /// Seconds spent in this stage before growing into the next one
+    pub fn duration_secs(&self) -> f32 {
+        match self {
+            LifeStage::Fry => 20.0,
+            LifeStage::Juvenile => 40.0,
+            LifeStage::Adult => f32::INFINITY,
+        }
+    }
+
+    /// The stage's name, for people
+    pub fn name(&self) -> &'static str {
+        match self {
+            LifeStage::Fry => "fry",
+            LifeStage::Juvenile => "juvenile",
+            LifeStage::Adult => "adult",
+        }
+    }
+
+    /// The stage that follows this one
+    pub fn next(&self) -> Self {
         match self {
-            FishSpecies::NewSmall1 => Color::Yellow,
-            FishSpecies::NewSmall2 => Color::Cyan,
-            FishSpecies::NewMedium1 => Color::Green,
-            FishSpecies::NewMedium2 => Color::Magenta,
-            FishSpecies::OldFancy => Color::Blue,
-            FishSpecies::OldSimple => Color::Red,
-            FishSpecies::OldWavy => Color::Green,
-            FishSpecies::OldTiny => Color::Yellow,
-            FishSpecies::OldCommaLarge => Color::Cyan,
-            FishSpecies::OldAngledFin => Color::Magenta,
-            FishSpecies::OldCommaSmall => Color::Blue,
-            FishSpecies::OldRounded => Color::Red,
+            LifeStage::Fry => LifeStage::Juvenile,
+            LifeStage::Juvenile | LifeStage::Adult => LifeStage::Adult,
         }
     }
+
+    /// Small sprites for the growing stages (right-facing, left-facing)
+    fn get_sprites(&self, rng: &mut RngHandle) -> Option<(Sprite, Sprite)> {
+        let (right_art, right_mask, left_art, left_mask) = match self {
+            LifeStage::Fry => ("><>", "614", "<><", "416"),
+            LifeStage::Juvenile => ("><'>", "6145", "<'><", "5416"),
+            LifeStage::Adult => return None,
+        };
+
+        Some((
+            fish_sprite(right_art, Some(right_mask), rng),
+            fish_sprite(left_art, Some(left_mask), rng),
+        ))
+    }
+}
+
+/// Seconds until a new fish's first bubble, a little sooner than the
+/// species' usual interval
+fn first_bubble(species: FishSpecies, rng: &mut RngHandle) -> f32 {
+    let (min, max) = species.info().bubble_secs;
+    rng.gen_range(min * 0.66..max * 0.8)
 }
 
 /// A fish entity that swims across the screen
@@ -473,21 +730,51 @@ pub struct Fish {
     alive: bool,
     bubble_timer: f32,
     age: Duration,
-    created_at: Instant,
+    stage: LifeStage,
+    stage_age: f32,
+    stage_sprites: Vec<(Sprite, Sprite)>, // Growing stages still ahead, current first
+    respawns: bool,
+    stamina: Stamina,
+    home_depth: Option<u8>, // Layer to return to while hiding from a shark
+    eaten: bool,            // Bitten by a shark, leaving a splat behind
+    hooked: bool,           // Caught on a hook and being reeled in
+    food: Option<Position>, // Nearest food in sight, to swim to
 }
 
 impl Fish {
     /// Create a new fish with random properties
     /// classic_mode: if true, only spawn old fish (matches -c flag)
-    pub fn new_random(id: EntityId, screen_bounds: Rect, classic_mode: bool) -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn new_random(
+        id: EntityId,
+        screen_bounds: Rect,
+        classic_mode: bool,
+        rng: &mut RngHandle,
+    ) -> Self {
+        Self::new_random_below(
+            id,
+            screen_bounds,
+            classic_mode,
+            crate::environment::WATERLINE_Y as u16,
+            rng,
+        )
+    }
 
-        let species = FishSpecies::random(classic_mode);
-        let (right_sprite, left_sprite) = species.get_sprites();
+    /// Create a random fish somewhere below `waterline` (see the tide in
+    /// [`crate::environment`])
+    pub fn new_random_below(
+        id: EntityId,
+        screen_bounds: Rect,
+        classic_mode: bool,
+        waterline: u16,
+        rng: &mut RngHandle,
+    ) -> Self {
+        let species = FishSpecies::random(classic_mode, rng);
+        let traits = species.traits();
+        let (right_sprite, left_sprite) = species.get_sprites(rng);
         let base_color = species.get_base_color();
 
         // Alternate direction based on fish ID (like original)
-        let direction = if id % 2 == 0 {
+        let direction = if id.is_multiple_of(2) {
             Direction::Right
         } else {
             Direction::Left
@@ -504,25 +791,24 @@ impl Fish {
                 // Start off-screen to the left, move right
                 // Original Perl: X = 1 - WIDTH (fish starts fully off left edge)
                 let x = 1.0 - sprite_bounds.0 as f32;
-                let speed = rng.gen_range(0.5..2.0);
+                let speed = rng.gen_range(traits.speed.0..traits.speed.1);
                 (x, speed)
             }
             Direction::Left => {
                 // Start near right edge, move left
                 // Original Perl: X = width - 2 (fish starts mostly visible)
                 let x = screen_bounds.width as f32 - 2.0;
-                let speed = rng.gen_range(0.5..2.0);
+                let speed = rng.gen_range(traits.speed.0..traits.speed.1);
                 (x, -speed)
             }
         };
 
-        // Random Y position in underwater area (below water surface)
-        let water_surface_y = 9; // Based on original code
+        // Random Y position within the species' band of the water column
         let min_y = screen_bounds.height.saturating_sub(sprite_bounds.1);
-        let y = rng.gen_range(water_surface_y..min_y.max(water_surface_y + 1)) as f32;
+        let y = traits.band_row(rng.gen_range(0.0..=1.0), waterline, min_y);
 
         // Random depth in fish layer
-        let depth = depth::random_fish_depth();
+        let depth = depth::random_fish_depth(rng);
 
         // Fish only move horizontally (no vertical movement in original)
         let dy = 0.0;
@@ -537,12 +823,43 @@ impl Fish {
             left_sprite,
             base_color,
             alive: true,
-            bubble_timer: rng.gen_range(2.0..8.0), // Seconds until next bubble
+            bubble_timer: first_bubble(species, rng), // Seconds until next bubble
             age: Duration::ZERO,
-            created_at: Instant::now(),
+            stage: LifeStage::Adult,
+            stage_age: 0.0,
+            stage_sprites: Vec::new(),
+            respawns: true,
+            stamina: Stamina::new(rng),
+            home_depth: None,
+            eaten: false,
+            hooked: false,
+            food: None,
         }
     }
 
+    /// Create a random fish like [`Fish::new_random_below`] with `label`
+    /// over it and its body in `color` (a chat viewer's fish, say)
+    ///
+    /// Labelled fish don't respawn: they are extras on top of the tank's
+    /// own fish.
+    pub fn new_labelled(
+        id: EntityId,
+        screen_bounds: Rect,
+        classic_mode: bool,
+        waterline: u16,
+        label: &str,
+        color: Color,
+        rng: &mut RngHandle,
+    ) -> Self {
+        let mut fish = Self::new_random_below(id, screen_bounds, classic_mode, waterline, rng);
+        let info = fish.species.info();
+        fish.right_sprite = labelled_sprite(info.right, label, color);
+        fish.left_sprite = labelled_sprite(info.left, label, color);
+        fish.base_color = color;
+        fish.respawns = false;
+        fish
+    }
+
     /// Create a fish with specific properties
     pub fn new(
         id: EntityId,
@@ -550,10 +867,10 @@ impl Fish {
         velocity: Velocity,
         direction: Direction,
         species: FishSpecies,
+        rng: &mut RngHandle,
     ) -> Self {
-        let (right_sprite, left_sprite) = species.get_sprites();
+        let (right_sprite, left_sprite) = species.get_sprites(rng);
         let base_color = species.get_base_color();
-        let mut rng = rand::thread_rng();
 
         Self {
             id,
@@ -565,9 +882,95 @@ impl Fish {
             left_sprite,
             base_color,
             alive: true,
-            bubble_timer: rng.gen_range(2.0..8.0),
+            bubble_timer: first_bubble(species, rng),
             age: Duration::ZERO,
-            created_at: Instant::now(),
+            stage: LifeStage::Adult,
+            stage_age: 0.0,
+            stage_sprites: Vec::new(),
+            respawns: true,
+            stamina: Stamina::new(rng),
+            home_depth: None,
+            eaten: false,
+            hooked: false,
+            food: None,
+        }
+    }
+
+    /// Create a newly born fry (ecology mode) that grows into `species`
+    pub fn new_juvenile(
+        id: EntityId,
+        position: Position,
+        direction: Direction,
+        species: FishSpecies,
+        rng: &mut RngHandle,
+    ) -> Self {
+        // Fry dawdle a little slower than adults
+        let speed = rng.gen_range(0.4..1.2);
+        let dx = match direction {
+            Direction::Right => speed,
+            Direction::Left => -speed,
+        };
+
+        let mut fish = Self::new(
+            id,
+            position,
+            Velocity::new(dx, 0.0),
+            direction,
+            species,
+            rng,
+        );
+        fish.stage = LifeStage::Fry;
+        fish.stage_sprites = [LifeStage::Fry, LifeStage::Juvenile]
+            .iter()
+            .filter_map(|stage| stage.get_sprites(rng))
+            .collect();
+        fish.respawns = false;
+        fish
+    }
+
+    /// Time this fish has been swimming
+    pub fn age(&self) -> Duration {
+        self.age
+    }
+
+    /// Get the current growth stage
+    pub fn stage(&self) -> LifeStage {
+        self.stage
+    }
+
+    /// Choose whether a replacement fish spawns when this one dies
+    pub fn set_respawns(&mut self, respawns: bool) {
+        self.respawns = respawns;
+    }
+
+    /// Whether this fish is hiding from a shark
+    pub fn is_hiding(&self) -> bool {
+        self.home_depth.is_some()
+    }
+
+    /// Whether this fish is hovering beside seaweed
+    pub fn is_resting(&self) -> bool {
+        self.stamina.is_resting()
+    }
+
+    /// Whether this fish is fully grown
+    pub fn is_adult(&self) -> bool {
+        self.stage == LifeStage::Adult
+    }
+
+    /// Advance the growth stage once enough time has passed
+    fn grow(&mut self, delta_time: Duration) {
+        if self.is_adult() {
+            return;
+        }
+
+        self.stage_age += delta_time.as_secs_f32();
+        if self.stage_age >= self.stage.duration_secs() {
+            self.stage = self.stage.next();
+            self.stage_age = 0.0;
+            if !self.stage_sprites.is_empty() {
+                self.stage_sprites.remove(0);
+            }
         }
     }
 
@@ -587,12 +990,12 @@ impl Fish {
     }
 
     /// Check if fish should emit a bubble
-    pub fn should_emit_bubble(&mut self, delta_time: Duration) -> bool {
+    pub fn should_emit_bubble(&mut self, delta_time: Duration, rng: &mut RngHandle) -> bool {
         self.bubble_timer -= delta_time.as_secs_f32();
         if self.bubble_timer <= 0.0 {
             // Reset timer for next bubble
-            let mut rng = rand::thread_rng();
-            self.bubble_timer = rng.gen_range(3.0..10.0);
+            let (min, max) = self.species.info().bubble_secs;
+            self.bubble_timer = rng.gen_range(min..max);
             true
         } else {
             false
@@ -627,20 +1030,16 @@ impl Fish {
         }
     }
 
-    /// Check if fish is off-screen and should die
-    fn check_offscreen_death(&mut self, screen_bounds: Rect) {
+    /// Check if fish has left the world and should die
+    ///
+    /// Uses the world bounds rather than the visible viewport so that panning
+    /// a camera never kills fish that are merely out of view.
+    fn check_offscreen_death(&mut self, world_bounds: Rect) {
         let sprite_bounds = self.get_current_sprite().get_bounding_box();
-        let pos_x = self.position.x;
-        let pos_y = self.position.y;
 
         // Match Term::Animation die_offscreen behavior
-        // Die when fish is completely off screen
-        let off_left = (pos_x + sprite_bounds.0 as f32) < 0.0;
-        let off_right = pos_x > (screen_bounds.width as f32);
-        let off_top = (pos_y + sprite_bounds.1 as f32) < 0.0;
-        let off_bottom = pos_y > (screen_bounds.height as f32);
-
-        if off_left || off_right || off_top || off_bottom {
+        // Die when fish is completely outside the world
+        if world::is_outside(world_bounds, self.position, sprite_bounds) {
             self.alive = false;
         }
     }
@@ -673,26 +1072,60 @@ impl Entity for Fish {
     }
 
     fn get_current_sprite(&self) -> &Sprite {
+        // Growing fish use the small stage sprites until they are adults
+        if let Some((right, left)) = self.stage_sprites.first() {
+            return match self.direction {
+                Direction::Right => right,
+                Direction::Left => left,
+            };
+        }
+
         match self.direction {
             Direction::Right => &self.right_sprite,
             Direction::Left => &self.left_sprite,
         }
     }
 
-    fn update(&mut self, delta_time: Duration, screen_bounds: Rect) {
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
         if !self.alive {
             return;
         }
 
         // Update age
-        self.age = self.created_at.elapsed();
+        self.age += delta_time;
+        self.grow(delta_time);
+
+        self.stamina.update(delta_time);
+
+        // A caught fish goes up with the hook and is gone above the tank
+        if self.hooked {
+            self.position.y -= hook::REEL_SPEED * timestep::ticks(delta_time);
+            let height = self.get_current_sprite().get_bounding_box().1 as f32;
+            if self.position.y + height < 0.0 {
+                self.alive = false;
+            }
+            return;
+        }
 
-        // Update position based on velocity (fish only move horizontally)
-        self.position.x += self.velocity.dx * delta_time.as_secs_f32() * 60.0; // Scale for 60 FPS
-        // Fish don't move vertically in the original implementation
+        // Head for food: turn to face it and rise or sink to its row
+        if let Some(food) = self.food {
+            let centre = self.centre();
+            let ahead = food.x - centre.x;
+            if ahead.abs() > FEED_TURN && ahead * self.velocity.dx < 0.0 {
+                self.velocity.dx = -self.velocity.dx;
+                self.update_direction();
+            }
+            let step = FEED_SWIM * timestep::ticks(delta_time);
+            self.position.y += (food.y - centre.y).clamp(-step, step);
+        }
+
+        // Update position based on velocity (fish only move horizontally
+        // unless feeding, and slow down while resting)
+        let speed_factor = self.stamina.speed_factor();
+        self.position.x += self.velocity.dx * speed_factor * timestep::ticks(delta_time);
 
-        // Check if fish should die (off-screen)
-        self.check_offscreen_death(screen_bounds);
+        // Check if fish should die (left the world)
+        self.check_offscreen_death(world_bounds);
     }
 
     fn is_alive(&self) -> bool {
@@ -703,20 +1136,115 @@ impl Entity for Fish {
         self.alive = false;
     }
 
-    fn entity_type(&self) -> &'static str {
-        "fish"
+    fn kind(&self) -> EntityKind {
+        EntityKind::Fish
+    }
+
+    fn population(&self) -> Option<Population> {
+        // Only the original population is replaced; bred fish and passing
+        // schools are extras
+        self.respawns.then_some(Population::Fish)
     }
 
-    fn death_callback(&self) -> Option<DeathCallback> {
-        Some(crate::spawning::add_fish)
+    fn respawn_policy(&self) -> RespawnPolicy {
+        if self.eaten {
+            RespawnPolicy::SpawnSplat { respawn: true }
+        } else {
+            RespawnPolicy::RespawnSame
+        }
     }
 
-    fn should_spawn_bubble(&mut self, delta_time: Duration) -> Option<Position> {
+    fn collision_layers(&self) -> CollisionLayers {
+        // Out of reach behind the scenery, or already caught
+        if self.is_hiding() || self.hooked {
+            CollisionLayers::NONE
+        } else {
+            CollisionLayers::FISH
+        }
+    }
+
+    fn on_contact(&mut self, contact: &Contact) {
+        if contact.target != self.id {
+            return;
+        }
+        match contact.kind {
+            ContactKind::Bite => {
+                self.alive = false;
+                self.eaten = true;
+            }
+            ContactKind::Hooked => self.hooked = true,
+            _ => {}
+        }
+    }
+
+    fn current_response(&self) -> f32 {
+        // Fish swim against the current, so only a slight push; a hooked
+        // one hangs on the line
+        if self.hooked {
+            0.0
+        } else {
+            0.2
+        }
+    }
+
+    fn is_prey(&self) -> bool {
+        // Sharks can't see fish hiding behind the scenery
+        self.alive && !self.is_hiding() && !self.hooked
+    }
+
+    fn shelter(&mut self, shelters: &[f32], rng: &mut RngHandle) {
+        let width = self.get_current_sprite().get_bounding_box().0;
+        let centre = self.position.x + width as f32 / 2.0;
+        self.stamina
+            .consider_rest(behavior::near_shelter(centre, shelters), rng);
+    }
+
+    fn smell_food(&mut self, food: &[Position]) {
+        // Hiding or caught fish have other things on their mind
+        self.food = if self.is_hiding() || self.hooked {
+            None
+        } else {
+            behavior::nearest_food(self.centre(), food)
+        };
+    }
+
+    fn notice_threats(&mut self, threats: &[f32]) {
+        let height = self.get_current_sprite().get_bounding_box().1;
+        let centre = self.position.y + height as f32 / 2.0;
+
+        match (behavior::is_threatened(centre, threats), self.home_depth) {
+            // Duck behind the seaweed and castle, remembering where we were
+            (true, None) => {
+                self.home_depth = Some(self.position.depth);
+                self.position.depth = depth::HIDING;
+            }
+            // The shark has gone; back to our own layer
+            (false, Some(home)) => {
+                self.position.depth = home;
+                self.home_depth = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn breeding_species(&self) -> Option<FishSpecies> {
+        if self.alive && self.is_adult() {
+            Some(self.species)
+        } else {
+            None
+        }
+    }
+
+    fn should_spawn_bubble(
+        &mut self,
+        delta_time: Duration,
+        rng: &mut RngHandle,
+    ) -> Option<Position> {
         if !self.alive {
             return None;
         }
 
-        if self.should_emit_bubble(delta_time) {
+        if self.should_emit_bubble(delta_time, rng) {
             Some(self.get_bubble_position())
         } else {
             None
@@ -732,13 +1260,67 @@ mod tests {
     #[test]
     fn test_fish_creation() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let fish = Fish::new_random(1, screen_bounds, false);
+        let fish = Fish::new_random(1, screen_bounds, false, &mut RngHandle::default());
 
         assert!(fish.is_alive());
         assert_eq!(fish.entity_type(), "fish");
         assert!(depth::is_fish_depth(fish.depth()));
     }
 
+    #[test]
+    fn test_masks_fit_art() {
+        for species in SPECIES.iter().map(|info| info.species) {
+            let (right, left) = species.get_sprites(&mut RngHandle::default());
+            for sprite in [right, left] {
+                if let Err(err) = sprite.check_mask() {
+                    panic!("{:?}: {}", species, err);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_classic_pairs_are_mirror_images() {
+        // Species whose left-facing art is exactly the right-facing art
+        // flipped (the rest redraw eyes and scales for each facing)
+        for species in [
+            FishSpecies::NewSmall1,
+            FishSpecies::OldSimple,
+            FishSpecies::OldAngledFin,
+            FishSpecies::OldRounded,
+        ] {
+            let (right, left) = species.get_sprites(&mut RngHandle::default());
+            let mirrored = right.mirrored();
+            assert_eq!(mirrored.lines(), left.lines(), "{:?}", species);
+            assert_eq!(
+                mirrored.get_non_transparent_positions(),
+                left.get_non_transparent_positions(),
+                "{:?}",
+                species
+            );
+        }
+    }
+
+    #[test]
+    fn test_species_table_follows_enum() {
+        for (index, info) in SPECIES.iter().enumerate() {
+            assert_eq!(info.species as usize, index);
+            assert!(info.rarity > 0.0);
+            assert!(info.bubble_secs.0 < info.bubble_secs.1);
+            let (_, rows) = info
+                .species
+                .get_sprites(&mut RngHandle::default())
+                .0
+                .get_bounding_box();
+            assert_eq!(
+                info.size == SizeClass::Tiny,
+                rows <= 3,
+                "{:?}",
+                info.species
+            );
+        }
+    }
+
     #[test]
     fn test_fish_species_count() {
         assert_eq!(FishSpecies::new_species().len(), 4);
@@ -757,7 +1339,7 @@ mod tests {
 
         // Test multiple fish to ensure all are old
         for i in 0..20 {
-            let fish = Fish::new_random(i, screen_bounds, true);
+            let fish = Fish::new_random(i, screen_bounds, true, &mut RngHandle::default());
             assert_eq!(
                 fish.species().category(),
                 FishCategory::Old,
@@ -785,19 +1367,20 @@ mod tests {
         ];
 
         for species in all_species {
-            let (right, left) = species.get_sprites();
+            let (right, left) = species.get_sprites(&mut RngHandle::default());
             assert!(
-                !right.lines.is_empty(),
+                !right.lines().is_empty(),
                 "Species {:?} has empty right sprite",
                 species
             );
             assert!(
-                !left.lines.is_empty(),
+                !left.lines().is_empty(),
                 "Species {:?} has empty left sprite",
                 species
             );
             assert_ne!(
-                right.lines, left.lines,
+                right.lines(),
+                left.lines(),
                 "Species {:?} has identical sprites",
                 species
             );
@@ -812,6 +1395,7 @@ mod tests {
             Velocity::new(1.0, 0.0),
             Direction::Right,
             FishSpecies::NewSmall1,
+            &mut RngHandle::default(),
         );
 
         let initial_x = fish.position().x;
@@ -821,6 +1405,188 @@ mod tests {
     }
 
     #[test]
+    fn test_fish_eyes_are_bold_white() {
+        let (right, _) = FishSpecies::NewSmall1.get_sprites(&mut RngHandle::default());
+        // ">=_('>" with mask "663745": the eye is the apostrophe
+        assert_eq!(right.get_char_at(4, 2), '\'');
+        assert_eq!(right.get_color_at(4, 2), Some(Color::White));
+        assert!(right.is_bold_at(4, 2));
+    }
+
+    #[test]
+    fn test_fish_hides_from_shark() {
+        let mut fish = Fish::new(
+            1,
+            Position::new(10.0, 10.0, depth::FISH_START + 2),
+            Velocity::new(1.0, 0.0),
+            Direction::Right,
+            FishSpecies::OldTiny,
+            &mut RngHandle::default(),
+        );
+
+        fish.notice_threats(&[30.0]);
+        assert!(!fish.is_hiding());
+
+        fish.notice_threats(&[14.0]);
+        assert!(fish.is_hiding());
+        assert_eq!(fish.depth(), depth::HIDING);
+        assert!(!fish.is_prey());
+
+        fish.notice_threats(&[]);
+        assert!(!fish.is_hiding());
+        assert_eq!(fish.depth(), depth::FISH_START + 2);
+    }
+
+    #[test]
+    fn test_only_bites_eat_fish() {
+        let mut fish = Fish::new(
+            2,
+            Position::new(10.0, 10.0, depth::FISH_START),
+            Velocity::new(1.0, 0.0),
+            Direction::Right,
+            FishSpecies::OldTiny,
+            &mut RngHandle::default(),
+        );
+        let contact = |kind, source, target| Contact {
+            kind,
+            source,
+            target,
+        };
+
+        fish.on_contact(&contact(ContactKind::Touch, 1, 2));
+        fish.on_contact(&contact(ContactKind::Fed, 1, 2));
+        // A bite the fish made itself isn't its death
+        fish.on_contact(&contact(ContactKind::Bite, 2, 1));
+        assert!(fish.is_alive());
+        assert_eq!(fish.respawn_policy(), RespawnPolicy::RespawnSame);
+
+        // Teeth leave a splat and a replacement fish behind
+        fish.on_contact(&contact(ContactKind::Bite, 1, 2));
+        assert!(!fish.is_alive());
+        assert_eq!(
+            fish.respawn_policy(),
+            RespawnPolicy::SpawnSplat { respawn: true }
+        );
+    }
+
+    #[test]
+    fn test_species_traits_table() {
+        for species in SPECIES.iter().map(|info| info.species) {
+            let traits = species.traits();
+            assert!(traits.speed.0 > 0.0 && traits.speed.0 < traits.speed.1);
+            assert!(0.0 <= traits.band.0 && traits.band.0 < traits.band.1);
+            assert!(traits.band.1 <= 1.0);
+        }
+
+        // Tiny fish keep near the surface, big old ones near the bottom
+        let tiny = FishSpecies::OldCommaSmall.traits();
+        let big = FishSpecies::OldFancy.traits();
+        assert!(tiny.band.1 < big.band.0);
+        assert!(tiny.speed.0 > big.speed.0);
+    }
+
+    #[test]
+    fn test_random_fish_follow_species_traits() {
+        let bounds = Rect::new(0, 0, 200, 60);
+        for id in 0..50 {
+            let fish = Fish::new_random_below(id, bounds, false, 9, &mut RngHandle::default());
+            let traits = fish.species.traits();
+            let speed = fish.velocity().dx.abs();
+            assert!(speed >= traits.speed.0 && speed < traits.speed.1);
+
+            let height = fish.get_current_sprite().get_bounding_box().1;
+            let bottom = bounds.height - height;
+            assert!(fish.position().y >= traits.band_row(0.0, 9, bottom));
+            assert!(fish.position().y <= traits.band_row(1.0, 9, bottom));
+        }
+    }
+
+    #[test]
+    fn test_tired_fish_rests_by_seaweed() {
+        let mut fish = Fish::new(
+            1,
+            Position::new(10.0, 10.0, depth::FISH_START),
+            Velocity::new(1.0, 0.0),
+            Direction::Right,
+            FishSpecies::NewSmall1,
+            &mut RngHandle::default(),
+        );
+        let bounds = Rect::new(0, 0, 200, 40);
+        fish.stamina = Stamina::with_drain(1.0);
+
+        // Seaweed far away doesn't tempt it
+        fish.update(Duration::from_secs(1), bounds);
+        fish.shelter(&[150.0], &mut RngHandle::default());
+        assert!(!fish.is_resting());
+
+        let x = fish.position().x;
+        let width = fish.get_current_sprite().get_bounding_box().0 as f32;
+        fish.shelter(&[x + width / 2.0], &mut RngHandle::default());
+        assert!(fish.is_resting());
+
+        // Hovers rather than cruising on at full speed
+        for _ in 0..10 {
+            fish.update(Duration::from_millis(100), bounds);
+        }
+        assert!(fish.position().x - x < 30.0);
+    }
+
+    #[test]
+    fn test_fish_turns_and_swims_to_food() {
+        let mut fish = Fish::new(
+            1,
+            Position::new(40.0, 10.0, depth::FISH_START),
+            Velocity::new(0.5, 0.0),
+            Direction::Right,
+            FishSpecies::NewSmall1,
+            &mut RngHandle::default(),
+        );
+        let bounds = Rect::new(0, 0, 200, 40);
+        let centre = fish.centre();
+
+        // Too far off to notice
+        fish.smell_food(&[Position::new(centre.x - 30.0, centre.y, 3)]);
+        fish.update(timestep::STEP, bounds);
+        assert_eq!(fish.velocity().dx, 0.5);
+
+        // Behind it and lower down: it turns round and sinks towards it
+        let flake = Position::new(centre.x - 10.0, centre.y + 4.0, 3);
+        fish.smell_food(&[flake]);
+        fish.update(timestep::STEP * 10, bounds);
+        assert_eq!(fish.velocity().dx, -0.5);
+        assert_eq!(fish.direction, Direction::Left);
+        assert!((fish.centre().y - (centre.y + 10.0 * FEED_SWIM)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_juvenile_grows_into_adult() {
+        let mut fish = Fish::new_juvenile(
+            1,
+            Position::new(10.0, 10.0, depth::FISH_START),
+            Direction::Right,
+            FishSpecies::OldFancy,
+            &mut RngHandle::default(),
+        );
+        let bounds = Rect::new(0, 0, 200, 40);
+        // Hold still so the long time steps don't swim it off-screen
+        fish.set_velocity(Velocity::zero());
+
+        assert_eq!(fish.stage(), LifeStage::Fry);
+        assert_eq!(fish.get_current_sprite().lines(), vec!["><>".to_string()]);
+        assert!(fish.breeding_species().is_none());
+        assert!(fish.population().is_none());
+
+        fish.update(Duration::from_secs_f32(20.0), bounds);
+        assert_eq!(fish.stage(), LifeStage::Juvenile);
+
+        fish.update(Duration::from_secs_f32(40.0), bounds);
+        assert!(fish.is_adult());
+        assert_eq!(fish.get_current_sprite().lines().len(), 6);
+        assert_eq!(fish.breeding_species(), Some(FishSpecies::OldFancy));
+    }
+
+    #[test]
+    #[allow(clippy::manual_range_contains)]
     fn test_fish_selection_distribution() {
         // Test that fish selection follows approximately 25%/75% distribution
         let screen_bounds = Rect::new(0, 0, 80, 24);
@@ -829,7 +1595,7 @@ mod tests {
         let mut old_count = 0;
 
         for i in 0..sample_size {
-            let fish = Fish::new_random(i, screen_bounds, false);
+            let fish = Fish::new_random(i, screen_bounds, false, &mut RngHandle::default());
             match fish.species().category() {
                 FishCategory::New => new_count += 1,
                 FishCategory::Old => old_count += 1,
@@ -0,0 +1,157 @@
+use crate::collision::{CollisionLayers, Contact, ContactKind};
+use crate::depth;
+use crate::entity::{Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::sprite;
+use crate::timestep;
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// Rows per tick a flake sinks
+const SINK_SPEED: f32 = 0.04;
+
+/// A flake of fish food dropped into the water, sinking until a fish
+/// reaches it or it settles on the sand
+#[derive(Debug, Clone)]
+pub struct Food {
+    id: EntityId,
+    position: Position,
+    velocity: Velocity,
+    sprite: Sprite,
+    alive: bool,
+}
+
+impl Food {
+    /// A flake at `x`, `y`, in front of the fish
+    pub fn new(id: EntityId, x: f32, y: f32) -> Self {
+        Self {
+            id,
+            position: Position::new(x, y, depth::FISH_START),
+            velocity: Velocity::new(0.0, SINK_SPEED),
+            sprite: sprite!("*", "Y"),
+            alive: true,
+        }
+    }
+}
+
+impl Entity for Food {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        self.velocity
+    }
+
+    fn set_velocity(&mut self, velocity: Velocity) {
+        self.velocity = velocity;
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    fn is_particle(&self) -> bool {
+        true
+    }
+
+    fn is_food(&self) -> bool {
+        self.alive
+    }
+
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
+        self.position.y += self.velocity.dy * timestep::ticks(delta_time);
+        // Dissolves once it reaches the sand
+        if self.position.y >= world_bounds.bottom().saturating_sub(1) as f32 {
+            self.alive = false;
+        }
+    }
+
+    fn current_response(&self) -> f32 {
+        // Light enough to drift
+        0.6
+    }
+
+    fn collision_layers(&self) -> CollisionLayers {
+        CollisionLayers::FOOD
+    }
+
+    fn collision_mask(&self) -> CollisionLayers {
+        CollisionLayers::FISH
+    }
+
+    fn on_contact(&mut self, contact: &Contact) {
+        // Eaten by the first fish to reach it
+        if contact.kind == ContactKind::Fed && contact.source == self.id {
+            self.alive = false;
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Food
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Fish, FishSpecies};
+    use crate::entity::{Direction, EntityManager};
+    use crate::rng::RngHandle;
+    use crate::world::WorldContext;
+
+    #[test]
+    fn test_food_sinks_until_eaten() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut food = Food::new(1, 10.0, 8.0);
+        food.update(timestep::STEP * 25, bounds);
+        assert_eq!(food.position().y, 9.0);
+        food.update(Duration::from_secs(60), bounds);
+        assert!(!food.is_alive());
+
+        // The first fish to reach a flake eats it
+        let mut manager = EntityManager::new();
+        let position = Position::new(10.5, 10.5, depth::FISH_START);
+        let fish = manager.spawn(|id| {
+            Fish::new(
+                id,
+                position,
+                Velocity::zero(),
+                Direction::Right,
+                FishSpecies::NewSmall1,
+                &mut RngHandle::default(),
+            )
+        });
+        let (x, y) = manager
+            .get_entity(fish)
+            .unwrap()
+            .get_current_sprite()
+            .get_non_transparent_positions()
+            .into_iter()
+            .min()
+            .unwrap();
+        let flake = manager.spawn(|id| Food::new(id, 10.5 + x as f32, 10.5 + y as f32));
+        manager.update_all(timestep::STEP, &WorldContext::new(bounds));
+        assert!(manager.get_entity(flake).is_none());
+        assert!(manager.get_entity(fish).is_some());
+    }
+}
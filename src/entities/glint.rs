@@ -0,0 +1,105 @@
+use crate::depth;
+use crate::entity::{Animation, Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// How long each sparkle frame is shown
+const FRAME_DURATION: Duration = Duration::from_millis(150);
+
+/// Sparkle frames: grows to a star and fades again
+const FRAMES: [&str; 7] = [".", "+", "*", "#", "*", "+", "."];
+
+/// A brief sparkle of treasure on the tank floor (ambient event)
+#[derive(Debug, Clone)]
+pub struct Glint {
+    id: EntityId,
+    position: Position,
+    animation: Animation,
+    alive: bool,
+}
+
+impl Glint {
+    /// Create a glint at (x, y), just in front of the castle
+    pub fn new(id: EntityId, x: f32, y: f32) -> Self {
+        let frames = FRAMES
+            .iter()
+            .map(|frame| Sprite::from_ascii_art(frame, Some("Y")).with_attribute_mask("f"))
+            .collect();
+
+        Self {
+            id,
+            position: Position::new(x, y, depth::CASTLE - 1),
+            animation: Animation::new(frames, FRAME_DURATION, false),
+            alive: true,
+        }
+    }
+}
+
+impl Entity for Glint {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        Velocity::zero()
+    }
+
+    fn set_velocity(&mut self, _velocity: Velocity) {
+        // Treasure stays put
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        self.animation.get_current_sprite()
+    }
+
+    fn update(&mut self, delta_time: Duration, _world_bounds: Rect) {
+        self.animation.update(delta_time);
+    }
+
+    fn glows(&self) -> bool {
+        true
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive && !self.animation.is_finished()
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Glint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glint_sparkles_then_fades() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut glint = Glint::new(1, 10.0, 20.0);
+        assert_eq!(glint.get_current_sprite().lines()[0], ".");
+
+        glint.update(Duration::from_secs_f32(0.35), bounds);
+        assert_eq!(glint.get_current_sprite().lines()[0], "*");
+        assert!(glint.is_alive());
+
+        glint.update(Duration::from_secs(1), bounds);
+        assert!(!glint.is_alive());
+    }
+}
@@ -0,0 +1,288 @@
+use crate::collision::{CollisionLayers, Contact, ContactKind};
+use crate::depth;
+use crate::entity::{Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::rng::RngHandle;
+use crate::sprite;
+use crate::timestep;
+use rand::Rng;
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// Rows per tick the hook is lowered and reeled in, with any catch
+pub const REEL_SPEED: f32 = 0.5;
+
+/// Column of the hook's eye, where the line is tied on
+pub const LINE_X: f32 = 7.0;
+
+/// How long the hook waits for a bite before it is reeled in empty
+const WAIT: Duration = Duration::from_secs(20);
+
+const HOOK_ART: &str = "       o\n      ||\n      ||\n/ \\   ||\n  \\__//\n  `--'";
+
+/// Where the hook is in its trip
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    Lowering,
+    /// Hanging still, for this long so far
+    Waiting(Duration),
+    Reeling,
+}
+
+/// A fishing hook lowered from above the tank (as in the original): it
+/// hangs three quarters of the way down until a fish bites, then is
+/// reeled back up with the catch
+#[derive(Debug, Clone)]
+pub struct Hook {
+    id: EntityId,
+    position: Position,
+    stage: Stage,
+    sprite: Sprite,
+    alive: bool,
+}
+
+impl Hook {
+    /// A hook just above a screen of `screen_bounds`, somewhere across it
+    pub fn new(id: EntityId, screen_bounds: Rect, rng: &mut RngHandle) -> Self {
+        let sprite = sprite!(HOOK_ART);
+        let height = sprite.get_bounding_box().1 as f32;
+        let x = rng.gen_range(10..screen_bounds.width.saturating_sub(20).max(11));
+        Self {
+            id,
+            position: Position::new(x as f32, -height, depth::WATER_LINE1),
+            stage: Stage::Lowering,
+            sprite,
+            alive: true,
+        }
+    }
+
+    /// Whether the hook is on its way back up
+    pub fn is_reeling(&self) -> bool {
+        self.stage == Stage::Reeling
+    }
+}
+
+impl Entity for Hook {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        match self.stage {
+            Stage::Lowering => Velocity::new(0.0, REEL_SPEED),
+            Stage::Waiting(_) => Velocity::zero(),
+            Stage::Reeling => Velocity::new(0.0, -REEL_SPEED),
+        }
+    }
+
+    fn set_velocity(&mut self, _velocity: Velocity) {
+        // The line only goes up and down
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
+        let height = self.sprite.get_bounding_box().1 as f32;
+        self.position.y += self.velocity().dy * timestep::ticks(delta_time);
+        match self.stage {
+            Stage::Lowering => {
+                let bottom = world_bounds.height as f32 * 3.0 / 4.0;
+                if self.position.y + height >= bottom {
+                    self.position.y = bottom - height;
+                    self.stage = Stage::Waiting(Duration::ZERO);
+                }
+            }
+            Stage::Waiting(waited) => {
+                let waited = waited + delta_time;
+                self.stage = if waited >= WAIT {
+                    Stage::Reeling
+                } else {
+                    Stage::Waiting(waited)
+                };
+            }
+            Stage::Reeling => {
+                if self.position.y + height < 0.0 {
+                    self.alive = false;
+                }
+            }
+        }
+    }
+
+    fn collision_layers(&self) -> CollisionLayers {
+        CollisionLayers::HOOK
+    }
+
+    fn collision_mask(&self) -> CollisionLayers {
+        // One fish per trip
+        if self.is_reeling() {
+            CollisionLayers::NONE
+        } else {
+            CollisionLayers::FISH
+        }
+    }
+
+    fn on_contact(&mut self, contact: &Contact) {
+        if contact.kind == ContactKind::Hooked && contact.source == self.id {
+            self.stage = Stage::Reeling;
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Hook
+    }
+}
+
+/// The line a [`Hook`] hangs from, attached above it and reaching past
+/// the top of the screen
+#[derive(Debug, Clone)]
+pub struct FishingLine {
+    id: EntityId,
+    position: Position,
+    sprite: Sprite,
+    alive: bool,
+}
+
+impl FishingLine {
+    /// A line `length` rows long
+    pub fn new(id: EntityId, length: u16) -> Self {
+        let art = vec!["|"; length as usize].join("\n");
+        let mask = vec!["W"; length as usize].join("\n");
+        Self {
+            id,
+            position: Position::new(0.0, 0.0, depth::WATER_LINE1),
+            sprite: Sprite::from_ascii_art(&art, Some(&mask)),
+            alive: true,
+        }
+    }
+}
+
+impl Entity for FishingLine {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        Velocity::zero()
+    }
+
+    fn set_velocity(&mut self, _velocity: Velocity) {
+        // Carried by the hook
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    fn update(&mut self, _delta_time: Duration, _world_bounds: Rect) {}
+
+    fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::FishingLine
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Fish, FishSpecies};
+    use crate::entity::{Direction, EntityManager};
+    use crate::world::WorldContext;
+
+    #[test]
+    fn test_hook_lowers_waits_and_reels_in() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut hook = Hook::new(1, bounds, &mut RngHandle::default());
+        assert_eq!(hook.position().y, -6.0);
+        assert!((10.0..60.0).contains(&hook.position().x));
+
+        hook.update(Duration::from_secs(2), bounds);
+        assert_eq!(hook.position().y, 12.0);
+        assert_eq!(hook.velocity(), Velocity::zero());
+        assert_eq!(hook.collision_mask(), CollisionLayers::FISH);
+
+        // A bite sends it back up, and it can't catch another
+        hook.on_contact(&Contact {
+            kind: ContactKind::Hooked,
+            source: 1,
+            target: 2,
+        });
+        assert!(hook.is_reeling());
+        assert_eq!(hook.collision_mask(), CollisionLayers::NONE);
+        hook.update(Duration::from_secs(1), bounds);
+        assert!(!hook.is_alive());
+    }
+
+    #[test]
+    fn test_caught_fish_is_reeled_in() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let world = WorldContext::new(bounds);
+        let mut manager = EntityManager::new();
+        let fish = manager.spawn(|id| {
+            Fish::new(
+                id,
+                Position::new(10.5, 10.5, depth::FISH_START),
+                Velocity::zero(),
+                Direction::Right,
+                FishSpecies::NewSmall1,
+                &mut RngHandle::default(),
+            )
+        });
+        // The point of the hook in the middle of the fish
+        let hook = manager.spawn(|id| Hook::new(id, bounds, &mut RngHandle::default()));
+        manager
+            .get_entity_mut(hook)
+            .unwrap()
+            .set_position(Position::new(10.5, 8.5, depth::WATER_LINE1));
+        manager.update_all(timestep::STEP, &world);
+        assert!(manager.get_as::<Hook>(hook).unwrap().is_reeling());
+
+        let y = manager.get_entity(fish).unwrap().position().y;
+        manager.update_all(timestep::STEP * 2, &world);
+        assert_eq!(
+            manager.get_entity(fish).unwrap().position().y,
+            y - 2.0 * REEL_SPEED
+        );
+        manager.update_all(Duration::from_secs(2), &world);
+        assert!(manager.get_entity(fish).is_none());
+    }
+}
@@ -3,24 +3,52 @@
 //! This module contains all the different types of entities that can appear
 //! in the aquarium, including fish, bubbles, seaweed, and other creatures.
 
+pub mod anchor;
 pub mod big_fish;
 pub mod bubble;
 pub mod castle;
+pub mod clock;
+pub mod dolphin;
+pub mod droplet;
+pub mod ducks;
 pub mod fish;
+pub mod food;
+pub mod glint;
+pub mod hook;
+pub mod pomodoro;
+pub mod puff;
+pub mod sand;
+pub mod scripted;
 pub mod sea_monster;
 pub mod seaweed;
 pub mod shark;
 pub mod ship;
+pub mod splash;
+pub mod splat;
 pub mod water_surface;
 pub mod whale;
 
+pub use anchor::Anchor;
 pub use big_fish::{BigFish, BigFishVariant};
 pub use bubble::Bubble;
 pub use castle::Castle;
-pub use fish::{Fish, FishSpecies};
+pub use clock::Clock;
+pub use dolphin::Dolphin;
+pub use droplet::Droplet;
+pub use ducks::Ducks;
+pub use fish::{Fish, FishSpecies, FishSpeciesInfo};
+pub use food::Food;
+pub use glint::Glint;
+pub use hook::{FishingLine, Hook};
+pub use pomodoro::{Pomodoro, PomodoroTimes};
+pub use puff::Puff;
+pub use sand::Sand;
+pub use scripted::ScriptedCreature;
 pub use sea_monster::SeaMonster;
 pub use seaweed::Seaweed;
 pub use shark::{Shark, SharkTeeth};
 pub use ship::Ship;
+pub use splash::Splash;
+pub use splat::Splat;
 pub use water_surface::WaterSurface;
 pub use whale::Whale;
@@ -0,0 +1,262 @@
+use crate::clock::Instant;
+use crate::depth;
+use crate::entity::{Entity, EntityId, EntityKind, Position, Spawn, Sprite, Velocity};
+use crate::rng::RngHandle;
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// Row the timer sits on, in the sky above the water
+const TIMER_Y: f32 = 1.0;
+
+/// Shortest interval `--pomodoro` accepts
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest interval `--pomodoro` accepts
+const MAX_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Lengths of the work and break intervals
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PomodoroTimes {
+    pub work: Duration,
+    pub rest: Duration,
+}
+
+impl Default for PomodoroTimes {
+    /// The classic 25 minutes of work and 5 of rest
+    fn default() -> Self {
+        Self {
+            work: Duration::from_secs(25 * 60),
+            rest: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+impl PomodoroTimes {
+    /// Parse `WORK[/BREAK]` in minutes, e.g. `50/10`; the break defaults
+    /// to a fifth of the work. Each interval must be from a second to a
+    /// day long.
+    pub fn parse(value: &str) -> Option<Self> {
+        let minutes = |text: &str| {
+            let minutes = text.trim().parse::<f32>().ok()?;
+            let interval = Duration::try_from_secs_f32(minutes * 60.0).ok()?;
+            (MIN_INTERVAL..=MAX_INTERVAL)
+                .contains(&interval)
+                .then_some(interval)
+        };
+        let (work, rest) = match value.split_once('/') {
+            Some((work, rest)) => (minutes(work)?, minutes(rest)?),
+            None => {
+                let work = minutes(value)?;
+                (work, (work / 5).max(MIN_INTERVAL))
+            }
+        };
+        Some(Self { work, rest })
+    }
+
+    /// Intervals completed `elapsed` after the start, whether the current
+    /// one is work, and the time left in it
+    fn at(&self, elapsed: Duration) -> (u64, bool, Duration) {
+        let cycle = (self.work + self.rest).as_secs_f64();
+        if cycle <= 0.0 {
+            return (0, true, Duration::ZERO);
+        }
+        let elapsed = elapsed.as_secs_f64();
+        let cycles = (elapsed / cycle).floor();
+        let into = elapsed - cycles * cycle;
+        let work = self.work.as_secs_f64();
+        let (done, working, left) = if into < work {
+            (0, true, work - into)
+        } else {
+            (1, false, cycle - into)
+        };
+        (
+            cycles as u64 * 2 + done,
+            working,
+            Duration::from_secs_f64(left),
+        )
+    }
+}
+
+/// A pomodoro timer in the sky band, counting down work and break
+/// intervals on the wall clock and sending in the dolphins whenever one
+/// ends
+#[derive(Debug, Clone)]
+pub struct Pomodoro {
+    id: EntityId,
+    position: Position,
+    times: PomodoroTimes,
+    started: Instant,
+    /// Intervals completed when last looked at
+    completed: u64,
+    celebrate: bool,
+    sprite: Sprite,
+    alive: bool,
+}
+
+impl Pomodoro {
+    /// A timer that began at `started`, in the top right of
+    /// `screen_bounds`
+    pub fn new(id: EntityId, screen_bounds: Rect, times: PomodoroTimes, started: Instant) -> Self {
+        let mut pomodoro = Self {
+            id,
+            position: Position::new(0.0, TIMER_Y, depth::GUI),
+            times,
+            started,
+            completed: 0,
+            celebrate: false,
+            sprite: Sprite::from_ascii_art("", None),
+            alive: true,
+        };
+        pomodoro.show(Instant::now());
+        // Intervals that ended before this entity existed were celebrated
+        // already, or missed
+        pomodoro.celebrate = false;
+        pomodoro.anchor(screen_bounds);
+        pomodoro
+    }
+
+    /// Update the display for `now`, noting an interval that just ended
+    fn show(&mut self, now: Instant) {
+        let (completed, working, left) = self.times.at(now.duration_since(self.started));
+        if completed > self.completed {
+            self.completed = completed;
+            self.celebrate = true;
+        }
+        let seconds = left.as_secs_f32().ceil() as u64;
+        let (label, color) = if working {
+            ("work", 'R')
+        } else {
+            ("break", 'G')
+        };
+        let text = format!("{label} {:02}:{:02}", seconds / 60, seconds % 60);
+        let mask: String = text
+            .chars()
+            .map(|ch| if ch == ' ' { ' ' } else { color })
+            .collect();
+        self.sprite = Sprite::from_ascii_art(&text, Some(&mask));
+    }
+
+    fn anchor(&mut self, screen_bounds: Rect) {
+        let width = self.sprite.get_bounding_box().0;
+        self.position.x = screen_bounds.width.saturating_sub(width + 1) as f32;
+    }
+}
+
+impl Entity for Pomodoro {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        Velocity::zero()
+    }
+
+    fn set_velocity(&mut self, _velocity: Velocity) {
+        // The timer stays in its corner
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    fn update(&mut self, _delta_time: Duration, world_bounds: Rect) {
+        self.show(Instant::now());
+        self.anchor(world_bounds);
+    }
+
+    fn on_resize(&mut self, _old: Rect, new: Rect) {
+        self.anchor(new);
+    }
+
+    fn pending_spawns(&mut self, _rng: &mut RngHandle) -> Vec<Spawn> {
+        if std::mem::take(&mut self.celebrate) {
+            vec![Spawn::Dolphins]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn glows(&self) -> bool {
+        true
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Pomodoro
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_times() {
+        let times = PomodoroTimes::parse("50/10").unwrap();
+        assert_eq!(times.work, Duration::from_secs(50 * 60));
+        assert_eq!(times.rest, Duration::from_secs(10 * 60));
+        assert_eq!(PomodoroTimes::parse("25"), Some(PomodoroTimes::default()));
+        assert_eq!(PomodoroTimes::parse("0/5"), None);
+        assert_eq!(PomodoroTimes::parse("soon"), None);
+        assert_eq!(PomodoroTimes::parse("1e30"), None);
+        assert_eq!(PomodoroTimes::parse("0.001"), None);
+        assert_eq!(PomodoroTimes::parse("1440/-1"), None);
+        let short = PomodoroTimes::parse("0.02").unwrap();
+        assert_eq!(short.rest, MIN_INTERVAL);
+    }
+
+    #[test]
+    fn test_intervals_count_down_and_celebrate() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let start = Instant::now();
+        let mut pomodoro = Pomodoro::new(1, bounds, PomodoroTimes::default(), start);
+        assert!(pomodoro
+            .pending_spawns(&mut RngHandle::default())
+            .is_empty());
+
+        pomodoro.show(start + Duration::from_secs(60));
+        assert_eq!(pomodoro.get_current_sprite().lines()[0], "work 24:00");
+        assert!(pomodoro
+            .pending_spawns(&mut RngHandle::default())
+            .is_empty());
+        pomodoro.anchor(bounds);
+        assert_eq!(pomodoro.position().x, 69.0);
+
+        pomodoro.show(start + Duration::from_secs(25 * 60 + 1));
+        assert_eq!(pomodoro.get_current_sprite().lines()[0], "break 04:59");
+        assert_eq!(
+            pomodoro.pending_spawns(&mut RngHandle::default()),
+            vec![Spawn::Dolphins]
+        );
+        assert!(pomodoro
+            .pending_spawns(&mut RngHandle::default())
+            .is_empty());
+
+        // Back to work after the break, and another celebration
+        pomodoro.show(start + Duration::from_secs(30 * 60));
+        assert_eq!(pomodoro.get_current_sprite().lines()[0], "work 25:00");
+        assert_eq!(
+            pomodoro.pending_spawns(&mut RngHandle::default()),
+            vec![Spawn::Dolphins]
+        );
+    }
+}
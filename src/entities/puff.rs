@@ -0,0 +1,117 @@
+use crate::entity::{Animation, Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::timestep;
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// Ticks each frame is shown
+const FRAME_TICKS: u32 = 6;
+
+/// A flash swelling into a ring of smoke that drifts apart
+const FRAMES: [&[&str]; 4] = [
+    &["", "   *", ""],
+    &["  . .", " ( * )", "  ' '"],
+    &[" ( . )", "(  o  )", " ( . )"],
+    &[" .   .", ".     .", " .   ."],
+];
+
+/// A puff of smoke where a creature was whisked out of the tank
+#[derive(Debug, Clone)]
+pub struct Puff {
+    id: EntityId,
+    position: Position,
+    animation: Animation,
+    alive: bool,
+}
+
+impl Puff {
+    /// Create a puff centred on `centre`
+    pub fn new(id: EntityId, centre: Position) -> Self {
+        let frames = FRAMES
+            .iter()
+            .enumerate()
+            .map(|(n, rows)| {
+                let art = rows.join("\n");
+                // White at first, greying as it clears
+                let color = if n < 2 { 'W' } else { 'w' };
+                let mask: String = art
+                    .chars()
+                    .map(|c| if c == ' ' || c == '\n' { c } else { color })
+                    .collect();
+                Sprite::from_ascii_art(&art, Some(&mask))
+            })
+            .collect();
+
+        Self {
+            id,
+            position: Position::new(centre.x - 3.0, centre.y - 1.0, centre.depth),
+            animation: Animation::new(frames, timestep::STEP * FRAME_TICKS, false),
+            alive: true,
+        }
+    }
+}
+
+impl Entity for Puff {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        Velocity::zero()
+    }
+
+    fn set_velocity(&mut self, _velocity: Velocity) {
+        // Hangs where the creature was
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        self.animation.get_current_sprite()
+    }
+
+    fn update(&mut self, delta_time: Duration, _world_bounds: Rect) {
+        self.animation.update(delta_time);
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive && !self.animation.is_finished()
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Puff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_puff_swells_then_clears() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut puff = Puff::new(1, Position::new(20.0, 10.0, 2));
+        assert_eq!(puff.position(), Position::new(17.0, 9.0, 2));
+        assert_eq!(puff.get_current_sprite().lines()[1], "   *");
+
+        puff.update(timestep::STEP * 12, bounds);
+        assert_eq!(puff.get_current_sprite().lines()[1], "(  o  )");
+        assert!(puff.is_alive());
+
+        puff.update(timestep::STEP * 12, bounds);
+        assert!(!puff.is_alive());
+    }
+}
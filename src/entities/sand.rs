@@ -0,0 +1,145 @@
+use crate::depth;
+use crate::entity::{Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::rng::RngHandle;
+use rand::Rng;
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// Rows of sand at the bottom of the tank
+pub const ROWS: u16 = 2;
+
+/// Sandy strip along the tank floor, behind the seaweed and castle
+///
+/// The texture is rolled once when the tank is filled, so it is rebuilt
+/// whenever the tank is redrawn; a resize rolls it again from the same
+/// seed at the new width.
+#[derive(Debug, Clone)]
+pub struct Sand {
+    id: EntityId,
+    position: Position,
+    sprite: Sprite,
+    seed: u64, // Seed the texture is rolled from
+    alive: bool,
+}
+
+impl Sand {
+    /// Create a floor strip spanning the width of the screen
+    pub fn new(id: EntityId, screen_bounds: Rect, rng: &mut RngHandle) -> Self {
+        Self::from_seed(id, screen_bounds, rng.gen())
+    }
+
+    fn from_seed(id: EntityId, screen_bounds: Rect, seed: u64) -> Self {
+        let y = screen_bounds.height.saturating_sub(ROWS) as f32;
+
+        Self {
+            id,
+            position: Position::new(0.0, y, depth::SAND),
+            sprite: Self::create_sprite(screen_bounds.width, &mut RngHandle::seeded(seed)),
+            seed,
+            alive: true,
+        }
+    }
+
+    /// Random texture: sparse ripples on top, denser sand below. Ripples
+    /// are plain yellow and grains dim yellow.
+    fn create_sprite(width: u16, rng: &mut impl Rng) -> Sprite {
+        let mut art = String::new();
+        let mut attributes = String::new();
+
+        for row in 0..ROWS {
+            // Chance of a grain and of a ripple in each column of this row
+            let (grain, ripple) = if row == 0 { (0.25, 0.1) } else { (0.5, 0.3) };
+            for _ in 0..width {
+                let roll: f32 = rng.gen();
+                let (ch, attribute) = if roll < ripple {
+                    ('~', ' ')
+                } else if roll < ripple + grain {
+                    ('.', 'd')
+                } else {
+                    (' ', ' ')
+                };
+                art.push(ch);
+                attributes.push(attribute);
+            }
+            art.push('\n');
+            attributes.push('\n');
+        }
+
+        let color_mask = art
+            .lines()
+            .map(|line| "y".repeat(line.len()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Sprite::from_ascii_art(&art, Some(&color_mask)).with_attribute_mask(&attributes)
+    }
+}
+
+impl Entity for Sand {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        Velocity::zero()
+    }
+
+    fn set_velocity(&mut self, _velocity: Velocity) {
+        // The floor stays put
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    fn update(&mut self, _delta_time: Duration, _screen_bounds: Rect) {}
+
+    fn on_resize(&mut self, _old: Rect, new: Rect) {
+        *self = Self::from_seed(self.id, new, self.seed);
+    }
+
+    fn is_backdrop(&self) -> bool {
+        true
+    }
+
+    fn is_static(&self) -> bool {
+        true
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Sand
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sand_spans_the_floor() {
+        let sand = Sand::new(1, Rect::new(0, 0, 80, 24), &mut RngHandle::default());
+
+        assert_eq!(sand.position().y, 22.0);
+        assert_eq!(sand.get_current_sprite().get_bounding_box(), (80, ROWS));
+        assert!(sand.depth() > depth::CASTLE);
+    }
+}
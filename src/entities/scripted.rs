@@ -0,0 +1,176 @@
+//! Creatures loaded from sprite files (see [`crate::assets`])
+//!
+//! A scripted creature plays its frames in a loop and swims straight across
+//! the tank, dying once it has left the world. Creatures with no speed stay
+//! where they were placed, like scenery.
+
+use crate::assets::{CreatureDef, Heading, Rows, MAX_FRAME_DELAY, MIN_FRAME_DELAY};
+use crate::entity::{
+    Animation, Direction, Entity, EntityId, EntityKind, Position, Sprite, Velocity,
+};
+use crate::rng::RngHandle;
+use rand::Rng;
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// A creature defined in a sprite file
+#[derive(Debug)]
+pub struct ScriptedCreature {
+    id: EntityId,
+    entity_type: &'static str,
+    position: Position,
+    velocity: Velocity,
+    direction: Direction,
+    animation: Animation,
+    alive: bool,
+}
+
+impl ScriptedCreature {
+    /// Place a new creature from `def` within `screen_bounds` (a whole
+    /// tank, or one segment of it for scenery) whose water starts at
+    /// `waterline`
+    pub fn new(
+        id: EntityId,
+        def: &CreatureDef,
+        entity_type: &'static str,
+        screen_bounds: Rect,
+        waterline: f32,
+        rng: &mut RngHandle,
+    ) -> Self {
+        let direction = match def.heading {
+            Heading::Left => Direction::Left,
+            Heading::Right => Direction::Right,
+            Heading::Random if rng.gen_bool(0.5) => Direction::Left,
+            Heading::Random => Direction::Right,
+        };
+
+        let frames = match direction {
+            Direction::Left if !def.left_frames.is_empty() => &def.left_frames,
+            _ => &def.frames,
+        };
+        let frames: Vec<Sprite> = frames
+            .iter()
+            .map(|frame| Sprite::from_ascii_art(&frame.art, frame.mask.as_deref()))
+            .collect();
+        let (width, height) = frames[0].get_bounding_box();
+
+        // Moving creatures come in from the edge they swim away from
+        let left = screen_bounds.x as f32;
+        let x = if def.speed == 0.0 {
+            left + rng.gen_range(0..screen_bounds.width.saturating_sub(width).max(1)) as f32
+        } else {
+            match direction {
+                Direction::Right => left - width as f32,
+                Direction::Left => left + screen_bounds.width as f32 - 1.0,
+            }
+        };
+        let floor = screen_bounds.height.saturating_sub(height) as f32;
+        let y = match def.rows {
+            Rows::Surface => (waterline - height as f32).max(0.0),
+            Rows::Floor => floor,
+            Rows::Water => {
+                let lowest = (floor - 1.0).max(waterline + 1.0);
+                rng.gen_range(waterline..lowest).floor()
+            }
+        };
+
+        let dx = match direction {
+            Direction::Right => def.speed,
+            Direction::Left => -def.speed,
+        };
+        // Definitions built in code skip the parser's bounds
+        let frame_duration =
+            Duration::try_from_secs_f32(def.frame_delay.clamp(MIN_FRAME_DELAY, MAX_FRAME_DELAY))
+                .unwrap_or(Duration::from_millis(500));
+
+        Self {
+            id,
+            entity_type,
+            position: Position::new(x, y, def.depth),
+            velocity: Velocity::new(dx, 0.0),
+            direction,
+            animation: Animation::new(frames, frame_duration, true),
+            alive: true,
+        }
+    }
+}
+
+impl Entity for ScriptedCreature {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        self.velocity
+    }
+
+    fn set_velocity(&mut self, velocity: Velocity) {
+        self.velocity = velocity;
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        self.animation.get_current_sprite()
+    }
+
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
+        self.animation.update(delta_time);
+        self.position.x += self.velocity.dx * delta_time.as_secs_f32();
+
+        // Die once fully outside the world
+        let width = self.get_current_sprite().get_bounding_box().0 as f32;
+        let gone = match self.direction {
+            _ if self.velocity.dx == 0.0 => false,
+            Direction::Right => self.position.x > world_bounds.right() as f32,
+            Direction::Left => self.position.x + width < world_bounds.x as f32,
+        };
+        if gone {
+            self.alive = false;
+        }
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Custom(self.entity_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swims_off_and_dies() {
+        let def = CreatureDef::parse("name = eel\ndirection = left\nspeed = 10\n--- frame\n~~~>")
+            .unwrap();
+        let bounds = Rect::new(0, 0, 40, 20);
+        let mut eel = ScriptedCreature::new(1, &def, "eel", bounds, 9.0, &mut RngHandle::default());
+
+        assert_eq!(eel.position().x, 39.0);
+        assert!((9.0..20.0).contains(&eel.position().y));
+        assert!(eel.population().is_none());
+
+        for _ in 0..50 {
+            eel.update(Duration::from_millis(100), bounds);
+        }
+        assert!(!eel.is_alive());
+    }
+}
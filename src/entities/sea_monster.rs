@@ -1,25 +1,26 @@
-use crate::entity::{DeathCallback, Direction, Entity, EntityId, Position, Sprite, Velocity};
+use crate::clock::Instant;
+use crate::entity::{
+    Animation, Direction, Entity, EntityId, EntityKind, Position, Sprite, Velocity,
+};
+use crate::rng::RngHandle;
+use crate::timestep;
 use rand::Rng;
 use ratatui::layout::Rect;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 pub struct SeaMonster {
     id: EntityId,
     position: Position,
     velocity: Velocity,
     direction: Direction,
-    animation_frame: usize,
-    last_frame_time: Instant,
-    sprites: Vec<Sprite>,
+    animation: Animation,
     #[allow(dead_code)]
     created_at: Instant,
     alive: bool,
 }
 
 impl SeaMonster {
-    pub fn new(id: EntityId, screen_bounds: Rect, classic_mode: bool) -> Self {
-        let mut rng = rand::thread_rng();
-
+    pub fn new(id: EntityId, screen_bounds: Rect, classic_mode: bool, rng: &mut RngHandle) -> Self {
         // Random direction
         let direction = if rng.gen_bool(0.5) {
             Direction::Right
@@ -64,9 +65,8 @@ impl SeaMonster {
             position,
             velocity,
             direction,
-            animation_frame: 0,
-            last_frame_time: Instant::now(),
-            sprites,
+            // Tentacles move every 250ms
+            animation: Animation::new(sprites, Duration::from_millis(250), true),
             created_at: Instant::now(),
             alive: true,
         }
@@ -159,14 +159,6 @@ impl SeaMonster {
         }
     }
 
-    fn update_animation(&mut self) {
-        // Update animation frame every 250ms for tentacle movement
-        if self.last_frame_time.elapsed().as_millis() > 250 {
-            self.animation_frame = (self.animation_frame + 1) % self.sprites.len();
-            self.last_frame_time = Instant::now();
-        }
-    }
-
     fn check_offscreen_death(&mut self, screen_bounds: Rect) {
         let is_off_screen = match self.direction {
             Direction::Right => self.position.x > screen_bounds.width as f32 + 60.0,
@@ -205,7 +197,7 @@ impl Entity for SeaMonster {
     }
 
     fn get_current_sprite(&self) -> &Sprite {
-        &self.sprites[self.animation_frame]
+        self.animation.get_current_sprite()
     }
 
     fn update(&mut self, delta_time: Duration, screen_bounds: Rect) {
@@ -213,11 +205,10 @@ impl Entity for SeaMonster {
             return;
         }
 
-        // Update animation
-        self.update_animation();
+        self.animation.update(delta_time);
 
         // Update position based on velocity
-        self.position.x += self.velocity.dx * delta_time.as_secs_f32() * 60.0; // Scale for 60 FPS
+        self.position.x += self.velocity.dx * timestep::ticks(delta_time);
 
         // Check if monster should die (off-screen)
         self.check_offscreen_death(screen_bounds);
@@ -231,12 +222,12 @@ impl Entity for SeaMonster {
         self.alive = false;
     }
 
-    fn entity_type(&self) -> &'static str {
-        "sea_monster"
+    fn ride_tide(&mut self, rows: f32) {
+        self.position.y += rows;
     }
 
-    fn death_callback(&self) -> Option<DeathCallback> {
-        Some(crate::spawning::random_object)
+    fn kind(&self) -> EntityKind {
+        EntityKind::SeaMonster
     }
 }
 
@@ -247,7 +238,7 @@ mod tests {
     #[test]
     fn test_sea_monster_creation() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let monster = SeaMonster::new(1, screen_bounds, false);
+        let monster = SeaMonster::new(1, screen_bounds, false, &mut RngHandle::default());
 
         assert!(monster.is_alive());
         assert_eq!(monster.entity_type(), "sea_monster");
@@ -260,7 +251,7 @@ mod tests {
 
         // Test multiple monsters to check randomization (modern mode)
         for _ in 0..10 {
-            let monster = SeaMonster::new(1, screen_bounds, false);
+            let monster = SeaMonster::new(1, screen_bounds, false, &mut RngHandle::default());
 
             match monster.direction {
                 Direction::Right => {
@@ -286,14 +277,14 @@ mod tests {
 
         // Check that sprites are not empty
         for sprite in &right_sprites {
-            assert!(!sprite.lines.is_empty());
+            assert!(!sprite.lines().is_empty());
         }
         for sprite in &left_sprites {
-            assert!(!sprite.lines.is_empty());
+            assert!(!sprite.lines().is_empty());
         }
 
         // Check that left and right sprites are different
-        assert_ne!(right_sprites[0].lines, left_sprites[0].lines);
+        assert_ne!(right_sprites[0].lines(), left_sprites[0].lines());
     }
 
     #[test]
@@ -302,8 +293,8 @@ mod tests {
         let left_sprites = SeaMonster::create_new_monster_sprites(&Direction::Left);
 
         // Check that sprites contain monster features
-        let right_text = right_sprites[0].lines.join("\n");
-        let left_text = left_sprites[0].lines.join("\n");
+        let right_text = right_sprites[0].lines().join("\n");
+        let left_text = left_sprites[0].lines().join("\n");
 
         // Should contain tentacle-like characters
         assert!(right_text.contains("?"));
@@ -317,41 +308,43 @@ mod tests {
     #[test]
     fn test_sea_monster_animation_frames() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let monster_new = SeaMonster::new(1, screen_bounds, false);
+        let monster_new = SeaMonster::new(1, screen_bounds, false, &mut RngHandle::default());
 
         // New monster should have 2 animation frames
-        assert_eq!(monster_new.sprites.len(), 2);
-        assert_ne!(monster_new.sprites[0].lines, monster_new.sprites[1].lines);
+        assert_eq!(monster_new.animation.frames.len(), 2);
+        assert_ne!(
+            monster_new.animation.frames[0].lines(),
+            monster_new.animation.frames[1].lines()
+        );
 
-        let monster_old = SeaMonster::new(2, screen_bounds, true);
+        let monster_old = SeaMonster::new(2, screen_bounds, true, &mut RngHandle::default());
 
         // Old monster should have 4 animation frames
-        assert_eq!(monster_old.sprites.len(), 4);
+        assert_eq!(monster_old.animation.frames.len(), 4);
     }
 
     #[test]
     fn test_sea_monster_animation_update() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let mut monster = SeaMonster::new(1, screen_bounds, false);
+        let mut monster = SeaMonster::new(1, screen_bounds, false, &mut RngHandle::default());
 
-        let initial_frame = monster.animation_frame;
+        let initial_frame = monster.animation.current_frame;
 
         // Animation should not update immediately
-        monster.update_animation();
-        assert_eq!(monster.animation_frame, initial_frame);
+        monster.animation.update(Duration::ZERO);
+        assert_eq!(monster.animation.current_frame, initial_frame);
 
         // Simulate time passing
-        monster.last_frame_time = Instant::now() - Duration::from_millis(300);
-        monster.update_animation();
+        monster.animation.update(Duration::from_millis(300));
 
         // Frame should have advanced
-        assert_ne!(monster.animation_frame, initial_frame);
+        assert_ne!(monster.animation.current_frame, initial_frame);
     }
 
     #[test]
     fn test_sea_monster_movement() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let mut monster = SeaMonster::new(1, screen_bounds, false);
+        let mut monster = SeaMonster::new(1, screen_bounds, false, &mut RngHandle::default());
 
         let initial_x = monster.position().x;
         monster.update(Duration::from_millis(16), screen_bounds); // ~60 FPS
@@ -363,7 +356,7 @@ mod tests {
     #[test]
     fn test_sea_monster_speed() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let monster = SeaMonster::new(1, screen_bounds, false);
+        let monster = SeaMonster::new(1, screen_bounds, false, &mut RngHandle::default());
 
         // Sea monsters should move faster than whales (speed 2)
         assert_eq!(monster.velocity().dx.abs(), 2.0);
@@ -372,7 +365,7 @@ mod tests {
     #[test]
     fn test_sea_monster_offscreen_death() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let mut monster = SeaMonster::new(1, screen_bounds, false);
+        let mut monster = SeaMonster::new(1, screen_bounds, false, &mut RngHandle::default());
 
         // Move monster far off screen
         match monster.direction {
@@ -387,7 +380,7 @@ mod tests {
     #[test]
     fn test_sea_monster_positioning() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let monster = SeaMonster::new(1, screen_bounds, false);
+        let monster = SeaMonster::new(1, screen_bounds, false, &mut RngHandle::default());
 
         // Monsters should be slightly below surface (y=2) and water_gap2 depth
         assert_eq!(monster.position().y, 2.0);
@@ -399,16 +392,16 @@ mod tests {
         let screen_bounds = Rect::new(0, 0, 80, 24);
 
         // Modern mode should use new sprites (2 frames)
-        let monster_modern = SeaMonster::new(1, screen_bounds, false);
-        assert_eq!(monster_modern.sprites.len(), 2);
+        let monster_modern = SeaMonster::new(1, screen_bounds, false, &mut RngHandle::default());
+        assert_eq!(monster_modern.animation.frames.len(), 2);
 
         // Classic mode should use old sprites (4 frames)
-        let monster_classic = SeaMonster::new(2, screen_bounds, true);
-        assert_eq!(monster_classic.sprites.len(), 4);
+        let monster_classic = SeaMonster::new(2, screen_bounds, true, &mut RngHandle::default());
+        assert_eq!(monster_classic.animation.frames.len(), 4);
 
         // Classic mode should have different spawn position for right-moving
         for _ in 0..10 {
-            let monster = SeaMonster::new(3, screen_bounds, true);
+            let monster = SeaMonster::new(3, screen_bounds, true, &mut RngHandle::default());
             if monster.direction == Direction::Right {
                 assert_eq!(monster.position.x, -64.0); // Old monster spawns at -64
             }
@@ -416,7 +409,7 @@ mod tests {
 
         // Modern mode spawns at -54 for right-moving
         for _ in 0..10 {
-            let monster = SeaMonster::new(4, screen_bounds, false);
+            let monster = SeaMonster::new(4, screen_bounds, false, &mut RngHandle::default());
             if monster.direction == Direction::Right {
                 assert_eq!(monster.position.x, -54.0); // New monster spawns at -54
             }
@@ -434,13 +427,13 @@ mod tests {
 
         // Check that sprites contain monster features
         for sprite in &right_sprites {
-            let text = sprite.lines.join("\n");
+            let text = sprite.lines().join("\n");
             assert!(text.contains("?"));
             assert!(text.contains("o")); // Eye
         }
 
         for sprite in &left_sprites {
-            let text = sprite.lines.join("\n");
+            let text = sprite.lines().join("\n");
             assert!(text.contains("?"));
             assert!(text.contains("o")); // Eye
         }
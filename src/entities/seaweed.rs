@@ -1,7 +1,13 @@
-use crate::entity::{Animation, DeathCallback, Entity, EntityId, Position, Sprite, Velocity};
+use crate::charset;
+use crate::entity::{Animation, Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::rng::RngHandle;
+use crate::scheduler::Population;
 use rand::Rng;
 use ratatui::layout::Rect;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+
+/// Braille replacements for the seaweed art with `--charset unicode`
+const UNICODE_GLYPHS: &[(char, char)] = &[('(', '⢎'), (')', '⡱')];
 
 /// A seaweed entity that sways at the bottom of the aquarium
 #[derive(Debug, Clone)]
@@ -10,16 +16,13 @@ pub struct Seaweed {
     position: Position,
     animation: Animation,
     alive: bool,
-    _created_at: Instant,
-    die_time: Instant,
+    lifetime: Duration,
     height: u8,
 }
 
 impl Seaweed {
     /// Create a new seaweed with random height and position
-    pub fn new_random(id: EntityId, screen_bounds: Rect) -> Self {
-        let mut rng = rand::thread_rng();
-
+    pub fn new_random(id: EntityId, screen_bounds: Rect, rng: &mut RngHandle) -> Self {
         // Random height between 3-7 characters (original: rand(4) + 3)
         let height = rng.gen_range(3..=6) as u8;
 
@@ -29,18 +32,17 @@ impl Seaweed {
         // Y position at bottom minus height (original: height() - height)
         let y = (screen_bounds.height.saturating_sub(height as u16)) as f32;
 
-        Self::new(id, x, y, height)
+        Self::new(id, x, y, height, rng)
     }
 
     /// Create a new seaweed with specific parameters
-    pub fn new(id: EntityId, x: f32, y: f32, height: u8) -> Self {
+    pub fn new(id: EntityId, x: f32, y: f32, height: u8, rng: &mut RngHandle) -> Self {
         let (left_sprite, right_sprite) = Self::create_seaweed_sprites(height);
 
         // Create 2-frame animation for swaying effect
         let frames = vec![left_sprite, right_sprite];
 
         // Random animation speed (original: rand(.05) + .25 = 0.25 to 0.30)
-        let mut rng = rand::thread_rng();
         let anim_speed_secs = rng.gen_range(0.25..0.30);
         let frame_duration = Duration::from_secs_f32(1.0 / anim_speed_secs);
 
@@ -50,15 +52,14 @@ impl Seaweed {
 
         // Seaweed lives for 8-12 minutes (original: rand(4*60) + (8*60))
         let lifetime_secs = rng.gen_range(8 * 60..12 * 60);
-        let die_time = Instant::now() + Duration::from_secs(lifetime_secs);
+        let lifetime = Duration::from_secs(lifetime_secs);
 
         Self {
             id,
             position,
             animation,
             alive: true,
-            _created_at: Instant::now(),
-            die_time,
+            lifetime,
             height,
         }
     }
@@ -96,16 +97,18 @@ impl Seaweed {
             right_image.pop();
         }
 
-        // Create green color masks
-        let left_mask = "G".repeat(left_image.lines().map(|line| line.len()).max().unwrap_or(1));
-        let right_mask = "G".repeat(
-            right_image
-                .lines()
-                .map(|line| line.len())
-                .max()
-                .unwrap_or(1),
-        );
-
+        // Green colour masks covering every row
+        let mask = |image: &str| -> String {
+            image
+                .chars()
+                .map(|c| if c == '\n' { c } else { 'G' })
+                .collect()
+        };
+        let left_mask = mask(&left_image);
+        let right_mask = mask(&right_image);
+
+        let left_image = charset::substitute(&left_image, UNICODE_GLYPHS);
+        let right_image = charset::substitute(&right_image, UNICODE_GLYPHS);
         let left_sprite = Sprite::from_ascii_art(&left_image, Some(&left_mask));
         let right_sprite = Sprite::from_ascii_art(&right_image, Some(&right_mask));
 
@@ -118,8 +121,9 @@ impl Seaweed {
     }
 
     /// Check if seaweed should die due to age
-    fn check_age_death(&mut self) {
-        if Instant::now() >= self.die_time {
+    fn check_age_death(&mut self, delta_time: Duration) {
+        self.lifetime = self.lifetime.saturating_sub(delta_time);
+        if self.lifetime.is_zero() {
             self.alive = false;
         }
     }
@@ -154,16 +158,24 @@ impl Entity for Seaweed {
         self.animation.get_current_sprite()
     }
 
-    fn update(&mut self, _delta_time: Duration, _screen_bounds: Rect) {
+    fn update(&mut self, delta_time: Duration, _screen_bounds: Rect) {
         if !self.alive {
             return;
         }
 
         // Update animation for swaying effect
-        self.animation.update();
+        self.animation.update(delta_time);
 
         // Check if seaweed should die from old age
-        self.check_age_death();
+        self.check_age_death(delta_time);
+    }
+
+    fn on_resize(&mut self, old: Rect, new: Rect) {
+        if old.width > 0 {
+            self.position.x *= new.width as f32 / old.width as f32;
+        }
+        // Stay rooted to the floor
+        self.position.y = new.height.saturating_sub(self.height as u16) as f32;
     }
 
     fn is_alive(&self) -> bool {
@@ -174,12 +186,16 @@ impl Entity for Seaweed {
         self.alive = false;
     }
 
-    fn entity_type(&self) -> &'static str {
-        "seaweed"
+    fn is_shelter(&self) -> bool {
+        true
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Seaweed
     }
 
-    fn death_callback(&self) -> Option<DeathCallback> {
-        Some(crate::spawning::add_seaweed)
+    fn population(&self) -> Option<Population> {
+        Some(Population::Seaweed)
     }
 }
 
@@ -190,7 +206,7 @@ mod tests {
     #[test]
     fn test_seaweed_creation() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let seaweed = Seaweed::new_random(1, screen_bounds);
+        let seaweed = Seaweed::new_random(1, screen_bounds, &mut RngHandle::default());
 
         assert!(seaweed.is_alive());
         assert_eq!(seaweed.entity_type(), "seaweed");
@@ -202,13 +218,13 @@ mod tests {
     fn test_seaweed_sprites() {
         let (left, right) = Seaweed::create_seaweed_sprites(3);
 
-        assert!(!left.lines.is_empty());
-        assert!(!right.lines.is_empty());
-        assert_eq!(left.lines.len(), 3);
-        assert_eq!(right.lines.len(), 3);
+        assert!(!left.lines().is_empty());
+        assert!(!right.lines().is_empty());
+        assert_eq!(left.lines().len(), 3);
+        assert_eq!(right.lines().len(), 3);
 
         // Should have different patterns for left and right
-        assert_ne!(left.lines, right.lines);
+        assert_ne!(left.lines(), right.lines());
     }
 
     #[test]
@@ -221,14 +237,14 @@ mod tests {
         // Row 3 (i=3, odd): left gets '(', right gets ' )'
         // Row 4 (i=4, even): left gets ' )', right gets '('
 
-        assert_eq!(left.lines[0], "(");
-        assert_eq!(left.lines[1], " )");
-        assert_eq!(left.lines[2], "(");
-        assert_eq!(left.lines[3], " )");
+        assert_eq!(left.lines()[0], "(");
+        assert_eq!(left.lines()[1], " )");
+        assert_eq!(left.lines()[2], "(");
+        assert_eq!(left.lines()[3], " )");
 
-        assert_eq!(right.lines[0], " )");
-        assert_eq!(right.lines[1], "(");
-        assert_eq!(right.lines[2], " )");
-        assert_eq!(right.lines[3], "(");
+        assert_eq!(right.lines()[0], " )");
+        assert_eq!(right.lines()[1], "(");
+        assert_eq!(right.lines()[2], " )");
+        assert_eq!(right.lines()[3], "(");
     }
 }
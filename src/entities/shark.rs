@@ -1,7 +1,25 @@
-use crate::entity::{DeathCallback, Direction, Entity, EntityId, Position, Sprite, Velocity};
+use crate::clock::Instant;
+use crate::collision::CollisionLayers;
+use crate::entity::{Direction, Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::environment::WATERLINE_Y;
+use crate::rng::RngHandle;
+use crate::sprite;
+use crate::timestep;
 use rand::Rng;
 use ratatui::layout::Rect;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+
+/// Default hunting aggressiveness (0.0 = always cruise, 1.0 = relentless)
+pub const DEFAULT_AGGRESSIVENESS: f32 = 0.5;
+
+/// How far ahead of its teeth (columns) the shark notices prey
+const HUNT_RANGE: f32 = 40.0;
+
+/// How far above or below its teeth (rows) the shark looks for prey
+const HUNT_BAND: f32 = 6.0;
+
+/// Vertical speed at full aggressiveness (rows per tick)
+const MAX_HUNT_DY: f32 = 0.25;
 
 /// A shark entity that hunts fish across the screen
 #[derive(Debug, Clone)]
@@ -15,12 +33,20 @@ pub struct Shark {
     alive: bool,
     _created_at: Instant,
     teeth_id: Option<EntityId>, // ID of associated teeth entity
+    aggressiveness: f32,
+    target: Option<Position>, // Prey currently being chased
+    surface_y: f32,           // Top of the hunting area, follows the tide
 }
 
 impl Shark {
-    /// Create a new shark with random direction and position
-    pub fn new_random(id: EntityId, screen_bounds: Rect) -> Self {
-        let mut rng = rand::thread_rng();
+    /// Create a new shark with random direction and position, somewhere
+    /// below `waterline`
+    pub fn new_random(
+        id: EntityId,
+        screen_bounds: Rect,
+        waterline: f32,
+        rng: &mut RngHandle,
+    ) -> Self {
         let direction = if rng.gen_bool(0.5) {
             Direction::Right
         } else {
@@ -46,7 +72,9 @@ impl Shark {
         };
 
         // Random Y position (original: rand(height - (10 + 9)) + 9)
-        let y = rng.gen_range(9..(screen_bounds.height.saturating_sub(19)).max(10)) as f32;
+        let top = waterline.max(0.0) as u16;
+        let y =
+            rng.gen_range(top..screen_bounds.height.saturating_sub(top + 10).max(top + 1)) as f32;
 
         let position = Position::new(x, y, crate::depth::SHARK);
 
@@ -60,6 +88,9 @@ impl Shark {
             alive: true,
             _created_at: Instant::now(),
             teeth_id: None,
+            aggressiveness: DEFAULT_AGGRESSIVENESS,
+            target: None,
+            surface_y: waterline,
         }
     }
 
@@ -77,6 +108,9 @@ impl Shark {
             alive: true,
             _created_at: Instant::now(),
             teeth_id: None,
+            aggressiveness: DEFAULT_AGGRESSIVENESS,
+            target: None,
+            surface_y: WATERLINE_Y,
         }
     }
 
@@ -134,19 +168,46 @@ impl Shark {
 
 "#;
 
-        let right_sprite = Sprite::from_ascii_art(right_art, Some(right_mask));
-        let left_sprite = Sprite::from_ascii_art(left_art, Some(left_mask));
+        // White belly, filled in under the outline
+        let right_belly = r#"
+
+
+
+
+
+
+                              wwwwww   wwwwww  wwwww
+                          wwwww"#;
+
+        let left_belly = r#"
+
+
+
+
+
+
+ wwwww  wwwwww   wwwwww
+                      wwwww"#;
+
+        let right_sprite =
+            Sprite::from_ascii_art(right_art, Some(right_mask)).with_background_mask(right_belly);
+        let left_sprite =
+            Sprite::from_ascii_art(left_art, Some(left_mask)).with_background_mask(left_belly);
 
         (right_sprite, left_sprite)
     }
 
-    /// Get the teeth position for this shark
-    pub fn get_teeth_position(&self) -> Position {
-        let teeth_offset = match self.direction {
+    /// Where the teeth sit relative to the shark's position (columns, rows)
+    pub fn teeth_offset(&self) -> (f32, f32) {
+        match self.direction {
             Direction::Right => (44.0, 7.0), // Original: teeth_x = -9, shark_x = -53, so offset = 44
             Direction::Left => (9.0, 7.0),   // Original: teeth_x = x + 9, so offset = 9
-        };
+        }
+    }
 
+    /// Get the teeth position for this shark
+    pub fn get_teeth_position(&self) -> Position {
+        let teeth_offset = self.teeth_offset();
         Position::new(
             self.position.x + teeth_offset.0,
             self.position.y + teeth_offset.1,
@@ -164,16 +225,73 @@ impl Shark {
         self.teeth_id
     }
 
+    /// Set how eagerly the shark chases prey (clamped to 0.0..=1.0)
+    pub fn set_aggressiveness(&mut self, aggressiveness: f32) {
+        self.aggressiveness = aggressiveness.clamp(0.0, 1.0);
+    }
+
+    /// Get the hunting aggressiveness
+    pub fn aggressiveness(&self) -> f32 {
+        self.aggressiveness
+    }
+
+    /// Whether the shark is currently chasing prey (otherwise cruising)
+    pub fn is_hunting(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Pick the nearest prey ahead of the teeth within the hunting band
+    fn find_target(&self, prey: &[Position]) -> Option<Position> {
+        let teeth = self.get_teeth_position();
+
+        prey.iter()
+            .filter(|p| {
+                let ahead = match self.direction {
+                    Direction::Right => p.x - teeth.x,
+                    Direction::Left => teeth.x - p.x,
+                };
+                (0.0..=HUNT_RANGE).contains(&ahead) && (p.y - teeth.y).abs() <= HUNT_BAND
+            })
+            .min_by(|a, b| {
+                let da = (a.x - teeth.x).powi(2) + (a.y - teeth.y).powi(2);
+                let db = (b.x - teeth.x).powi(2) + (b.y - teeth.y).powi(2);
+                da.total_cmp(&db)
+            })
+            .copied()
+    }
+
+    /// Steer vertically towards the current target, or level out
+    fn steer(&mut self, world_bounds: Rect) {
+        let max_dy = MAX_HUNT_DY * self.aggressiveness;
+
+        self.velocity.dy = match self.target {
+            Some(target) => {
+                let offset = target.y - self.get_teeth_position().y;
+                (offset * 0.1).clamp(-max_dy, max_dy)
+            }
+            None => 0.0, // Cruise in a straight line like the original
+        };
+
+        // Keep the whole body underwater and on screen
+        let height = self.get_current_sprite().get_bounding_box().1 as f32;
+        let max_y = (world_bounds.bottom() as f32 - height).max(self.surface_y);
+        if (self.position.y <= self.surface_y && self.velocity.dy < 0.0)
+            || (self.position.y >= max_y && self.velocity.dy > 0.0)
+        {
+            self.velocity.dy = 0.0;
+        }
+    }
+
     /// Get shark dimensions for collision calculations
     pub fn get_dimensions() -> (u16, u16) {
         (53, 11) // Shark is roughly 53 wide, 11 tall
     }
 
-    /// Check if shark has moved off screen
-    fn is_off_screen(&self, screen_bounds: Rect) -> bool {
+    /// Check if shark has left the world (not just the visible viewport)
+    fn is_off_screen(&self, world_bounds: Rect) -> bool {
         match self.direction {
-            Direction::Right => self.position.x > (screen_bounds.width + 10) as f32,
-            Direction::Left => self.position.x < -60.0,
+            Direction::Right => self.position.x > world_bounds.right() as f32 + 10.0,
+            Direction::Left => self.position.x < world_bounds.x as f32 - 60.0,
         }
     }
 }
@@ -210,22 +328,36 @@ impl Entity for Shark {
         }
     }
 
-    fn update(&mut self, delta_time: Duration, screen_bounds: Rect) {
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
         if !self.alive {
             return;
         }
 
+        self.steer(world_bounds);
+
         // Update position based on velocity
-        let dt_secs = delta_time.as_secs_f32();
-        self.position.x += self.velocity.dx * dt_secs * 60.0; // Scale for 60 FPS equivalent
-        self.position.y += self.velocity.dy * dt_secs * 60.0;
+        let ticks = timestep::ticks(delta_time);
+        self.position.x += self.velocity.dx * ticks;
+        self.position.y += self.velocity.dy * ticks;
 
-        // Check if shark has moved off screen
-        if self.is_off_screen(screen_bounds) {
+        // Check if shark has left the world
+        if self.is_off_screen(world_bounds) {
             self.alive = false;
         }
     }
 
+    fn hunt(&mut self, prey: &[Position]) {
+        self.target = if self.aggressiveness > 0.0 {
+            self.find_target(prey)
+        } else {
+            None
+        };
+    }
+
+    fn is_threat(&self) -> bool {
+        self.alive
+    }
+
     fn is_alive(&self) -> bool {
         self.alive
     }
@@ -234,12 +366,12 @@ impl Entity for Shark {
         self.alive = false;
     }
 
-    fn entity_type(&self) -> &'static str {
-        "shark"
+    fn ride_tide(&mut self, rows: f32) {
+        self.surface_y += rows;
     }
 
-    fn death_callback(&self) -> Option<DeathCallback> {
-        Some(crate::spawning::shark_death)
+    fn kind(&self) -> EntityKind {
+        EntityKind::Shark
     }
 }
 
@@ -258,7 +390,7 @@ pub struct SharkTeeth {
 impl SharkTeeth {
     /// Create new shark teeth
     pub fn new(id: EntityId, position: Position, velocity: Velocity, shark_id: EntityId) -> Self {
-        let sprite = Sprite::from_ascii_art("*", Some("R")); // Red asterisk
+        let sprite = sprite!("*", "R"); // Red asterisk
 
         Self {
             id,
@@ -276,9 +408,10 @@ impl SharkTeeth {
         self.shark_id
     }
 
-    /// Check if teeth have moved off screen
-    fn is_off_screen(&self, screen_bounds: Rect) -> bool {
-        self.position.x < -10.0 || self.position.x > (screen_bounds.width + 10) as f32
+    /// Check if teeth have left the world
+    fn is_off_screen(&self, world_bounds: Rect) -> bool {
+        self.position.x < world_bounds.x as f32 - 10.0
+            || self.position.x > world_bounds.right() as f32 + 10.0
     }
 }
 
@@ -311,18 +444,18 @@ impl Entity for SharkTeeth {
         &self.sprite
     }
 
-    fn update(&mut self, delta_time: Duration, screen_bounds: Rect) {
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
         if !self.alive {
             return;
         }
 
         // Update position based on velocity
-        let dt_secs = delta_time.as_secs_f32();
-        self.position.x += self.velocity.dx * dt_secs * 60.0; // Scale for 60 FPS equivalent
-        self.position.y += self.velocity.dy * dt_secs * 60.0;
+        let ticks = timestep::ticks(delta_time);
+        self.position.x += self.velocity.dx * ticks;
+        self.position.y += self.velocity.dy * ticks;
 
-        // Check if teeth have moved off screen
-        if self.is_off_screen(screen_bounds) {
+        // Check if teeth have left the world
+        if self.is_off_screen(world_bounds) {
             self.alive = false;
         }
     }
@@ -335,8 +468,16 @@ impl Entity for SharkTeeth {
         self.alive = false;
     }
 
-    fn entity_type(&self) -> &'static str {
-        "shark_teeth"
+    fn kind(&self) -> EntityKind {
+        EntityKind::SharkTeeth
+    }
+
+    fn collision_layers(&self) -> CollisionLayers {
+        CollisionLayers::TEETH
+    }
+
+    fn collision_mask(&self) -> CollisionLayers {
+        CollisionLayers::FISH
     }
 }
 
@@ -347,7 +488,7 @@ mod tests {
     #[test]
     fn test_shark_creation() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let shark = Shark::new_random(1, screen_bounds);
+        let shark = Shark::new_random(1, screen_bounds, WATERLINE_Y, &mut RngHandle::default());
 
         assert!(shark.is_alive());
         assert_eq!(shark.entity_type(), "shark");
@@ -356,13 +497,39 @@ mod tests {
 
     #[test]
     fn test_shark_sprites() {
-        let shark = Shark::new_random(1, Rect::new(0, 0, 80, 24));
+        let shark = Shark::new_random(
+            1,
+            Rect::new(0, 0, 80, 24),
+            WATERLINE_Y,
+            &mut RngHandle::default(),
+        );
         let right_sprite = &shark.right_sprite;
         let left_sprite = &shark.left_sprite;
 
-        assert!(!right_sprite.lines.is_empty());
-        assert!(!left_sprite.lines.is_empty());
-        assert_ne!(right_sprite.lines, left_sprite.lines);
+        assert!(!right_sprite.lines().is_empty());
+        assert!(!left_sprite.lines().is_empty());
+        assert_ne!(right_sprite.lines(), left_sprite.lines());
+    }
+
+    #[test]
+    fn test_shark_belly_only_fills_inside() {
+        let shark = Shark::new_random(
+            1,
+            Rect::new(0, 0, 80, 24),
+            WATERLINE_Y,
+            &mut RngHandle::default(),
+        );
+        for sprite in [&shark.right_sprite, &shark.left_sprite] {
+            let belly: Vec<(usize, usize)> = (0..sprite.lines().len())
+                .flat_map(|row| (0..60).map(move |col| (col, row)))
+                .filter(|&(col, row)| sprite.get_background_at(col, row).is_some())
+                .collect();
+
+            assert!(!belly.is_empty());
+            assert!(belly
+                .iter()
+                .all(|&(col, row)| sprite.is_transparent_at(col, row)));
+        }
     }
 
     #[test]
@@ -388,6 +555,69 @@ mod tests {
         assert_eq!(teeth.get_shark_id(), 1);
     }
 
+    #[test]
+    fn test_shark_hunts_nearest_prey() {
+        let position = Position::new(10.0, 12.0, crate::depth::SHARK);
+        let mut shark = Shark::new(1, position, Velocity::new(2.0, 0.0), Direction::Right);
+        shark.set_aggressiveness(1.0);
+        let screen_bounds = Rect::new(0, 0, 200, 60);
+
+        // Teeth are at (54, 19); prey below and ahead
+        let prey = [
+            Position::new(70.0, 23.0, 10),
+            Position::new(90.0, 20.0, 10),
+            Position::new(30.0, 19.0, 10), // Behind the shark, ignored
+        ];
+        shark.hunt(&prey);
+        assert!(shark.is_hunting());
+        assert_eq!(shark.target.unwrap().x, 70.0);
+
+        shark.update(Duration::from_millis(16), screen_bounds);
+        assert!(shark.velocity().dy > 0.0);
+        assert!(shark.position().y > 12.0);
+    }
+
+    #[test]
+    fn test_shark_cruises_without_prey() {
+        let position = Position::new(10.0, 12.0, crate::depth::SHARK);
+        let mut shark = Shark::new(1, position, Velocity::new(2.0, 0.0), Direction::Right);
+
+        // Prey far outside the depth band
+        shark.hunt(&[Position::new(70.0, 50.0, 10)]);
+        assert!(!shark.is_hunting());
+
+        shark.update(Duration::from_millis(16), Rect::new(0, 0, 200, 60));
+        assert_eq!(shark.velocity().dy, 0.0);
+    }
+
+    #[test]
+    fn test_passive_shark_never_hunts() {
+        let position = Position::new(10.0, 12.0, crate::depth::SHARK);
+        let mut shark = Shark::new(1, position, Velocity::new(2.0, 0.0), Direction::Right);
+        shark.set_aggressiveness(0.0);
+
+        shark.hunt(&[Position::new(60.0, 20.0, 10)]);
+        assert!(!shark.is_hunting());
+    }
+
+    #[test]
+    fn test_shark_stays_below_a_high_tide() {
+        let screen_bounds = Rect::new(0, 0, 200, 60);
+        let shark = Shark::new_random(1, screen_bounds, 14.0, &mut RngHandle::default());
+        assert!(shark.position().y >= 14.0);
+
+        // Prey just under the old waterline: the shark may not rise to it
+        let position = Position::new(10.0, 12.0, crate::depth::SHARK);
+        let mut shark = Shark::new(1, position, Velocity::new(2.0, 0.0), Direction::Right);
+        shark.set_aggressiveness(1.0);
+        shark.ride_tide(3.0);
+        shark.hunt(&[Position::new(70.0, 15.0, 10)]);
+        assert!(shark.is_hunting());
+
+        shark.update(Duration::from_millis(16), screen_bounds);
+        assert_eq!(shark.velocity().dy, 0.0);
+    }
+
     #[test]
     fn test_shark_movement() {
         let position = Position::new(10.0, 10.0, crate::depth::SHARK);
@@ -1,7 +1,11 @@
-use crate::entity::{DeathCallback, Direction, Entity, EntityId, Position, Sprite, Velocity};
+use crate::clock::Instant;
+use crate::entity::{Direction, Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::interaction::{self, Bob};
+use crate::rng::RngHandle;
+use crate::timestep;
 use rand::Rng;
 use ratatui::layout::Rect;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 pub struct Ship {
     id: EntityId,
@@ -12,12 +16,11 @@ pub struct Ship {
     #[allow(dead_code)]
     created_at: Instant,
     alive: bool,
+    bob: Bob, // Lift from bubbles popping under the hull
 }
 
 impl Ship {
-    pub fn new(id: EntityId, screen_bounds: Rect) -> Self {
-        let mut rng = rand::thread_rng();
-
+    pub fn new(id: EntityId, screen_bounds: Rect, rng: &mut RngHandle) -> Self {
         // Random direction
         let direction = if rng.gen_bool(0.5) {
             Direction::Right
@@ -57,6 +60,7 @@ impl Ship {
             sprite,
             created_at: Instant::now(),
             alive: true,
+            bob: Bob::default(),
         }
     }
 
@@ -105,10 +109,11 @@ yywwwyyyyyyyyyyyyyyyyyyyy
         Sprite::from_ascii_art(ship_ascii, Some(ship_mask))
     }
 
-    fn check_offscreen_death(&mut self, screen_bounds: Rect) {
+    /// Die once fully outside the world (camera panning doesn't count)
+    fn check_offscreen_death(&mut self, world_bounds: Rect) {
         let is_off_screen = match self.direction {
-            Direction::Right => self.position.x > screen_bounds.width as f32 + 30.0,
-            Direction::Left => self.position.x < -30.0,
+            Direction::Right => self.position.x > world_bounds.right() as f32 + 30.0,
+            Direction::Left => self.position.x < world_bounds.x as f32 - 30.0,
         };
 
         if is_off_screen {
@@ -123,11 +128,19 @@ impl Entity for Ship {
     }
 
     fn position(&self) -> Position {
-        self.position
+        // Bobbing is purely visual, so it is applied on the way out
+        Position {
+            y: self.position.y + self.bob.y_offset(),
+            ..self.position
+        }
     }
 
     fn set_position(&mut self, position: Position) {
-        self.position = position;
+        // Takes a position as `position` reports it, bob included
+        self.position = Position {
+            y: position.y - self.bob.y_offset(),
+            ..position
+        };
     }
 
     fn velocity(&self) -> Velocity {
@@ -146,16 +159,18 @@ impl Entity for Ship {
         &self.sprite
     }
 
-    fn update(&mut self, delta_time: Duration, screen_bounds: Rect) {
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
         if !self.alive {
             return;
         }
 
+        self.bob.update(delta_time);
+
         // Update position based on velocity
-        self.position.x += self.velocity.dx * delta_time.as_secs_f32() * 60.0; // Scale for 60 FPS
+        self.position.x += self.velocity.dx * timestep::ticks(delta_time);
 
         // Check if ship should die (off-screen)
-        self.check_offscreen_death(screen_bounds);
+        self.check_offscreen_death(world_bounds);
     }
 
     fn is_alive(&self) -> bool {
@@ -166,12 +181,19 @@ impl Entity for Ship {
         self.alive = false;
     }
 
-    fn entity_type(&self) -> &'static str {
-        "ship"
+    fn ride_tide(&mut self, rows: f32) {
+        self.position.y += rows;
     }
 
-    fn death_callback(&self) -> Option<DeathCallback> {
-        Some(crate::spawning::random_object)
+    fn kind(&self) -> EntityKind {
+        EntityKind::Ship
+    }
+
+    fn on_bubble_pop(&mut self, position: Position) {
+        let width = self.sprite.get_bounding_box().0;
+        if interaction::is_under(position.x, self.position.x, width) {
+            self.bob.trigger();
+        }
     }
 }
 
@@ -182,7 +204,7 @@ mod tests {
     #[test]
     fn test_ship_creation() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let ship = Ship::new(1, screen_bounds);
+        let ship = Ship::new(1, screen_bounds, &mut RngHandle::default());
 
         assert!(ship.is_alive());
         assert_eq!(ship.entity_type(), "ship");
@@ -195,7 +217,7 @@ mod tests {
 
         // Test multiple ships to check randomization
         for _ in 0..10 {
-            let ship = Ship::new(1, screen_bounds);
+            let ship = Ship::new(1, screen_bounds, &mut RngHandle::default());
 
             match ship.direction {
                 Direction::Right => {
@@ -216,13 +238,13 @@ mod tests {
         let right_sprite = Ship::create_ship_sprite(&Direction::Right);
         let left_sprite = Ship::create_ship_sprite(&Direction::Left);
 
-        assert!(!right_sprite.lines.is_empty());
-        assert!(!left_sprite.lines.is_empty());
-        assert_ne!(right_sprite.lines, left_sprite.lines);
+        assert!(!right_sprite.lines().is_empty());
+        assert!(!left_sprite.lines().is_empty());
+        assert_ne!(right_sprite.lines(), left_sprite.lines());
 
         // Check that sprites contain ship features
-        let right_text = right_sprite.lines.join("\n");
-        let left_text = left_sprite.lines.join("\n");
+        let right_text = right_sprite.lines().join("\n");
+        let left_text = left_sprite.lines().join("\n");
 
         assert!(right_text.contains("|")); // Masts
         assert!(left_text.contains("|")); // Masts
@@ -236,13 +258,13 @@ mod tests {
         let left_sprite = Ship::create_ship_sprite(&Direction::Left);
 
         // Check that color masks contain yellow (y) and white (w) colors
-        if let Some(ref mask) = right_sprite.color_mask {
+        if let Some(mask) = right_sprite.color_mask() {
             let mask_text = mask.join("\n");
             assert!(mask_text.contains("y")); // Yellow masts
             assert!(mask_text.contains("w")); // White hull parts
         }
 
-        if let Some(ref mask) = left_sprite.color_mask {
+        if let Some(mask) = left_sprite.color_mask() {
             let mask_text = mask.join("\n");
             assert!(mask_text.contains("y")); // Yellow masts
             assert!(mask_text.contains("w")); // White hull parts
@@ -252,7 +274,7 @@ mod tests {
     #[test]
     fn test_ship_movement() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let mut ship = Ship::new(1, screen_bounds);
+        let mut ship = Ship::new(1, screen_bounds, &mut RngHandle::default());
 
         let initial_x = ship.position().x;
         ship.update(Duration::from_millis(16), screen_bounds); // ~60 FPS
@@ -264,7 +286,7 @@ mod tests {
     #[test]
     fn test_ship_offscreen_death() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let mut ship = Ship::new(1, screen_bounds);
+        let mut ship = Ship::new(1, screen_bounds, &mut RngHandle::default());
 
         // Move ship far off screen
         match ship.direction {
@@ -279,10 +301,31 @@ mod tests {
     #[test]
     fn test_ship_surface_positioning() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let ship = Ship::new(1, screen_bounds);
+        let ship = Ship::new(1, screen_bounds, &mut RngHandle::default());
 
         // Ships should be at surface level (y=0) and water_gap1 depth
         assert_eq!(ship.position().y, 0.0);
         assert_eq!(ship.depth(), 7);
     }
+
+    #[test]
+    fn test_ship_bobs_on_bubble_pop() {
+        let screen_bounds = Rect::new(0, 0, 80, 24);
+        let mut ship = Ship::new(1, screen_bounds, &mut RngHandle::default());
+        let x = ship.position().x;
+
+        // A pop far away does nothing
+        ship.on_bubble_pop(Position::new(x + 100.0, 9.0, 5));
+        assert_eq!(ship.position().y, 0.0);
+
+        ship.on_bubble_pop(Position::new(x + 3.0, 9.0, 5));
+        assert_eq!(ship.position().y, -1.0);
+
+        // Moving a bobbing ship doesn't add the bob to where it rests
+        ship.set_position(ship.position());
+        assert_eq!(ship.position().y, -1.0);
+
+        ship.update(Duration::from_secs(1), screen_bounds);
+        assert_eq!(ship.position().y, 0.0);
+    }
 }
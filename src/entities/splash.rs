@@ -0,0 +1,141 @@
+use crate::depth;
+use crate::entity::{Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::pool::Recycle;
+use crate::rng::RngHandle;
+use crate::sprite;
+use crate::timestep;
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// Row of the lowest water surface line, where bubbles break through
+pub const SPLASH_Y: f32 = 8.0;
+
+/// How long a splash stays on the surface: three ticks, a little over one
+/// frame at the usual 30 fps, so it is drawn once however the frame's
+/// ticks fall
+pub const SPLASH_TIME: Duration = timestep::STEP.saturating_mul(3);
+
+/// A one-frame ripple left on the water surface where a bubble popped
+#[derive(Debug, Clone)]
+pub struct Splash {
+    id: EntityId,
+    position: Position,
+    sprite: Sprite,
+    time_left: Duration,
+}
+
+impl Splash {
+    /// Create a splash on the surface above the column where a bubble popped
+    pub fn new(id: EntityId, bubble_position: Position) -> Self {
+        Self {
+            id,
+            position: Self::above(bubble_position),
+            sprite: sprite!("*", "W"),
+            time_left: SPLASH_TIME,
+        }
+    }
+
+    /// In front of the lowest water line so it replaces the wave character
+    fn above(bubble_position: Position) -> Position {
+        Position::new(
+            bubble_position.x.round(),
+            SPLASH_Y,
+            depth::water_line_depth(3),
+        )
+    }
+}
+
+impl Recycle for Splash {
+    type Start = Position;
+
+    fn create(id: EntityId, bubble_position: Position, _rng: &mut RngHandle) -> Self {
+        Self::new(id, bubble_position)
+    }
+
+    fn restart(&mut self, id: EntityId, bubble_position: Position, _rng: &mut RngHandle) {
+        self.id = id;
+        self.position = Self::above(bubble_position);
+        self.time_left = SPLASH_TIME;
+    }
+}
+
+impl Entity for Splash {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        Velocity::zero()
+    }
+
+    fn set_velocity(&mut self, _velocity: Velocity) {
+        // Splashes stay where the bubble popped
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    fn update(&mut self, delta_time: Duration, _world_bounds: Rect) {
+        self.time_left = self.time_left.saturating_sub(delta_time);
+    }
+
+    fn is_alive(&self) -> bool {
+        !self.time_left.is_zero()
+    }
+
+    fn kill(&mut self) {
+        self.time_left = Duration::ZERO;
+    }
+
+    fn ride_tide(&mut self, rows: f32) {
+        self.position.y += rows;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Splash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splash_on_surface() {
+        let splash = Splash::new(1, Position::new(12.4, 9.0, 10));
+
+        assert!(splash.is_alive());
+        assert_eq!(splash.entity_type(), "splash");
+        assert_eq!(splash.position().x, 12.0);
+        assert_eq!(splash.position().y, SPLASH_Y);
+        assert_eq!(splash.depth(), depth::water_line_depth(3));
+    }
+
+    #[test]
+    fn test_splash_drawn_with_two_ticks_per_frame() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        // Spawned after the first tick's updates, the second still runs
+        // before the frame is drawn
+        let mut splash = Splash::new(1, Position::new(12.0, 9.0, 10));
+        splash.update(timestep::STEP, bounds);
+        assert!(splash.is_alive());
+
+        // Gone by the end of the next frame
+        splash.update(timestep::STEP, bounds);
+        splash.update(timestep::STEP, bounds);
+        assert!(!splash.is_alive());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::entity::{Animation, Entity, EntityId, EntityKind, Position, Sprite, Velocity};
+use crate::timestep;
+use ratatui::layout::Rect;
+use std::time::Duration;
+
+/// Ticks each frame is shown (original: callback_args [0, 0, 0, .25]);
+/// the last is cut short as the splat dies at tick 15 (die_frame => 15)
+const FRAME_TICKS: [u32; 4] = [4, 4, 4, 3];
+
+/// Splat frames from the original's add_splat
+const FRAMES: [&[&str]; 4] = [
+    &["", "   .", "  ***", "   '", ""],
+    &["", " \",*;`", " \"*,**", " *\"'~'", ""],
+    &["  , ,", " \" \",\"'", " *\" *'\"", "  \" ; .", ""],
+    &[
+        "* ' , ' `",
+        "' ` * . '",
+        " ' `' \",'",
+        "* ' \" * .",
+        "\" * ', '",
+    ],
+];
+
+/// A short red splat left where something met a grisly end
+#[derive(Debug, Clone)]
+pub struct Splat {
+    id: EntityId,
+    position: Position,
+    animation: Animation,
+    alive: bool,
+}
+
+impl Splat {
+    /// Create a splat centred on `centre`, just in front of it
+    pub fn new(id: EntityId, centre: Position) -> Self {
+        let frames = FRAMES
+            .iter()
+            .map(|rows| {
+                let art = rows.join("\n");
+                let mask: String = art
+                    .chars()
+                    .map(|c| if c == ' ' || c == '\n' { c } else { 'R' })
+                    .collect();
+                Sprite::from_ascii_art(&art, Some(&mask))
+            })
+            .collect();
+
+        Self {
+            id,
+            position: Position::new(
+                centre.x - 4.0,
+                centre.y - 2.0,
+                centre.depth.saturating_sub(2),
+            ),
+            animation: Animation::with_durations(
+                frames,
+                FRAME_TICKS
+                    .iter()
+                    .map(|&ticks| timestep::STEP * ticks)
+                    .collect(),
+                false,
+            ),
+            alive: true,
+        }
+    }
+}
+
+impl Entity for Splat {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn velocity(&self) -> Velocity {
+        Velocity::zero()
+    }
+
+    fn set_velocity(&mut self, _velocity: Velocity) {
+        // Splats stay where they landed
+    }
+
+    fn depth(&self) -> u8 {
+        self.position.depth
+    }
+
+    fn get_current_sprite(&self) -> &Sprite {
+        self.animation.get_current_sprite()
+    }
+
+    fn update(&mut self, delta_time: Duration, _world_bounds: Rect) {
+        self.animation.update(delta_time);
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive && !self.animation.is_finished()
+    }
+
+    fn kill(&mut self) {
+        self.alive = false;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Splat
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splat_spreads_then_vanishes() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut splat = Splat::new(1, Position::new(20.0, 10.0, 10));
+        assert_eq!(splat.position(), Position::new(16.0, 8.0, 8));
+        assert_eq!(splat.get_current_sprite().lines()[2], "  ***");
+
+        splat.update(timestep::STEP * 13, bounds);
+        assert_eq!(splat.get_current_sprite().lines()[0], "* ' , ' `");
+        assert!(splat.is_alive());
+
+        splat.update(timestep::STEP * 3, bounds);
+        assert!(!splat.is_alive());
+    }
+}
@@ -1,39 +1,63 @@
 use crate::depth;
-use crate::entity::{Entity, EntityId, Position, Sprite, Velocity};
+use crate::entity::{Entity, EntityId, EntityKind, Position, Sprite, Velocity};
 use ratatui::layout::Rect;
 use std::time::Duration;
 
-/// Water surface entity - static, no animation (matches original Perl behavior)
+/// Number of water surface lines, stacked just above the waterline
+pub const LAYERS: u8 = 4;
+
+/// Horizontal scroll speed of each layer in columns per second. Lower
+/// layers drift faster, which gives a little parallax.
+const SCROLL_SPEEDS: [f32; LAYERS as usize] = [0.4, 0.7, 1.0, 1.3];
+
+/// Water surface entity - static like the original Perl unless scrolling
+/// is switched on with [`WaterSurface::with_scroll`]
 #[derive(Debug, Clone)]
 pub struct WaterSurface {
     id: EntityId,
     position: Position,
-    layer_index: u8, // 0-3 for the 4 water layers
-    sprite: Sprite,
+    layer_index: u8,     // 0-3 for the 4 water layers
+    frames: Vec<Sprite>, // The tiled line rotated by each whole column of scroll
+    frame: usize,        // Frame shown, the whole columns scrolled
+    screen_width: u16,   // Width the line is tiled across
     alive: bool,
+    scroll_speed: f32, // Columns per second, 0.0 when static
+    scroll: f32,       // Columns scrolled so far
 }
 
 impl WaterSurface {
     /// Create a new water surface layer
     pub fn new(id: EntityId, layer_index: u8, screen_width: u16) -> Self {
-        let (sprite, depth) = Self::create_water_layer_sprite(layer_index, screen_width);
-
         // Position at the top of screen for water surface
         let y = 5.0 + layer_index as f32; // Start at Y=5, each layer below the previous
-        let position = Position::new(0.0, y, depth);
+        let position = Position::new(0.0, y, Self::layer_depth(layer_index));
 
         Self {
             id,
             position,
             layer_index,
-            sprite,
+            frames: Self::layer_frames(layer_index, screen_width, false),
+            frame: 0,
+            screen_width,
             alive: true,
+            scroll_speed: 0.0,
+            scroll: 0.0,
         }
     }
 
-    /// Create a static sprite for a specific water layer with proper tiling
-    fn create_water_layer_sprite(layer_index: u8, screen_width: u16) -> (Sprite, u8) {
-        // Original water surface patterns from asciiquarium.pl
+    /// Let the layer drift sideways at its own speed
+    pub fn with_scroll(mut self, enabled: bool) -> Self {
+        self.scroll_speed = if enabled {
+            SCROLL_SPEEDS[self.layer_index as usize % SCROLL_SPEEDS.len()]
+        } else {
+            0.0
+        };
+        self.resize(self.screen_width);
+        self
+    }
+
+    /// Original water surface patterns from asciiquarium.pl
+    fn segment(layer_index: u8) -> &'static str {
         let water_segments = [
             "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~", // Layer 0
             "^^^^ ^^^  ^^^   ^^^    ^^^^      ", // Layer 1
@@ -41,36 +65,51 @@ impl WaterSurface {
             "^^      ^^^^      ^^^    ^^^^^^  ", // Layer 3
         ];
 
-        let segment = water_segments[layer_index as usize % 4];
-        let segment_length = segment.len();
+        water_segments[layer_index as usize % 4]
+    }
+
+    /// Depth of a water layer
+    fn layer_depth(layer_index: u8) -> u8 {
+        depth::water_line_depth(if layer_index < LAYERS { layer_index } else { 0 })
+    }
+
+    /// Sprites of a layer's line tiled across the screen: the line rotated
+    /// by each whole column it can scroll, or just the line when still
+    ///
+    /// Tiles are whole segments, so the seam never shows. Scrolling then
+    /// only picks a frame, with no strings built as the water moves.
+    fn layer_frames(layer_index: u8, screen_width: u16, scrolling: bool) -> Vec<Sprite> {
+        let segment = Self::segment(layer_index);
 
         // Calculate how many times to repeat the segment to fill screen width
         // Original Perl: $segment_repeat = int($anim->width()/$segment_size) + 1;
-        let repeat_count = (screen_width as usize / segment_length) + 1;
-
-        // Tile the segment to fill the screen width
-        let tiled_segment = segment.repeat(repeat_count);
-
-        // Create sprite with cyan color mask
-        let color_mask = "C".repeat(tiled_segment.len());
-        let sprite = Sprite::from_ascii_art(&tiled_segment, Some(&color_mask));
-
-        // Get appropriate depth for this layer
-        let depth = match layer_index {
-            0 => depth::water_line_depth(0),
-            1 => depth::water_line_depth(1),
-            2 => depth::water_line_depth(2),
-            3 => depth::water_line_depth(3),
-            _ => depth::water_line_depth(0),
-        };
+        // The spare segment also covers the gap left when scrolling
+        let repeat_count = (screen_width as usize / segment.len()) + 1;
+        let tiled = segment.repeat(repeat_count);
+
+        let shifts = if scrolling { segment.len() } else { 1 };
+        (0..shifts)
+            .map(|shift| Self::layer_sprite(&format!("{}{}", &tiled[shift..], &tiled[..shift])))
+            .collect()
+    }
 
-        (sprite, depth)
+    /// Sprite for one tiled line, with a cyan color mask
+    fn layer_sprite(line: &str) -> Sprite {
+        let color_mask = "C".repeat(line.len());
+        Sprite::from_ascii_art(line, Some(&color_mask))
+    }
+
+    /// Show the frame for the whole columns scrolled so far
+    fn apply_scroll(&mut self) {
+        self.frame = self.scroll as usize % self.frames.len();
     }
 
     /// Update the water surface to resize for new screen width
     pub fn resize(&mut self, new_screen_width: u16) {
-        let (new_sprite, _) = Self::create_water_layer_sprite(self.layer_index, new_screen_width);
-        self.sprite = new_sprite;
+        let scrolling = self.scroll_speed > 0.0;
+        self.screen_width = new_screen_width;
+        self.frames = Self::layer_frames(self.layer_index, new_screen_width, scrolling);
+        self.apply_scroll();
     }
 
     /// Get the layer index for this water surface
@@ -105,11 +144,19 @@ impl Entity for WaterSurface {
     }
 
     fn get_current_sprite(&self) -> &Sprite {
-        &self.sprite
+        &self.frames[self.frame]
+    }
+
+    fn update(&mut self, delta_time: Duration, _screen_bounds: Rect) {
+        if self.scroll_speed > 0.0 {
+            let period = Self::segment(self.layer_index).len() as f32;
+            self.scroll = (self.scroll + self.scroll_speed * delta_time.as_secs_f32()) % period;
+            self.apply_scroll();
+        }
     }
 
-    fn update(&mut self, _delta_time: Duration, _screen_bounds: Rect) {
-        // Water surface is completely static - no updates needed
+    fn is_static(&self) -> bool {
+        self.scroll_speed == 0.0
     }
 
     fn is_alive(&self) -> bool {
@@ -120,8 +167,16 @@ impl Entity for WaterSurface {
         self.alive = false;
     }
 
-    fn entity_type(&self) -> &'static str {
-        "water_surface"
+    fn on_resize(&mut self, _old: Rect, new: Rect) {
+        self.resize(new.width);
+    }
+
+    fn ride_tide(&mut self, rows: f32) {
+        self.position.y += rows;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::WaterSurface
     }
 }
 
@@ -160,19 +215,19 @@ mod tests {
 
     #[test]
     fn test_sprite_tiling() {
-        let (sprite, _) = WaterSurface::create_water_layer_sprite(0, 80);
+        let sprite = &WaterSurface::layer_frames(0, 80, false)[0];
 
-        assert!(!sprite.lines.is_empty());
+        assert!(!sprite.lines().is_empty());
 
         // Should create a line at least 80 characters wide
-        let line_length = sprite.lines[0].len();
+        let line_length = sprite.lines()[0].len();
         assert!(line_length >= 80);
     }
 
     #[test]
     fn test_water_is_static() {
         let mut water = WaterSurface::new(1, 0, 80);
-        let original_sprite_lines = water.sprite.lines.clone();
+        let original_sprite_lines = water.get_current_sprite().lines().to_vec();
 
         // Update multiple times
         water.update(Duration::from_secs(1), Rect::new(0, 0, 80, 24));
@@ -180,6 +235,24 @@ mod tests {
         water.update(Duration::from_secs(1), Rect::new(0, 0, 80, 24));
 
         // Sprite should not change - water is static
-        assert_eq!(water.sprite.lines, original_sprite_lines);
+        assert_eq!(water.get_current_sprite().lines(), original_sprite_lines);
+    }
+
+    #[test]
+    fn test_scrolling_layers_drift_at_their_own_speed() {
+        let mut top = WaterSurface::new(1, 0, 80).with_scroll(true);
+        let mut bottom = WaterSurface::new(2, 3, 80).with_scroll(true);
+        let original = bottom.get_current_sprite().lines()[0].clone();
+
+        top.update(Duration::from_secs(2), Rect::new(0, 0, 80, 24));
+        bottom.update(Duration::from_secs(2), Rect::new(0, 0, 80, 24));
+
+        // 1.3 columns per second for 2 seconds: shifted left by 2
+        assert_eq!(
+            bottom.get_current_sprite().lines()[0][..40],
+            original[2..42]
+        );
+        assert_eq!(bottom.get_current_sprite().lines()[0].len(), original.len());
+        assert!(top.scroll < bottom.scroll);
     }
 }
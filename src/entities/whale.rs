@@ -1,25 +1,40 @@
-use crate::entity::{DeathCallback, Direction, Entity, EntityId, Position, Sprite, Velocity};
+use crate::clock::Instant;
+use crate::entity::{
+    Animation, Direction, Entity, EntityId, EntityKind, Position, Spawn, Sprite, Velocity,
+};
+use crate::rng::RngHandle;
+use crate::timestep;
 use rand::Rng;
 use ratatui::layout::Rect;
-use std::time::{Duration, Instant};
+use std::sync::OnceLock;
+use std::time::Duration;
 
 pub struct Whale {
     id: EntityId,
     position: Position,
     velocity: Velocity,
     direction: Direction,
-    sprite: Sprite,
-    animation_frame: usize,
-    last_frame_time: Instant,
+    animation: Animation, // Five plain frames, then seven of spouting
     #[allow(dead_code)]
     created_at: Instant,
     alive: bool,
+    droplets_pending: bool, // Spout just finished; throw droplets
 }
 
-impl Whale {
-    pub fn new(id: EntityId, screen_bounds: Rect) -> Self {
-        let mut rng = rand::thread_rng();
+/// Number of droplets thrown at the end of each spout
+const SPOUT_DROPLETS: usize = 4;
+
+/// Frames of plain whale before the spout starts
+const PLAIN_FRAMES: usize = 5;
+
+/// Frames of the spout
+const SPOUT_FRAMES: usize = 7;
 
+/// How long each frame is shown
+const FRAME_DURATION: Duration = Duration::from_millis(500);
+
+impl Whale {
+    pub fn new(id: EntityId, screen_bounds: Rect, rng: &mut RngHandle) -> Self {
         // Random direction
         let direction = if rng.gen_bool(0.5) {
             Direction::Right
@@ -48,128 +63,152 @@ impl Whale {
         let position = Position::new(x, y, depth);
         let velocity = Velocity::new(dx, 0.0);
 
-        // Create initial sprite (whale without spout)
-        let sprite = Self::create_whale_sprite(&direction, false, 0);
+        let animation = Animation::new(Self::frames(&direction), FRAME_DURATION, true);
 
         Self {
             id,
             position,
             velocity,
             direction,
-            sprite,
-            animation_frame: 0,
-            last_frame_time: Instant::now(),
+            animation,
             created_at: Instant::now(),
             alive: true,
+            droplets_pending: false,
         }
     }
 
+    /// The plain whale, then each frame of the spout
+    ///
+    /// The frames are composed once per facing, the first time a whale
+    /// needs them; every whale after that gets a copy.
+    fn frames(direction: &Direction) -> Vec<Sprite> {
+        static FRAMES: OnceLock<[Vec<Sprite>; 2]> = OnceLock::new();
+        let [right, left] = FRAMES.get_or_init(|| {
+            [Direction::Right, Direction::Left].map(|direction| Self::compose_frames(&direction))
+        });
+        match direction {
+            Direction::Right => right.clone(),
+            Direction::Left => left.clone(),
+        }
+    }
+
+    fn compose_frames(direction: &Direction) -> Vec<Sprite> {
+        let plain = Self::create_whale_sprite(direction, false, 0);
+        let mut frames = vec![plain; PLAIN_FRAMES];
+        frames.extend(
+            (0..SPOUT_FRAMES)
+                .map(|spout_frame| Self::create_whale_sprite(direction, true, spout_frame)),
+        );
+        frames
+    }
+
     fn create_whale_sprite(direction: &Direction, has_spout: bool, spout_frame: usize) -> Sprite {
         let whale_ascii = match direction {
             Direction::Right => {
-                "\n\n\n        .-----:\n      .'       `.\n,????/       (o) \\\n\\`._/          ,__)"
+                "        .-----:\n      .'       `.\n,????/       (o) \\\n\\`._/          ,__)"
             }
             Direction::Left => {
-                "\n\n\n    :-----.\n  .'       `.\n / (o)       \\????,\n(__,          \\_.'/'"
+                "    :-----.\n  .'       `.\n / (o)       \\????,\n(__,          \\_.'/'"
             }
         };
 
         let whale_mask = match direction {
             Direction::Right => {
-                "\n\n\n             C C\n           CCCCCCC\n           C  C  C\n        BBBBBBB\n      BB       BB\nB    B       BWB B\nBBBBB          BBBB"
+                "        BBBBBBB\n      BB       BB\nB    B       BWB B\nBBBBB          BBBB"
             }
             Direction::Left => {
-                "\n\n\n   C C\n CCCCCCC\n C  C  C\n    BBBBBBB\n  BB       BB\n B BWB       B    B\nBBBB          BBBBB"
+                "    BBBBBBB\n  BB       BB\n B BWB       B    B\nBBBB          BBBBB"
             }
         };
 
-        if !has_spout {
-            return Sprite::from_ascii_art(whale_ascii, Some(whale_mask));
-        }
-
-        // Create whale with water spout
-        let spout_ascii = Self::get_water_spout_frame(spout_frame);
+        // The spout rows stay empty on a plain whale
+        let spout_ascii = if has_spout {
+            Self::get_water_spout_frame(spout_frame)
+        } else {
+            "\n\n"
+        };
         let spout_alignment = match direction {
             Direction::Right => 11,
             Direction::Left => 1,
         };
 
-        // Align the spout above the whale
-        let aligned_spout = spout_ascii
-            .lines()
-            .map(|line| format!("{}{}", " ".repeat(spout_alignment), line))
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        let combined_ascii = format!(
-            "{}{}",
-            aligned_spout,
-            whale_ascii
-                .trim_start_matches('\n')
-                .trim_start_matches('\n')
-                .trim_start_matches('\n')
-        );
-
-        // Create spout color mask (all 'C' for cyan water)
-        let spout_color_mask = spout_ascii
-            .lines()
-            .map(|line| {
-                let colored_line = line
-                    .chars()
-                    .map(|c| if c == ' ' { ' ' } else { 'C' })
-                    .collect::<String>();
-                format!("{}{}", " ".repeat(spout_alignment), colored_line)
-            })
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        let combined_mask = format!(
-            "{}{}",
-            spout_color_mask,
-            whale_mask
-                .trim_start_matches('\n')
-                .trim_start_matches('\n')
-                .trim_start_matches('\n')
-        );
+        // Align the spout above the whale, coloured cyan for water
+        let mut combined_ascii = String::new();
+        let mut combined_mask = String::new();
+        for line in spout_ascii.split('\n') {
+            if !line.is_empty() {
+                let indent = " ".repeat(spout_alignment);
+                combined_ascii.push_str(&indent);
+                combined_ascii.push_str(line);
+                combined_mask.push_str(&indent);
+                combined_mask.extend(line.chars().map(|c| if c == ' ' { ' ' } else { 'C' }));
+            }
+            combined_ascii.push('\n');
+            combined_mask.push('\n');
+        }
+        combined_ascii.push_str(whale_ascii);
+        combined_mask.push_str(whale_mask);
 
         Sprite::from_ascii_art(&combined_ascii, Some(&combined_mask))
     }
 
+    /// The three rows of spout above the whale's back
     fn get_water_spout_frame(frame: usize) -> &'static str {
         match frame {
-            0 => "\n\n\n   :",
-            1 => "\n\n   :\n   :",
-            2 => "\n  . .\n  -:-\n   :",
-            3 => "\n  . .\n .-:-.\n   :",
-            4 => "\n  . .\n'.-:-.`\n'  :  '",
-            5 => "\n\n .- -.\n;  :  ;",
-            6 => "\n\n\n;     ;",
+            0 => "\n\n   :",
+            1 => "\n   :\n   :",
+            2 => "  . .\n  -:-\n   :",
+            3 => "  . .\n .-:-.\n   :",
+            4 => "  . .\n'.-:-.`\n'  :  '",
+            5 => "\n .- -.\n;  :  ;",
+            6 => "\n\n;     ;",
             _ => "",
         }
     }
 
-    fn update_animation(&mut self) {
-        // Update animation frame every 500ms
-        if self.last_frame_time.elapsed().as_millis() > 500 {
-            self.animation_frame = (self.animation_frame + 1) % 12; // 5 frames without spout + 7 frames with spout
-            self.last_frame_time = Instant::now();
-
-            // Update sprite based on animation frame
-            if self.animation_frame < 5 {
-                // Whale without spout
-                self.sprite = Self::create_whale_sprite(&self.direction, false, 0);
-            } else {
-                // Whale with spout
-                let spout_frame = self.animation_frame - 5;
-                self.sprite = Self::create_whale_sprite(&self.direction, true, spout_frame);
-            }
+    fn update_animation(&mut self, delta_time: Duration) {
+        // Wrapping back to the plain whale means the spout just finished
+        if self.animation.update(delta_time) > 0 {
+            self.droplets_pending = true;
         }
     }
 
-    fn check_offscreen_death(&mut self, screen_bounds: Rect) {
+    /// Where the spout column meets the top of the spout
+    fn spout_origin(&self) -> Position {
+        let spout_alignment = match self.direction {
+            Direction::Right => 11.0,
+            Direction::Left => 1.0,
+        };
+
+        // The ':' of the spout is 3 columns into the spout art
+        Position::new(
+            self.position.x + spout_alignment + 3.0,
+            self.position.y,
+            self.position.depth,
+        )
+    }
+
+    /// Droplets arcing outward on both sides of the spout
+    fn spout_droplets(&self, rng: &mut RngHandle) -> Vec<Spawn> {
+        let origin = self.spout_origin();
+
+        (0..SPOUT_DROPLETS)
+            .map(|i| {
+                let side = if i % 2 == 0 { -1.0 } else { 1.0 };
+                let velocity = Velocity::new(
+                    side * rng.gen_range(0.15..0.45),
+                    rng.gen_range(-0.35..-0.15),
+                );
+                Spawn::Droplet(origin, velocity)
+            })
+            .collect()
+    }
+
+    /// Die once fully outside the world (camera panning doesn't count)
+    fn check_offscreen_death(&mut self, world_bounds: Rect) {
         let is_off_screen = match self.direction {
-            Direction::Right => self.position.x > screen_bounds.width as f32 + 20.0,
-            Direction::Left => self.position.x < -20.0,
+            Direction::Right => self.position.x > world_bounds.right() as f32 + 20.0,
+            Direction::Left => self.position.x < world_bounds.x as f32 - 20.0,
         };
 
         if is_off_screen {
@@ -204,22 +243,22 @@ impl Entity for Whale {
     }
 
     fn get_current_sprite(&self) -> &Sprite {
-        &self.sprite
+        self.animation.get_current_sprite()
     }
 
-    fn update(&mut self, delta_time: Duration, screen_bounds: Rect) {
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
         if !self.alive {
             return;
         }
 
         // Update animation
-        self.update_animation();
+        self.update_animation(delta_time);
 
         // Update position based on velocity
-        self.position.x += self.velocity.dx * delta_time.as_secs_f32() * 60.0; // Scale for 60 FPS
+        self.position.x += self.velocity.dx * timestep::ticks(delta_time);
 
         // Check if whale should die (off-screen)
-        self.check_offscreen_death(screen_bounds);
+        self.check_offscreen_death(world_bounds);
     }
 
     fn is_alive(&self) -> bool {
@@ -230,12 +269,19 @@ impl Entity for Whale {
         self.alive = false;
     }
 
-    fn entity_type(&self) -> &'static str {
-        "whale"
+    fn ride_tide(&mut self, rows: f32) {
+        self.position.y += rows;
+    }
+
+    fn kind(&self) -> EntityKind {
+        EntityKind::Whale
     }
 
-    fn death_callback(&self) -> Option<DeathCallback> {
-        Some(crate::spawning::random_object)
+    fn pending_spawns(&mut self, rng: &mut RngHandle) -> Vec<Spawn> {
+        if !std::mem::take(&mut self.droplets_pending) {
+            return Vec::new();
+        }
+        self.spout_droplets(rng)
     }
 }
 
@@ -246,7 +292,7 @@ mod tests {
     #[test]
     fn test_whale_creation() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let whale = Whale::new(1, screen_bounds);
+        let whale = Whale::new(1, screen_bounds, &mut RngHandle::default());
 
         assert!(whale.is_alive());
         assert_eq!(whale.entity_type(), "whale");
@@ -259,7 +305,7 @@ mod tests {
 
         // Test multiple whales to check randomization
         for _ in 0..10 {
-            let whale = Whale::new(1, screen_bounds);
+            let whale = Whale::new(1, screen_bounds, &mut RngHandle::default());
 
             match whale.direction {
                 Direction::Right => {
@@ -280,16 +326,24 @@ mod tests {
         let right_sprite = Whale::create_whale_sprite(&Direction::Right, false, 0);
         let left_sprite = Whale::create_whale_sprite(&Direction::Left, false, 0);
 
-        assert!(!right_sprite.lines.is_empty());
-        assert!(!left_sprite.lines.is_empty());
-        assert_ne!(right_sprite.lines, left_sprite.lines);
+        assert!(!right_sprite.lines().is_empty());
+        assert!(!left_sprite.lines().is_empty());
+        assert_ne!(right_sprite.lines(), left_sprite.lines());
 
         // Check that sprites contain whale features
-        let right_text = right_sprite.lines.join("\n");
-        let left_text = left_sprite.lines.join("\n");
+        let right_text = right_sprite.lines().join("\n");
+        let left_text = left_sprite.lines().join("\n");
 
         assert!(right_text.contains(".-----:"));
         assert!(left_text.contains(":-----."));
+
+        // Spout rows sit above the body, the masks line up with the art
+        for direction in [Direction::Right, Direction::Left] {
+            for frame in Whale::frames(&direction) {
+                assert_eq!(frame.lines().len(), 7);
+                assert_eq!(frame.check_mask(), Ok(()));
+            }
+        }
     }
 
     #[test]
@@ -298,17 +352,17 @@ mod tests {
         let sprite_with_spout = Whale::create_whale_sprite(&Direction::Right, true, 0);
 
         // Check that spout sprite contains spout character
-        let spout_text = sprite_with_spout.lines.join("\n");
+        let spout_text = sprite_with_spout.lines().join("\n");
         assert!(spout_text.contains(":"));
 
         // Check that sprites are different
-        assert_ne!(sprite_without_spout.lines, sprite_with_spout.lines);
+        assert_ne!(sprite_without_spout.lines(), sprite_with_spout.lines());
 
         // Sprite with spout should contain water spout elements
         assert!(spout_text.contains(":"));
 
         // Both should contain whale body
-        let whale_text = sprite_without_spout.lines.join("\n");
+        let whale_text = sprite_without_spout.lines().join("\n");
         assert!(whale_text.contains(".-----:"));
         assert!(spout_text.contains(".-----:"));
     }
@@ -329,26 +383,25 @@ mod tests {
     #[test]
     fn test_whale_animation_update() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let mut whale = Whale::new(1, screen_bounds);
+        let mut whale = Whale::new(1, screen_bounds, &mut RngHandle::default());
 
-        let initial_frame = whale.animation_frame;
+        let initial_frame = whale.animation.current_frame;
 
         // Animation should not update immediately
-        whale.update_animation();
-        assert_eq!(whale.animation_frame, initial_frame);
+        whale.update_animation(Duration::ZERO);
+        assert_eq!(whale.animation.current_frame, initial_frame);
 
         // Simulate time passing
-        whale.last_frame_time = Instant::now() - Duration::from_millis(600);
-        whale.update_animation();
+        whale.update_animation(Duration::from_millis(600));
 
         // Frame should have advanced
-        assert_ne!(whale.animation_frame, initial_frame);
+        assert_ne!(whale.animation.current_frame, initial_frame);
     }
 
     #[test]
     fn test_whale_movement() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let mut whale = Whale::new(1, screen_bounds);
+        let mut whale = Whale::new(1, screen_bounds, &mut RngHandle::default());
 
         let initial_x = whale.position().x;
         whale.update(Duration::from_millis(16), screen_bounds); // ~60 FPS
@@ -360,7 +413,7 @@ mod tests {
     #[test]
     fn test_whale_offscreen_death() {
         let screen_bounds = Rect::new(0, 0, 80, 24);
-        let mut whale = Whale::new(1, screen_bounds);
+        let mut whale = Whale::new(1, screen_bounds, &mut RngHandle::default());
 
         // Move whale far off screen
         match whale.direction {
@@ -371,4 +424,29 @@ mod tests {
         whale.update(Duration::from_millis(16), screen_bounds);
         assert!(!whale.is_alive());
     }
+
+    #[test]
+    fn test_whale_throws_droplets_after_spout() {
+        let screen_bounds = Rect::new(0, 0, 80, 24);
+        let mut whale = Whale::new(1, screen_bounds, &mut RngHandle::default());
+        assert!(whale.pending_spawns(&mut RngHandle::default()).is_empty());
+
+        // Jump to the last spout frame and let it wrap around
+        whale.animation.current_frame = PLAIN_FRAMES + SPOUT_FRAMES - 1;
+        whale.update_animation(Duration::from_millis(600));
+        assert_eq!(whale.animation.current_frame, 0);
+
+        let spawns = whale.pending_spawns(&mut RngHandle::default());
+        assert_eq!(spawns.len(), SPOUT_DROPLETS);
+        for spawn in &spawns {
+            let Spawn::Droplet(position, velocity) = spawn else {
+                panic!("whales only throw droplets");
+            };
+            assert_eq!(position.y, whale.position().y);
+            assert!(velocity.dy < 0.0, "droplets are thrown upward first");
+        }
+
+        // Only once per spout
+        assert!(whale.pending_spawns(&mut RngHandle::default()).is_empty());
+    }
 }
@@ -1,19 +1,264 @@
-use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+use crate::ambient::AmbientScheduler;
+use crate::backgrounds::Background;
+use crate::clock::Instant;
+use crate::collision::{self, Aabb, CollisionLayers, Contact};
+use crate::ecology::{self, Ecology, Mate};
+use crate::entities::FishSpecies;
+use crate::environment::Environment;
+use crate::hooks::{AquariumEvent, EventHooks};
+use crate::palette::{self, PALETTE_SIZE};
+use crate::pool::{EntityPool, Recycle};
+use crate::registry::{Category, SpawnRegistry};
+use crate::render::{self, RenderOptions};
+use crate::rng::RngHandle;
+use crate::scheduler::{Population, RespawnPolicy, SpawnScheduler};
+use crate::spatial::SpatialIndex;
+use crate::store::EntityStore;
+use crate::world::WorldContext;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+};
+use std::any::Any;
+use std::cell::{Cell, OnceCell};
 use std::collections::{HashMap, HashSet};
-use std::time::{Duration, Instant};
+use std::ops::Range;
+use std::time::Duration;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-/// Unique identifier for entities
+/// Unique identifier for entities, naming a slot of the [`EntityStore`]
+/// and its generation
 pub type EntityId = u64;
 
+/// Glyphs that turn into each other when art is flipped left to right
+pub const MIRROR_PAIRS: &[(&str, &str)] =
+    &[("<", ">"), ("(", ")"), ("[", "]"), ("{", "}"), ("/", "\\")];
+
+/// The mirror image of `glyph` (itself if it has none)
+fn mirror_glyph(glyph: &str) -> &str {
+    MIRROR_PAIRS
+        .iter()
+        .find_map(|&(a, b)| {
+            if glyph == a {
+                Some(b)
+            } else if glyph == b {
+                Some(a)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(glyph)
+}
+
 /// Characters that are considered transparent and won't be rendered
 pub const TRANSPARENCY_CHARS: &[char] = &[' ', '?', '·', '\0'];
 
+/// Whether `code` may appear in a colour mask: a space (no colour), a
+/// palette digit '1'-'9' or one of the colour letters, either case
+pub const fn is_mask_char(code: char) -> bool {
+    matches!(
+        code,
+        ' ' | '1'
+            ..='9'
+                | 'r'
+                | 'R'
+                | 'g'
+                | 'G'
+                | 'b'
+                | 'B'
+                | 'y'
+                | 'Y'
+                | 'm'
+                | 'M'
+                | 'c'
+                | 'C'
+                | 'w'
+                | 'W'
+                | 'k'
+                | 'K'
+    )
+}
+
+/// Whether a colour mask fits its art: no more lines than the art, no
+/// line longer than the art line under it and nothing but
+/// [mask characters](is_mask_char)
+///
+/// Art lines are measured in characters, so art with combining marks
+/// passes more easily than it should; [`Sprite::check_mask`] measures
+/// glyphs. Being a `const fn`, this is what [`sprite!`](crate::sprite) checks at
+/// compile time.
+pub const fn mask_fits(art: &str, mask: &str) -> bool {
+    let (art, mask) = (art.as_bytes(), mask.as_bytes());
+    let (mut a, mut m) = (0, 0);
+    while m < mask.len() {
+        let mut room = 0;
+        while a < art.len() && art[a] != b'\n' {
+            // Count characters by their first byte
+            if art[a] & 0xC0 != 0x80 {
+                room += 1;
+            }
+            a += 1;
+        }
+        a += 1;
+
+        let mut used = 0;
+        while m < mask.len() && mask[m] != b'\n' {
+            if !mask[m].is_ascii() || !is_mask_char(mask[m] as char) {
+                return false;
+            }
+            used += 1;
+            m += 1;
+        }
+        m += 1;
+
+        if used > room {
+            return false;
+        }
+    }
+    true
+}
+
+/// Build a [`Sprite`] from constant art and an optional colour mask,
+/// refusing to compile if the mask doesn't fit the art (see
+/// [`mask_fits`])
+///
+/// ```
+/// use asciiquarium_rs::sprite;
+///
+/// const ART: &str = "><_'>";
+/// let fish = sprite!(ART, "GGGWG");
+/// assert_eq!(fish.get_bounding_box(), (5, 1));
+/// ```
+///
+/// A mask one column too wide is caught before the program runs:
+///
+/// ```compile_fail
+/// let fish = asciiquarium_rs::sprite!("><_'>", "GGGWGG");
+/// ```
+#[macro_export]
+macro_rules! sprite {
+    ($art:expr) => {
+        $crate::entity::Sprite::from_ascii_art($art, None)
+    };
+    ($art:expr, $mask:expr) => {{
+        const _: () = assert!(
+            $crate::entity::mask_fits($art, $mask),
+            "colour mask does not fit its art"
+        );
+        $crate::entity::Sprite::from_ascii_art($art, Some($mask))
+    }};
+}
+
 /// Represents a sprite with ASCII art and optional color mask
+///
+/// Glyphs, mask characters and the cells that aren't transparent are laid
+/// out once when the sprite is built, so lookups while rendering and
+/// colliding don't walk the lines. The fields are read through getters,
+/// and the setters lay the sprite out again.
 #[derive(Debug, Clone)]
 pub struct Sprite {
-    pub lines: Vec<String>,
-    pub color_mask: Option<Vec<String>>,
-    pub transparent_chars: HashSet<char>,
+    lines: Vec<String>,
+    color_mask: Option<Vec<String>>,
+    transparent_chars: HashSet<char>,
+    /// RGB colours for mask digits '1'-'9' (truecolor sprites only)
+    palette: Option<[Color; PALETTE_SIZE]>,
+    /// Second mask layer giving cell background colours, with the same
+    /// letters as the colour mask; case makes no difference here
+    background_mask: Option<Vec<String>>,
+    /// Mask layer of text attributes: 'b' bold, 'd' dim, 'f' flashing
+    /// (blink); anything else leaves the character plain
+    attribute_mask: Option<Vec<String>>,
+    grid: Grid,
+}
+
+/// A sprite's glyphs and masks by line and glyph index, and a bitmap of
+/// its opaque screen cells
+#[derive(Debug, Clone, Default)]
+struct Grid {
+    /// (screen column, byte range in the line) of each glyph
+    glyphs: Vec<Vec<(usize, Range<usize>)>>,
+    /// First character of each glyph
+    chars: Vec<Vec<char>>,
+    color: Vec<Vec<char>>,
+    background: Vec<Vec<char>>,
+    attributes: Vec<Vec<char>>,
+    /// One bit per screen column, `words` words per line
+    opaque: Vec<u64>,
+    words: usize,
+    width: u16,
+}
+
+impl Grid {
+    fn new(sprite: &Sprite) -> Self {
+        let glyphs: Vec<Vec<(usize, Range<usize>)>> = sprite
+            .lines
+            .iter()
+            .map(|line| {
+                let mut column = 0;
+                line.grapheme_indices(true)
+                    .map(|(start, glyph)| {
+                        let cell = (column, start..start + glyph.len());
+                        column += glyph_width(glyph);
+                        cell
+                    })
+                    .collect()
+            })
+            .collect();
+        let width = glyphs
+            .iter()
+            .zip(&sprite.lines)
+            .filter_map(|(row, line)| {
+                let (column, range) = row.last()?;
+                Some(column + glyph_width(&line[range.clone()]))
+            })
+            .max()
+            .unwrap_or(0);
+        let words = width.div_ceil(64);
+        let mut opaque = vec![0; words * glyphs.len()];
+        for (row, (cells, line)) in glyphs.iter().zip(&sprite.lines).enumerate() {
+            for (column, range) in cells {
+                let glyph = &line[range.clone()];
+                if sprite.is_transparent_glyph(glyph) {
+                    continue;
+                }
+                for bit in *column..column + glyph_width(glyph) {
+                    opaque[row * words + bit / 64] |= 1 << (bit % 64);
+                }
+            }
+        }
+        let chars = glyphs
+            .iter()
+            .zip(&sprite.lines)
+            .map(|(cells, line)| {
+                cells
+                    .iter()
+                    .map(|(_, range)| line[range.clone()].chars().next().unwrap_or(' '))
+                    .collect()
+            })
+            .collect();
+        let mask_chars = |mask: &Option<Vec<String>>| -> Vec<Vec<char>> {
+            mask.iter()
+                .flatten()
+                .map(|line| line.chars().collect())
+                .collect()
+        };
+        Self {
+            glyphs,
+            chars,
+            color: mask_chars(&sprite.color_mask),
+            background: mask_chars(&sprite.background_mask),
+            attributes: mask_chars(&sprite.attribute_mask),
+            opaque,
+            words,
+            width: width as u16,
+        }
+    }
+
+    fn mask_char(mask: &[Vec<char>], col: usize, row: usize) -> Option<char> {
+        mask.get(row)?.get(col).copied()
+    }
 }
 
 impl Sprite {
@@ -29,118 +274,286 @@ impl Sprite {
             lines,
             color_mask,
             transparent_chars,
+            palette: None,
+            background_mask: None,
+            attribute_mask: None,
+            grid: Grid::default(),
         }
+        .laid_out()
     }
 
-    /// Get the bounding box (width, height) of the sprite
-    pub fn get_bounding_box(&self) -> (u16, u16) {
-        let height = self.lines.len() as u16;
-        let width = self
-            .lines
-            .iter()
-            .map(|line| line.chars().count())
-            .max()
-            .unwrap_or(0) as u16;
-        (width, height)
+    /// Create a sprite whose mask digits '1'-'9' use the given RGB palette
+    pub fn from_ascii_art_with_palette(
+        art: &str,
+        mask: Option<&str>,
+        palette: [Color; PALETTE_SIZE],
+    ) -> Self {
+        Self {
+            palette: Some(palette),
+            ..Self::from_ascii_art(art, mask)
+        }
     }
 
-    /// Check if a character at given position is transparent
-    pub fn is_transparent_at(&self, col: usize, row: usize) -> bool {
-        if row >= self.lines.len() {
-            return true;
+    /// Add a background mask layer
+    ///
+    /// Cells it colours are filled even where the art is transparent, so a
+    /// mask over the spaces inside an outline shades the whole shape.
+    pub fn with_background_mask(self, mask: &str) -> Self {
+        Self {
+            background_mask: Some(mask.lines().map(|s| s.to_string()).collect()),
+            ..self
+        }
+        .laid_out()
+    }
+
+    /// Add an attribute mask layer (see [`Sprite::attribute_mask`])
+    pub fn with_attribute_mask(self, mask: &str) -> Self {
+        Self {
+            attribute_mask: Some(mask.lines().map(|s| s.to_string()).collect()),
+            ..self
         }
+        .laid_out()
+    }
+
+    /// Work out the grid for the sprite's current lines and masks
+    fn laid_out(mut self) -> Self {
+        self.grid = Grid::new(&self);
+        self
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn color_mask(&self) -> Option<&[String]> {
+        self.color_mask.as_deref()
+    }
+
+    pub fn transparent_chars(&self) -> &HashSet<char> {
+        &self.transparent_chars
+    }
+
+    /// RGB colours for mask digits '1'-'9' (truecolor sprites only)
+    pub fn palette(&self) -> Option<&[Color; PALETTE_SIZE]> {
+        self.palette.as_ref()
+    }
+
+    /// See [`Sprite::with_background_mask`]
+    pub fn background_mask(&self) -> Option<&[String]> {
+        self.background_mask.as_deref()
+    }
+
+    /// 'b' bold, 'd' dim, 'f' flashing (blink); anything else leaves the
+    /// character plain
+    pub fn attribute_mask(&self) -> Option<&[String]> {
+        self.attribute_mask.as_deref()
+    }
+
+    /// Replace the art, keeping the masks
+    pub fn set_lines(&mut self, lines: Vec<String>) {
+        self.lines = lines;
+        self.grid = Grid::new(self);
+    }
+
+    pub fn set_color_mask(&mut self, mask: Option<Vec<String>>) {
+        self.color_mask = mask;
+        self.grid = Grid::new(self);
+    }
+
+    pub fn set_transparent_chars(&mut self, chars: HashSet<char>) {
+        self.transparent_chars = chars;
+        self.grid = Grid::new(self);
+    }
+
+    pub fn set_palette(&mut self, palette: Option<[Color; PALETTE_SIZE]>) {
+        self.palette = palette;
+    }
+
+    pub fn set_background_mask(&mut self, mask: Option<Vec<String>>) {
+        self.background_mask = mask;
+        self.grid = Grid::new(self);
+    }
 
-        let line = &self.lines[row];
-        let chars: Vec<char> = line.chars().collect();
+    pub fn set_attribute_mask(&mut self, mask: Option<Vec<String>>) {
+        self.attribute_mask = mask;
+        self.grid = Grid::new(self);
+    }
 
-        if col >= chars.len() {
-            return true;
+    /// Check that the colour mask fits the art, for sprites whose art is
+    /// only known at run time (compare [`mask_fits`])
+    ///
+    /// The error names the first row where the mask has more glyphs than
+    /// the art or a character that isn't a mask colour.
+    pub fn check_mask(&self) -> Result<(), String> {
+        let Some(mask) = &self.color_mask else {
+            return Ok(());
+        };
+        if mask.len() > self.lines.len() {
+            return Err(format!(
+                "mask has {} rows but the art has {}",
+                mask.len(),
+                self.lines.len()
+            ));
+        }
+        for (row, (mask_line, line)) in mask.iter().zip(&self.lines).enumerate() {
+            if let Some(code) = mask_line.chars().find(|&code| !is_mask_char(code)) {
+                return Err(format!("row {row}: {code:?} is not a mask colour"));
+            }
+            let (used, room) = (mask_line.chars().count(), line.graphemes(true).count());
+            if used > room {
+                return Err(format!(
+                    "row {row}: mask is {used} glyphs wide but the art is {room}"
+                ));
+            }
         }
+        Ok(())
+    }
 
-        self.transparent_chars.contains(&chars[col])
+    /// Get the bounding box (width, height) of the sprite
+    pub fn get_bounding_box(&self) -> (u16, u16) {
+        (self.grid.width, self.lines.len() as u16)
     }
 
-    /// Get the character at given position, or space if out of bounds
-    pub fn get_char_at(&self, col: usize, row: usize) -> char {
-        if row >= self.lines.len() {
-            return ' ';
+    /// Glyphs of line `row` as (screen column, glyph index, glyph)
+    ///
+    /// A glyph is one grapheme cluster: a character together with any
+    /// combining marks, variation selectors or zero-width joiners that
+    /// belong to it, so "e\u{301}" or a family emoji is one glyph. Wide
+    /// glyphs (CJK, most emoji) take two columns, so the column runs ahead
+    /// of the glyph index. Colour masks have one character per glyph and
+    /// are looked up by glyph index.
+    pub fn cells(&self, row: usize) -> impl Iterator<Item = (usize, usize, &str)> + '_ {
+        let line = self.lines.get(row).map_or("", String::as_str);
+        self.grid
+            .glyphs
+            .get(row)
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(move |(index, (column, range))| (*column, index, &line[range.clone()]))
+    }
+
+    /// Check if a glyph at given position is transparent
+    pub fn is_transparent_at(&self, col: usize, row: usize) -> bool {
+        self.grid
+            .glyphs
+            .get(row)
+            .and_then(|cells| cells.get(col))
+            .is_none_or(|(column, _)| !self.is_opaque(*column as u16, row as u16))
+    }
+
+    /// Whether the screen cell `x` columns across and `y` lines down from
+    /// the sprite's origin shows part of a glyph that isn't transparent
+    pub fn is_opaque(&self, x: u16, y: u16) -> bool {
+        let (x, y) = (x as usize, y as usize);
+        if x >= self.grid.width as usize || y >= self.lines.len() {
+            return false;
         }
+        self.grid.opaque[y * self.grid.words + x / 64] & (1 << (x % 64)) != 0
+    }
 
-        let line = &self.lines[row];
-        let chars: Vec<char> = line.chars().collect();
+    /// Screen cells relative to the sprite origin that aren't transparent,
+    /// line by line
+    pub fn opaque_cells(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        let (width, height) = self.get_bounding_box();
+        (0..height).flat_map(move |y| {
+            (0..width)
+                .filter(move |&x| self.is_opaque(x, y))
+                .map(move |x| (x, y))
+        })
+    }
 
-        if col >= chars.len() {
-            return ' ';
+    /// Whether `glyph` is one of the sprite's transparent characters
+    pub fn is_transparent_glyph(&self, glyph: &str) -> bool {
+        let mut chars = glyph.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => self.transparent_chars.contains(&ch),
+            _ => false,
         }
+    }
 
-        chars[col]
+    /// Get the (first) character of the glyph at given position, or space
+    /// if out of bounds
+    pub fn get_char_at(&self, col: usize, row: usize) -> char {
+        Grid::mask_char(&self.grid.chars, col, row).unwrap_or(' ')
     }
 
     /// Get the color for a character based on color mask with randomization
     pub fn get_color_at(&self, col: usize, row: usize) -> Option<Color> {
-        let color_mask = self.color_mask.as_ref()?;
-
-        if row >= color_mask.len() {
-            return None;
-        }
-
-        let mask_line = &color_mask[row];
-        let mask_chars: Vec<char> = mask_line.chars().collect();
-
-        if col >= mask_chars.len() {
-            return None;
-        }
-
-        // Convert color mask character to color following original Perl implementation
-        match mask_chars[col] {
-            // Direct color codes (castle uses these)
-            'R' => Some(Color::Red),
-            'r' => Some(Color::Red),
-            'G' => Some(Color::Green),
-            'g' => Some(Color::Green),
-            'B' => Some(Color::Blue),
-            'b' => Some(Color::Blue),
-            'Y' => Some(Color::Yellow),
-            'y' => Some(Color::Yellow),
-            'M' => Some(Color::Magenta),
-            'm' => Some(Color::Magenta),
-            'C' => Some(Color::Cyan),
-            'c' => Some(Color::Cyan),
-            'W' => Some(Color::White),
-            'w' => Some(Color::White),
-
-            // Randomized color codes from Perl rand_color function
-            // These are the result of converting numbers 1-9 to random colors
-            // Original Perl colors: ('c','C','r','R','y','Y','b','B','g','G','m','M')
-            '1' => Some(Color::Red),     // Fallback for unrandomized masks
-            '2' => Some(Color::Green),   // Fallback for unrandomized masks
-            '3' => Some(Color::Yellow),  // Fallback for unrandomized masks
-            '4' => Some(Color::Blue),    // Fallback for unrandomized masks
-            '5' => Some(Color::Magenta), // Fallback for unrandomized masks
-            '6' => Some(Color::Cyan),    // Fallback for unrandomized masks
-            '7' => Some(Color::White),   // Fallback for unrandomized masks
-            '8' => Some(Color::Red),     // Fallback for unrandomized masks
-            '9' => Some(Color::Green),   // Fallback for unrandomized masks
-            _ => None,
+        let code = Grid::mask_char(&self.grid.color, col, row)?;
+
+        // Truecolor sprites carry their own colours for the digits
+        if let (Some(palette), Some(digit)) = (&self.palette, code.to_digit(10)) {
+            if digit >= 1 {
+                return Some(palette[digit as usize - 1]);
+            }
         }
+
+        mask_color(code).map(|color| palette::current().remap(color))
+    }
+
+    /// Get the background colour at a position from the background mask
+    pub fn get_background_at(&self, col: usize, row: usize) -> Option<Color> {
+        Grid::mask_char(&self.grid.background, col, row)
+            .filter(|code| !code.is_ascii_digit())
+            .and_then(mask_color)
+    }
+
+    /// Text attributes the attribute mask gives a position
+    pub fn get_modifiers_at(&self, col: usize, row: usize) -> Modifier {
+        match Grid::mask_char(&self.grid.attributes, col, row) {
+            Some('b') => Modifier::BOLD,
+            Some('d') => Modifier::DIM,
+            Some('f') => Modifier::SLOW_BLINK,
+            _ => Modifier::empty(),
+        }
+    }
+
+    /// Whether the mask asks for a bold colour at this position
+    ///
+    /// As in Term::Animation, uppercase colour letters are the bold variants
+    /// of their lowercase counterparts.
+    pub fn is_bold_at(&self, col: usize, row: usize) -> bool {
+        Grid::mask_char(&self.grid.color, col, row).is_some_and(|ch| ch.is_ascii_uppercase())
     }
 
     /// Create a sprite with randomized colors (matching original Perl rand_color function)
-    pub fn from_ascii_art_with_random_colors(art: &str, mask: Option<&str>) -> Self {
+    ///
+    /// On truecolor terminals the digits get a random RGB palette instead,
+    /// unless a colour-blind palette was chosen: random hues could clash.
+    pub fn from_ascii_art_with_random_colors(
+        art: &str,
+        mask: Option<&str>,
+        rng: &mut RngHandle,
+    ) -> Self {
+        if palette::truecolor_supported() && palette::current() == palette::Palette::Standard {
+            let palette = palette::random_palette(rng);
+            Self::from_ascii_art_with_palette(art, mask, palette)
+        } else {
+            Self::from_ascii_art_with_ansi_colors(art, mask, rng)
+        }
+    }
+
+    /// Create a sprite with each mask digit mapped to a random ANSI colour
+    pub fn from_ascii_art_with_ansi_colors(
+        art: &str,
+        mask: Option<&str>,
+        rng: &mut RngHandle,
+    ) -> Self {
         use rand::Rng;
 
         let lines: Vec<String> = art.lines().map(|s| s.to_string()).collect();
 
         let color_mask = if let Some(m) = mask {
-            let mut rng = rand::thread_rng();
-
             // Original Perl colors: ('c','C','r','R','y','Y','b','B','g','G','m','M')
             let colors = ['c', 'C', 'r', 'R', 'y', 'Y', 'b', 'B', 'g', 'G', 'm', 'M'];
 
-            // Create a mapping for each number 1-9 to a random color
+            // Create a mapping for each number 1-9 to a random color. The
+            // original picks with int(rand($#colors)), which never chooses
+            // the last entry ('M'); keep that quirk for parity
             let mut color_map = std::collections::HashMap::new();
             for i in 1..=9 {
-                let random_color = colors[rng.gen_range(0..colors.len())];
+                let random_color = colors[rng.gen_range(0..colors.len() - 1)];
                 color_map.insert(char::from_digit(i, 10).unwrap(), random_color);
             }
 
@@ -162,25 +575,112 @@ impl Sprite {
             lines,
             color_mask,
             transparent_chars,
+            palette: None,
+            background_mask: None,
+            attribute_mask: None,
+            grid: Grid::default(),
         }
+        .laid_out()
     }
 
-    /// Get all non-transparent character positions relative to sprite origin
-    pub fn get_non_transparent_positions(&self) -> HashSet<(u16, u16)> {
-        let mut positions = HashSet::new();
+    /// The sprite flipped left to right, for deriving one facing from the
+    /// other
+    ///
+    /// Lines are padded to the sprite's width first so the art stays
+    /// aligned, and directional glyphs (see [`MIRROR_PAIRS`]) are swapped
+    /// for their mirror images. The masks are flipped along with the art.
+    pub fn mirrored(&self) -> Self {
+        let width = self.get_bounding_box().0 as usize;
+        let mut glyph_counts = Vec::with_capacity(self.lines.len());
+        let lines = self
+            .lines
+            .iter()
+            .map(|line| {
+                let mut glyphs: Vec<&str> = line.graphemes(true).map(mirror_glyph).collect();
+                let columns: usize = glyphs.iter().map(|glyph| glyph_width(glyph)).sum();
+                glyphs.resize(glyphs.len() + width.saturating_sub(columns), " ");
+                glyph_counts.push(glyphs.len());
+                glyphs.reverse();
+                glyphs.concat().trim_end_matches(' ').to_string()
+            })
+            .collect();
+
+        // Masks have one character per glyph
+        let flip = |mask: &Vec<String>| -> Vec<String> {
+            mask.iter()
+                .enumerate()
+                .map(|(row, line)| {
+                    let mut chars: Vec<char> = line.chars().collect();
+                    chars.resize(glyph_counts.get(row).copied().unwrap_or(0), ' ');
+                    chars.reverse();
+                    chars.into_iter().collect::<String>().trim_end().to_string()
+                })
+                .collect()
+        };
 
-        for (row, line) in self.lines.iter().enumerate() {
-            for (col, ch) in line.chars().enumerate() {
-                if !self.transparent_chars.contains(&ch) {
-                    positions.insert((col as u16, row as u16));
-                }
-            }
+        Self {
+            lines,
+            color_mask: self.color_mask.as_ref().map(flip),
+            transparent_chars: self.transparent_chars.clone(),
+            palette: self.palette,
+            background_mask: self.background_mask.as_ref().map(flip),
+            attribute_mask: self.attribute_mask.as_ref().map(flip),
+            grid: Grid::default(),
         }
+        .laid_out()
+    }
+
+    /// Get all non-transparent character positions relative to sprite origin
+    pub fn get_non_transparent_positions(&self) -> HashSet<(u16, u16)> {
+        self.opaque_cells().collect()
+    }
+}
 
-        positions
+/// Colour for a mask character (digits fall back to fixed colours when a
+/// sprite's mask hasn't been randomised)
+fn mask_color(code: char) -> Option<Color> {
+    // Convert color mask character to color following original Perl implementation
+    match code {
+        // Direct color codes (castle uses these)
+        'R' => Some(Color::Red),
+        'r' => Some(Color::Red),
+        'G' => Some(Color::Green),
+        'g' => Some(Color::Green),
+        'B' => Some(Color::Blue),
+        'b' => Some(Color::Blue),
+        'Y' => Some(Color::Yellow),
+        'y' => Some(Color::Yellow),
+        'M' => Some(Color::Magenta),
+        'm' => Some(Color::Magenta),
+        'C' => Some(Color::Cyan),
+        'c' => Some(Color::Cyan),
+        'W' => Some(Color::White),
+        'w' => Some(Color::White),
+        'K' => Some(Color::Black),
+        'k' => Some(Color::Black),
+
+        // Randomized color codes from Perl rand_color function
+        // These are the result of converting numbers 1-9 to random colors
+        // Original Perl colors: ('c','C','r','R','y','Y','b','B','g','G','m','M')
+        '1' => Some(Color::Red),     // Fallback for unrandomized masks
+        '2' => Some(Color::Green),   // Fallback for unrandomized masks
+        '3' => Some(Color::Yellow),  // Fallback for unrandomized masks
+        '4' => Some(Color::Blue),    // Fallback for unrandomized masks
+        '5' => Some(Color::Magenta), // Fallback for unrandomized masks
+        '6' => Some(Color::Cyan),    // Fallback for unrandomized masks
+        '7' => Some(Color::White),   // Fallback for unrandomized masks
+        '8' => Some(Color::Red),     // Fallback for unrandomized masks
+        '9' => Some(Color::Green),   // Fallback for unrandomized masks
+        _ => None,
     }
 }
 
+/// Columns a glyph takes on screen (at least one, so stray zero-width
+/// characters still get a cell of their own)
+pub fn glyph_width(glyph: &str) -> usize {
+    glyph.width().max(1)
+}
+
 /// Direction an entity is facing
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
@@ -228,41 +728,81 @@ impl Velocity {
 pub struct Animation {
     pub frames: Vec<Sprite>,
     pub current_frame: usize,
-    pub frame_duration: Duration,
-    pub last_frame_time: Instant,
+    /// How long each frame is shown, one entry per frame
+    pub frame_durations: Vec<Duration>,
+    /// How long the current frame has been shown
+    pub frame_time: Duration,
     pub looping: bool,
 }
 
 impl Animation {
+    /// Frames shown for `frame_duration` each
     pub fn new(frames: Vec<Sprite>, frame_duration: Duration, looping: bool) -> Self {
+        let frame_durations = vec![frame_duration; frames.len()];
+        Self::with_durations(frames, frame_durations, looping)
+    }
+
+    /// Frame `i` shown for `frame_durations[i]`
+    pub fn with_durations(
+        frames: Vec<Sprite>,
+        frame_durations: Vec<Duration>,
+        looping: bool,
+    ) -> Self {
+        debug_assert_eq!(frames.len(), frame_durations.len());
         Self {
             frames,
             current_frame: 0,
-            frame_duration,
-            last_frame_time: Instant::now(),
+            frame_durations,
+            frame_time: Duration::ZERO,
             looping,
         }
     }
 
-    pub fn update(&mut self) {
-        if self.frames.len() <= 1 {
-            return;
-        }
-
-        if self.last_frame_time.elapsed() >= self.frame_duration {
-            self.advance_frame();
-            self.last_frame_time = Instant::now();
+    /// Advance by `delta_time` of simulated time, through as many frames
+    /// as it covers. Returns how often a looping animation wrapped back to
+    /// its first frame.
+    pub fn update(&mut self, delta_time: Duration) -> u32 {
+        if self.frames.is_empty() || self.is_finished() {
+            return 0;
         }
-    }
 
-    fn advance_frame(&mut self) {
-        if self.current_frame + 1 >= self.frames.len() {
-            if self.looping {
+        self.frame_time += delta_time;
+        let mut wraps = 0;
+        loop {
+            let duration = self.frame_durations[self.current_frame];
+            if self.frame_time < duration {
+                break;
+            }
+            let last = self.current_frame + 1 >= self.frames.len();
+            if last && !self.looping {
+                // One-shot animations rest on their last frame
+                self.frame_time = duration;
+                break;
+            }
+            self.frame_time -= duration;
+            if last {
                 self.current_frame = 0;
+                wraps += 1;
+            } else {
+                self.current_frame += 1;
+            }
+            if duration.is_zero() {
+                break; // Zero-length frames advance once per update
             }
-        } else {
-            self.current_frame += 1;
         }
+        wraps
+    }
+
+    /// Whether a one-shot animation has shown its last frame for its full
+    /// duration (never true for looping ones; at once for one with no
+    /// frames)
+    pub fn is_finished(&self) -> bool {
+        !self.looping
+            && self.current_frame + 1 >= self.frames.len()
+            && self
+                .frame_durations
+                .get(self.current_frame)
+                .is_none_or(|duration| self.frame_time >= *duration)
     }
 
     pub fn get_current_sprite(&self) -> &Sprite {
@@ -271,15 +811,143 @@ impl Animation {
 
     pub fn reset(&mut self) {
         self.current_frame = 0;
-        self.last_frame_time = Instant::now();
+        self.frame_time = Duration::ZERO;
+    }
+}
+
+/// A child entity requested by another entity during its update
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Spawn {
+    /// A falling water droplet (e.g. from a whale spout)
+    Droplet(Position, Velocity),
+    /// A pod of dolphins leaping across the surface (a celebration)
+    Dolphins,
+}
+
+/// What an entity is, for queries such as "all fish" or "the shark"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    Fish,
+    /// Either of the big fish variants
+    BigFish,
+    Shark,
+    SharkTeeth,
+    Whale,
+    Ship,
+    SeaMonster,
+    Bubble,
+    Droplet,
+    Splash,
+    Splat,
+    /// Smoke left where a creature was taken out of the tank
+    Puff,
+    Seaweed,
+    Castle,
+    Sand,
+    WaterSurface,
+    Glint,
+    Anchor,
+    Dolphin,
+    Ducks,
+    /// A fishing hook, and the line it hangs from
+    Hook,
+    FishingLine,
+    /// Flakes dropped in with the mouse
+    Food,
+    /// The clock overlay
+    Clock,
+    /// The pomodoro timer overlay
+    Pomodoro,
+    /// A creature defined outside the crate (sprite files, embedders),
+    /// by name
+    Custom(&'static str),
+}
+
+impl EntityKind {
+    /// The kind's name, as used by spawners, hooks and sprite files
+    pub fn name(self) -> &'static str {
+        match self {
+            EntityKind::Fish => "fish",
+            EntityKind::BigFish => "big_fish",
+            EntityKind::Shark => "shark",
+            EntityKind::SharkTeeth => "shark_teeth",
+            EntityKind::Whale => "whale",
+            EntityKind::Ship => "ship",
+            EntityKind::SeaMonster => "sea_monster",
+            EntityKind::Bubble => "bubble",
+            EntityKind::Droplet => "droplet",
+            EntityKind::Splash => "splash",
+            EntityKind::Splat => "splat",
+            EntityKind::Puff => "puff",
+            EntityKind::Seaweed => "seaweed",
+            EntityKind::Castle => "castle",
+            EntityKind::Sand => "sand",
+            EntityKind::WaterSurface => "water_surface",
+            EntityKind::Glint => "glint",
+            EntityKind::Anchor => "anchor",
+            EntityKind::Dolphin => "dolphin",
+            EntityKind::Ducks => "ducks",
+            EntityKind::Hook => "hook",
+            EntityKind::FishingLine => "fishing_line",
+            EntityKind::Food => "food",
+            EntityKind::Clock => "clock",
+            EntityKind::Pomodoro => "pomodoro",
+            EntityKind::Custom(name) => name,
+        }
+    }
+
+    /// The built-in kind called `name` (see [`EntityKind::name`]); custom
+    /// kinds are named by whoever registered them
+    pub fn from_name(name: &str) -> Option<Self> {
+        let kind = match name {
+            "fish" => EntityKind::Fish,
+            "big_fish" => EntityKind::BigFish,
+            "shark" => EntityKind::Shark,
+            "shark_teeth" => EntityKind::SharkTeeth,
+            "whale" => EntityKind::Whale,
+            "ship" => EntityKind::Ship,
+            "sea_monster" => EntityKind::SeaMonster,
+            "bubble" => EntityKind::Bubble,
+            "droplet" => EntityKind::Droplet,
+            "splash" => EntityKind::Splash,
+            "splat" => EntityKind::Splat,
+            "puff" => EntityKind::Puff,
+            "seaweed" => EntityKind::Seaweed,
+            "castle" => EntityKind::Castle,
+            "sand" => EntityKind::Sand,
+            "water_surface" => EntityKind::WaterSurface,
+            "glint" => EntityKind::Glint,
+            "anchor" => EntityKind::Anchor,
+            "dolphin" => EntityKind::Dolphin,
+            "ducks" => EntityKind::Ducks,
+            "hook" => EntityKind::Hook,
+            "fishing_line" => EntityKind::FishingLine,
+            "food" => EntityKind::Food,
+            "clock" => EntityKind::Clock,
+            "pomodoro" => EntityKind::Pomodoro,
+            _ => return None,
+        };
+        Some(kind)
     }
 }
 
-/// Death callback function type
-pub type DeathCallback = fn(&mut EntityManager, Rect);
+/// Where an attached entity rides relative to its parent, see
+/// [`EntityManager::attach`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attachment {
+    pub parent: EntityId,
+    /// Columns right of the parent's position
+    pub dx: f32,
+    /// Rows below the parent's position
+    pub dy: f32,
+}
 
 /// Core entity trait that all aquarium entities must implement
-pub trait Entity {
+///
+/// Entities are `Any`, so a `&dyn Entity` can be downcast to its concrete
+/// type; see [`EntityManager::get_as`]. They are `Send` so that large
+/// tanks can update them on several threads (see [`PARALLEL_UPDATE_MIN`]).
+pub trait Entity: Any + Send {
     fn id(&self) -> EntityId;
     fn position(&self) -> Position;
     fn set_position(&mut self, position: Position);
@@ -287,66 +955,205 @@ pub trait Entity {
     fn set_velocity(&mut self, velocity: Velocity);
     fn depth(&self) -> u8;
     fn get_current_sprite(&self) -> &Sprite;
-    fn update(&mut self, delta_time: Duration, screen_bounds: Rect);
+    /// Advance the entity; `world_bounds` are the world extents (see
+    /// [`WorldContext`]), which may be larger than the visible screen
+    fn update(&mut self, delta_time: Duration, world_bounds: Rect);
     fn is_alive(&self) -> bool;
     fn kill(&mut self);
-    fn entity_type(&self) -> &'static str;
+    fn kind(&self) -> EntityKind;
+
+    /// Name of the entity's type; the kind's name unless a variant needs
+    /// telling apart (e.g. `big_fish_1`)
+    fn entity_type(&self) -> &'static str {
+        self.kind().name()
+    }
 
-    /// Get death callback function (None means no replacement spawning)
-    fn death_callback(&self) -> Option<DeathCallback> {
+    /// Population this entity belongs to; its death books a replacement
+    /// with the [`SpawnScheduler`]
+    fn population(&self) -> Option<Population> {
         None
     }
 
+    /// What follows this entity's death unless the manager has an
+    /// override for its kind
+    fn respawn_policy(&self) -> RespawnPolicy {
+        RespawnPolicy::RespawnSame
+    }
+
+    /// Stacking among entities at the same depth: higher hints are drawn
+    /// over lower ones, and on a tie the newer entity is drawn on top.
+    /// Read when the entity is added and after every update pass.
+    fn z_hint(&self) -> i32 {
+        0
+    }
+
+    /// Take any child entities this entity wants spawned this frame
+    fn pending_spawns(&mut self, _rng: &mut RngHandle) -> Vec<Spawn> {
+        Vec::new()
+    }
+
     /// Check if entity should spawn a bubble and return the bubble position
     /// Returns Some(position) if a bubble should be spawned, None otherwise
-    fn should_spawn_bubble(&mut self, _delta_time: Duration) -> Option<Position> {
+    fn should_spawn_bubble(
+        &mut self,
+        _delta_time: Duration,
+        _rng: &mut RngHandle,
+    ) -> Option<Position> {
+        None
+    }
+
+    /// Species this entity can breed as in ecology mode (adult fish only)
+    fn breeding_species(&self) -> Option<FishSpecies> {
         None
     }
 
-    /// Check if this entity collides with another at given positions
-    fn collides_with(&self, other: &dyn Entity) -> bool {
-        let self_pos = self.position().to_screen_coords();
-        let other_pos = other.position().to_screen_coords();
+    /// How strongly the water current pushes this entity (0.0 = not at all,
+    /// 1.0 = drifts with the full current)
+    fn current_response(&self) -> f32 {
+        0.0
+    }
+
+    /// The tank was resized from `old` to `new`. By default the entity
+    /// keeps its place relative to the tank's width and stays above the
+    /// new floor; scenery overrides this to re-anchor itself.
+    fn on_resize(&mut self, old: Rect, new: Rect) {
+        let height = self.get_current_sprite().get_bounding_box().1;
+        let mut position = self.position();
+        if old.width > 0 {
+            position.x *= new.width as f32 / old.width as f32;
+        }
+        position.y = position.y.min(new.height.saturating_sub(height) as f32);
+        self.set_position(position);
+    }
 
-        let self_sprite = self.get_current_sprite();
-        let other_sprite = other.get_current_sprite();
+    /// The tide moved the water surface by `rows` (positive = down); move
+    /// whatever is tied to the surface along with it
+    fn ride_tide(&mut self, _rows: f32) {}
 
-        let self_bounds = self_sprite.get_non_transparent_positions();
-        let other_bounds = other_sprite.get_non_transparent_positions();
+    /// Where this entity popped at the water surface, if it just did so
+    /// (bubbles only)
+    fn surface_pop(&self) -> Option<Position> {
+        None
+    }
 
-        // Check if any non-transparent pixels overlap
-        for &(sx, sy) in &self_bounds {
-            let world_x = self_pos.0 + sx;
-            let world_y = self_pos.1 + sy;
+    /// Something happened in the tank (see [`crate::hooks`])
+    fn on_event(&mut self, _event: &AquariumEvent) {}
 
-            for &(ox, oy) in &other_bounds {
-                let other_world_x = other_pos.0 + ox;
-                let other_world_y = other_pos.1 + oy;
+    /// A bubble popped at the surface at `position`; floating entities
+    /// above it may react (see [`crate::interaction`])
+    fn on_bubble_pop(&mut self, _position: Position) {}
 
-                if world_x == other_world_x && world_y == other_world_y {
-                    return true;
-                }
-            }
-        }
+    /// Whether predators should chase this entity
+    fn is_prey(&self) -> bool {
+        false
+    }
+
+    /// Let a predator see where prey is (centres of all prey entities)
+    fn hunt(&mut self, _prey: &[Position]) {}
+
+    /// Whether fish should hide from this entity
+    fn is_threat(&self) -> bool {
+        false
+    }
+
+    /// Let an entity see where threats are (centre rows of all threats)
+    fn notice_threats(&mut self, _threats: &[f32]) {}
+
+    /// Whether fish swim to this entity to eat it
+    fn is_food(&self) -> bool {
+        false
+    }
+
+    /// Let an entity see where food is (centres of all food entities, see
+    /// [`crate::behavior`])
+    fn smell_food(&mut self, _food: &[Position]) {}
+
+    /// Whether tired fish may rest beside this entity (seaweed)
+    fn is_shelter(&self) -> bool {
+        false
+    }
+
+    /// Let an entity see where shelters are (centre columns of all
+    /// shelter entities, see [`crate::behavior`])
+    fn shelter(&mut self, _shelters: &[f32], _rng: &mut RngHandle) {}
+
+    /// Whether this is a single-cell particle (e.g. a spout droplet) that
+    /// half-block rendering may draw at double vertical resolution
+    fn is_particle(&self) -> bool {
+        false
+    }
 
+    /// Whether this is scenery spanning the tank (e.g. the sand) that
+    /// never blocks placement or takes part in collisions
+    fn is_backdrop(&self) -> bool {
         false
     }
 
+    /// Whether this entity never changes by itself (the castle, the sand)
+    ///
+    /// [`EntityManager::update_all`] doesn't update static entities, and
+    /// those drawn behind everything that moves make up the static layer
+    /// the tank cache draws once and reuses (see [`crate::dirty`]).
+    fn is_static(&self) -> bool {
+        false
+    }
+
+    /// Whether this entity gives off its own light, staying bright in
+    /// night mode (see [`crate::render::night_pass`])
+    fn glows(&self) -> bool {
+        false
+    }
+
+    /// Centre of the sprite's bounding box
+    fn centre(&self) -> Position {
+        let position = self.position();
+        let (width, height) = self.get_current_sprite().get_bounding_box();
+        Position::new(
+            position.x + width as f32 / 2.0,
+            position.y + height as f32 / 2.0,
+            position.depth,
+        )
+    }
+
+    /// Collision layers this entity is on (none by default, so nothing
+    /// can touch it; see [`crate::collision`])
+    fn collision_layers(&self) -> CollisionLayers {
+        CollisionLayers::NONE
+    }
+
+    /// Collision layers this entity makes contact with
+    fn collision_mask(&self) -> CollisionLayers {
+        CollisionLayers::NONE
+    }
+
+    /// React to a contact this entity made or received
+    fn on_contact(&mut self, _contact: &Contact) {}
+
     /// Render the entity to the buffer with transparency
-    fn render(&self, buffer: &mut Buffer, screen_bounds: Rect) {
+    fn render(&self, buffer: &mut Buffer, screen_bounds: Rect, options: &RenderOptions) {
         let position = self.position();
         let sprite = self.get_current_sprite();
 
-        for (row_idx, line) in sprite.lines.iter().enumerate() {
-            for (col_idx, ch) in line.chars().enumerate() {
+        if options.half_block && self.is_particle() {
+            let color = sprite.get_color_at(0, 0).unwrap_or(Color::White);
+            let style = options.style_for(color, false, self.depth());
+            let color = style.fg.unwrap_or(color);
+            render::draw_half_block(buffer, screen_bounds, position.x, position.y, color);
+            return;
+        }
+
+        for row_idx in 0..sprite.lines.len() {
+            for (column, col_idx, glyph) in sprite.cells(row_idx) {
                 // Calculate screen position using i32 to handle negative coordinates
-                let x = position.x as i32 + col_idx as i32;
+                let x = position.x as i32 + column as i32;
                 let y = position.y as i32 + row_idx as i32;
 
-                // Skip if off-screen (negative or beyond bounds)
+                // Skip if off-screen (negative or beyond bounds); wide
+                // glyphs must fit whole
+                let width = glyph_width(glyph) as i32;
                 if x < 0
                     || y < 0
-                    || x >= screen_bounds.width as i32
+                    || x + width > screen_bounds.width as i32
                     || y >= screen_bounds.height as i32
                 {
                     continue;
@@ -355,64 +1162,147 @@ pub trait Entity {
                 let x_u16 = x as u16;
                 let y_u16 = y as u16;
 
-                // Skip transparent characters
-                if sprite.is_transparent_at(col_idx, row_idx) {
+                let background = sprite
+                    .get_background_at(col_idx, row_idx)
+                    .map(|color| options.dimmed(color, self.depth()));
+
+                // Skip transparent characters, unless the background mask
+                // fills them
+                if sprite.is_transparent_glyph(glyph) {
+                    if let Some(background) = background {
+                        if let Some(cell) = buffer.cell_mut((x_u16, y_u16)) {
+                            cell.set_char(' ').set_bg(background);
+                        }
+                    }
                     continue;
                 }
 
-                // Get the cell and update it
-                if x_u16 < buffer.area.width && y_u16 < buffer.area.height {
+                // Get the cell and update it (the buffer may only cover
+                // part of the screen)
+                if buffer.area.contains((x_u16, y_u16).into()) {
+                    // Drawing over the right half of a wide glyph from further
+                    // back: that glyph can't be half shown, so blank it
+                    if let Some(left) = x_u16
+                        .checked_sub(1)
+                        .and_then(|left_x| buffer.cell_mut((left_x, y_u16)))
+                    {
+                        if glyph_width(left.symbol()) > 1 {
+                            left.set_char(' ');
+                        }
+                    }
+
                     let cell = buffer.cell_mut((x_u16, y_u16)).unwrap();
-                    cell.set_char(ch);
+                    cell.set_symbol(glyph);
+                    // Attributes of whatever was drawn here before don't carry over
+                    cell.modifier = Modifier::empty();
 
                     // Apply color from mask if available, or default colors by entity type
                     if let Some(color) = sprite.get_color_at(col_idx, row_idx) {
-                        cell.set_fg(color);
+                        let bold = sprite.is_bold_at(col_idx, row_idx);
+                        cell.set_style(options.style_for(color, bold, self.depth()));
                     } else {
                         // Apply default colors based on entity type
-                        let default_color = match self.entity_type() {
-                            "bubble" => Color::Cyan,
-                            "fish" => Color::Yellow,
-                            "seaweed" => Color::Green,
-                            "shark" => Color::White,
-                            "whale" => Color::Blue,
+                        let default_color = match self.kind() {
+                            EntityKind::Bubble => Color::Cyan,
+                            EntityKind::Fish => Color::Yellow,
+                            EntityKind::Seaweed => Color::Green,
+                            EntityKind::Shark => Color::White,
+                            EntityKind::Whale => Color::Blue,
                             _ => Color::White,
                         };
-                        cell.set_fg(default_color);
+                        cell.set_style(options.style_for(default_color, false, self.depth()));
                     }
+
+                    if let Some(background) = background {
+                        cell.set_bg(background);
+                    }
+                    cell.modifier |= sprite.get_modifiers_at(col_idx, row_idx);
                 }
             }
         }
     }
 }
 
+/// Distance between two points in columns, rows counting double
+fn cell_distance(a: Position, b: Position) -> f32 {
+    (b.x - a.x).hypot((b.y - a.y) * 2.0)
+}
+
 /// Entity manager handles all entities and rendering
 pub struct EntityManager {
-    entities: HashMap<EntityId, Box<dyn Entity>>,
-    depth_layers: HashMap<u8, Vec<EntityId>>,
-    next_id: EntityId,
-    large_creature_id: Option<EntityId>, // Track single large creature
-    classic_mode: bool,                  // Classic mode flag (disables new fish/monsters)
+    entities: EntityStore,             // Entities by id, with their render order
+    large_creature_ids: Vec<EntityId>, // Track live large creatures
+    classic_mode: bool,                // Classic mode flag (disables new fish/monsters)
+    water_scroll: bool,                // Water surface layers drift sideways
+    background: Background,            // Scene in the bottom-right corner
+    shark_aggressiveness: f32,         // How eagerly new sharks hunt (0.0-1.0)
+    time_scale: f32,                   // Simulated time per real time (0.0 freezes the tank)
+    fish_density: f32,                 // Fish kept, as a multiple of the original count
+    environment: Environment,          // Water current and other global state
+    hooks: EventHooks,                 // Embedder callbacks for spawn/kill/weather
+    ecology: Option<Ecology>,          // Breeding state, present only in ecology mode
+    ambient: Option<AmbientScheduler>, // Ambient event clock, absent when disabled
+    registry: SpawnRegistry,           // Spawners for large creatures, ambient events and scenery
+    scheduler: SpawnScheduler,         // Population targets and booked replacements
+    respawn_overrides: HashMap<EntityKind, RespawnPolicy>, // Configured in place of entities' own policies
+    touching: HashSet<Contact>, // Contacts in progress, to report each once
+    pool: EntityPool,           // Dead short-lived entities kept for reuse
+    rng: RngHandle,             // The generator everything random draws from
+    attachments: HashMap<EntityId, Attachment>, // Children by id, riding along with their parents
+    collision_time: Cell<Duration>, // Time spent in collision checks, for the perf readout
+    spatial: OnceCell<SpatialIndex>, // Where entities are, built on demand after changes
 }
 
 impl EntityManager {
     pub fn new() -> Self {
         Self {
-            entities: HashMap::new(),
-            depth_layers: HashMap::new(),
-            next_id: 1,
-            large_creature_id: None,
+            entities: EntityStore::new(),
+            large_creature_ids: Vec::new(),
             classic_mode: false,
+            water_scroll: true,
+            background: Background::Castle,
+            shark_aggressiveness: crate::entities::shark::DEFAULT_AGGRESSIVENESS,
+            time_scale: 1.0,
+            fish_density: 1.0,
+            environment: Environment::new(),
+            hooks: EventHooks::new(),
+            ecology: None,
+            ambient: None,
+            registry: SpawnRegistry::new(),
+            scheduler: SpawnScheduler::new(),
+            respawn_overrides: HashMap::new(),
+            touching: HashSet::new(),
+            pool: EntityPool::new(),
+            rng: RngHandle::from_entropy(),
+            attachments: HashMap::new(),
+            collision_time: Cell::default(),
+            spatial: OnceCell::new(),
         }
     }
 
     pub fn new_classic() -> Self {
         Self {
-            entities: HashMap::new(),
-            depth_layers: HashMap::new(),
-            next_id: 1,
-            large_creature_id: None,
+            entities: EntityStore::new(),
+            large_creature_ids: Vec::new(),
             classic_mode: true,
+            water_scroll: false,
+            background: Background::Castle,
+            shark_aggressiveness: crate::entities::shark::DEFAULT_AGGRESSIVENESS,
+            time_scale: 1.0,
+            fish_density: 1.0,
+            environment: Environment::new(),
+            hooks: EventHooks::new(),
+            ecology: None,
+            ambient: None,
+            registry: SpawnRegistry::new(),
+            scheduler: SpawnScheduler::new(),
+            respawn_overrides: HashMap::new(),
+            touching: HashSet::new(),
+            pool: EntityPool::new(),
+            rng: RngHandle::from_entropy(),
+            attachments: HashMap::new(),
+            collision_time: Cell::default(),
+            spatial: OnceCell::new(),
         }
     }
 
@@ -424,152 +1314,1058 @@ impl EntityManager {
         self.classic_mode = classic_mode;
     }
 
-    pub fn get_next_id(&self) -> EntityId {
-        self.next_id
+    /// Whether new water surface layers scroll (off in classic mode, which
+    /// keeps the original's static surface)
+    pub fn water_scroll(&self) -> bool {
+        self.water_scroll
     }
 
-    pub fn add_entity(&mut self, entity: Box<dyn Entity>) -> EntityId {
-        let id = self.next_id;
-        self.next_id += 1;
+    pub fn set_water_scroll(&mut self, water_scroll: bool) {
+        self.water_scroll = water_scroll;
+    }
 
-        let depth = entity.depth();
+    /// Scene placed in the bottom-right corner when the tank is filled
+    pub fn background(&self) -> Background {
+        self.background
+    }
 
-        // Update entity ID (this requires entities to implement a set_id method)
-        // For now, we'll assume the entity constructor sets the ID
+    pub fn set_background(&mut self, background: Background) {
+        self.background = background;
+    }
 
-        self.depth_layers.entry(depth).or_default().push(id);
+    /// Spawners the tank is filled and replenished from
+    pub fn registry(&self) -> &SpawnRegistry {
+        &self.registry
+    }
 
-        self.entities.insert(id, entity);
-        id
+    pub fn registry_mut(&mut self) -> &mut SpawnRegistry {
+        &mut self.registry
     }
 
-    pub fn remove_entity(&mut self, id: EntityId) {
-        if let Some(entity) = self.entities.remove(&id) {
-            let depth = entity.depth();
-            if let Some(layer) = self.depth_layers.get_mut(&depth) {
-                layer.retain(|&entity_id| entity_id != id);
-                if layer.is_empty() {
-                    self.depth_layers.remove(&depth);
-                }
-            }
-        }
+    /// Population targets and respawn timing
+    pub fn scheduler(&self) -> &SpawnScheduler {
+        &self.scheduler
     }
 
-    pub fn update_all(&mut self, delta_time: Duration, screen_bounds: Rect) {
-        let mut dead_entities = Vec::new();
-        let mut bubble_spawns = Vec::new();
+    pub fn scheduler_mut(&mut self) -> &mut SpawnScheduler {
+        &mut self.scheduler
+    }
 
-        for (id, entity) in &mut self.entities {
-            entity.update(delta_time, screen_bounds);
-            if !entity.is_alive() {
-                dead_entities.push(*id);
-            }
+    /// Use `policy` for every entity of `kind` instead of its own
+    pub fn set_respawn_policy(&mut self, kind: EntityKind, policy: RespawnPolicy) {
+        self.respawn_overrides.insert(kind, policy);
+    }
 
-            // Check if entity wants to spawn a bubble
-            if let Some(bubble_pos) = entity.should_spawn_bubble(delta_time) {
-                bubble_spawns.push(bubble_pos);
-            }
-        }
+    /// Go back to entities of `kind` choosing their own policy
+    pub fn clear_respawn_policy(&mut self, kind: EntityKind) {
+        self.respawn_overrides.remove(&kind);
+    }
 
-        // Spawn bubbles
-        for bubble_pos in bubble_spawns {
-            self.spawn_bubble(bubble_pos);
+    /// The policy applied when `entity` dies
+    pub fn respawn_policy(&self, entity: &dyn Entity) -> RespawnPolicy {
+        self.respawn_overrides
+            .get(&entity.kind())
+            .copied()
+            .unwrap_or_else(|| entity.respawn_policy())
+    }
+
+    /// Live members of `population`
+    pub fn population_size(&self, population: Population) -> usize {
+        match population {
+            Population::Large => self.large_creature_ids.len(),
+            _ => self
+                .entities
+                .values()
+                .filter(|entity| entity.is_alive() && entity.population() == Some(population))
+                .count(),
         }
+    }
 
-        // Handle death callbacks and remove dead entities
-        for id in dead_entities {
-            self.handle_entity_death(id, screen_bounds);
+    /// Run the replacements that are due after `delta_time`, skipping any
+    /// whose population is already at its target
+    fn spawn_due(&mut self, delta_time: Duration, screen_bounds: Rect) {
+        for population in self.scheduler.advance(delta_time) {
+            if self.population_size(population) < self.scheduler.target(population) {
+                (population.spawner())(self, screen_bounds);
+            }
         }
     }
 
-    /// Spawn a bubble at the given position
-    fn spawn_bubble(&mut self, position: Position) {
-        use crate::entities::Bubble;
-        let bubble_id = self.get_next_id();
-        let bubble = Bubble::new(bubble_id, position);
-        self.add_entity(Box::new(bubble));
+    pub fn environment(&self) -> &Environment {
+        &self.environment
     }
 
-    /// Handle entity death and trigger death callbacks
-    pub fn handle_entity_death(&mut self, id: EntityId, screen_bounds: Rect) {
-        if let Some(entity) = self.entities.get(&id) {
-            let death_callback = entity.death_callback();
-            let _entity_type = entity.entity_type().to_string();
+    pub fn environment_mut(&mut self) -> &mut Environment {
+        &mut self.environment
+    }
 
-            // Check if this was the large creature
-            if self.large_creature_id == Some(id) {
-                self.large_creature_id = None;
-            }
+    /// Create an empty manager with the same settings, taking over the
+    /// registered event callbacks (used on resize and redraw)
+    pub fn fresh(&mut self) -> EntityManager {
+        // Preserve classic_mode setting when reinitializing
+        let mut entity_manager = if self.classic_mode {
+            EntityManager::new_classic()
+        } else {
+            EntityManager::new()
+        };
+        entity_manager.set_ecology_mode(self.ecology_mode());
+        entity_manager.set_water_scroll(self.water_scroll);
+        entity_manager.set_background(self.background);
+        entity_manager.registry = self.registry.clone();
+        entity_manager.scheduler = self.scheduler.fresh();
+        entity_manager.respawn_overrides = self.respawn_overrides.clone();
+        entity_manager.set_shark_aggressiveness(self.shark_aggressiveness);
+        entity_manager.set_time_scale(self.time_scale);
+        entity_manager.set_fish_density(self.fish_density);
+        entity_manager.set_ambient_frequency(self.ambient_frequency());
+        entity_manager.set_hooks(self.take_hooks());
+        entity_manager.set_rng(self.rng.clone());
+        entity_manager
+    }
 
-            // Remove the entity first
-            self.remove_entity(id);
+    /// The generator everything random in the tank draws from
+    pub fn rng(&self) -> RngHandle {
+        self.rng.clone()
+    }
 
-            // Then trigger death callback if one exists
-            if let Some(callback) = death_callback {
-                callback(self, screen_bounds);
-            }
-        }
+    /// Draw random numbers from `rng` (see [`crate::rng`])
+    pub fn set_rng(&mut self, rng: RngHandle) {
+        self.rng = rng;
     }
 
-    /// Check if a large creature already exists
-    pub fn has_large_creature(&self) -> bool {
-        self.large_creature_id.is_some()
+    /// Start the tank's random numbers over from `seed`, so a run can be
+    /// repeated
+    pub fn seed(&mut self, seed: u64) {
+        self.set_rng(RngHandle::seeded(seed));
     }
 
-    /// Set the current large creature ID
+    /// Register a callback for spawn, kill and weather events
+    pub fn on_event(&mut self, callback: impl FnMut(&AquariumEvent) + 'static) {
+        self.hooks.subscribe(callback);
+    }
+
+    /// Hand the registered callbacks over (used when the manager is rebuilt)
+    pub fn take_hooks(&mut self) -> EventHooks {
+        std::mem::take(&mut self.hooks)
+    }
+
+    /// Install previously registered callbacks
+    pub fn set_hooks(&mut self, hooks: EventHooks) {
+        self.hooks = hooks;
+    }
+
+    fn emit(&mut self, event: AquariumEvent) {
+        self.spatial.take();
+        for entity in self.entities.values_mut() {
+            entity.on_event(&event);
+        }
+        if !self.hooks.is_empty() {
+            self.hooks.emit(&event);
+        }
+    }
+
+    /// Average ambient events per minute (0.0 when disabled)
+    pub fn ambient_frequency(&self) -> f32 {
+        self.ambient
+            .as_ref()
+            .map_or(0.0, AmbientScheduler::events_per_minute)
+    }
+
+    /// Set the average ambient events per minute; 0.0 disables them
+    pub fn set_ambient_frequency(&mut self, events_per_minute: f32) {
+        if events_per_minute != self.ambient_frequency() {
+            self.ambient = (events_per_minute > 0.0)
+                .then(|| AmbientScheduler::new(events_per_minute, &mut self.rng));
+        }
+    }
+
+    pub fn shark_aggressiveness(&self) -> f32 {
+        self.shark_aggressiveness
+    }
+
+    pub fn set_shark_aggressiveness(&mut self, aggressiveness: f32) {
+        self.shark_aggressiveness = aggressiveness.clamp(0.0, 1.0);
+    }
+
+    /// How fast the tank runs: 1.0 is real time, 0.5 slow motion, 2.0
+    /// fast forward and 0.0 freezes it
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Set the time scale, clamped to 0.0-[`timestep::MAX_TIME_SCALE`]
+    ///
+    /// [`timestep::MAX_TIME_SCALE`]: crate::timestep::MAX_TIME_SCALE
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.time_scale = scale.clamp(0.0, crate::timestep::MAX_TIME_SCALE);
+    }
+
+    /// How many fish the tank keeps, as a multiple of the original
+    /// formula's count (see [`crate::spawning::fish_target`])
+    pub fn fish_density(&self) -> f32 {
+        self.fish_density
+    }
+
+    /// Set the fish density, clamped to
+    /// 0.0-[`spawning::MAX_FISH_DENSITY`]; apply it to a filled tank with
+    /// [`spawning::fit_fish`]
+    ///
+    /// [`spawning::MAX_FISH_DENSITY`]: crate::spawning::MAX_FISH_DENSITY
+    /// [`spawning::fit_fish`]: crate::spawning::fit_fish
+    pub fn set_fish_density(&mut self, density: f32) {
+        self.fish_density = density.clamp(0.0, crate::spawning::MAX_FISH_DENSITY);
+    }
+
+    pub fn ecology_mode(&self) -> bool {
+        self.ecology.is_some()
+    }
+
+    pub fn set_ecology_mode(&mut self, ecology_mode: bool) {
+        if ecology_mode != self.ecology_mode() {
+            self.ecology = ecology_mode.then(Ecology::new);
+        }
+    }
+
+    /// Build an entity with the id the manager allocates for it and add it
+    ///
+    /// Ids only come from the manager, so entities added this way can't
+    /// share one.
+    pub fn spawn<E: Entity>(&mut self, build: impl FnOnce(EntityId) -> E) -> EntityId {
+        let entity = build(self.entities.next_id());
+        self.add_entity(Box::new(entity))
+    }
+
+    /// [`EntityManager::spawn`] a creature taking a large creature slot
+    pub fn spawn_large<E: Entity>(&mut self, build: impl FnOnce(EntityId) -> E) -> EntityId {
+        self.set_large_creature(self.entities.next_id());
+        self.spawn(build)
+    }
+
+    /// [`EntityManager::spawn`] an entity of a [`Recycle`] kind, restarting
+    /// a dead one from the [`EntityPool`] if there is one
+    pub fn spawn_recycled<E: Recycle>(&mut self, start: E::Start) -> EntityId {
+        let entity = self
+            .pool
+            .get::<E>(self.entities.next_id(), start, &mut self.rng);
+        self.add_entity(entity)
+    }
+
+    /// [`EntityManager::spawn`] the first of up to `attempts` candidates
+    /// that lands in a free spot, or the last one if none does, so a
+    /// crowded tank still gets its entity
+    pub fn spawn_clear<E: Entity>(
+        &mut self,
+        attempts: usize,
+        mut candidate: impl FnMut(EntityId) -> E,
+    ) -> EntityId {
+        let id = self.entities.next_id();
+        let mut entity = candidate(id);
+        for _ in 1..attempts {
+            let size = entity.get_current_sprite().get_bounding_box();
+            if self.is_area_free(entity.position(), size) {
+                break;
+            }
+            entity = candidate(id);
+        }
+        self.add_entity(Box::new(entity))
+    }
+
+    /// Dead entities kept for [`EntityManager::spawn_recycled`]
+    pub fn pool(&self) -> &EntityPool {
+        &self.pool
+    }
+
+    /// Add an entity built with the id the store hands out next; positions
+    /// are given relative to the mid-tide surface and shifted to the
+    /// current tide here
+    fn add_entity(&mut self, mut entity: Box<dyn Entity>) -> EntityId {
+        let tide_offset = self.environment.tide_offset();
+        if tide_offset != 0 {
+            entity.ride_tide(tide_offset as f32);
+        }
+
+        assert_eq!(
+            entity.id(),
+            self.entities.next_id(),
+            "entity built with an id other than the one it was given"
+        );
+
+        let entity_type = entity.entity_type();
+        let id = self.entities.insert(entity);
+        self.spatial.take();
+        self.emit(AquariumEvent::Spawned { id, entity_type });
+        if self.large_creature_ids.contains(&id) {
+            self.emit(AquariumEvent::LargeCreatureEntered { id, entity_type });
+        }
+        id
+    }
+
+    /// Remove an entity along with everything attached to it
+    pub fn remove_entity(&mut self, id: EntityId) {
+        self.attachments.remove(&id);
+        self.large_creature_ids.retain(|&large_id| large_id != id);
+        for child in self.children_of(id) {
+            self.remove_entity(child);
+        }
+        if let Some(entity) = self.entities.remove(id) {
+            self.spatial.take();
+            self.emit(AquariumEvent::Killed {
+                id,
+                entity_type: entity.entity_type(),
+            });
+            self.pool.put(entity);
+        }
+    }
+
+    /// Let every entity adjust to the tank being resized from `old` to `new`
+    pub fn resize_all(&mut self, old: Rect, new: Rect) {
+        self.spatial.take();
+        for entity in self.entities.values_mut() {
+            entity.on_resize(old, new);
+        }
+        self.sync_attachments();
+    }
+
+    /// Attach `child` to `parent`, `dx` columns right and `dy` rows below
+    /// it: the child follows the parent every update (keeping its own
+    /// depth) and is removed with it
+    pub fn attach(&mut self, child: EntityId, parent: EntityId, dx: f32, dy: f32) {
+        if !self.entities.contains(parent) || self.is_attached_below(parent, child) {
+            return;
+        }
+        self.attachments
+            .insert(child, Attachment { parent, dx, dy });
+        self.sync_attachments();
+    }
+
+    /// Let `child` move on its own again
+    pub fn detach(&mut self, child: EntityId) {
+        self.attachments.remove(&child);
+    }
+
+    pub fn attachment(&self, child: EntityId) -> Option<Attachment> {
+        self.attachments.get(&child).copied()
+    }
+
+    /// Entities attached directly to `parent`, oldest first
+    pub fn children_of(&self, parent: EntityId) -> Vec<EntityId> {
+        let mut children: Vec<EntityId> = self
+            .attachments
+            .iter()
+            .filter(|(_, attachment)| attachment.parent == parent)
+            .map(|(&child, _)| child)
+            .collect();
+        children.sort_by_key(|&child| self.spawn_sequence(child));
+        children
+    }
+
+    /// Move attached entities to their parents, parents first so chains of
+    /// attachments settle in one pass
+    fn sync_attachments(&mut self) {
+        self.spatial.take();
+        let mut children: Vec<EntityId> = self.attachments.keys().copied().collect();
+        children.sort_by_key(|&child| self.attachment_depth(child));
+        for child in children {
+            let attachment = self.attachments[&child];
+            let Some(parent) = self.entities.get(attachment.parent) else {
+                continue;
+            };
+            let origin = parent.position();
+            if let Some(entity) = self.entities.get_mut(child) {
+                let depth = entity.position().depth;
+                entity.set_position(Position::new(
+                    origin.x + attachment.dx,
+                    origin.y + attachment.dy,
+                    depth,
+                ));
+            }
+        }
+    }
+
+    /// Number of parents above `child`
+    fn attachment_depth(&self, mut child: EntityId) -> usize {
+        let mut depth = 0;
+        while let Some(attachment) = self.attachments.get(&child) {
+            child = attachment.parent;
+            depth += 1;
+        }
+        depth
+    }
+
+    /// Whether `id` is `ancestor` or hangs (at any remove) from it, which
+    /// would make attaching `ancestor` to `id` a cycle
+    fn is_attached_below(&self, mut id: EntityId, ancestor: EntityId) -> bool {
+        loop {
+            if id == ancestor {
+                return true;
+            }
+            match self.attachments.get(&id) {
+                Some(attachment) => id = attachment.parent,
+                None => return false,
+            }
+        }
+    }
+
+    /// Update every entity against the world bounds (never the viewport, so
+    /// a camera can pan without killing or spawning anything)
+    ///
+    /// `delta_time` is real time; it is scaled by the
+    /// [time scale](EntityManager::time_scale) first.
+    pub fn update_all(&mut self, delta_time: Duration, world: &WorldContext) {
+        // Everything below runs on scaled time; a frozen tank stands still
+        let delta_time = delta_time.mul_f32(self.time_scale);
+        if delta_time.is_zero() {
+            return;
+        }
+        self.entities.advance(delta_time);
+        self.spatial.take();
+        let world_bounds = world.bounds();
+
+        // Centres of everything predators may chase
+        let prey: Vec<Position> = self
+            .entities
+            .values()
+            .filter(|entity| entity.is_prey())
+            .map(|entity| entity.centre())
+            .collect();
+
+        // Centre rows of everything fish hide from
+        let threats: Vec<f32> = self
+            .entities
+            .values()
+            .filter(|entity| entity.is_threat())
+            .map(|entity| {
+                let height = entity.get_current_sprite().get_bounding_box().1;
+                entity.position().y + height as f32 / 2.0
+            })
+            .collect();
+
+        // Centres of everything fish swim to and eat
+        let food: Vec<Position> = self
+            .entities
+            .values()
+            .filter(|entity| entity.is_food())
+            .map(|entity| entity.centre())
+            .collect();
+
+        // Centre columns of everything fish may rest beside
+        let shelters: Vec<f32> = self
+            .entities
+            .values()
+            .filter(|entity| entity.is_shelter())
+            .map(|entity| {
+                let width = entity.get_current_sprite().get_bounding_box().0;
+                entity.position().x + width as f32 / 2.0
+            })
+            .collect();
+
+        let tide_before = self.environment.tide_offset();
+        if let Some(target) = self.environment.update(delta_time, &mut self.rng) {
+            self.emit(AquariumEvent::CurrentChanged { target });
+        }
+        let tide_shift = self.environment.tide_offset() - tide_before;
+
+        let inputs = UpdateInputs {
+            delta_time,
+            world_bounds,
+            tide_shift: tide_shift as f32,
+            prey: &prey,
+            threats: &threats,
+            food: &food,
+            shelters: &shelters,
+            environment: &self.environment,
+        };
+        let UpdateOutputs {
+            mut dead_entities,
+            bubble_spawns,
+            surface_pops,
+            child_spawns,
+        } = if self.entities.len() < PARALLEL_UPDATE_MIN {
+            let mut outputs = UpdateOutputs::default();
+            for (id, entity) in self.entities.iter_mut() {
+                update_entity(id, entity, &inputs, &mut outputs, &mut self.rng);
+            }
+            outputs
+        } else {
+            update_in_parallel(self.entities.iter_mut().collect(), &inputs, &mut self.rng)
+        };
+
+        // Entities may have changed layer (e.g. fish hiding), and have moved
+        self.entities.restack();
+        self.spatial.take();
+
+        // Bubbles popping at the surface nudge whatever floats above them
+        // and leave a splash on the waterline
+        for pop in surface_pops {
+            for entity in self.entities.values_mut() {
+                entity.on_bubble_pop(pop);
+            }
+            self.emit(AquariumEvent::BubblePopped { position: pop });
+            self.spawn_splash(pop);
+        }
+
+        // Move attached entities along with their parents
+        self.sync_attachments();
+
+        // Spawn bubbles
+        for bubble_pos in bubble_spawns {
+            self.spawn_bubble(bubble_pos);
+        }
+
+        for spawn in child_spawns {
+            self.spawn_child(spawn, world_bounds);
+        }
+
+        self.resolve_contacts(&mut dead_entities);
+
+        if self.ecology.is_some() {
+            self.update_breeding(delta_time, world_bounds);
+        }
+
+        let ambient_due = self
+            .ambient
+            .as_mut()
+            .is_some_and(|ambient| ambient.update(delta_time, &mut self.rng));
+        if ambient_due {
+            if let Some(entry) = self
+                .registry
+                .choose(Category::Ambient, |_| true, &mut self.rng)
+            {
+                self.emit(AquariumEvent::Ambient { name: entry.name });
+                (entry.spawn)(self, world_bounds);
+            }
+        }
+
+        // Replacements booked earlier may now be due
+        self.spawn_due(delta_time, world_bounds);
+
+        // Remove dead entities and book their replacements
+        for id in dead_entities {
+            self.handle_entity_death(id, world_bounds);
+        }
+    }
+
+    /// Hand every contact that started this frame to both entities and
+    /// report it as a collision; entities it kills join `dead`
+    fn resolve_contacts(&mut self, dead: &mut Vec<EntityId>) {
+        let contacts = self.check_collisions();
+        let started: Vec<Contact> = contacts
+            .iter()
+            .filter(|contact| !self.touching.contains(contact))
+            .copied()
+            .collect();
+        self.touching = contacts.into_iter().collect();
+
+        for contact in started {
+            for id in [contact.source, contact.target] {
+                if let Some(entity) = self.entities.get_mut(id) {
+                    let alive = entity.is_alive();
+                    entity.on_contact(&contact);
+                    if alive && !entity.is_alive() {
+                        dead.push(id);
+                    }
+                }
+            }
+            self.emit(AquariumEvent::Collision {
+                first: contact.source,
+                second: contact.target,
+                kind: contact.kind,
+            });
+        }
+    }
+
+    /// Spawn a bubble at the given position
+    fn spawn_bubble(&mut self, position: Position) {
+        self.spawn_recycled::<crate::entities::Bubble>(position);
+    }
+
+    /// Spawn a child entity requested through [`Entity::pending_spawns`]
+    fn spawn_child(&mut self, spawn: Spawn, world_bounds: Rect) {
+        use crate::entities::Droplet;
+        match spawn {
+            Spawn::Droplet(position, velocity) => {
+                self.spawn_recycled::<Droplet>((position, velocity));
+            }
+            Spawn::Dolphins => crate::spawning::add_dolphins(self, world_bounds),
+        }
+    }
+
+    /// Spawn a short-lived splash on the surface above a popped bubble
+    fn spawn_splash(&mut self, bubble_position: Position) {
+        self.spawn_recycled::<crate::entities::Splash>(bubble_position);
+    }
+
+    /// Let lingering pairs of adult fish produce juveniles (ecology mode)
+    fn update_breeding(&mut self, delta_time: Duration, screen_bounds: Rect) {
+        let mates: Vec<Mate> = self
+            .entities
+            .values()
+            .filter_map(|entity| {
+                entity.breeding_species().map(|species| Mate {
+                    id: entity.id(),
+                    species,
+                    position: entity.position(),
+                    size: entity.get_current_sprite().get_bounding_box(),
+                })
+            })
+            .collect();
+        let fish_count = self.get_entities_of_kind(EntityKind::Fish).len();
+        let cap = ecology::population_cap(screen_bounds, self.environment.waterline());
+
+        let Some(ecology) = self.ecology.as_mut() else {
+            return;
+        };
+        let births = ecology.update(&mates, fish_count, delta_time, cap);
+
+        for birth in births {
+            use crate::entities::Fish;
+            let direction = match self.entities.get(birth.parent_id) {
+                Some(parent) if parent.velocity().dx < 0.0 => Direction::Left,
+                _ => Direction::Right,
+            };
+            let mut rng = self.rng.clone();
+            self.spawn(|id| {
+                Fish::new_juvenile(id, birth.position, direction, birth.species, &mut rng)
+            });
+        }
+    }
+
+    /// Remove a dead entity and carry out its [`RespawnPolicy`]
+    ///
+    /// Replacements are booked with the scheduler; ones due straight away
+    /// spawn before this returns.
+    pub fn handle_entity_death(&mut self, id: EntityId, screen_bounds: Rect) {
+        if let Some(entity) = self.entities.get(id) {
+            let policy = self.respawn_policy(entity);
+            let same = if self.large_creature_ids.contains(&id) {
+                Some(Population::Large)
+            } else {
+                entity.population()
+            };
+            let centre = entity.centre();
+
+            // Remove the entity first
+            self.remove_entity(id);
+
+            let replacement = match policy {
+                RespawnPolicy::None => None,
+                RespawnPolicy::RespawnSame => same,
+                RespawnPolicy::RespawnRandomLarge => Some(Population::Large),
+                RespawnPolicy::SpawnSplat { respawn } => {
+                    self.spawn(|id| crate::entities::Splat::new(id, centre));
+                    same.filter(|_| respawn)
+                }
+            };
+            if let Some(population) = replacement {
+                self.scheduler.book(population);
+                self.spawn_due(Duration::ZERO, screen_bounds);
+            }
+        }
+    }
+
+    /// Check if a large creature already exists
+    pub fn has_large_creature(&self) -> bool {
+        !self.large_creature_ids.is_empty()
+    }
+
+    /// Check if every large creature slot is taken
+    pub fn large_creatures_full(&self) -> bool {
+        self.large_creature_ids.len() >= self.large_creature_limit()
+    }
+
+    /// Number of large creatures allowed at once (1 except on very wide screens)
+    pub fn large_creature_limit(&self) -> usize {
+        self.scheduler.target(Population::Large)
+    }
+
+    /// Set the number of large creatures allowed at once
+    pub fn set_large_creature_limit(&mut self, limit: usize) {
+        self.scheduler.set_target(Population::Large, limit.max(1));
+    }
+
+    /// Free a large creature slot for one summoned on demand, removing
+    /// the oldest large creature if every slot is taken; nothing is booked
+    /// to replace it
+    pub fn make_room_for_large(&mut self) {
+        if self.large_creatures_full() && !self.large_creature_ids.is_empty() {
+            let id = self.large_creature_ids.remove(0);
+            self.remove_entity(id);
+        }
+    }
+
+    /// Remove every large creature of one of `kinds` straight away,
+    /// without booking replacements, returning where their middles were
+    pub fn remove_large_of_kinds(&mut self, kinds: &[EntityKind]) -> Vec<Position> {
+        let ids: Vec<EntityId> = self
+            .large_creature_ids
+            .iter()
+            .copied()
+            .filter(|&id| {
+                self.get_entity(id)
+                    .is_some_and(|entity| kinds.contains(&entity.kind()))
+            })
+            .collect();
+        let mut centres = Vec::new();
+        for id in ids {
+            if let Some(entity) = self.get_entity(id) {
+                centres.push(entity.centre());
+            }
+            self.remove_entity(id);
+        }
+        centres
+    }
+
+    /// Kill the newest large creatures until they fit the limit again
+    /// (after it was lowered for a narrower screen)
+    pub fn trim_large_creatures(&mut self, screen_bounds: Rect) {
+        while self.large_creature_ids.len() > self.large_creature_limit() {
+            let Some(id) = self.large_creature_ids.pop() else {
+                break;
+            };
+            self.handle_entity_death(id, screen_bounds);
+        }
+    }
+
+    /// Record a newly added large creature
     pub fn set_large_creature(&mut self, id: EntityId) {
-        self.large_creature_id = Some(id);
+        self.large_creature_ids.push(id);
+    }
+
+    pub fn render_all(&self, buffer: &mut Buffer, screen_bounds: Rect, options: &RenderOptions) {
+        self.render_static_layer(buffer, screen_bounds, options);
+        self.render_moving(buffer, screen_bounds, options);
+    }
+
+    /// Static entities drawn behind everything that moves, back to front
+    /// (see [`Entity::is_static`])
+    pub fn static_layer(&self) -> impl Iterator<Item = &dyn Entity> {
+        self.entities
+            .render_order()
+            .map(|id| &self.entities[id])
+            .take_while(|entity| entity.is_static())
+    }
+
+    /// Render only the static layer
+    pub fn render_static_layer(
+        &self,
+        buffer: &mut Buffer,
+        screen_bounds: Rect,
+        options: &RenderOptions,
+    ) {
+        Self::render_each(self.static_layer(), buffer, screen_bounds, options);
     }
 
-    pub fn render_all(&self, buffer: &mut Buffer, screen_bounds: Rect) {
-        // Get all depth layers and sort them (render back to front)
-        let mut depths: Vec<u8> = self.depth_layers.keys().cloned().collect();
-        depths.sort_by(|a, b| b.cmp(a)); // Reverse order: higher depth first (background)
+    /// Render every entity in front of the static layer
+    pub fn render_moving(&self, buffer: &mut Buffer, screen_bounds: Rect, options: &RenderOptions) {
+        let behind = self.static_layer().count();
+        let moving = self
+            .entities
+            .render_order()
+            .skip(behind)
+            .map(|id| &self.entities[id]);
+        Self::render_each(moving, buffer, screen_bounds, options);
+    }
+
+    fn render_each<'a>(
+        entities: impl Iterator<Item = &'a dyn Entity>,
+        buffer: &mut Buffer,
+        screen_bounds: Rect,
+        options: &RenderOptions,
+    ) {
+        // Back to front: higher depth first (background)
+        for entity in entities {
+            // The buffer may be a single dirty region
+            let footprint = crate::dirty::footprint_area(entity, screen_bounds);
+            if buffer.area.intersects(footprint) {
+                entity.render(buffer, screen_bounds, options);
+            }
+        }
+    }
 
-        for depth in depths {
-            if let Some(entity_ids) = self.depth_layers.get(&depth) {
-                for &entity_id in entity_ids {
-                    if let Some(entity) = self.entities.get(&entity_id) {
-                        entity.render(buffer, screen_bounds);
+    /// Screen cells showing a glowing entity once everything is rendered
+    ///
+    /// Walks the entities in render order, so a glowing entity hidden
+    /// behind a dark one doesn't light up the cells covering it.
+    pub fn glowing_cells(&self, screen_bounds: Rect) -> HashSet<(u16, u16)> {
+        let mut cells = HashSet::new();
+        for id in self.entities.render_order() {
+            let entity = &self.entities[id];
+            {
+                let position = entity.position();
+                let sprite = entity.get_current_sprite();
+                for row_idx in 0..sprite.lines.len() {
+                    for (column, _, glyph) in sprite.cells(row_idx) {
+                        let x = position.x as i32 + column as i32;
+                        let y = position.y as i32 + row_idx as i32;
+                        if x < 0
+                            || y < 0
+                            || x >= screen_bounds.width as i32
+                            || y >= screen_bounds.height as i32
+                            || sprite.is_transparent_glyph(glyph)
+                        {
+                            continue;
+                        }
+                        if entity.glows() {
+                            cells.insert((x as u16, y as u16));
+                        } else {
+                            cells.remove(&(x as u16, y as u16));
+                        }
                     }
                 }
             }
         }
+
+        cells
     }
 
     pub fn get_entities_by_type(&self, entity_type: &str) -> Vec<&dyn Entity> {
         self.entities
             .values()
             .filter(|entity| entity.entity_type() == entity_type)
-            .map(|boxed| boxed.as_ref())
             .collect()
     }
 
-    pub fn check_collisions(&self) -> Vec<(EntityId, EntityId)> {
-        let mut collisions = Vec::new();
-        let entity_ids: Vec<EntityId> = self.entities.keys().cloned().collect();
+    pub fn get_entities_of_kind(&self, kind: EntityKind) -> Vec<&dyn Entity> {
+        self.entities
+            .values()
+            .filter(|entity| entity.kind() == kind)
+            .collect()
+    }
 
-        for i in 0..entity_ids.len() {
-            for j in (i + 1)..entity_ids.len() {
-                let id1 = entity_ids[i];
-                let id2 = entity_ids[j];
+    /// Every entity of concrete type `T`
+    pub fn entities_of<T: Entity>(&self) -> impl Iterator<Item = &T> {
+        self.entities
+            .values()
+            .filter_map(|entity| (entity as &dyn Any).downcast_ref::<T>())
+    }
+
+    /// The entity `id` as its concrete type, if it is a `T`
+    pub fn get_as<T: Entity>(&self, id: EntityId) -> Option<&T> {
+        let entity = self.entities.get(id)?;
+        (entity as &dyn Any).downcast_ref::<T>()
+    }
+
+    /// The entity `id` as its concrete type, if it is a `T`
+    pub fn get_as_mut<T: Entity>(&mut self, id: EntityId) -> Option<&mut T> {
+        self.spatial.take();
+        let entity = self.entities.get_mut(id)?;
+        (entity as &mut dyn Any).downcast_mut::<T>()
+    }
+
+    pub fn get_entity(&self, id: EntityId) -> Option<&dyn Entity> {
+        self.entities.get(id)
+    }
+
+    /// Where `id` comes among everything spawned into the tank, lower
+    /// for older; ids themselves aren't in spawn order once slots are
+    /// reused
+    pub fn spawn_sequence(&self, id: EntityId) -> Option<u64> {
+        self.entities.sequence(id)
+    }
+
+    /// Simulated time since `id` was spawned
+    pub fn age_of(&self, id: EntityId) -> Option<Duration> {
+        self.entities.age(id)
+    }
+
+    pub fn get_entity_mut(&mut self, id: EntityId) -> Option<&mut dyn Entity> {
+        self.spatial.take();
+        self.entities.get_mut(id)
+    }
 
-                if let (Some(entity1), Some(entity2)) =
-                    (self.entities.get(&id1), self.entities.get(&id2))
+    /// The spatial index, built on first use after anything changed
+    fn spatial_index(&self) -> &SpatialIndex {
+        self.spatial.get_or_init(|| {
+            let mut index = SpatialIndex::new();
+            for (id, entity) in self.entities.iter() {
+                let size = entity.get_current_sprite().get_bounding_box();
+                index.insert(id, entity.position(), size);
+            }
+            index
+        })
+    }
+
+    /// Entities whose bounding boxes may overlap a `size` box at `position`
+    fn candidates_near(&self, position: Position, size: (u16, u16)) -> Vec<EntityId> {
+        self.spatial_index().candidates(
+            position.x,
+            position.y,
+            position.x + size.0 as f32,
+            position.y + size.1 as f32,
+        )
+    }
+
+    /// Entities whose sprite bounding boxes overlap a `size` box at `position`
+    pub fn occupants(&self, position: Position, size: (u16, u16)) -> Vec<EntityId> {
+        self.candidates_near(position, size)
+            .into_iter()
+            .filter(|id| Self::occupies(&self.entities[*id], position, size))
+            .collect()
+    }
+
+    /// Whether nothing occupies a `size` box at `position`
+    pub fn is_area_free(&self, position: Position, size: (u16, u16)) -> bool {
+        let started = Instant::now();
+        let free = !self
+            .candidates_near(position, size)
+            .iter()
+            .any(|id| Self::occupies(&self.entities[*id], position, size));
+        self.add_collision_time(started.elapsed());
+        free
+    }
+
+    /// Entities whose sprite bounding boxes overlap `area`
+    pub fn entities_in_rect(&self, area: Rect) -> Vec<EntityId> {
+        let position = Position::new(area.x as f32, area.y as f32, 0);
+        let size = (area.width, area.height);
+        self.candidates_near(position, size)
+            .into_iter()
+            .filter(|id| {
+                let entity = &self.entities[*id];
+                let entity_size = entity.get_current_sprite().get_bounding_box();
+                crate::world::overlaps(position, size, entity.position(), entity_size)
+            })
+            .collect()
+    }
+
+    /// Live entities whose centres lie within `radius` columns of `centre`,
+    /// nearest first (rows count double, as cells are about twice as tall
+    /// as they are wide)
+    pub fn entities_within(&self, centre: Position, radius: f32) -> Vec<EntityId> {
+        let mut found: Vec<(f32, EntityId)> = self
+            .spatial_index()
+            .candidates(
+                centre.x - radius,
+                centre.y - radius / 2.0,
+                centre.x + radius,
+                centre.y + radius / 2.0,
+            )
+            .into_iter()
+            .filter_map(|id| {
+                let entity = &self.entities[id];
+                let distance = cell_distance(centre, entity.centre());
+                (entity.is_alive() && distance <= radius).then_some((distance, id))
+            })
+            .collect();
+        found.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        found.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// The live entity of `kind` whose centre is nearest `position`
+    pub fn nearest_of_kind(&self, kind: EntityKind, position: Position) -> Option<EntityId> {
+        let (width, height) = self.spatial_index().extent()?;
+        // Widen the search until something turns up or it spans the index
+        let mut radius = crate::spatial::CELL_WIDTH as f32;
+        loop {
+            let nearest = self
+                .entities_within(position, radius)
+                .into_iter()
+                .find(|&id| self.entities[id].kind() == kind);
+            let covers_index = position.x - radius <= 0.0
+                && position.x + radius >= width as f32
+                && position.y - radius / 2.0 <= 0.0
+                && position.y + radius / 2.0 >= height as f32;
+            if nearest.is_some() || covers_index {
+                return nearest;
+            }
+            radius *= 2.0;
+        }
+    }
+
+    /// Time spent in collision and placement checks since last asked
+    pub fn take_collision_time(&self) -> Duration {
+        self.collision_time.take()
+    }
+
+    fn add_collision_time(&self, elapsed: Duration) {
+        self.collision_time.set(self.collision_time.get() + elapsed);
+    }
+
+    fn occupies(entity: &dyn Entity, position: Position, size: (u16, u16)) -> bool {
+        if entity.is_backdrop() {
+            return false;
+        }
+        let entity_size = entity.get_current_sprite().get_bounding_box();
+        crate::world::overlaps(position, size, entity.position(), entity_size)
+    }
+
+    /// Contacts between entities touching now, in source then target id
+    /// order; only entities with a collision mask look for them
+    pub fn check_collisions(&self) -> Vec<Contact> {
+        let started = Instant::now();
+        let mut contacts = Vec::new();
+
+        // Only entities whose boxes overlap on a masked layer can touch
+        for (id, entity) in self.entities.iter() {
+            let mask = entity.collision_mask();
+            let area = Aabb::of(entity);
+            if mask.is_empty() || area.is_empty() {
+                continue;
+            }
+            for other_id in self.candidates_near(
+                entity.position(),
+                entity.get_current_sprite().get_bounding_box(),
+            ) {
+                let other = &self.entities[other_id];
+                if other_id == id
+                    || !mask.intersects(other.collision_layers())
+                    || !area.intersects(&Aabb::of(other))
                 {
-                    if entity1.collides_with(entity2.as_ref()) {
-                        collisions.push((id1, id2));
-                    }
+                    continue;
                 }
+                let contact = collision::contact_between(entity, other)
+                    .filter(|_| collision::sprites_overlap(entity, other));
+                contacts.extend(contact);
             }
         }
+        contacts.sort_unstable_by_key(|contact| (contact.source, contact.target));
 
-        collisions
+        self.add_collision_time(started.elapsed());
+        contacts
+    }
+
+    /// Every entity, in no particular order
+    pub fn entities(&self) -> impl Iterator<Item = &dyn Entity> {
+        self.entities.values()
     }
 
     pub fn entity_count(&self) -> usize {
         self.entities.len()
     }
+
+    /// Number of entities in each depth layer, front to back
+    pub fn layer_counts(&self) -> Vec<(u8, usize)> {
+        self.entities.layer_counts()
+    }
+
+    /// Verify internal bookkeeping is consistent (used by the stress test)
+    pub fn check_invariants(&self) -> Result<(), String> {
+        self.entities.check()?;
+
+        for (id, entity) in self.entities.iter() {
+            let position = entity.position();
+            if !position.x.is_finite() || !position.y.is_finite() {
+                return Err(format!("entity {id} has non-finite position {position:?}"));
+            }
+        }
+
+        if self.large_creature_ids.len() > self.large_creature_limit() {
+            return Err(format!(
+                "{} large creatures exceed the limit of {}",
+                self.large_creature_ids.len(),
+                self.large_creature_limit()
+            ));
+        }
+        if let Some(id) = self
+            .large_creature_ids
+            .iter()
+            .find(|&&id| !self.entities.contains(id))
+        {
+            return Err(format!("large creature {id} no longer exists"));
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for EntityManager {
@@ -578,16 +2374,140 @@ impl Default for EntityManager {
     }
 }
 
+/// Entities from which the update pass is split across threads
+pub const PARALLEL_UPDATE_MIN: usize = 256;
+
+/// Entities per chunk of a split update pass
+///
+/// Each chunk draws from its own generator, seeded from the tank's in
+/// chunk order, so a seeded run repeats whatever the number of cores.
+const UPDATE_CHUNK: usize = 64;
+
+/// What the update pass gives every entity besides the time step
+struct UpdateInputs<'a> {
+    delta_time: Duration,
+    world_bounds: Rect,
+    tide_shift: f32,
+    prey: &'a [Position],
+    threats: &'a [f32],
+    food: &'a [Position],
+    shelters: &'a [f32],
+    environment: &'a Environment,
+}
+
+/// What updated entities ask of the manager, in entity order
+#[derive(Default)]
+struct UpdateOutputs {
+    dead_entities: Vec<EntityId>,
+    bubble_spawns: Vec<Position>,
+    surface_pops: Vec<Position>,
+    child_spawns: Vec<Spawn>,
+}
+
+impl UpdateOutputs {
+    fn append(&mut self, mut other: Self) {
+        self.dead_entities.append(&mut other.dead_entities);
+        self.bubble_spawns.append(&mut other.bubble_spawns);
+        self.surface_pops.append(&mut other.surface_pops);
+        self.child_spawns.append(&mut other.child_spawns);
+    }
+}
+
+/// Run one entity's part of the update pass
+fn update_entity(
+    id: EntityId,
+    entity: &mut dyn Entity,
+    inputs: &UpdateInputs,
+    outputs: &mut UpdateOutputs,
+    rng: &mut RngHandle,
+) {
+    let delta_time = inputs.delta_time;
+    if inputs.tide_shift != 0.0 {
+        entity.ride_tide(inputs.tide_shift);
+    }
+    if !entity.is_static() {
+        entity.hunt(inputs.prey);
+        entity.shelter(inputs.shelters, rng);
+        entity.notice_threats(inputs.threats);
+        entity.smell_food(inputs.food);
+        entity.update(delta_time, inputs.world_bounds);
+    }
+
+    // Let the water current carry the entity along
+    let response = entity.current_response();
+    if response > 0.0 {
+        let (drift_x, drift_y) = inputs.environment.drift(response, delta_time);
+        let mut position = entity.position();
+        position.x += drift_x;
+        position.y += drift_y;
+        entity.set_position(position);
+    }
+    outputs.child_spawns.extend(entity.pending_spawns(rng));
+    if !entity.is_alive() {
+        outputs.dead_entities.push(id);
+        if let Some(pop) = entity.surface_pop() {
+            outputs.surface_pops.push(pop);
+        }
+    }
+
+    // Check if entity wants to spawn a bubble
+    if let Some(bubble_pos) = entity.should_spawn_bubble(delta_time, rng) {
+        outputs.bubble_spawns.push(bubble_pos);
+    }
+}
+
+/// Update `entities` in chunks on rayon's pool of worker threads, which
+/// lives for the whole run
+fn update_in_parallel(
+    mut entities: Vec<(EntityId, &mut dyn Entity)>,
+    inputs: &UpdateInputs,
+    rng: &mut RngHandle,
+) -> UpdateOutputs {
+    use rand::Rng;
+    use rayon::prelude::*;
+
+    let chunks: Vec<_> = entities
+        .chunks_mut(UPDATE_CHUNK)
+        .map(|chunk| (rng.gen(), chunk))
+        .collect();
+    let results: Vec<UpdateOutputs> = chunks
+        .into_par_iter()
+        .map(|(seed, chunk)| update_chunk(seed, chunk, inputs))
+        .collect();
+
+    // Collected in chunk order, so the manager sees entity order
+    let mut outputs = UpdateOutputs::default();
+    for result in results {
+        outputs.append(result);
+    }
+    outputs
+}
+
+fn update_chunk(
+    seed: u64,
+    chunk: &mut [(EntityId, &mut dyn Entity)],
+    inputs: &UpdateInputs,
+) -> UpdateOutputs {
+    let mut rng = RngHandle::seeded(seed);
+    let mut outputs = UpdateOutputs::default();
+    for (id, entity) in chunk.iter_mut() {
+        update_entity(*id, &mut **entity, inputs, &mut outputs, &mut rng);
+    }
+    outputs
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::environment::WATERLINE_Y;
 
     #[test]
     fn test_color_randomization() {
         let art = "123\n456\n789";
         let mask = "123\n456\n789";
 
-        let sprite = Sprite::from_ascii_art_with_random_colors(art, Some(mask));
+        let sprite =
+            Sprite::from_ascii_art_with_ansi_colors(art, Some(mask), &mut RngHandle::default());
 
         // Check that the sprite was created
         assert_eq!(sprite.lines.len(), 3);
@@ -614,6 +2534,571 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mask_validation() {
+        assert!(mask_fits("<><\n ><>", "RGB\n GG"));
+        // Shorter masks leave the rest uncoloured
+        assert!(mask_fits("<><\n ><>", "R"));
+        assert!(!mask_fits("<><", "RGBY"));
+        assert!(!mask_fits("<><", "R\nG"));
+        assert!(!mask_fits("<><", "R.B"));
+        // Multi-byte art is measured in characters
+        assert!(mask_fits("·◦°", "CCC"));
+
+        assert!(Sprite::from_ascii_art("<><", Some("RGB"))
+            .check_mask()
+            .is_ok());
+        assert_eq!(
+            Sprite::from_ascii_art("<><\n><", Some("RGB\nRGB")).check_mask(),
+            Err("row 1: mask is 3 glyphs wide but the art is 2".to_string())
+        );
+        assert_eq!(
+            Sprite::from_ascii_art("<><", Some("RxB")).check_mask(),
+            Err("row 0: 'x' is not a mask colour".to_string())
+        );
+    }
+
+    #[test]
+    fn test_setters_lay_the_sprite_out_again() {
+        let mut sprite = Sprite::from_ascii_art("ab", Some("rr"));
+        sprite.set_lines(vec!["abc?".to_string()]);
+        assert_eq!(sprite.get_bounding_box(), (4, 1));
+        assert_eq!(sprite.get_char_at(2, 0), 'c');
+        sprite.set_transparent_chars(HashSet::from(['c']));
+        assert!(!sprite.is_opaque(2, 0));
+        sprite.set_color_mask(Some(vec!["  g".to_string()]));
+        assert_eq!(sprite.get_color_at(0, 0), None);
+        assert!(sprite.get_color_at(2, 0).is_some());
+    }
+
+    #[test]
+    fn test_mirrored_sprite() {
+        let sprite = Sprite::from_ascii_art("  /\\\n<(o)=", Some("  ab\ncdWef"));
+        let mirrored = sprite.mirrored();
+        assert_eq!(mirrored.lines, vec![" /\\", "=(o)>"]);
+        assert_eq!(
+            mirrored.color_mask,
+            Some(vec![" ba".to_string(), "feWdc".to_string()])
+        );
+        assert_eq!(mirrored.mirrored().lines, vec!["  /\\", "<(o)="]);
+    }
+
+    #[test]
+    fn test_animation_follows_simulated_time() {
+        let frames = vec![
+            Sprite::from_ascii_art("a", None),
+            Sprite::from_ascii_art("b", None),
+        ];
+        let mut animation = Animation::new(frames, crate::timestep::STEP * 6, true);
+
+        for _ in 0..5 {
+            animation.update(crate::timestep::STEP);
+        }
+        assert_eq!(animation.current_frame, 0);
+        animation.update(crate::timestep::STEP);
+        assert_eq!(animation.current_frame, 1);
+    }
+
+    #[test]
+    fn test_animation_durations_and_one_shot() {
+        let frames = vec![
+            Sprite::from_ascii_art("a", None),
+            Sprite::from_ascii_art("b", None),
+            Sprite::from_ascii_art("c", None),
+        ];
+        let durations = vec![
+            Duration::from_millis(100),
+            Duration::from_millis(300),
+            Duration::from_millis(100),
+        ];
+
+        let mut looping = Animation::with_durations(frames.clone(), durations.clone(), true);
+        assert_eq!(looping.update(Duration::from_millis(350)), 0);
+        assert_eq!(looping.current_frame, 1);
+        assert_eq!(looping.update(Duration::from_millis(200)), 1);
+        assert_eq!(looping.current_frame, 0);
+        assert!(!looping.is_finished());
+
+        let mut once = Animation::with_durations(frames, durations, false);
+        once.update(Duration::from_millis(450));
+        assert_eq!(once.current_frame, 2);
+        assert!(!once.is_finished());
+        assert_eq!(once.update(Duration::from_secs(5)), 0);
+        assert_eq!(once.get_current_sprite().lines[0], "c");
+        assert!(once.is_finished());
+
+        let mut empty = Animation::new(Vec::new(), Duration::from_millis(100), false);
+        assert_eq!(empty.update(Duration::from_secs(1)), 0);
+        assert!(empty.is_finished());
+        assert!(!Animation::new(Vec::new(), Duration::ZERO, true).is_finished());
+    }
+
+    #[test]
+    fn test_event_hooks_see_spawn_and_kill() {
+        use crate::entities::Bubble;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut manager = EntityManager::new();
+        let sink = Rc::clone(&seen);
+        manager.on_event(move |event| sink.borrow_mut().push(*event));
+
+        let id = manager
+            .spawn(|id| Bubble::new(id, Position::new(5.0, 15.0, 10), &mut RngHandle::default()));
+        manager.remove_entity(id);
+
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                AquariumEvent::Spawned {
+                    id,
+                    entity_type: "bubble"
+                },
+                AquariumEvent::Killed {
+                    id,
+                    entity_type: "bubble"
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_large_creature_slot_is_refilled() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let bounds = Rect::new(0, 0, 80, 24);
+        let entered = Rc::new(RefCell::new(Vec::new()));
+        let mut manager = EntityManager::new();
+        let sink = Rc::clone(&entered);
+        manager.on_event(move |event| {
+            if let AquariumEvent::LargeCreatureEntered { id, .. } = event {
+                sink.borrow_mut().push(*id);
+            }
+        });
+
+        crate::spawning::add_whale(&mut manager, bounds);
+        let whale = entered.borrow()[0];
+        manager.handle_entity_death(whale, bounds);
+
+        assert!(manager.large_creatures_full());
+        assert_eq!(entered.borrow().len(), 2);
+        assert!(manager.get_entity(whale).is_none());
+    }
+
+    #[test]
+    fn test_summoned_creature_replaces_the_current_one() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut manager = EntityManager::new();
+        crate::spawning::add_whale(&mut manager, bounds);
+        crate::spawning::add_hook(&mut manager, bounds);
+        assert!(manager.get_entities_of_kind(EntityKind::Hook).is_empty());
+
+        manager.make_room_for_large();
+        crate::spawning::add_hook(&mut manager, bounds);
+        assert!(manager.get_entities_of_kind(EntityKind::Whale).is_empty());
+        let hook = manager.get_entities_of_kind(EntityKind::Hook)[0].id();
+        let line = manager.get_entities_of_kind(EntityKind::FishingLine)[0].id();
+        assert_eq!(manager.attachment(line).map(|a| a.parent), Some(hook));
+        assert_eq!(manager.large_creature_ids, vec![hook]);
+    }
+
+    #[test]
+    fn test_trim_skips_large_creatures_already_gone() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut manager = EntityManager::new();
+        crate::spawning::add_whale(&mut manager, bounds);
+        manager.set_large_creature(999);
+        manager.set_large_creature(1000);
+
+        manager.trim_large_creatures(bounds);
+        assert_eq!(manager.large_creature_ids.len(), 1);
+    }
+
+    #[test]
+    fn test_removing_a_large_creature_frees_its_slot() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut manager = EntityManager::new();
+        crate::spawning::add_whale(&mut manager, bounds);
+        let whale = manager.get_entities_of_kind(EntityKind::Whale)[0].id();
+
+        manager.remove_entity(whale);
+        assert!(!manager.has_large_creature());
+    }
+
+    #[test]
+    fn test_dead_fish_replaced_after_respawn_delay() {
+        use crate::entities::Fish;
+
+        let bounds = Rect::new(0, 0, 80, 24);
+        let world = WorldContext::new(bounds);
+        let mut manager = EntityManager::new();
+        manager
+            .scheduler_mut()
+            .policy_mut(Population::Fish)
+            .respawn_delay = Duration::from_secs(1);
+        crate::spawning::add_all_fish(&mut manager, bounds);
+        let target = manager.scheduler().target(Population::Fish);
+        assert_eq!(manager.population_size(Population::Fish), target);
+
+        // Hold the fish still so none swims off during the test
+        let ids: Vec<EntityId> = manager
+            .entities_of::<Fish>()
+            .map(|fish| fish.id())
+            .collect();
+        for &id in &ids {
+            manager
+                .get_entity_mut(id)
+                .unwrap()
+                .set_velocity(Velocity::zero());
+        }
+        manager.handle_entity_death(ids[0], bounds);
+        assert_eq!(manager.population_size(Population::Fish), target - 1);
+
+        manager.update_all(Duration::from_millis(500), &world);
+        assert_eq!(manager.scheduler().booked(Population::Fish), 1);
+        manager.update_all(Duration::from_millis(500), &world);
+        assert_eq!(manager.scheduler().booked(Population::Fish), 0);
+        assert_eq!(manager.population_size(Population::Fish), target);
+    }
+
+    #[test]
+    fn test_time_scale() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let world = WorldContext::new(bounds);
+        let mut manager = EntityManager::new();
+        manager.set_time_scale(9.0);
+        assert_eq!(manager.time_scale(), crate::timestep::MAX_TIME_SCALE);
+
+        manager
+            .scheduler_mut()
+            .policy_mut(Population::Fish)
+            .respawn_delay = Duration::from_secs(1);
+        manager.scheduler_mut().book(Population::Fish);
+        manager.scheduler_mut().set_target(Population::Fish, 1);
+
+        // Frozen: no time passes however long we wait
+        manager.set_time_scale(0.0);
+        manager.update_all(Duration::from_secs(10), &world);
+        assert_eq!(manager.scheduler().booked(Population::Fish), 1);
+
+        // Double speed: the second's delay is over in half a second
+        manager.set_time_scale(2.0);
+        manager.update_all(Duration::from_millis(500), &world);
+        assert_eq!(manager.scheduler().booked(Population::Fish), 0);
+        assert_eq!(manager.population_size(Population::Fish), 1);
+
+        // The speed survives a rebuilt tank
+        assert_eq!(manager.fresh().time_scale(), 2.0);
+    }
+
+    #[test]
+    fn test_respawn_policy_overrides() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut manager = EntityManager::new();
+        manager.set_respawn_policy(
+            EntityKind::Fish,
+            RespawnPolicy::SpawnSplat { respawn: true },
+        );
+        manager.set_respawn_policy(EntityKind::Whale, RespawnPolicy::None);
+        crate::spawning::add_all_fish(&mut manager, bounds);
+        crate::spawning::add_whale(&mut manager, bounds);
+        let fish_before = manager.population_size(Population::Fish);
+
+        let fish = manager.get_entities_of_kind(EntityKind::Fish)[0].id();
+        manager.handle_entity_death(fish, bounds);
+        assert_eq!(manager.get_entities_of_kind(EntityKind::Splat).len(), 1);
+        assert_eq!(manager.population_size(Population::Fish), fish_before);
+
+        let whale = manager.get_entities_of_kind(EntityKind::Whale)[0].id();
+        manager.handle_entity_death(whale, bounds);
+        assert!(!manager.has_large_creature());
+
+        manager.clear_respawn_policy(EntityKind::Fish);
+        let fish = manager.get_entities_of_kind(EntityKind::Fish)[0].id();
+        manager.handle_entity_death(fish, bounds);
+        assert_eq!(manager.get_entities_of_kind(EntityKind::Splat).len(), 1);
+    }
+
+    /// A still fish and shark teeth on its first visible cell, both mid-cell
+    /// so the current can't nudge them apart
+    fn fish_and_teeth(manager: &mut EntityManager) -> (EntityId, EntityId) {
+        use crate::entities::{Fish, FishSpecies, SharkTeeth};
+
+        let position = Position::new(10.5, 10.5, crate::depth::FISH_START);
+        let fish = manager.spawn(|id| {
+            Fish::new(
+                id,
+                position,
+                Velocity::zero(),
+                Direction::Right,
+                FishSpecies::NewSmall1,
+                &mut RngHandle::default(),
+            )
+        });
+        let (x, y) = manager
+            .get_entity(fish)
+            .unwrap()
+            .get_current_sprite()
+            .get_non_transparent_positions()
+            .into_iter()
+            .min()
+            .unwrap();
+        let bite = Position::new(10.5 + x as f32, 10.5 + y as f32, crate::depth::SHARK);
+        let teeth = manager.spawn(|id| SharkTeeth::new(id, bite, Velocity::zero(), 0));
+        (fish, teeth)
+    }
+
+    #[test]
+    fn test_teeth_bite_fish() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let world = WorldContext::new(Rect::new(0, 0, 80, 24));
+        let collisions = Rc::new(RefCell::new(Vec::new()));
+        let mut manager = EntityManager::new();
+        let sink = Rc::clone(&collisions);
+        manager.on_event(move |event| {
+            if let AquariumEvent::Collision {
+                first,
+                second,
+                kind,
+            } = event
+            {
+                sink.borrow_mut().push((*first, *second, *kind));
+            }
+        });
+
+        let (fish, teeth) = fish_and_teeth(&mut manager);
+        manager.update_all(Duration::from_millis(10), &world);
+        manager.update_all(Duration::from_millis(10), &world);
+
+        // Reported once, from the teeth; the fish is gone, leaving a splat
+        assert_eq!(
+            *collisions.borrow(),
+            vec![(teeth, fish, collision::ContactKind::Bite)]
+        );
+        assert!(manager.get_entity(fish).is_none());
+        assert_eq!(manager.get_entities_of_kind(EntityKind::Splat).len(), 1);
+    }
+
+    #[test]
+    fn test_ambient_events_spawn_entities() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let world = WorldContext::new(Rect::new(0, 0, 120, 40));
+        let mut manager = EntityManager::new();
+        assert_eq!(manager.ambient_frequency(), 0.0);
+        manager.set_ambient_frequency(6000.0);
+
+        let seen = Rc::new(RefCell::new(0));
+        let sink = Rc::clone(&seen);
+        manager.on_event(move |event| {
+            if matches!(event, AquariumEvent::Ambient { .. }) {
+                *sink.borrow_mut() += 1;
+            }
+        });
+
+        manager.update_all(Duration::from_secs(1), &world);
+        assert_eq!(*seen.borrow(), 1);
+        assert!(manager.entity_count() > 0);
+
+        // Disabled again, and the setting survives a rebuild
+        manager.set_ambient_frequency(0.0);
+        assert_eq!(manager.fresh().ambient_frequency(), 0.0);
+    }
+
+    #[test]
+    fn test_depth_changes_move_layers() {
+        use crate::entities::{Fish, FishSpecies, Shark};
+        let world = WorldContext::new(Rect::new(0, 0, 200, 40));
+        let mut manager = EntityManager::new();
+        let fish = manager.spawn(|id| {
+            Fish::new(
+                id,
+                Position::new(50.0, 20.0, crate::depth::FISH_START),
+                Velocity::zero(),
+                Direction::Right,
+                FishSpecies::OldTiny,
+                &mut RngHandle::default(),
+            )
+        });
+        manager.spawn(|id| {
+            Shark::new(
+                id,
+                Position::new(100.0, 16.0, crate::depth::SHARK),
+                Velocity::zero(),
+                Direction::Left,
+            )
+        });
+
+        manager.update_all(Duration::from_millis(16), &world);
+        manager.check_invariants().unwrap();
+        assert!(manager.entities.layer(crate::depth::HIDING).contains(&fish));
+
+        manager.remove_entity(fish);
+        manager.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn test_parallel_update_repeats_with_seed() {
+        use crate::entities::Fish;
+
+        let world = WorldContext::new(Rect::new(0, 0, 300, 100));
+        let run = || {
+            let mut manager = EntityManager::new();
+            manager.seed(3);
+            let mut rng = manager.rng();
+            for _ in 0..PARALLEL_UPDATE_MIN + UPDATE_CHUNK / 2 {
+                manager.spawn(|id| Fish::new_random(id, world.bounds(), false, &mut rng));
+            }
+            for _ in 0..30 {
+                manager.update_all(crate::timestep::STEP, &world);
+            }
+            manager.check_invariants().unwrap();
+            let mut fish: Vec<_> = manager
+                .entities()
+                .map(|entity| (entity.id(), entity.position()))
+                .collect();
+            fish.sort_by_key(|&(id, _)| id);
+            fish
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn test_seed_leaves_the_water_alone() {
+        let mut manager = EntityManager::new();
+        manager
+            .environment_mut()
+            .set_current(Velocity::new(0.2, 0.0));
+        manager.seed(1);
+        assert_eq!(manager.environment().current(), Velocity::new(0.2, 0.0));
+    }
+
+    #[test]
+    fn test_managers_on_one_thread_keep_their_own_seed() {
+        let world = WorldContext::new(Rect::new(0, 0, 120, 40));
+        let seeded = |seed| {
+            let mut manager = EntityManager::new();
+            manager.seed(seed);
+            crate::spawning::initialize_aquarium(&mut manager, world.bounds());
+            manager
+        };
+        let positions = |manager: &EntityManager| {
+            let mut positions: Vec<_> = manager
+                .entities()
+                .map(|entity| (entity.id(), entity.position()))
+                .collect();
+            positions.sort_by_key(|&(id, _)| id);
+            positions
+        };
+
+        // Stepped in turn, a differently seeded tank doesn't disturb the
+        // draws of the other two
+        let (mut first, mut other, mut second) = (seeded(5), seeded(6), seeded(5));
+        for _ in 0..60 {
+            first.update_all(crate::timestep::STEP, &world);
+            other.update_all(crate::timestep::STEP, &world);
+            second.update_all(crate::timestep::STEP, &world);
+        }
+        assert_eq!(positions(&first), positions(&second));
+        assert_ne!(positions(&first), positions(&other));
+    }
+
+    #[test]
+    fn test_layer_order_is_stable() {
+        use crate::entities::{Fish, FishSpecies};
+        let mut manager = EntityManager::new();
+        let depth = crate::depth::FISH_START;
+        let ids: Vec<EntityId> = (0..3)
+            .map(|i| {
+                manager.spawn(|id| {
+                    Fish::new(
+                        id,
+                        Position::new(10.0 * i as f32, 10.0, depth),
+                        Velocity::zero(),
+                        Direction::Right,
+                        FishSpecies::OldTiny,
+                        &mut RngHandle::default(),
+                    )
+                })
+            })
+            .collect();
+        assert_eq!(manager.entities.layer(depth), ids);
+
+        // Leaving the layer and coming back restores the same stacking
+        let middle = manager.get_entity_mut(ids[1]).unwrap();
+        let mut position = middle.position();
+        position.depth = depth + 1;
+        middle.set_position(position);
+        manager.entities.restack();
+        position.depth = depth;
+        manager
+            .get_entity_mut(ids[1])
+            .unwrap()
+            .set_position(position);
+        manager.entities.restack();
+        assert_eq!(manager.entities.layer(depth), ids);
+        manager.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn test_occupancy_query() {
+        use crate::entities::Castle;
+        let mut manager = EntityManager::new();
+        let castle = manager.spawn(|id| Castle::new_at_position(id, 10.0, 10.0));
+
+        assert_eq!(
+            manager.occupants(Position::new(12.0, 12.0, 0), (3, 1)),
+            vec![castle]
+        );
+        assert!(manager.is_area_free(Position::new(0.0, 10.0, 0), (5, 3)));
+        assert!(!manager.is_area_free(Position::new(8.0, 10.0, 0), (5, 3)));
+    }
+
+    #[test]
+    fn test_spawn_clear_skips_taken_spots() {
+        use crate::entities::{Castle, Glint};
+        let mut manager = EntityManager::new();
+        manager.spawn(|id| Castle::new_at_position(id, 10.0, 10.0));
+
+        let mut spots = [(12.0, 12.0), (0.0, 0.0)].into_iter();
+        let glint = manager.spawn_clear(5, |id| {
+            let (x, y) = spots.next().unwrap_or((12.0, 12.0));
+            Glint::new(id, x, y)
+        });
+        assert_eq!(manager.get_entity(glint).unwrap().position().x, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "id other than")]
+    fn test_spawn_rejects_made_up_ids() {
+        let mut manager = EntityManager::new();
+        manager.spawn(|_| crate::entities::Glint::new(42, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_tide_moves_surface_entities() {
+        use crate::entities::WaterSurface;
+        let world = WorldContext::new(Rect::new(0, 0, 80, 24));
+        let mut manager = EntityManager::new();
+        manager.spawn(|id| WaterSurface::new(id, 0, 80));
+
+        // Run the clock until the tide has turned a little
+        while manager.environment().tide_offset() == 0 {
+            manager.update_all(Duration::from_secs(5), &world);
+        }
+        let offset = manager.environment().tide_offset() as f32;
+        // Newly added surface entities start at the current tide too
+        manager.spawn(|id| WaterSurface::new(id, 0, 80));
+        for water in manager.get_entities_of_kind(EntityKind::WaterSurface) {
+            assert_eq!(water.position().y, 5.0 + offset);
+        }
+    }
+
     #[test]
     fn test_sprite_color_mapping() {
         let sprite = Sprite::from_ascii_art("X", Some("r"));
@@ -628,4 +3113,356 @@ mod tests {
         let color = sprite.get_color_at(0, 0);
         assert_eq!(color, Some(Color::Red)); // Fallback mapping
     }
+
+    #[test]
+    fn test_wide_characters_take_two_columns() {
+        let sprite = Sprite::from_ascii_art("a🐟b", Some("rgb"));
+        assert_eq!(sprite.get_bounding_box(), (4, 1));
+        let cells: Vec<_> = sprite.cells(0).collect();
+        assert_eq!(cells, vec![(0, 0, "a"), (1, 1, "🐟"), (3, 2, "b")]);
+        assert!(sprite.get_non_transparent_positions().contains(&(2, 0)));
+
+        // The mask still goes by character
+        assert_eq!(sprite.get_color_at(2, 0), Some(Color::Blue));
+    }
+
+    #[test]
+    fn test_graphemes_are_single_glyphs() {
+        // A combining accent and a joined family emoji each take one mask
+        // character and stay in one cell (two for the emoji)
+        let sprite = Sprite::from_ascii_art("e\u{301}👨\u{200d}👩\u{200d}👧>", Some("rgb"));
+        let cells: Vec<_> = sprite
+            .cells(0)
+            .map(|(column, index, _)| (column, index))
+            .collect();
+        assert_eq!(cells, vec![(0, 0), (1, 1), (3, 2)]);
+        assert_eq!(sprite.get_bounding_box(), (4, 1));
+        assert_eq!(sprite.get_char_at(2, 0), '>');
+        assert_eq!(sprite.get_color_at(2, 0), Some(Color::Blue));
+    }
+
+    #[test]
+    fn test_opaque_cell_bitmap() {
+        // Wider than one bitmap word, with a wide glyph and a gap
+        let art = format!("{}🐟 x\n  #", "-".repeat(64));
+        let sprite = Sprite::from_ascii_art(&art, None);
+        assert_eq!(sprite.get_bounding_box(), (68, 2));
+        assert!(sprite.is_opaque(63, 0));
+        assert!(sprite.is_opaque(64, 0) && sprite.is_opaque(65, 0));
+        assert!(!sprite.is_opaque(66, 0));
+        assert!(sprite.is_opaque(67, 0));
+        assert!(!sprite.is_opaque(0, 1) && !sprite.is_opaque(68, 0));
+        assert!(sprite.is_transparent_at(0, 1));
+        assert!(!sprite.is_transparent_at(2, 1));
+        assert_eq!(sprite.opaque_cells().count(), 64 + 2 + 1 + 1);
+        assert_eq!(sprite.mirrored().opaque_cells().count(), 68);
+    }
+
+    #[test]
+    fn test_narrow_glyph_over_wide_one_blanks_it() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 1));
+        buffer[(0, 0)].set_symbol("🐟");
+
+        let glint = crate::entities::Glint::new(1, 1.0, 0.0);
+        glint.render(
+            &mut buffer,
+            Rect::new(0, 0, 4, 1),
+            &RenderOptions::default(),
+        );
+        assert_eq!(buffer[(0, 0)].symbol(), " ");
+        assert_eq!(buffer[(1, 0)].symbol(), ".");
+    }
+
+    #[test]
+    fn test_background_mask_fills_transparent_cells() {
+        let sprite = Sprite::from_ascii_art("( )", Some("r r")).with_background_mask(" w");
+        assert_eq!(sprite.get_background_at(0, 0), None);
+        assert_eq!(sprite.get_background_at(1, 0), Some(Color::White));
+        assert_eq!(sprite.get_color_at(0, 0), Some(Color::Red));
+    }
+
+    #[test]
+    fn test_attribute_mask() {
+        let sprite = Sprite::from_ascii_art("o>x", Some("www")).with_attribute_mask("bd");
+        assert_eq!(sprite.get_modifiers_at(0, 0), Modifier::BOLD);
+        assert_eq!(sprite.get_modifiers_at(1, 0), Modifier::DIM);
+        assert_eq!(sprite.get_modifiers_at(2, 0), Modifier::empty());
+
+        // Glints blink
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 1, 1));
+        let glint = crate::entities::Glint::new(1, 0.0, 0.0);
+        glint.render(
+            &mut buffer,
+            Rect::new(0, 0, 1, 1),
+            &RenderOptions::default(),
+        );
+        assert!(buffer[(0, 0)].modifier.contains(Modifier::SLOW_BLINK));
+    }
+
+    #[test]
+    fn test_spawn_allocates_ids() {
+        use crate::entities::{Glint, Ship};
+
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut manager = EntityManager::new();
+        let glint = manager.spawn(|id| Glint::new(id, 2.0, 1.0));
+        let ship = manager.spawn_large(|id| Ship::new(id, bounds, &mut RngHandle::default()));
+        assert_ne!(glint, ship);
+        assert_eq!(manager.get_entity(glint).unwrap().id(), glint);
+        assert_eq!(manager.get_entity(ship).unwrap().id(), ship);
+        assert!(manager.large_creatures_full());
+
+        // Ids are never handed out twice, even after removals
+        manager.remove_entity(ship);
+        assert!(manager.spawn(|id| Glint::new(id, 2.0, 1.0)) > ship);
+    }
+
+    #[test]
+    fn test_bubbles_are_recycled() {
+        use crate::entities::Bubble;
+
+        let world = WorldContext::new(Rect::new(0, 0, 80, 24));
+        let mut manager = EntityManager::new();
+        // Bubbles that reach the surface pop and go back to the pool
+        for x in [5.0, 10.0, 15.0] {
+            manager.spawn_recycled::<Bubble>(Position::new(x, 10.0, 5));
+        }
+        for _ in 0..600 {
+            manager.update_all(crate::timestep::STEP, &world);
+        }
+        assert!(manager.get_entities_of_kind(EntityKind::Bubble).is_empty());
+
+        let (created, restarted) = manager.pool().counts();
+        let bubble = manager.spawn_recycled::<Bubble>(Position::new(20.0, 12.0, 5));
+        assert_eq!(manager.pool().counts(), (created, restarted + 1));
+        let bubble = manager.get_entity(bubble).unwrap();
+        assert_eq!(bubble.position(), Position::new(20.0, 12.0, 5));
+        assert!(bubble.is_alive());
+    }
+
+    #[test]
+    fn test_splash_drawn_at_two_ticks_per_frame() {
+        use crate::entities::Bubble;
+
+        let world = WorldContext::new(Rect::new(0, 0, 80, 24));
+        let mut manager = EntityManager::new();
+        manager.spawn_recycled::<Bubble>(Position::new(20.0, 12.0, 5));
+
+        let mut drawn = false;
+        for _ in 0..120 {
+            for _ in 0..2 {
+                manager.update_all(crate::timestep::STEP, &world);
+            }
+            drawn |= !manager.get_entities_of_kind(EntityKind::Splash).is_empty();
+        }
+        assert!(drawn);
+    }
+
+    #[test]
+    fn test_layer_counts_front_to_back() {
+        let mut manager = EntityManager::new();
+        for x in [0.0, 5.0] {
+            manager.spawn(|id| crate::entities::Glint::new(id, x, 1.0));
+        }
+        manager.spawn(|id| crate::entities::Castle::new_at_position(id, 0.0, 0.0));
+
+        assert_eq!(
+            manager.layer_counts(),
+            vec![(crate::depth::CASTLE - 1, 2), (crate::depth::CASTLE, 1)]
+        );
+    }
+
+    #[test]
+    fn test_check_collisions() {
+        use crate::collision::ContactKind;
+        use crate::entities::Glint;
+
+        let mut manager = EntityManager::new();
+        let (fish, teeth) = fish_and_teeth(&mut manager);
+        // Overlapping, but on no layer anything looks for
+        manager.spawn(|id| Glint::new(id, 10.0, 10.0));
+        manager.spawn(|id| Glint::new(id, 10.0, 10.0));
+
+        assert_eq!(
+            manager.check_collisions(),
+            vec![Contact {
+                kind: ContactKind::Bite,
+                source: teeth,
+                target: fish,
+            }]
+        );
+
+        // The index follows the fish out of reach
+        let fish = manager.get_entity_mut(fish).unwrap();
+        let depth = fish.position().depth;
+        fish.set_position(Position::new(60.0, 20.0, depth));
+        assert!(manager.check_collisions().is_empty());
+    }
+
+    #[test]
+    fn test_kind_names_round_trip() {
+        for kind in [
+            EntityKind::Fish,
+            EntityKind::SeaMonster,
+            EntityKind::Pomodoro,
+        ] {
+            assert_eq!(EntityKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(EntityKind::from_name("eel"), None);
+    }
+
+    #[test]
+    fn test_kinds_and_downcasting() {
+        use crate::entities::{BigFish, BigFishVariant, Castle, Glint};
+
+        let mut manager = EntityManager::new();
+        let bounds = Rect::new(0, 0, 80, 24);
+        let glint = manager.spawn(|id| Glint::new(id, 2.0, 1.0));
+        let big = manager.spawn(|id| {
+            BigFish::new_variant(
+                id,
+                bounds,
+                BigFishVariant::Variant2,
+                WATERLINE_Y,
+                &mut RngHandle::default(),
+            )
+        });
+
+        assert_eq!(manager.get_entities_of_kind(EntityKind::BigFish).len(), 1);
+        assert_eq!(manager.get_entity(big).unwrap().entity_type(), "big_fish_2");
+        assert!(manager.get_as::<Glint>(glint).is_some());
+        assert!(manager.get_as::<Castle>(glint).is_none());
+        assert!(manager.get_as_mut::<BigFish>(big).is_some());
+        assert_eq!(manager.entities_of::<Glint>().count(), 1);
+    }
+
+    #[test]
+    fn test_attached_children_follow_and_leave_with_parent() {
+        use crate::entities::Glint;
+
+        let world = WorldContext::new(Rect::new(0, 0, 80, 24));
+        let mut manager = EntityManager::new();
+        let parent = manager.spawn(|id| Glint::new(id, 10.0, 5.0));
+        let child = manager.spawn(|id| Glint::new(id, 0.0, 0.0));
+        let grandchild = manager.spawn(|id| Glint::new(id, 0.0, 0.0));
+        manager.attach(child, parent, 3.0, 1.0);
+        manager.attach(grandchild, child, 1.0, 0.0);
+        // Cycles are refused
+        manager.attach(parent, grandchild, 0.0, 0.0);
+        assert_eq!(manager.attachment(parent), None);
+
+        let mut position = manager.get_entity(parent).unwrap().position();
+        position.x = 20.0;
+        manager
+            .get_entity_mut(parent)
+            .unwrap()
+            .set_position(position);
+        manager.update_all(Duration::from_millis(10), &world);
+        let child_position = manager.get_entity(child).unwrap().position();
+        assert_eq!((child_position.x, child_position.y), (23.0, 6.0));
+        assert_eq!(manager.get_entity(grandchild).unwrap().position().x, 24.0);
+        assert_eq!(manager.children_of(parent), vec![child]);
+
+        manager.remove_entity(parent);
+        assert_eq!(manager.entity_count(), 0);
+        assert!(manager.attachment(child).is_none());
+    }
+
+    #[test]
+    fn test_region_queries() {
+        use crate::entities::{Bubble, Glint};
+
+        let mut manager = EntityManager::new();
+        let near = manager.spawn(|id| Glint::new(id, 10.0, 5.0));
+        let far = manager.spawn(|id| Glint::new(id, 70.0, 20.0));
+        let bubble = manager
+            .spawn(|id| Bubble::new(id, Position::new(40.0, 20.0, 5), &mut RngHandle::default()));
+
+        assert_eq!(
+            manager.entities_in_rect(Rect::new(0, 0, 20, 10)),
+            vec![near]
+        );
+        assert_eq!(
+            manager.entities_in_rect(Rect::new(40, 20, 40, 4)),
+            vec![far, bubble]
+        );
+        let origin = Position::new(10.5, 5.5, 0);
+        assert_eq!(manager.entities_within(origin, 5.0), vec![near]);
+        assert_eq!(
+            manager.entities_within(origin, 100.0),
+            vec![near, bubble, far]
+        );
+        assert_eq!(
+            manager.nearest_of_kind(EntityKind::Glint, Position::new(60.0, 20.0, 0)),
+            Some(far)
+        );
+        assert_eq!(
+            manager.nearest_of_kind(EntityKind::Bubble, origin),
+            Some(bubble)
+        );
+        assert_eq!(manager.nearest_of_kind(EntityKind::Whale, origin), None);
+
+        // The index follows entities that move
+        let mut position = manager.get_entity(near).unwrap().position();
+        position.x = 75.0;
+        manager.get_entity_mut(near).unwrap().set_position(position);
+        assert!(manager.entities_in_rect(Rect::new(0, 0, 20, 10)).is_empty());
+    }
+
+    #[test]
+    fn test_glowing_cells() {
+        let mut manager = EntityManager::new();
+        manager.spawn(|id| crate::entities::Glint::new(id, 2.0, 1.0));
+        manager.spawn(|id| crate::entities::Glint::new(id, -5.0, 1.0));
+
+        let cells = manager.glowing_cells(Rect::new(0, 0, 10, 5));
+        assert_eq!(cells, HashSet::from([(2, 1)]));
+    }
+
+    #[test]
+    fn test_uppercase_mask_letters_are_bold() {
+        let sprite = Sprite::from_ascii_art("abc", Some("rR "));
+        assert!(!sprite.is_bold_at(0, 0));
+        assert!(sprite.is_bold_at(1, 0));
+        assert!(!sprite.is_bold_at(2, 0));
+        assert!(!sprite.is_bold_at(5, 5));
+        assert_eq!(sprite.get_color_at(0, 0), sprite.get_color_at(1, 0));
+
+        // Rendering carries the bold through (glints use an uppercase 'Y')
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 1));
+        let glint = crate::entities::Glint::new(1, 0.0, 0.0);
+        glint.render(
+            &mut buffer,
+            Rect::new(0, 0, 3, 1),
+            &RenderOptions::default(),
+        );
+        assert_eq!(buffer[(0, 0)].fg, Color::Yellow);
+        assert!(buffer[(0, 0)].modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn test_random_colors_follow_original_quirk() {
+        // int(rand($#colors)) never picks the last colour, 'M'
+        for _ in 0..50 {
+            let sprite = Sprite::from_ascii_art_with_ansi_colors(
+                "123456789",
+                Some("123456789"),
+                &mut RngHandle::default(),
+            );
+            assert!(!sprite.color_mask.unwrap()[0].contains('M'));
+        }
+    }
+
+    #[test]
+    fn test_sprite_palette_colors_digits() {
+        let mut palette = [Color::Black; PALETTE_SIZE];
+        palette[0] = Color::Rgb(10, 20, 30);
+        palette[8] = Color::Rgb(90, 80, 70);
+
+        let sprite = Sprite::from_ascii_art_with_palette("XYZ", Some("19W"), palette);
+        assert_eq!(sprite.get_color_at(0, 0), Some(Color::Rgb(10, 20, 30)));
+        assert_eq!(sprite.get_color_at(1, 0), Some(Color::Rgb(90, 80, 70)));
+        // Letters keep their fixed colours
+        assert_eq!(sprite.get_color_at(2, 0), Some(Color::White));
+    }
 }
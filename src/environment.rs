@@ -0,0 +1,239 @@
+//! Global environment state shared by all entities
+//!
+//! The water current is a slowly wandering drift vector. Every few minutes a
+//! new target current is picked and the actual current eases towards it, so
+//! the tank never changes direction abruptly. Entities opt in to being pushed
+//! around via [`Entity::current_response`].
+//!
+//! A storm overrides the wandering current with a strong one that builds
+//! up within seconds, sweeping bubbles and small creatures along, until it
+//! is called off.
+//!
+//! The tide raises and lowers the whole water surface by a couple of rows
+//! over several minutes. Anything that cares where the water starts should
+//! ask [`Environment::waterline`] rather than assume the original row 9.
+//!
+//! [`Entity::current_response`]: crate::entity::Entity::current_response
+
+use crate::entity::Velocity;
+use crate::rng::RngHandle;
+use crate::timestep;
+use rand::Rng;
+use std::time::Duration;
+
+/// Strongest horizontal current (columns per tick)
+pub const MAX_CURRENT_DX: f32 = 0.3;
+
+/// Strongest vertical current (rows per tick)
+pub const MAX_CURRENT_DY: f32 = 0.05;
+
+/// Seconds between picking new target currents (the range is randomised)
+const CURRENT_CHANGE_SECS: std::ops::Range<f32> = 90.0..240.0;
+
+/// Fraction of the remaining difference closed per second when easing
+const CURRENT_EASING_PER_SEC: f32 = 0.02;
+
+/// Horizontal current during a storm (columns per tick)
+pub const STORM_CURRENT_DX: f32 = 1.0;
+
+/// How much faster the current changes in a storm than in calm water
+const STORM_EASING_PER_SEC: f32 = 0.5;
+
+/// First row of open water below the surface at mid tide (original: 9)
+pub const WATERLINE_Y: f32 = 9.0;
+
+/// Length of one full tide cycle
+const TIDE_PERIOD_SECS: f32 = 480.0;
+
+/// How far the tide moves the surface either way (rounds to 1-2 rows)
+const TIDE_AMPLITUDE: f32 = 1.5;
+
+/// Environmental state that evolves over time
+#[derive(Debug, Clone)]
+pub struct Environment {
+    current: Velocity,
+    target_current: Velocity,
+    time_until_change: f32,
+    tide_phase: f32,
+    storm: bool,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Environment {
+    /// Start with still water; the first update picks where the current
+    /// heads
+    pub fn new() -> Self {
+        Self {
+            current: Velocity::zero(),
+            target_current: Velocity::zero(),
+            time_until_change: 0.0,
+            tide_phase: 0.0,
+            storm: false,
+        }
+    }
+
+    /// Current water drift applied to entities
+    pub fn current(&self) -> Velocity {
+        self.current
+    }
+
+    /// Where the current is heading
+    pub fn target_current(&self) -> Velocity {
+        self.target_current
+    }
+
+    /// Force a specific current (mostly for tests and tooling)
+    pub fn set_current(&mut self, current: Velocity) {
+        self.current = current;
+        self.target_current = current;
+    }
+
+    /// Whether a storm is blowing
+    pub fn is_storm(&self) -> bool {
+        self.storm
+    }
+
+    /// Start or call off a storm; the current builds up or dies down
+    /// towards the calm target over the next few seconds
+    pub fn set_storm(&mut self, storm: bool) {
+        self.storm = storm;
+    }
+
+    /// Rows the tide currently moves the surface by (positive = lower)
+    pub fn tide_offset(&self) -> i16 {
+        let angle = self.tide_phase / TIDE_PERIOD_SECS * std::f32::consts::TAU;
+        (angle.sin() * TIDE_AMPLITUDE).round() as i16
+    }
+
+    /// First row of open water below the surface, including the tide
+    pub fn waterline(&self) -> f32 {
+        WATERLINE_Y + self.tide_offset() as f32
+    }
+
+    /// Advance the environment by `delta_time`
+    ///
+    /// Returns the new target current when one was picked this update.
+    pub fn update(&mut self, delta_time: Duration, rng: &mut RngHandle) -> Option<Velocity> {
+        let dt = delta_time.as_secs_f32();
+        self.tide_phase = (self.tide_phase + dt) % TIDE_PERIOD_SECS;
+
+        self.time_until_change -= dt;
+        let new_target = if self.time_until_change <= 0.0 {
+            self.pick_target(rng);
+            Some(self.target_current)
+        } else {
+            None
+        };
+
+        // A storm blows the way the calm current was heading
+        let (target, easing) = if self.storm {
+            let dx = STORM_CURRENT_DX.copysign(self.target_current.dx);
+            (Velocity::new(dx, 0.0), STORM_EASING_PER_SEC)
+        } else {
+            (self.target_current, CURRENT_EASING_PER_SEC)
+        };
+        let blend = (easing * dt).min(1.0);
+        self.current.dx += (target.dx - self.current.dx) * blend;
+        self.current.dy += (target.dy - self.current.dy) * blend;
+
+        new_target
+    }
+
+    /// Drift (columns, rows) for an entity with the given response this frame
+    pub fn drift(&self, response: f32, delta_time: Duration) -> (f32, f32) {
+        let scale = response * timestep::ticks(delta_time);
+        (self.current.dx * scale, self.current.dy * scale)
+    }
+
+    fn pick_target(&mut self, rng: &mut RngHandle) {
+        self.target_current = Velocity::new(
+            rng.gen_range(-MAX_CURRENT_DX..=MAX_CURRENT_DX),
+            rng.gen_range(-MAX_CURRENT_DY..=MAX_CURRENT_DY),
+        );
+        self.time_until_change = rng.gen_range(CURRENT_CHANGE_SECS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_environment_starts_still() {
+        let mut environment = Environment::new();
+        assert_eq!(environment.current(), Velocity::zero());
+
+        let target = environment.update(Duration::ZERO, &mut RngHandle::default());
+        assert_eq!(target, Some(environment.target_current()));
+        assert!(environment.target_current().dx.abs() <= MAX_CURRENT_DX);
+        assert!(environment.target_current().dy.abs() <= MAX_CURRENT_DY);
+    }
+
+    #[test]
+    fn test_current_eases_slowly() {
+        let mut rng = RngHandle::default();
+        let mut environment = Environment::new();
+        environment.target_current = Velocity::new(MAX_CURRENT_DX, 0.0);
+        environment.time_until_change = f32::INFINITY;
+
+        environment.update(Duration::from_secs(1), &mut rng);
+        let after_one_second = environment.current().dx;
+        assert!(after_one_second > 0.0);
+        assert!(after_one_second < MAX_CURRENT_DX * 0.1);
+    }
+
+    #[test]
+    fn test_storm_builds_quickly_and_passes() {
+        let mut rng = RngHandle::default();
+        let mut environment = Environment::new();
+        environment.target_current = Velocity::new(-0.1, 0.0);
+        environment.time_until_change = f32::INFINITY;
+
+        environment.set_storm(true);
+        for _ in 0..10 {
+            environment.update(Duration::from_secs(1), &mut rng);
+        }
+        assert!(environment.current().dx < -STORM_CURRENT_DX * 0.9);
+
+        environment.set_storm(false);
+        for _ in 0..30 {
+            environment.update(Duration::from_secs(10), &mut rng);
+        }
+        assert!((environment.current().dx + 0.1).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_tide_moves_waterline() {
+        let mut rng = RngHandle::default();
+        let mut environment = Environment::new();
+        assert_eq!(environment.tide_offset(), 0);
+        assert_eq!(environment.waterline(), WATERLINE_Y);
+
+        let mut offsets = Vec::new();
+        for _ in 0..48 {
+            environment.update(Duration::from_secs(10), &mut rng);
+            offsets.push(environment.tide_offset());
+        }
+        assert_eq!(offsets.iter().max(), Some(&2));
+        assert_eq!(offsets.iter().min(), Some(&-2));
+        // Slow: never more than a row per ten seconds
+        assert!(offsets.windows(2).all(|w| (w[0] - w[1]).abs() <= 1));
+    }
+
+    #[test]
+    fn test_drift_scales_with_response() {
+        let mut environment = Environment::new();
+        environment.set_current(Velocity::new(0.2, 0.0));
+
+        let full = environment.drift(1.0, Duration::from_secs(1));
+        let slight = environment.drift(0.2, Duration::from_secs(1));
+        assert!((full.0 - 12.0).abs() < 1e-4);
+        assert!((slight.0 - 2.4).abs() < 1e-4);
+        assert_eq!(environment.drift(0.0, Duration::from_secs(1)), (0.0, 0.0));
+    }
+}
@@ -1,13 +1,74 @@
+use crate::control::ControlRequest;
+use crate::mouse::{MouseAction, MouseTracker};
 use color_eyre::eyre::WrapErr;
 use ratatui::crossterm::event::{self, Event as CrosstermEvent};
 use std::{
-    sync::mpsc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc,
+    },
     thread,
     time::{Duration, Instant},
 };
 
 /// The frequency at which tick events are emitted.
-const TICK_FPS: f64 = 30.0;
+pub const TICK_FPS: f64 = 30.0;
+
+/// How long before a tick is due the event thread stops sleeping and yields instead, since sleeps
+/// can overshoot by a scheduler time slice.
+const SPIN_MARGIN: Duration = Duration::from_millis(1);
+
+/// Schedules ticks on a fixed grid of deadlines.
+///
+/// A tick that comes late doesn't push back the ones after it, so the average rate stays exact
+/// under load. Ticks missed entirely, when a whole interval or more went by (a slow draw, a
+/// suspended terminal), are skipped and counted rather than sent in a burst.
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+    interval: Duration,
+    next: Instant,
+    dropped: u64,
+}
+
+impl FramePacer {
+    /// Constructs a pacer whose first tick is due one `interval` after `now`.
+    pub fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            next: now + interval,
+            dropped: 0,
+        }
+    }
+
+    /// Change the time between ticks; the next tick comes no later than one new interval away.
+    pub fn set_interval(&mut self, interval: Duration, now: Instant) {
+        if interval != self.interval {
+            self.interval = interval;
+            self.next = self.next.min(now + interval);
+        }
+    }
+
+    /// Time left before the next tick is due.
+    pub fn timeout(&self, now: Instant) -> Duration {
+        self.next.saturating_duration_since(now)
+    }
+
+    /// Whether a tick is due at `now`, scheduling the next one if so.
+    pub fn poll(&mut self, now: Instant) -> bool {
+        if now < self.next {
+            return false;
+        }
+        let missed = (now.duration_since(self.next).as_nanos() / self.interval.as_nanos()) as u32;
+        self.dropped += missed as u64;
+        self.next += self.interval * (missed + 1);
+        true
+    }
+
+    /// Ticks skipped so far because they came too late.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
 
 /// Representation of all possible events.
 #[derive(Clone, Debug)]
@@ -35,6 +96,10 @@ pub enum Event {
 pub enum AppEvent {
     /// Quit the application.
     Quit,
+    /// A command from the control socket, to be answered.
+    Control(ControlRequest),
+    /// A click or drag, made out of the terminal's mouse reports.
+    Mouse(MouseAction),
 }
 
 /// Terminal event handler.
@@ -44,6 +109,10 @@ pub struct EventHandler {
     sender: mpsc::Sender<Event>,
     /// Event receiver channel.
     receiver: mpsc::Receiver<Event>,
+    /// Time between ticks in nanoseconds, shared with the event thread.
+    tick_interval: Arc<AtomicU64>,
+    /// Ticks the event thread skipped, shared with it.
+    dropped_ticks: Arc<AtomicU64>,
 }
 
 impl Default for EventHandler {
@@ -56,9 +125,39 @@ impl EventHandler {
     /// Constructs a new instance of [`EventHandler`] and spawns a new thread to handle events.
     pub fn new() -> Self {
         let (sender, receiver) = mpsc::channel();
-        let actor = EventThread::new(sender.clone());
+        let tick_interval = Arc::new(AtomicU64::new(interval_nanos(TICK_FPS)));
+        let dropped_ticks = Arc::new(AtomicU64::new(0));
+        let actor = EventThread::new(
+            sender.clone(),
+            Arc::clone(&tick_interval),
+            Arc::clone(&dropped_ticks),
+        );
         thread::spawn(|| actor.run());
-        Self { sender, receiver }
+        Self {
+            sender,
+            receiver,
+            tick_interval,
+            dropped_ticks,
+        }
+    }
+
+    /// A sender for queueing events from other threads.
+    pub fn sender(&self) -> mpsc::Sender<Event> {
+        self.sender.clone()
+    }
+
+    /// Ticks skipped so far because the app fell a whole interval or more behind.
+    pub fn dropped_ticks(&self) -> u64 {
+        self.dropped_ticks.load(Ordering::Relaxed)
+    }
+
+    /// Change how many tick events are emitted per second.
+    ///
+    /// A slower rate takes effect after the tick already waited for; a faster one as soon as the
+    /// event thread next wakes up, which any terminal event does.
+    pub fn set_tick_rate(&self, fps: f64) {
+        self.tick_interval
+            .store(interval_nanos(fps), Ordering::Relaxed);
     }
 
     /// Receives an event from the sender.
@@ -74,6 +173,11 @@ impl EventHandler {
         Ok(self.receiver.recv()?)
     }
 
+    /// Receives an event if one is waiting, without blocking.
+    pub fn try_next(&self) -> Option<Event> {
+        self.receiver.try_recv().ok()
+    }
+
     /// Queue an app event to be sent to the event receiver.
     ///
     /// This is useful for sending events to the event handler which will be processed by the next
@@ -85,35 +189,67 @@ impl EventHandler {
     }
 }
 
+fn interval_nanos(fps: f64) -> u64 {
+    Duration::from_secs_f64(1.0 / fps).as_nanos() as u64
+}
+
 /// A thread that handles reading crossterm events and emitting tick events on a regular schedule.
 struct EventThread {
     /// Event sender channel.
     sender: mpsc::Sender<Event>,
+    /// Time between ticks in nanoseconds.
+    tick_interval: Arc<AtomicU64>,
+    /// Ticks skipped so far.
+    dropped_ticks: Arc<AtomicU64>,
 }
 
 impl EventThread {
     /// Constructs a new instance of [`EventThread`].
-    fn new(sender: mpsc::Sender<Event>) -> Self {
-        Self { sender }
+    fn new(
+        sender: mpsc::Sender<Event>,
+        tick_interval: Arc<AtomicU64>,
+        dropped_ticks: Arc<AtomicU64>,
+    ) -> Self {
+        Self {
+            sender,
+            tick_interval,
+            dropped_ticks,
+        }
     }
 
     /// Runs the event thread.
     ///
-    /// This function emits tick events at a fixed rate and polls for crossterm events in between.
+    /// This function emits tick events as the [`FramePacer`] schedules them and polls for
+    /// crossterm events in between, sleeping until shortly before each tick and yielding for the
+    /// rest so ticks aren't late by a whole time slice. Mouse reports are sent on as
+    /// [`AppEvent::Mouse`] once they add up to a click or drag.
     fn run(self) -> color_eyre::Result<()> {
-        let tick_interval = Duration::from_secs_f64(1.0 / TICK_FPS);
-        let mut last_tick = Instant::now();
+        let interval = || Duration::from_nanos(self.tick_interval.load(Ordering::Relaxed));
+        let mut pacer = FramePacer::new(interval(), Instant::now());
+        let mut mouse = MouseTracker::new();
         loop {
-            // emit tick events at a fixed rate
-            let timeout = tick_interval.saturating_sub(last_tick.elapsed());
-            if timeout == Duration::ZERO {
-                last_tick = Instant::now();
+            let now = Instant::now();
+            pacer.set_interval(interval(), now);
+            if pacer.poll(now) {
+                self.dropped_ticks.store(pacer.dropped(), Ordering::Relaxed);
                 self.send(Event::Tick);
+                continue;
+            }
+            let timeout = pacer.timeout(now);
+            if timeout <= SPIN_MARGIN {
+                thread::yield_now();
+                continue;
             }
             // poll for crossterm events, ensuring that we don't block the tick interval
-            if event::poll(timeout).wrap_err("failed to poll for crossterm events")? {
-                let event = event::read().wrap_err("failed to read crossterm event")?;
-                self.send(Event::Crossterm(event));
+            if event::poll(timeout - SPIN_MARGIN).wrap_err("failed to poll for crossterm events")? {
+                match event::read().wrap_err("failed to read crossterm event")? {
+                    CrosstermEvent::Mouse(report) => {
+                        if let Some(action) = mouse.track(&report) {
+                            self.send(Event::App(AppEvent::Mouse(action)));
+                        }
+                    }
+                    event => self.send(Event::Crossterm(event)),
+                }
             }
         }
     }
@@ -125,3 +261,40 @@ impl EventThread {
         let _ = self.sender.send(event);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pacer_keeps_to_its_grid() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(10);
+        let mut pacer = FramePacer::new(interval, start);
+        assert!(!pacer.poll(start + Duration::from_millis(9)));
+
+        // A late tick doesn't delay the next one
+        assert!(pacer.poll(start + Duration::from_millis(14)));
+        assert_eq!(
+            pacer.timeout(start + Duration::from_millis(14)),
+            Duration::from_millis(6)
+        );
+
+        // Falling far behind skips the missed ticks instead of bunching them
+        assert!(pacer.poll(start + Duration::from_millis(45)));
+        assert!(!pacer.poll(start + Duration::from_millis(46)));
+        assert_eq!(pacer.dropped(), 2);
+        assert!(pacer.poll(start + Duration::from_millis(50)));
+
+        // A shorter interval brings the next tick forward
+        pacer.set_interval(
+            Duration::from_millis(500),
+            start + Duration::from_millis(50),
+        );
+        pacer.set_interval(interval, start + Duration::from_millis(55));
+        assert_eq!(
+            pacer.timeout(start + Duration::from_millis(55)),
+            Duration::from_millis(5)
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Aquarium events as newline-delimited JSON
+//!
+//! `--events-json TARGET` writes every [`AquariumEvent`] (spawns, deaths,
+//! collisions such as a shark catching a fish, large creatures arriving,
+//! ambient happenings) as one JSON object per line, for overlays, sound
+//! players or dashboards to follow:
+//!
+//! ```text
+//! {"time":12.034,"event":"spawned","id":41,"type":"shark"}
+//! {"time":12.034,"event":"large_creature_entered","id":41,"type":"shark"}
+//! {"time":14.210,"event":"collision","first":41,"second":17,"kind":"bite"}
+//! ```
+//!
+//! `time` is seconds since the log was opened. `TARGET` is a file path, or
+//! a number for a file descriptor the aquarium inherited (`3` for
+//! `asciiquarium --events-json 3 3>events.log`); stdin, stdout and stderr
+//! are not accepted. Lines are flushed as they are written.
+
+use std::fs::File;
+use std::io::{self, LineWriter, Write};
+use std::path::PathBuf;
+
+use crate::clock::Instant;
+use crate::hooks::AquariumEvent;
+
+/// Where the log goes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Path(PathBuf),
+    Fd(i32),
+}
+
+impl Target {
+    /// Parse an `--events-json` value: all digits for a file descriptor,
+    /// anything else for a path
+    pub fn parse(value: &str) -> Self {
+        match value.parse::<i32>() {
+            Ok(fd) if value.bytes().all(|byte| byte.is_ascii_digit()) => Self::Fd(fd),
+            _ => Self::Path(PathBuf::from(value)),
+        }
+    }
+
+    /// Open for writing, truncating a file
+    ///
+    /// A file descriptor must be open and above 2; the log writes to a
+    /// duplicate, so closing it leaves the inherited one alone.
+    pub fn open(&self) -> io::Result<File> {
+        match self {
+            Self::Path(path) => File::create(path),
+            #[cfg(unix)]
+            Self::Fd(fd) => {
+                use std::os::fd::FromRawFd;
+
+                if *fd <= 2 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "stdin, stdout and stderr can't take the event log",
+                    ));
+                }
+                // SAFETY: fcntl only looks the descriptor up
+                if unsafe { libc::fcntl(*fd, libc::F_GETFD) } == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                // SAFETY: duplicating an open descriptor touches nothing else
+                let copy = unsafe { libc::fcntl(*fd, libc::F_DUPFD_CLOEXEC, 3) };
+                if copy == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                // SAFETY: `copy` was just created and nothing else has it
+                Ok(unsafe { File::from_raw_fd(copy) })
+            }
+            #[cfg(not(unix))]
+            Self::Fd(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "file descriptors are only supported on Unix",
+            )),
+        }
+    }
+}
+
+/// Writes events to `out`, a line each
+pub struct EventLog<W: Write> {
+    out: LineWriter<W>,
+    opened: Instant,
+    /// Set after a write fails (a closed pipe), to stop trying
+    failed: bool,
+}
+
+impl<W: Write> EventLog<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out: LineWriter::new(out),
+            opened: Instant::now(),
+            failed: false,
+        }
+    }
+
+    /// Write `event`; after the first failure nothing more is written
+    pub fn record(&mut self, event: &AquariumEvent) {
+        if self.failed {
+            return;
+        }
+        let time = self.opened.elapsed().as_secs_f64();
+        let line = format!(r#"{{"time":{time:.3},{}}}"#, event.json_fields());
+        self.failed = writeln!(self.out, "{line}").is_err();
+    }
+
+    /// Whether a write has failed and the log stopped
+    pub fn has_failed(&self) -> bool {
+        self.failed
+    }
+
+    /// The writer the log goes to
+    pub fn get_ref(&self) -> &W {
+        self.out.get_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(Target::parse("3"), Target::Fd(3));
+        assert_eq!(
+            Target::parse("events.jsonl"),
+            Target::Path(PathBuf::from("events.jsonl"))
+        );
+        assert_eq!(Target::parse("+3"), Target::Path(PathBuf::from("+3")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_open_fd_checks_and_duplicates() {
+        use std::os::fd::AsRawFd;
+
+        assert_eq!(
+            Target::Fd(1).open().unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert!(Target::Fd(i32::MAX).open().is_err());
+
+        let path = std::env::temp_dir().join(format!("asciiquarium-events-{}", std::process::id()));
+        let mut original = File::create(&path).unwrap();
+        let mut log = Target::Fd(original.as_raw_fd()).open().unwrap();
+        assert_ne!(log.as_raw_fd(), original.as_raw_fd());
+        writeln!(log, "one").unwrap();
+        drop(log);
+        // The inherited descriptor is still open
+        writeln!(original, "two").unwrap();
+        drop(original);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_log_writes_a_line_per_event() {
+        let mut log = EventLog::new(Vec::new());
+        log.record(&AquariumEvent::Spawned {
+            id: 1,
+            entity_type: "fish",
+        });
+        log.record(&AquariumEvent::Ambient { name: "school" });
+        assert!(!log.has_failed());
+
+        let text = String::from_utf8(log.get_ref().clone()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(r#"{"time":0.0"#));
+        assert!(lines[0].ends_with(r#","event":"spawned","id":1,"type":"fish"}"#));
+        assert!(lines[1].ends_with(r#""event":"ambient","name":"school"}"#));
+    }
+}
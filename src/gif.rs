@@ -0,0 +1,476 @@
+//! Animated GIF export
+//!
+//! `--export-gif PATH` runs the tank headless for a few seconds and writes
+//! what it drew as a looping GIF, for README art and sharing. No terminal
+//! or font is involved: each cell is drawn on a [`CELL_WIDTH`] x
+//! [`CELL_HEIGHT`] pixel grid in a bundled 5x7 bitmap font, in the cell's
+//! own colours. Printable ASCII and the block elements used by half-block
+//! rendering come out as they look on screen; any other glyph is drawn as
+//! a hollow box.
+//!
+//! Each frame carries a colour table of just the colours it uses. A frame
+//! with more than 256 (truecolor fish over a gradient) is matched to the
+//! xterm 256-colour palette instead.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+
+use crate::palette;
+use crate::simulation::Simulation;
+use crate::timestep;
+
+/// Pixels across one cell
+pub const CELL_WIDTH: usize = 6;
+
+/// Pixels down one cell; terminal cells are about twice as tall as wide
+pub const CELL_HEIGHT: usize = 10;
+
+/// Frames per second of exported animations
+pub const FRAME_RATE: u32 = 10;
+
+/// Length of an export when `--export-seconds` isn't given
+pub const DEFAULT_SECONDS: f32 = 5.0;
+
+/// Pixel row of a cell where the font's top row goes
+const GLYPH_TOP: usize = 1;
+
+/// Colours for cells that use the terminal's default
+const DEFAULT_FG: Rgb = (204, 204, 204);
+const DEFAULT_BG: Rgb = (0, 0, 0);
+
+type Rgb = (u8, u8, u8);
+
+/// Columns of the glyphs ' ' to '~', left to right, top row in the lowest
+/// bit
+const FONT: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // \
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
+
+/// Whether `ch` covers pixel (`x`, `y`) of its cell
+fn covers(ch: char, x: usize, y: usize) -> bool {
+    let (half_x, half_y) = (x < CELL_WIDTH / 2, y < CELL_HEIGHT / 2);
+    match ch {
+        ' '..='~' => {
+            let row = y.wrapping_sub(GLYPH_TOP);
+            x < 5 && row < 7 && FONT[ch as usize - ' ' as usize][x] >> row & 1 == 1
+        }
+        '█' => true,
+        '▀' => half_y,
+        '▄' => !half_y,
+        '▌' => half_x,
+        '▐' => !half_x,
+        '░' => x.is_multiple_of(2) && y.is_multiple_of(2),
+        '▒' => (x + y).is_multiple_of(2),
+        '▓' => !(x.is_multiple_of(2) && y.is_multiple_of(2)),
+        _ => {
+            let row = y.wrapping_sub(GLYPH_TOP);
+            x < 5 && row < 7 && (x == 0 || x == 4 || row == 0 || row == 6)
+        }
+    }
+}
+
+/// RGB value of a colour as xterm shows it; `None` for the terminal's
+/// default
+fn color_rgb(color: Color) -> Option<Rgb> {
+    match color {
+        Color::Indexed(index) => Some(palette::xterm_rgb(index)),
+        color => palette::to_rgb(color),
+    }
+}
+
+/// One picture of the tank: a colour index per pixel and the colours
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub width: u16,
+    pub height: u16,
+    pub pixels: Vec<u8>,
+    pub palette: Vec<Rgb>,
+}
+
+impl Frame {
+    /// Draw `buffer` a cell at a time
+    pub fn rasterize(buffer: &Buffer) -> Self {
+        let area = buffer.area;
+        let width = area.width as usize * CELL_WIDTH;
+        let height = area.height as usize * CELL_HEIGHT;
+        let mut colors = vec![DEFAULT_BG; width * height];
+        for (row, y) in (area.top()..area.bottom()).enumerate() {
+            for (column, x) in (area.left()..area.right()).enumerate() {
+                let cell = &buffer[(x, y)];
+                let mut fg = color_rgb(cell.fg).unwrap_or(DEFAULT_FG);
+                let mut bg = color_rgb(cell.bg).unwrap_or(DEFAULT_BG);
+                if cell.modifier.contains(Modifier::REVERSED) {
+                    std::mem::swap(&mut fg, &mut bg);
+                }
+                // The second half of a wide glyph has an empty symbol
+                let ch = cell.symbol().chars().next().unwrap_or(' ');
+                for py in 0..CELL_HEIGHT {
+                    let start = (row * CELL_HEIGHT + py) * width + column * CELL_WIDTH;
+                    for (px, pixel) in colors[start..start + CELL_WIDTH].iter_mut().enumerate() {
+                        *pixel = if covers(ch, px, py) { fg } else { bg };
+                    }
+                }
+            }
+        }
+
+        let mut palette = Vec::new();
+        let mut indices = HashMap::new();
+        for &rgb in &colors {
+            if palette.len() > 256 {
+                break;
+            }
+            indices.entry(rgb).or_insert_with(|| {
+                palette.push(rgb);
+                palette.len() - 1
+            });
+        }
+        if palette.len() > 256 {
+            palette = (0..=255).map(palette::xterm_rgb).collect();
+            indices.clear();
+        }
+        let pixels = colors
+            .iter()
+            .map(|&rgb| {
+                *indices
+                    .entry(rgb)
+                    .or_insert_with(|| palette::nearest_xterm(rgb) as usize) as u8
+            })
+            .collect();
+        Self {
+            width: width as u16,
+            height: height as u16,
+            pixels,
+            palette,
+        }
+    }
+}
+
+/// Writes frames to a looping GIF
+#[derive(Debug)]
+pub struct GifEncoder<W: Write> {
+    out: W,
+    width: u16,
+    height: u16,
+}
+
+impl<W: Write> GifEncoder<W> {
+    /// Start a `width` x `height` animation that loops forever
+    pub fn new(mut out: W, width: u16, height: u16) -> io::Result<Self> {
+        out.write_all(b"GIF89a")?;
+        out.write_all(&width.to_le_bytes())?;
+        out.write_all(&height.to_le_bytes())?;
+        // No global colour table, background 0, square pixels
+        out.write_all(&[0, 0, 0])?;
+        out.write_all(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+        Ok(Self { out, width, height })
+    }
+
+    /// Add `frame`, shown for `delay` hundredths of a second
+    pub fn add_frame(&mut self, frame: &Frame, delay: u16) -> io::Result<()> {
+        if (frame.width, frame.height) != (self.width, self.height) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "frame size differs from the animation's",
+            ));
+        }
+        // Table sizes are powers of two from 4 up
+        let bits = (usize::BITS - (frame.palette.len().max(4) - 1).leading_zeros()) as u8;
+
+        // Graphic control: leave the frame in place, no transparency
+        self.out.write_all(&[0x21, 0xF9, 0x04, 0x04])?;
+        self.out.write_all(&delay.to_le_bytes())?;
+        self.out.write_all(&[0, 0])?;
+
+        self.out.write_all(&[0x2C, 0, 0, 0, 0])?;
+        self.out.write_all(&frame.width.to_le_bytes())?;
+        self.out.write_all(&frame.height.to_le_bytes())?;
+        self.out.write_all(&[0x80 | (bits - 1)])?;
+        for index in 0..1 << bits {
+            let (r, g, b) = frame.palette.get(index).copied().unwrap_or(DEFAULT_BG);
+            self.out.write_all(&[r, g, b])?;
+        }
+
+        self.out.write_all(&[bits])?;
+        for block in lzw_encode(&frame.pixels, bits).chunks(255) {
+            self.out.write_all(&[block.len() as u8])?;
+            self.out.write_all(block)?;
+        }
+        self.out.write_all(&[0])
+    }
+
+    /// End the animation, handing back the writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.write_all(&[0x3B])?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Codes packed least significant bit first
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    pending: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.pending |= (code as u32) << self.bits;
+        self.bits += size as u32;
+        while self.bits >= 8 {
+            self.bytes.push(self.pending as u8);
+            self.pending >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.pending as u8);
+        }
+        self.bytes
+    }
+}
+
+/// GIF's variable-width LZW compression of `indices`, whose values fit in
+/// `min_code_size` bits
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    const MAX_CODES: u16 = 4096;
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut writer = BitWriter::default();
+    let mut size = min_code_size + 1;
+    let mut next = end + 1;
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+
+    writer.write(clear, size);
+    let mut indices = indices.iter();
+    if let Some(&first) = indices.next() {
+        let mut prefix = first as u16;
+        for &index in indices {
+            if let Some(&code) = table.get(&(prefix, index)) {
+                prefix = code;
+                continue;
+            }
+            writer.write(prefix, size);
+            if next == MAX_CODES {
+                writer.write(clear, size);
+                table.clear();
+                size = min_code_size + 1;
+                next = end + 1;
+            } else {
+                table.insert((prefix, index), next);
+                // The decoder widens one code later, having just added the
+                // same entry
+                if next == 1 << size && size < 12 {
+                    size += 1;
+                }
+                next += 1;
+            }
+            prefix = index as u16;
+        }
+        writer.write(prefix, size);
+        if next == 1 << size && size < 12 {
+            size += 1;
+        }
+    }
+    writer.write(end, size);
+    writer.finish()
+}
+
+/// Record `seconds` of `simulation` as a looping GIF written to `out`
+pub fn record<W: Write>(simulation: &mut Simulation, seconds: f32, out: W) -> io::Result<W> {
+    let area = simulation.world().bounds();
+    let width = (area.width as usize * CELL_WIDTH).min(u16::MAX as usize) as u16;
+    let height = (area.height as usize * CELL_HEIGHT).min(u16::MAX as usize) as u16;
+    let mut encoder = GifEncoder::new(out, width, height)?;
+    let frames = (seconds * FRAME_RATE as f32).round().max(1.0) as u32;
+    for _ in 0..frames {
+        encoder.add_frame(
+            &Frame::rasterize(&simulation.render()),
+            100 / FRAME_RATE as u16,
+        )?;
+        simulation.run((timestep::TICK_RATE / FRAME_RATE) as u64);
+    }
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+
+    /// Undo [`lzw_encode`]
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let (mut bit, mut size) = (0, min_code_size as usize + 1);
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut previous: Option<Vec<u8>> = None;
+        let mut out = Vec::new();
+        loop {
+            let code = (0..size).fold(0, |code, i| {
+                let at = bit + i;
+                code | ((data[at / 8] as usize >> (at % 8)) & 1) << i
+            });
+            bit += size;
+            if code == clear {
+                table = (0..clear).map(|index| vec![index as u8]).collect();
+                table.extend([Vec::new(), Vec::new()]);
+                size = min_code_size as usize + 1;
+                previous = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return out;
+            }
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [previous.as_slice(), &previous[..1]].concat(),
+                (None, None) => panic!("code {code} before any entry"),
+            };
+            if let Some(previous) = previous {
+                table.push([previous.as_slice(), &entry[..1]].concat());
+                if table.len() == 1 << size && size < 12 {
+                    size += 1;
+                }
+            }
+            out.extend(&entry);
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn test_lzw_round_trip() {
+        // Long enough to fill the table and start over
+        let pixels: Vec<u8> = (0..40_000u32).map(|i| (i * i / 7 % 13) as u8).collect();
+        assert_eq!(lzw_decode(&lzw_encode(&pixels, 4), 4), pixels);
+        assert_eq!(lzw_decode(&lzw_encode(&[], 2), 2), []);
+        let flat = [1u8; 5000];
+        assert_eq!(lzw_decode(&lzw_encode(&flat, 2), 2), flat);
+    }
+
+    #[test]
+    fn test_rasterize_cells() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 2, 1));
+        buffer[(0, 0)].set_char('|').set_fg(Color::Red);
+        buffer[(1, 0)]
+            .set_char('▀')
+            .set_fg(Color::Blue)
+            .set_bg(Color::Green);
+        let frame = Frame::rasterize(&buffer);
+        assert_eq!((frame.width, frame.height), (12, 10));
+
+        let at = |x: usize, y: usize| frame.palette[frame.pixels[y * 12 + x] as usize];
+        assert_eq!(at(2, 3), (205, 0, 0));
+        assert_eq!(at(0, 3), DEFAULT_BG);
+        assert_eq!(at(8, 0), (0, 0, 238));
+        assert_eq!(at(8, 9), (0, 205, 0));
+    }
+
+    #[test]
+    fn test_record_writes_a_gif() {
+        let mut simulation = Simulation::new(20, 8);
+        let gif = record(&mut simulation, 0.5, Vec::new()).unwrap();
+        assert!(gif.starts_with(b"GIF89a\x78\x00\x50\x00"));
+        assert_eq!(gif.last(), Some(&0x3B));
+        assert_eq!(simulation.ticks(), 30);
+    }
+}
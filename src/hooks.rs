@@ -0,0 +1,212 @@
+//! Typed event hooks for embedders
+//!
+//! Callbacks registered with [`EntityManager::on_event`] (or
+//! [`App::on_event`]) are invoked synchronously whenever something notable
+//! happens in the tank, so integrations can drive sounds, logs or external
+//! effects without polling the entity list every frame.
+//!
+//! Entities hear the same events through [`Entity::on_event`], which lets
+//! them react to each other (shark teeth leave with their shark) without
+//! reaching into the spawning functions.
+//!
+//! [`Entity::on_event`]: crate::entity::Entity::on_event
+//! [`EntityManager::on_event`]: crate::entity::EntityManager::on_event
+//! [`App::on_event`]: crate::app::App::on_event
+
+use crate::collision::ContactKind;
+use crate::entity::{EntityId, Position, Velocity};
+
+/// Something that happened in the aquarium
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AquariumEvent {
+    /// An entity was added to the tank
+    Spawned {
+        id: EntityId,
+        entity_type: &'static str,
+    },
+    /// An entity was removed from the tank (died or was cleaned up)
+    Killed {
+        id: EntityId,
+        entity_type: &'static str,
+    },
+    /// A large creature took one of the large creature slots
+    LargeCreatureEntered {
+        id: EntityId,
+        entity_type: &'static str,
+    },
+    /// Two entities started touching: `first` made the contact and
+    /// `second` received it (see [`crate::collision`] for which pairs can)
+    Collision {
+        first: EntityId,
+        second: EntityId,
+        kind: ContactKind,
+    },
+    /// A bubble reached the water surface and popped
+    BubblePopped { position: Position },
+    /// The water current started drifting towards a new target
+    CurrentChanged { target: Velocity },
+    /// An ambient happening was started (bubble burst, school, ...), named
+    /// after its spawner in the registry
+    Ambient { name: &'static str },
+}
+
+impl AquariumEvent {
+    /// The event as a one-line JSON object, named in snake case
+    ///
+    /// ```
+    /// use asciiquarium_rs::hooks::AquariumEvent;
+    ///
+    /// let event = AquariumEvent::Killed { id: 7, entity_type: "fish" };
+    /// assert_eq!(event.to_json(), r#"{"event":"killed","id":7,"type":"fish"}"#);
+    /// ```
+    pub fn to_json(&self) -> String {
+        format!("{{{}}}", self.json_fields())
+    }
+
+    /// The members of [`AquariumEvent::to_json`]'s object without the
+    /// braces, for adding more
+    pub fn json_fields(&self) -> String {
+        let fields = match self {
+            Self::Spawned { id, entity_type } => {
+                format!(r#""spawned","id":{id},"type":{}"#, json_string(entity_type))
+            }
+            Self::Killed { id, entity_type } => {
+                format!(r#""killed","id":{id},"type":{}"#, json_string(entity_type))
+            }
+            Self::LargeCreatureEntered { id, entity_type } => format!(
+                r#""large_creature_entered","id":{id},"type":{}"#,
+                json_string(entity_type)
+            ),
+            Self::Collision {
+                first,
+                second,
+                kind,
+            } => format!(
+                r#""collision","first":{first},"second":{second},"kind":"{}""#,
+                kind.name()
+            ),
+            Self::BubblePopped { position } => format!(
+                r#""bubble_popped","x":{},"y":{}"#,
+                json_number(position.x),
+                json_number(position.y)
+            ),
+            Self::CurrentChanged { target } => format!(
+                r#""current_changed","dx":{},"dy":{}"#,
+                json_number(target.dx),
+                json_number(target.dy)
+            ),
+            Self::Ambient { name } => format!(r#""ambient","name":{}"#, json_string(name)),
+        };
+        format!(r#""event":{fields}"#)
+    }
+}
+
+/// `text` as a JSON string literal
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            ch if ch.is_control() => quoted.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// `value` as a JSON number (JSON has no infinities or NaN, so those are
+/// null)
+fn json_number(value: f32) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// Boxed callback receiving aquarium events
+pub type EventCallback = Box<dyn FnMut(&AquariumEvent)>;
+
+/// Registered event callbacks
+#[derive(Default)]
+pub struct EventHooks {
+    callbacks: Vec<EventCallback>,
+}
+
+impl EventHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a callback for all future events
+    pub fn subscribe(&mut self, callback: impl FnMut(&AquariumEvent) + 'static) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    /// Whether anyone is listening (lets callers skip building events)
+    pub fn is_empty(&self) -> bool {
+        self.callbacks.is_empty()
+    }
+
+    /// Deliver an event to every callback in registration order
+    pub fn emit(&mut self, event: &AquariumEvent) {
+        for callback in &mut self.callbacks {
+            callback(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_emit_reaches_all_subscribers() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut hooks = EventHooks::new();
+        assert!(hooks.is_empty());
+
+        for _ in 0..2 {
+            let seen = Rc::clone(&seen);
+            hooks.subscribe(move |event| seen.borrow_mut().push(*event));
+        }
+
+        let event = AquariumEvent::Spawned {
+            id: 1,
+            entity_type: "fish",
+        };
+        hooks.emit(&event);
+        assert_eq!(*seen.borrow(), vec![event, event]);
+    }
+
+    #[test]
+    fn test_events_as_json() {
+        let event = AquariumEvent::BubblePopped {
+            position: Position {
+                x: 3.5,
+                y: 0.0,
+                depth: 2,
+            },
+        };
+        assert_eq!(
+            event.to_json(),
+            r#"{"event":"bubble_popped","x":3.5,"y":0}"#
+        );
+        assert_eq!(json_string("say \"hi\"\n"), r#""say \"hi\"\u000a""#);
+
+        let bite = AquariumEvent::Collision {
+            first: 3,
+            second: 4,
+            kind: ContactKind::Bite,
+        };
+        assert_eq!(
+            bite.to_json(),
+            r#"{"event":"collision","first":3,"second":4,"kind":"bite"}"#
+        );
+    }
+}
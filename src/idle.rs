@@ -0,0 +1,99 @@
+//! Dropping to a low-power tick when nobody is watching
+//!
+//! A tank left running all day shouldn't keep a core busy redrawing the
+//! same frame. The app goes idle, ticking at [`IDLE_TICK_FPS`], once
+//! [`IDLE_AFTER_FRAMES`] frames in a row left the tank unchanged, as when
+//! paused or frozen. The next input or changed frame brings it back to
+//! full rate.
+//!
+//! Losing focus alone doesn't idle: an unfocused terminal is usually still
+//! on screen, and the tank should keep swimming there.
+
+use crate::event::TICK_FPS;
+
+/// Ticks per second while idle
+pub const IDLE_TICK_FPS: f64 = 2.0;
+
+/// Unchanged frames in a row before going idle (a second at full rate)
+pub const IDLE_AFTER_FRAMES: u32 = 30;
+
+/// Watches frames to pick the tick rate
+#[derive(Debug, Clone)]
+pub struct IdleDetector {
+    enabled: bool,
+    unchanged_frames: u32,
+}
+
+impl Default for IdleDetector {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            unchanged_frames: 0,
+        }
+    }
+}
+
+impl IdleDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Never go idle when `enabled` is false (`--no-idle`)
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Note a drawn frame and whether it differed from the one before
+    pub fn frame(&mut self, changed: bool) {
+        self.unchanged_frames = if changed {
+            0
+        } else {
+            self.unchanged_frames.saturating_add(1)
+        };
+    }
+
+    /// Note input or anything else that should bring back the full rate
+    pub fn wake(&mut self) {
+        self.unchanged_frames = 0;
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.enabled && self.unchanged_frames >= IDLE_AFTER_FRAMES
+    }
+
+    /// Ticks per second to run at
+    pub fn tick_fps(&self) -> f64 {
+        if self.is_idle() {
+            IDLE_TICK_FPS
+        } else {
+            TICK_FPS
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idles_until_woken() {
+        let mut idle = IdleDetector::new();
+        for _ in 0..IDLE_AFTER_FRAMES - 1 {
+            idle.frame(false);
+        }
+        assert!(!idle.is_idle());
+        idle.frame(false);
+        assert_eq!(idle.tick_fps(), IDLE_TICK_FPS);
+        idle.wake();
+        assert_eq!(idle.tick_fps(), TICK_FPS);
+
+        idle.frame(true);
+        assert!(!idle.is_idle());
+        for _ in 0..IDLE_AFTER_FRAMES {
+            idle.frame(false);
+        }
+        assert!(idle.is_idle());
+        idle.set_enabled(false);
+        assert!(!idle.is_idle());
+    }
+}
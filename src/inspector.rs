@@ -0,0 +1,174 @@
+//! Picking out one creature to look at
+//!
+//! Tab steps the selection through the creatures in the tank (oldest
+//! first, then back to none); the selected one is outlined, an inspector
+//! line describes it, and over a `--world` tank the camera follows it.
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Style, Stylize};
+
+use crate::entities::Fish;
+use crate::entity::{Entity, EntityId, EntityKind, EntityManager};
+use crate::mouse;
+use crate::timestep;
+
+/// Whether `entity` is a creature worth inspecting, rather than scenery,
+/// a particle or an overlay
+pub fn is_selectable(entity: &dyn Entity) -> bool {
+    matches!(
+        entity.kind(),
+        EntityKind::Fish
+            | EntityKind::BigFish
+            | EntityKind::Shark
+            | EntityKind::Whale
+            | EntityKind::Ship
+            | EntityKind::SeaMonster
+            | EntityKind::Dolphin
+            | EntityKind::Ducks
+            | EntityKind::Custom(_)
+    )
+}
+
+/// The creature after `current` in spawn order, or before it if
+/// `backwards`; `None` once the end is passed, so the cycle includes
+/// selecting nothing
+pub fn cycle(
+    manager: &EntityManager,
+    current: Option<EntityId>,
+    backwards: bool,
+) -> Option<EntityId> {
+    let mut creatures: Vec<(u64, EntityId)> = manager
+        .entities()
+        .filter(|entity| is_selectable(*entity))
+        .filter_map(|entity| Some((manager.spawn_sequence(entity.id())?, entity.id())))
+        .collect();
+    creatures.sort_unstable();
+    if backwards {
+        creatures.reverse();
+    }
+    let current = current.and_then(|id| manager.spawn_sequence(id));
+    let (_, id) = match current {
+        None => creatures.first().copied(),
+        Some(current) => creatures.into_iter().find(|&(sequence, _)| {
+            if backwards {
+                sequence < current
+            } else {
+                sequence > current
+            }
+        }),
+    }?;
+    Some(id)
+}
+
+/// The inspector line for an entity: what it is (with species and stage
+/// for fish) and its age, its speed in cells per second, depth and position
+pub fn describe(manager: &EntityManager, id: EntityId) -> Option<String> {
+    let entity = manager.get_entity(id)?;
+    let what = match manager.get_as::<Fish>(id) {
+        Some(fish) => mouse::describe_fish(fish),
+        None => format!(
+            "{}, {}",
+            entity.kind().name().replace('_', " "),
+            mouse::format_age(manager.age_of(id)?)
+        ),
+    };
+    let velocity = entity.velocity();
+    let speed = velocity.dx.hypot(velocity.dy) * timestep::TICK_RATE as f32;
+    let position = entity.position();
+    Some(format!(
+        "#{id} {what} | speed {speed:.1}/s | depth {} | at {},{}",
+        entity.depth(),
+        position.x.floor(),
+        position.y.floor()
+    ))
+}
+
+/// Draw the inspector line across `row` of `area`
+pub fn render(text: &str, buf: &mut Buffer, area: Rect, row: u16) {
+    if row >= area.height {
+        return;
+    }
+    let line = format!(" {text} ");
+    buf.set_stringn(
+        area.x,
+        area.y + row,
+        line,
+        area.width as usize,
+        Style::default().black().on_cyan(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::depth;
+    use crate::entities::{FishSpecies, Seaweed, Shark};
+    use crate::entity::{Direction, Position, Velocity};
+    use crate::rng::RngHandle;
+
+    #[test]
+    fn test_cycle_skips_scenery_and_wraps_through_none() {
+        let mut manager = EntityManager::new();
+        let fish = |manager: &mut EntityManager, x| {
+            manager.spawn(|id| {
+                Fish::new(
+                    id,
+                    Position::new(x, 10.0, depth::FISH_START),
+                    Velocity::new(0.5, 0.0),
+                    Direction::Right,
+                    FishSpecies::NewSmall1,
+                    &mut RngHandle::default(),
+                )
+            })
+        };
+        let first = fish(&mut manager, 10.0);
+        manager.spawn(|id| Seaweed::new(id, 20.0, 15.0, 4, &mut RngHandle::default()));
+        let second = fish(&mut manager, 30.0);
+
+        assert_eq!(cycle(&manager, None, false), Some(first));
+        assert_eq!(cycle(&manager, Some(first), false), Some(second));
+        assert_eq!(cycle(&manager, Some(second), false), None);
+        assert_eq!(cycle(&manager, None, true), Some(second));
+        assert_eq!(cycle(&manager, Some(first), true), None);
+
+        let line = describe(&manager, second).unwrap();
+        assert!(line.starts_with(&format!("#{second} ")));
+        assert!(
+            line.ends_with("| speed 30.0/s | depth 3 | at 30,10"),
+            "{line}"
+        );
+    }
+
+    #[test]
+    fn test_cycle_follows_spawn_order_through_reused_slots() {
+        let mut manager = EntityManager::new();
+        let shark = |manager: &mut EntityManager| {
+            manager.spawn(|id| {
+                Shark::new(
+                    id,
+                    Position::new(0.0, 10.0, depth::SHARK),
+                    Velocity::new(0.0, 0.0),
+                    Direction::Right,
+                )
+            })
+        };
+        let first = shark(&mut manager);
+        let second = shark(&mut manager);
+        let third = shark(&mut manager);
+        manager.remove_entity(first);
+        manager.remove_entity(third);
+        let fourth = shark(&mut manager);
+        let fifth = shark(&mut manager);
+        // The newest shark took the lowest slot
+        assert!(fifth < fourth);
+
+        assert_eq!(cycle(&manager, None, false), Some(second));
+        assert_eq!(cycle(&manager, Some(second), false), Some(fourth));
+        assert_eq!(cycle(&manager, Some(fourth), false), Some(fifth));
+        assert_eq!(cycle(&manager, Some(fifth), true), Some(fourth));
+
+        let line = describe(&manager, fifth).unwrap();
+        assert!(line.starts_with(&format!("#{fifth} shark, 0s |")), "{line}");
+    }
+}
@@ -0,0 +1,80 @@
+//! Small physical interactions between entities
+//!
+//! Interactions are delivered by the [`EntityManager`] as events after each
+//! update pass. The first one is bubble lift: when a bubble pops at the
+//! waterline underneath something floating on the surface, the floating
+//! entity bobs up a cell for a moment.
+//!
+//! [`EntityManager`]: crate::entity::EntityManager
+
+use std::time::Duration;
+
+/// How long (seconds) a floating entity stays lifted after a bubble pops
+pub const BOB_SECONDS: f32 = 0.4;
+
+/// How far (rows) a popping bubble lifts a floating entity
+pub const BOB_HEIGHT: f32 = 1.0;
+
+/// Short-lived upward bob of a floating entity
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Bob {
+    remaining: f32,
+}
+
+impl Bob {
+    /// Start (or restart) a bob
+    pub fn trigger(&mut self) {
+        self.remaining = BOB_SECONDS;
+    }
+
+    /// Let the bob settle back down
+    pub fn update(&mut self, delta_time: Duration) {
+        self.remaining = (self.remaining - delta_time.as_secs_f32()).max(0.0);
+    }
+
+    /// Whether the entity is currently lifted
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    /// Vertical offset to apply to the entity (negative is up)
+    pub fn y_offset(&self) -> f32 {
+        if self.is_active() {
+            -BOB_HEIGHT
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Whether a bubble popping at column `x` is underneath a floating entity
+/// spanning `width` columns from `left`
+pub fn is_under(x: f32, left: f32, width: u16) -> bool {
+    x >= left && x < left + width as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bob_lifts_then_settles() {
+        let mut bob = Bob::default();
+        assert_eq!(bob.y_offset(), 0.0);
+
+        bob.trigger();
+        assert_eq!(bob.y_offset(), -BOB_HEIGHT);
+
+        bob.update(Duration::from_secs_f32(BOB_SECONDS));
+        assert!(!bob.is_active());
+        assert_eq!(bob.y_offset(), 0.0);
+    }
+
+    #[test]
+    fn test_is_under() {
+        assert!(is_under(10.0, 10.0, 5));
+        assert!(is_under(14.5, 10.0, 5));
+        assert!(!is_under(15.0, 10.0, 5));
+        assert!(!is_under(9.0, 10.0, 5));
+    }
+}
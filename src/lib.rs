@@ -0,0 +1,127 @@
+//! Asciiquarium as a library
+//!
+//! The binary is a thin wrapper around this crate; dashboards, games and
+//! screensavers can drive the same aquarium themselves.
+//!
+//! - [`App`] is the full terminal application: event loop, key bindings,
+//!   status bar and overlays.
+//! - [`Aquarium`] and [`AquariumWidget`] put just the tank into any
+//!   ratatui layout.
+//! - [`Simulation`](simulation::Simulation) steps a tank without a
+//!   terminal and captures its frames.
+//! - [`EntityManager`] owns the entities, updates and renders them. Fill it
+//!   with the functions in [`spawning`], or add your own [`Entity`]
+//!   implementations drawn from a [`Sprite`]; [`depth`] lists the layers
+//!   they are drawn in. Which spawners fill and replenish the tank is up
+//!   to its [`registry`], how many and how soon to its [`scheduler`].
+//!
+//! Everything but the terminal frontend also builds for
+//! `wasm32-unknown-unknown`; the `web` directory embeds the tank in a web
+//! page that way.
+//!
+//! Rendering the tank into an off-screen buffer:
+//!
+//! ```
+//! use asciiquarium_rs::{Aquarium, AquariumWidget};
+//! use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+//!
+//! let area = Rect::new(0, 0, 80, 24);
+//! let mut buffer = Buffer::empty(area);
+//! let mut aquarium = Aquarium::new();
+//!
+//! AquariumWidget::new().render(area, &mut buffer, &mut aquarium);
+//! aquarium.tick();
+//! assert_eq!(aquarium.entity_manager().get_entities_by_type("castle").len(), 1);
+//! ```
+//!
+//! Driving the simulation without drawing anything:
+//!
+//! ```
+//! use std::time::Duration;
+//! use asciiquarium_rs::{spawning, world::WorldContext, EntityManager};
+//! use ratatui::layout::Rect;
+//!
+//! let bounds = Rect::new(0, 0, 120, 40);
+//! let mut manager = EntityManager::new();
+//! spawning::initialize_aquarium(&mut manager, bounds);
+//! manager.update_all(Duration::from_millis(50), &WorldContext::new(bounds));
+//! assert!(manager.entity_count() > 0);
+//! ```
+
+pub mod ambient;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod app;
+pub mod assets;
+pub mod backend;
+pub mod backgrounds;
+pub mod behavior;
+pub mod charset;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod chat;
+pub mod cli;
+pub mod clock;
+pub mod collision;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod control;
+pub mod depth;
+pub mod dirty;
+pub mod ecology;
+pub mod entities;
+pub mod entity;
+pub mod environment;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod event;
+pub mod event_log;
+pub mod gif;
+pub mod hooks;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod idle;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod inspector;
+pub mod interaction;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod metrics;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod mouse;
+pub mod palette;
+pub mod perf;
+pub mod perl_sprites;
+pub mod pool;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod prompt;
+pub mod registry;
+pub mod render;
+pub mod rng;
+pub mod scheduler;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod screensaver;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod screenshot;
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "scripting")]
+pub mod scripting;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod serve;
+pub mod simulation;
+pub mod sound;
+pub mod spatial;
+pub mod spawning;
+pub mod stats;
+pub mod status;
+pub mod store;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod stream;
+pub mod stress;
+pub mod strip;
+#[cfg(feature = "sysmon")]
+pub mod sysmon;
+pub mod timestep;
+pub mod title;
+pub mod toast;
+pub mod ui;
+pub mod world;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use app::App;
+pub use entity::{Entity, EntityManager, Sprite};
+pub use ui::{Aquarium, AquariumWidget};
@@ -1,17 +1,151 @@
-use crate::app::App;
-
-pub mod app;
-pub mod depth;
-pub mod entities;
-pub mod entity;
-pub mod event;
-pub mod spawning;
-pub mod ui;
+use asciiquarium_rs::chat::ChatConfig;
+use asciiquarium_rs::cli::{self, Options};
+use asciiquarium_rs::entity::EntityKind;
+use asciiquarium_rs::sound::SoundEffects;
+use asciiquarium_rs::{assets, charset, palette, screensaver, App};
+use std::io::IsTerminal;
+use std::sync::Arc;
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
-    let terminal = ratatui::init();
-    let result = App::new().run(terminal);
-    ratatui::restore();
-    result
+    let mut options = match Options::from_env() {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("asciiquarium: {error}");
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        print!("{}", cli::HELP);
+        return Ok(());
+    }
+    options.screensaver |= screensaver::detected();
+    if options.screensaver_config {
+        let program = std::env::current_exe()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|_| "asciiquarium".to_string());
+        print!("{}", screensaver::config(&program));
+        return Ok(());
+    }
+    // Sprites pick their glyphs and colours as they are built, so choose
+    // them first
+    charset::set(options.charset);
+    palette::set(options.palette);
+    if options.serve {
+        let Some(address) = options.ssh_address.clone() else {
+            return Err(color_eyre::eyre::eyre!(
+                "serve needs an address, as in serve --ssh :2222"
+            ));
+        };
+        #[cfg(feature = "ssh")]
+        return Ok(asciiquarium_rs::serve::serve_ssh(&address, options)?);
+        #[cfg(not(feature = "ssh"))]
+        return Err(color_eyre::eyre::eyre!(
+            "this build can't serve {address}; enable the ssh feature"
+        ));
+    }
+    let mut app = App::with_options(&options);
+
+    // Creatures from sprite files join the built-in ones; broken files are
+    // reported before the screen is taken over
+    if let Some(dir) = options.sprite_dir.clone().or_else(assets::default_dir) {
+        let (defs, errors) = assets::load_dir(&dir);
+        for error in errors {
+            eprintln!("asciiquarium: skipping {error}");
+        }
+        assets::register_all(app.entity_manager.registry_mut(), defs);
+    }
+    #[cfg(feature = "scripting")]
+    if let Some(dir) = options
+        .script_dir
+        .clone()
+        .or_else(asciiquarium_rs::scripting::default_dir)
+    {
+        let (scripts, errors) = asciiquarium_rs::scripting::load_dir(&dir);
+        for error in errors {
+            eprintln!("asciiquarium: skipping {error}");
+        }
+        if !scripts.is_empty() {
+            app.run_scripts(scripts);
+        }
+    }
+    #[cfg(not(feature = "scripting"))]
+    if options.script_dir.is_some() {
+        eprintln!("asciiquarium: this build has no --scripts; enable the scripting feature");
+    }
+    for (name, weight) in &options.creature_weights {
+        if !app.entity_manager.registry_mut().set_weight(name, *weight) {
+            eprintln!("asciiquarium: no creature called {name} to weigh");
+        }
+    }
+    for (name, policy) in &options.respawn_policies {
+        let manager = &mut app.entity_manager;
+        let kind = EntityKind::from_name(name).or_else(|| {
+            let entry = manager.registry().get(name)?;
+            Some(EntityKind::Custom(entry.name))
+        });
+        match kind {
+            Some(kind) => manager.set_respawn_policy(kind, *policy),
+            None => eprintln!("asciiquarium: no kind of entity called {name} to respawn"),
+        }
+    }
+
+    if let Some(target) = &options.events_json {
+        if let Err(error) = app.log_events(target) {
+            eprintln!("asciiquarium: not logging events: {error}");
+        }
+    }
+    if let Some(address) = &options.metrics_address {
+        if let Err(error) = app.serve_metrics(address) {
+            eprintln!("asciiquarium: no metrics on {address}: {error}");
+        }
+    }
+    if options.sysmon {
+        #[cfg(feature = "sysmon")]
+        app.monitor_host();
+        #[cfg(not(feature = "sysmon"))]
+        eprintln!("asciiquarium: this build has no --sysmon; enable the sysmon feature");
+    }
+    if let Some(player) = &options.sound {
+        app.play_sounds(SoundEffects::new(player.clone(), &options.sound_events));
+    }
+    if let Some(channel) = &options.chat_channel {
+        app.join_chat(ChatConfig::new(channel, options.chat_server.as_deref()));
+    }
+    #[cfg(unix)]
+    if let Some(path) = &options.control_socket {
+        if let Err(error) = app.listen_for_control(path) {
+            eprintln!(
+                "asciiquarium: no control socket at {}: {error}",
+                path.display()
+            );
+        }
+    }
+
+    if options.snapshot {
+        return app.snapshot(options.snapshot_ticks, &mut std::io::stdout().lock());
+    }
+    if options.dump {
+        return app.dump(
+            options.snapshot_ticks,
+            &mut std::io::stdout().lock(),
+            options.dump_colors.as_deref(),
+        );
+    }
+    if let Some(path) = &options.export_gif {
+        return app.export_gif(path, options.export_seconds);
+    }
+
+    // Closing down on a signal restores the terminal on the way out
+    #[cfg(unix)]
+    if let Err(error) = screensaver::stop_on_signals(&app.stop) {
+        eprintln!("asciiquarium: not handling signals: {error}");
+    }
+    if app.screensaver && !std::io::stdin().is_terminal() {
+        screensaver::stop_on_stdin_eof(Arc::clone(&app.stop));
+    }
+
+    // Full screen on a terminal; pipes, serial consoles and dumb terminals
+    // get cursor-addressed output unless a backend was picked
+    options.backend.run(app)
 }
@@ -0,0 +1,330 @@
+//! Metrics for monitoring a long-running aquarium
+//!
+//! With `--metrics ADDR` the app serves its counters and gauges over HTTP
+//! in the Prometheus text format, so an aquarium left running on an info
+//! display can be scraped and alerted on like any other service:
+//!
+//! ```text
+//! $ asciiquarium --metrics 9100 &
+//! $ curl -s localhost:9100/metrics | grep fish
+//! aquarium_entities{type="fish"} 14
+//! ```
+//!
+//! A bare port listens on localhost only; give a full address such as
+//! `0.0.0.0:9100` to be reachable from elsewhere. Only `GET /metrics` is
+//! answered.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use crate::collision::ContactKind;
+use crate::entity::EntityManager;
+use crate::hooks::AquariumEvent;
+
+/// How long a client gets to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Counters fed by aquarium events and gauges sampled every frame
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    entities: BTreeMap<&'static str, u64>,
+    spawned: BTreeMap<&'static str, u64>,
+    killed: BTreeMap<&'static str, u64>,
+    shark_attacks: u64,
+    ticks: u64,
+    frames: u64,
+    frame_seconds: f64,
+    dropped_ticks: u64,
+    time_scale: f32,
+    paused: bool,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the counters from an aquarium event
+    pub fn record(&mut self, event: &AquariumEvent) {
+        match *event {
+            AquariumEvent::Spawned { entity_type, .. } => {
+                *self.spawned.entry(entity_type).or_default() += 1;
+            }
+            AquariumEvent::Killed { entity_type, .. } => {
+                *self.killed.entry(entity_type).or_default() += 1;
+            }
+            AquariumEvent::Collision {
+                kind: ContactKind::Bite,
+                ..
+            } => self.shark_attacks += 1,
+            _ => {}
+        }
+    }
+
+    /// Count `ticks` more simulation steps
+    pub fn record_ticks(&mut self, ticks: u64) {
+        self.ticks += ticks;
+    }
+
+    /// Record a frame that took `frame_time` to draw, with the ticks
+    /// dropped so far
+    pub fn record_frame(&mut self, frame_time: Duration, dropped_ticks: u64) {
+        self.frames += 1;
+        self.frame_seconds = frame_time.as_secs_f64();
+        self.dropped_ticks = dropped_ticks;
+    }
+
+    /// Sample what is in the tank now
+    pub fn record_tank(&mut self, manager: &EntityManager, paused: bool) {
+        self.entities.clear();
+        for entity in manager.entities() {
+            *self.entities.entry(entity.entity_type()).or_default() += 1;
+        }
+        self.time_scale = manager.time_scale();
+        self.paused = paused;
+    }
+
+    /// Everything in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        family(
+            &mut out,
+            "aquarium_entities",
+            "gauge",
+            "Entities in the tank by type",
+            &by_type(&self.entities),
+        );
+        family(
+            &mut out,
+            "aquarium_spawned_total",
+            "counter",
+            "Entities spawned by type",
+            &by_type(&self.spawned),
+        );
+        family(
+            &mut out,
+            "aquarium_killed_total",
+            "counter",
+            "Entities removed by type",
+            &by_type(&self.killed),
+        );
+        let single = [
+            (
+                "aquarium_shark_attacks_total",
+                "counter",
+                "Fish bitten by sharks",
+                self.shark_attacks as f64,
+            ),
+            (
+                "aquarium_ticks_total",
+                "counter",
+                "Simulation steps run",
+                self.ticks as f64,
+            ),
+            (
+                "aquarium_frames_total",
+                "counter",
+                "Frames drawn",
+                self.frames as f64,
+            ),
+            (
+                "aquarium_frame_seconds",
+                "gauge",
+                "Time taken to draw the last frame",
+                self.frame_seconds,
+            ),
+            (
+                "aquarium_dropped_ticks_total",
+                "counter",
+                "Ticks skipped for running late",
+                self.dropped_ticks as f64,
+            ),
+            (
+                "aquarium_time_scale",
+                "gauge",
+                "Simulation speed as a multiple of real time",
+                self.time_scale as f64,
+            ),
+            (
+                "aquarium_paused",
+                "gauge",
+                "Whether the simulation is paused",
+                u8::from(self.paused) as f64,
+            ),
+        ];
+        for (name, kind, help, value) in single {
+            family(&mut out, name, kind, help, &[(String::new(), value)]);
+        }
+        out
+    }
+}
+
+/// Samples labelled with their entity type
+fn by_type(counts: &BTreeMap<&'static str, u64>) -> Vec<(String, f64)> {
+    counts
+        .iter()
+        .map(|(kind, &count)| (format!(r#"{{type="{kind}"}}"#), count as f64))
+        .collect()
+}
+
+/// Write one metric with its help and type lines
+fn family(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(String, f64)]) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{name}{labels} {value}");
+    }
+}
+
+/// Lock `metrics`, carrying on after a panic elsewhere
+pub fn lock(metrics: &Mutex<Metrics>) -> MutexGuard<'_, Metrics> {
+    metrics.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// An HTTP listener serving [`Metrics`] from a background thread
+#[derive(Debug)]
+pub struct MetricsServer {
+    address: SocketAddr,
+    metrics: Arc<Mutex<Metrics>>,
+}
+
+impl MetricsServer {
+    /// Listen at `address` (`host:port`, or a bare port for localhost)
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let listener = if address.bytes().all(|byte| byte.is_ascii_digit()) {
+            TcpListener::bind(format!("127.0.0.1:{address}"))?
+        } else {
+            TcpListener::bind(address)?
+        };
+        let address = listener.local_addr()?;
+        let metrics = Arc::new(Mutex::new(Metrics::new()));
+        let shared = Arc::clone(&metrics);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = serve(stream, &shared);
+            }
+        });
+        Ok(Self { address, metrics })
+    }
+
+    /// Where the server is listening
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// The figures being served, for the app to update
+    pub fn metrics(&self) -> &Arc<Mutex<Metrics>> {
+        &self.metrics
+    }
+}
+
+/// Answer one request
+fn serve(stream: TcpStream, metrics: &Mutex<Metrics>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // Headers aren't needed, but are read so the client sees a clean close
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+
+    let (status, content_type, body) = match request.split_whitespace().collect::<Vec<_>>()[..] {
+        ["GET", "/metrics", ..] => (
+            "200 OK",
+            "text/plain; version=0.0.4",
+            lock(metrics).render(),
+        ),
+        ["GET", ..] => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "only GET is supported\n".to_string(),
+        ),
+    };
+    write!(
+        writer,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_render_counts_events() {
+        let mut metrics = Metrics::new();
+        for event in [
+            AquariumEvent::Spawned {
+                id: 3,
+                entity_type: "shark_teeth",
+            },
+            AquariumEvent::Spawned {
+                id: 4,
+                entity_type: "fish",
+            },
+            AquariumEvent::Collision {
+                first: 3,
+                second: 4,
+                kind: ContactKind::Bite,
+            },
+            AquariumEvent::Killed {
+                id: 4,
+                entity_type: "fish",
+            },
+            AquariumEvent::Killed {
+                id: 3,
+                entity_type: "shark_teeth",
+            },
+            // Not a bite
+            AquariumEvent::Collision {
+                first: 6,
+                second: 5,
+                kind: ContactKind::Hooked,
+            },
+        ] {
+            metrics.record(&event);
+        }
+        metrics.record_ticks(60);
+        metrics.record_frame(Duration::from_millis(2), 1);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE aquarium_spawned_total counter\n"));
+        assert!(text.contains("aquarium_spawned_total{type=\"fish\"} 1\n"));
+        assert!(text.contains("aquarium_killed_total{type=\"shark_teeth\"} 1\n"));
+        assert!(text.contains("aquarium_shark_attacks_total 1\n"));
+        assert!(text.contains("aquarium_ticks_total 60\n"));
+        assert!(text.contains("aquarium_frame_seconds 0.002\n"));
+        assert!(text.contains("aquarium_paused 0\n"));
+    }
+
+    #[test]
+    fn test_server_answers_scrapes() {
+        let server = MetricsServer::bind("127.0.0.1:0").unwrap();
+        lock(server.metrics()).record_ticks(5);
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(server.address()).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: test\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = get("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("aquarium_paused 0\n"));
+        assert!(response.contains("\naquarium_ticks_total 5\n"));
+        assert!(get("/").starts_with("HTTP/1.1 404"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Mouse input for the terminal app
+//!
+//! With mouse capture on, the event thread turns raw crossterm reports into
+//! [`MouseAction`]s: a left click that didn't move drops a food flake into
+//! the water, or shows a [`Tooltip`] for the fish under it; dragging pans
+//! the camera over a tank larger than the terminal (`--world WxH`), as the
+//! arrow keys do. In feed mode the crosshair follows the pointer.
+
+use std::time::{Duration, Instant};
+
+use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use ratatui::style::{Style, Stylize};
+
+use crate::entities::Fish;
+
+/// How long a tooltip stays up
+pub const TOOLTIP_TIME: Duration = Duration::from_secs(3);
+
+/// What the user did with the mouse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseAction {
+    /// Left button pressed and released without moving, at a screen cell
+    Click { column: u16, row: u16 },
+    /// Left button held and moved by `dx`, `dy` cells since the last report
+    Drag { dx: i32, dy: i32 },
+    /// Pointer moved, with no button held, to a screen cell
+    Move { column: u16, row: u16 },
+}
+
+/// Tells clicks from drags across a stream of mouse reports
+#[derive(Debug, Clone, Default)]
+pub struct MouseTracker {
+    /// Where the left button was last seen while held
+    held: Option<(u16, u16)>,
+    dragged: bool,
+}
+
+impl MouseTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The action `event` completes, if any
+    pub fn track(&mut self, event: &MouseEvent) -> Option<MouseAction> {
+        let at = (event.column, event.row);
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.held = Some(at);
+                self.dragged = false;
+                None
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                let last = self.held.replace(at)?;
+                let (dx, dy) = (at.0 as i32 - last.0 as i32, at.1 as i32 - last.1 as i32);
+                if (dx, dy) == (0, 0) {
+                    return None;
+                }
+                self.dragged = true;
+                Some(MouseAction::Drag { dx, dy })
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                self.held.take()?;
+                let (column, row) = at;
+                (!std::mem::take(&mut self.dragged)).then_some(MouseAction::Click { column, row })
+            }
+            MouseEventKind::Moved => Some(MouseAction::Move {
+                column: event.column,
+                row: event.row,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Tooltip text for a fish: its species, life stage and age
+pub fn describe_fish(fish: &Fish) -> String {
+    format!(
+        "{}, {}, {}",
+        fish.species().name(),
+        fish.stage().name(),
+        format_age(fish.age())
+    )
+}
+
+/// An age as `42s`, or `3m05s` from a minute up
+pub fn format_age(age: Duration) -> String {
+    let age = age.as_secs();
+    if age < 60 {
+        format!("{age}s")
+    } else {
+        format!("{}m{:02}s", age / 60, age % 60)
+    }
+}
+
+/// A line of text shown above a clicked cell for a few seconds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tooltip {
+    text: String,
+    column: u16,
+    row: u16,
+    until: Instant,
+}
+
+impl Tooltip {
+    /// Show `text` over the screen cell at `column`, `row` from `now`
+    pub fn new(text: String, column: u16, row: u16, now: Instant) -> Self {
+        Self {
+            text: format!(" {text} "),
+            column,
+            row,
+            until: now + TOOLTIP_TIME,
+        }
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now >= self.until
+    }
+
+    /// Draw the tooltip a row above its cell, kept inside `area`
+    pub fn render(&self, buf: &mut Buffer, area: Rect, now: Instant) {
+        if self.is_expired(now) || area.is_empty() {
+            return;
+        }
+        let width = (self.text.chars().count() as u16).min(area.width);
+        let x = self
+            .column
+            .saturating_sub(width / 2)
+            .min(area.width - width);
+        let y = self.row.saturating_sub(1).min(area.height - 1);
+        let style = Style::default().black().on_yellow();
+        for (offset, ch) in self.text.chars().take(width as usize).enumerate() {
+            if let Some(cell) = buf.cell_mut((area.x + x + offset as u16, area.y + y)) {
+                cell.set_char(ch);
+                cell.set_style(style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::KeyModifiers;
+
+    fn report(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn test_tracker_tells_clicks_from_drags() {
+        let mut tracker = MouseTracker::new();
+        let left = MouseButton::Left;
+        assert_eq!(
+            tracker.track(&report(MouseEventKind::Down(left), 10, 5)),
+            None
+        );
+        assert_eq!(
+            tracker.track(&report(MouseEventKind::Up(left), 10, 5)),
+            Some(MouseAction::Click { column: 10, row: 5 })
+        );
+
+        tracker.track(&report(MouseEventKind::Down(left), 10, 5));
+        assert_eq!(
+            tracker.track(&report(MouseEventKind::Drag(left), 7, 6)),
+            Some(MouseAction::Drag { dx: -3, dy: 1 })
+        );
+        assert_eq!(
+            tracker.track(&report(MouseEventKind::Drag(left), 8, 6)),
+            Some(MouseAction::Drag { dx: 1, dy: 0 })
+        );
+        // Letting go after a drag isn't a click
+        assert_eq!(tracker.track(&report(MouseEventKind::Up(left), 8, 6)), None);
+        assert_eq!(
+            tracker.track(&report(MouseEventKind::Moved, 9, 6)),
+            Some(MouseAction::Move { column: 9, row: 6 })
+        );
+    }
+
+    #[test]
+    fn test_tooltip_stays_on_screen_and_expires() {
+        let area = Rect::new(0, 0, 20, 5);
+        let now = Instant::now();
+        let tooltip = Tooltip::new("tiny fish".to_string(), 19, 0, now);
+        let mut buf = Buffer::empty(area);
+        tooltip.render(&mut buf, area, now);
+        let row: String = (0..20).map(|x| buf[(x, 0)].symbol()).collect();
+        assert_eq!(row, "          tiny fish ");
+
+        let mut buf = Buffer::empty(area);
+        tooltip.render(&mut buf, area, now + TOOLTIP_TIME);
+        assert_eq!(buf, Buffer::empty(area));
+    }
+}
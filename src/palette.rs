@@ -0,0 +1,351 @@
+//! Random colour palettes for sprites
+//!
+//! The original colours each mask digit of a fish with one of twelve ANSI
+//! colours. On terminals that advertise 24-bit colour (`COLORTERM` set to
+//! `truecolor` or `24bit`) each fish instead gets its own palette of nine RGB
+//! colours built around a random base hue, so the digits of one fish look
+//! like they belong together.
+//!
+//! `--palette` swaps the mask colours for hues that stay apart under a
+//! colour vision deficiency. Like the character set, it is chosen once at
+//! startup.
+
+use rand::Rng;
+use ratatui::style::Color;
+use std::sync::OnceLock;
+
+/// Number of mask digits ('1' to '9') a palette covers
+pub const PALETTE_SIZE: usize = 9;
+
+/// Hue offsets (degrees) from the base hue for each mask digit: mostly
+/// analogous colours with a couple of complementary accents
+const HUE_OFFSETS: [f32; PALETTE_SIZE] = [0.0, 30.0, -30.0, 180.0, 15.0, -15.0, 150.0, 210.0, 45.0];
+
+/// Colour vision a palette is tuned for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Palette {
+    /// The original ANSI colours
+    #[default]
+    Standard,
+    /// Red-green, weak green
+    Deuteranopia,
+    /// Red-green, weak red (reds look dark)
+    Protanopia,
+    /// Blue-yellow
+    Tritanopia,
+}
+
+/// Replacements for red, green, yellow, blue, magenta and cyan, mostly
+/// from the Okabe-Ito set
+const DEUTERANOPIA: [(u8, u8, u8); 6] = [
+    (213, 94, 0),
+    (0, 158, 115),
+    (240, 228, 66),
+    (0, 114, 178),
+    (204, 121, 167),
+    (86, 180, 233),
+];
+const PROTANOPIA: [(u8, u8, u8); 6] = [
+    (230, 159, 0),
+    (0, 158, 115),
+    (240, 228, 66),
+    (0, 114, 178),
+    (204, 121, 167),
+    (86, 180, 233),
+];
+const TRITANOPIA: [(u8, u8, u8); 6] = [
+    (213, 94, 0),
+    (0, 158, 115),
+    (238, 170, 187),
+    (0, 68, 136),
+    (170, 51, 119),
+    (187, 187, 187),
+];
+
+impl Palette {
+    /// Parse a `--palette` value
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "standard" | "default" => Some(Self::Standard),
+            "deuteranopia" => Some(Self::Deuteranopia),
+            "protanopia" => Some(Self::Protanopia),
+            "tritanopia" => Some(Self::Tritanopia),
+            _ => None,
+        }
+    }
+
+    /// `color` as this palette shows it; colours outside the mask table
+    /// (RGB, black, white, grey) are left alone
+    ///
+    /// Replacements are RGB on truecolor terminals and the closest xterm
+    /// palette entry elsewhere.
+    pub fn remap(self, color: Color) -> Color {
+        self.remap_for(color, truecolor_supported())
+    }
+
+    fn remap_for(self, color: Color, truecolor: bool) -> Color {
+        let table = match self {
+            Self::Standard => return color,
+            Self::Deuteranopia => &DEUTERANOPIA,
+            Self::Protanopia => &PROTANOPIA,
+            Self::Tritanopia => &TRITANOPIA,
+        };
+        let hue = match color {
+            Color::Red | Color::LightRed => 0,
+            Color::Green | Color::LightGreen => 1,
+            Color::Yellow | Color::LightYellow => 2,
+            Color::Blue | Color::LightBlue => 3,
+            Color::Magenta | Color::LightMagenta => 4,
+            Color::Cyan | Color::LightCyan => 5,
+            _ => return color,
+        };
+        let (r, g, b) = table[hue];
+        if truecolor {
+            Color::Rgb(r, g, b)
+        } else {
+            Color::Indexed(nearest_xterm((r, g, b)))
+        }
+    }
+}
+
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+
+/// Choose the palette; only the first call has any effect
+pub fn set(palette: Palette) {
+    let _ = PALETTE.set(palette);
+}
+
+/// The palette chosen at startup (standard if none was chosen)
+pub fn current() -> Palette {
+    PALETTE.get().copied().unwrap_or_default()
+}
+
+/// Whether the terminal claims 24-bit colour support (checked once)
+pub fn truecolor_supported() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        std::env::var("COLORTERM")
+            .map(|value| is_truecolor_value(&value))
+            .unwrap_or(false)
+    })
+}
+
+fn is_truecolor_value(colorterm: &str) -> bool {
+    matches!(
+        colorterm.to_ascii_lowercase().as_str(),
+        "truecolor" | "24bit"
+    )
+}
+
+/// Build a harmonious palette around a random base hue
+pub fn random_palette<R: Rng>(rng: &mut R) -> [Color; PALETTE_SIZE] {
+    let base_hue = rng.gen_range(0.0..360.0);
+    let saturation = rng.gen_range(0.55..0.9);
+
+    HUE_OFFSETS.map(|offset| {
+        let lightness = rng.gen_range(0.45..0.7);
+        hsl_to_rgb(base_hue + offset, saturation, lightness)
+    })
+}
+
+/// Convert hue (degrees), saturation and lightness (0.0-1.0) to RGB
+pub fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> Color {
+    let hue = hue.rem_euclid(360.0) / 60.0;
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |value: f32| ((value + m) * 255.0).round() as u8;
+    Color::Rgb(channel(r), channel(g), channel(b))
+}
+
+/// Approximate RGB value of a colour (xterm defaults for the named ANSI
+/// colours); `None` for indexed and reset colours
+pub fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    let rgb = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        Color::Reset | Color::Indexed(_) => return None,
+    };
+    Some(rgb)
+}
+
+/// Entry `index` of the xterm 256-colour palette
+pub fn xterm_rgb(index: u8) -> (u8, u8, u8) {
+    const ANSI: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::Gray,
+        Color::DarkGray,
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+        Color::LightMagenta,
+        Color::LightCyan,
+        Color::White,
+    ];
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=15 => to_rgb(ANSI[index as usize]).unwrap_or_default(),
+        16..=231 => {
+            let cube = index as usize - 16;
+            (LEVELS[cube / 36], LEVELS[cube / 6 % 6], LEVELS[cube % 6])
+        }
+        _ => {
+            let grey = 8 + 10 * (index - 232);
+            (grey, grey, grey)
+        }
+    }
+}
+
+/// Entry of the xterm palette closest to `rgb`
+pub fn nearest_xterm(rgb: (u8, u8, u8)) -> u8 {
+    let distance = |(r, g, b): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, rgb.0) + d(g, rgb.1) + d(b, rgb.2)
+    };
+    (0..=255)
+        .min_by_key(|&index| distance(xterm_rgb(index)))
+        .unwrap_or(0)
+}
+
+/// `rgb` as the terminal can show it: itself on truecolor terminals, the
+/// closest xterm palette entry elsewhere
+pub fn terminal_color(rgb: (u8, u8, u8)) -> Color {
+    if truecolor_supported() {
+        Color::Rgb(rgb.0, rgb.1, rgb.2)
+    } else {
+        Color::Indexed(nearest_xterm(rgb))
+    }
+}
+
+/// Scale a colour's brightness by `factor` (0.0 = black, 1.0 = unchanged)
+///
+/// Colours without a known RGB value are returned unchanged.
+pub fn scale(color: Color, factor: f32) -> Color {
+    match to_rgb(color) {
+        Some((r, g, b)) => {
+            let channel = |value: u8| (value as f32 * factor).round().clamp(0.0, 255.0) as u8;
+            Color::Rgb(channel(r), channel(g), channel(b))
+        }
+        None => color,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hsl_to_rgb_primaries() {
+        assert_eq!(hsl_to_rgb(0.0, 1.0, 0.5), Color::Rgb(255, 0, 0));
+        assert_eq!(hsl_to_rgb(120.0, 1.0, 0.5), Color::Rgb(0, 255, 0));
+        assert_eq!(hsl_to_rgb(240.0, 1.0, 0.5), Color::Rgb(0, 0, 255));
+        assert_eq!(hsl_to_rgb(-120.0, 1.0, 0.5), Color::Rgb(0, 0, 255));
+        assert_eq!(hsl_to_rgb(0.0, 0.0, 1.0), Color::Rgb(255, 255, 255));
+    }
+
+    #[test]
+    fn test_random_palette_is_rgb() {
+        let palette = random_palette(&mut crate::rng::RngHandle::default());
+        assert!(palette
+            .iter()
+            .all(|color| matches!(color, Color::Rgb(_, _, _))));
+    }
+
+    #[test]
+    fn test_scale() {
+        assert_eq!(scale(Color::Rgb(200, 100, 0), 0.5), Color::Rgb(100, 50, 0));
+        assert_eq!(scale(Color::White, 1.0), Color::Rgb(255, 255, 255));
+        assert_eq!(scale(Color::Reset, 0.5), Color::Reset);
+    }
+
+    #[test]
+    fn test_palette_remap() {
+        assert_eq!(Palette::Standard.remap_for(Color::Red, true), Color::Red);
+        assert_eq!(
+            Palette::Deuteranopia.remap_for(Color::Red, true),
+            Color::Rgb(213, 94, 0)
+        );
+        assert_eq!(
+            Palette::Protanopia.remap_for(Color::LightRed, true),
+            Color::Rgb(230, 159, 0)
+        );
+        assert_eq!(
+            Palette::Protanopia.remap_for(Color::LightRed, false),
+            Color::Indexed(nearest_xterm((230, 159, 0)))
+        );
+        assert_eq!(Palette::Standard.remap_for(Color::Red, false), Color::Red);
+        assert_eq!(
+            Palette::Tritanopia.remap_for(Color::White, true),
+            Color::White
+        );
+
+        // Every palette keeps the six mask hues apart, with or without
+        // truecolor
+        for (palette, truecolor) in [
+            Palette::Deuteranopia,
+            Palette::Protanopia,
+            Palette::Tritanopia,
+        ]
+        .into_iter()
+        .flat_map(|palette| [(palette, true), (palette, false)])
+        {
+            let hues = [
+                Color::Red,
+                Color::Green,
+                Color::Yellow,
+                Color::Blue,
+                Color::Magenta,
+                Color::Cyan,
+            ]
+            .map(|color| palette.remap_for(color, truecolor));
+            for (i, a) in hues.iter().enumerate() {
+                assert!(hues[i + 1..].iter().all(|b| a != b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_palette_parse() {
+        assert_eq!(Palette::parse("Tritanopia"), Some(Palette::Tritanopia));
+        assert_eq!(Palette::parse("deuteranopia"), Some(Palette::Deuteranopia));
+        assert_eq!(Palette::parse("sepia"), None);
+    }
+
+    #[test]
+    fn test_truecolor_detection() {
+        assert!(is_truecolor_value("truecolor"));
+        assert!(is_truecolor_value("24BIT"));
+        assert!(!is_truecolor_value("256color"));
+        assert!(!is_truecolor_value(""));
+    }
+}
@@ -0,0 +1,113 @@
+//! Frame timing for the performance readout
+//!
+//! The app records when each frame is drawn and how long the simulation
+//! update and the collision checks inside it took. Pressing 'f' adds a
+//! segment with these figures to the status bar, to help tune density
+//! on slow terminals.
+
+use crate::clock::Instant;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// How far back frames are counted for the FPS figure
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Rolling frame and update timings
+#[derive(Debug, Clone, Default)]
+pub struct PerfCounter {
+    frames: VecDeque<Instant>,
+    frame_time: Duration,
+    update_time: Duration,
+    collision_time: Duration,
+    dropped_ticks: u64,
+}
+
+impl PerfCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a frame drawn at `at` that took `frame_time` to render
+    pub fn record_frame(&mut self, at: Instant, frame_time: Duration) {
+        self.frames.push_back(at);
+        while self
+            .frames
+            .front()
+            .is_some_and(|&first| at.duration_since(first) > WINDOW)
+        {
+            self.frames.pop_front();
+        }
+        self.frame_time = frame_time;
+    }
+
+    /// Record how long the last simulation update and the collision
+    /// checks within it took
+    pub fn record_update(&mut self, update_time: Duration, collision_time: Duration) {
+        self.update_time = update_time;
+        self.collision_time = collision_time;
+    }
+
+    /// Record how many ticks have been skipped so far for running late
+    pub fn record_dropped(&mut self, dropped_ticks: u64) {
+        self.dropped_ticks = dropped_ticks;
+    }
+
+    /// Frames drawn per second over the last `WINDOW`
+    pub fn fps(&self) -> f32 {
+        match (self.frames.front(), self.frames.back()) {
+            (Some(&first), Some(&last)) if self.frames.len() > 1 => {
+                let span = last.duration_since(first).as_secs_f32();
+                (self.frames.len() - 1) as f32 / span.max(f32::EPSILON)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Status bar segment, with entity counts per depth layer
+    pub fn summary(&self, layers: &[(u8, usize)]) -> String {
+        let layers: Vec<String> = layers
+            .iter()
+            .map(|(depth, count)| format!("{depth}:{count}"))
+            .collect();
+        format!(
+            "FPS: {:.1} | Frame: {:.1}ms | Update: {:.2}ms | Collide: {:.2}ms | Dropped: {} | Layers: {}",
+            self.fps(),
+            self.frame_time.as_secs_f64() * 1000.0,
+            self.update_time.as_secs_f64() * 1000.0,
+            self.collision_time.as_secs_f64() * 1000.0,
+            self.dropped_ticks,
+            layers.join(" ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fps_over_window() {
+        let mut perf = PerfCounter::new();
+        assert_eq!(perf.fps(), 0.0);
+
+        let start = Instant::now();
+        for frame in 0..=40 {
+            perf.record_frame(start + Duration::from_millis(frame * 50), Duration::ZERO);
+        }
+        // 2 seconds of 20 FPS, of which only the last second is kept
+        assert_eq!(perf.frames.len(), 21);
+        assert!((perf.fps() - 20.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut perf = PerfCounter::new();
+        perf.record_update(Duration::from_micros(1500), Duration::from_micros(250));
+        perf.record_dropped(4);
+        let summary = perf.summary(&[(2, 1), (21, 3)]);
+        assert!(summary.contains("Update: 1.50ms"));
+        assert!(summary.contains("Collide: 0.25ms"));
+        assert!(summary.contains("Dropped: 4"));
+        assert!(summary.ends_with("Layers: 2:1 21:3"));
+    }
+}
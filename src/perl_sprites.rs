@@ -0,0 +1,443 @@
+//! Sprites from the original asciiquarium.pl
+//!
+//! The Perl program and the sprite patches written for it define each
+//! creature as a list of art strings and a matching list of colour masks:
+//!
+//! ```perl
+//! my @shark_image = (
+//! q{
+//!  __
+//! ( '>
+//! },
+//! q{
+//!  __
+//! <' )
+//! });
+//! my @shark_mask = ( q{ ... }, q{ ... } );
+//! ```
+//!
+//! [`parse`] picks every `@name_image` / `$name_image` assignment made of
+//! string literals (`q{}`, `q##` and friends, or `'...'`) out of such a
+//! source file and pairs it with the `name_mask` assignment of the same
+//! name. `?` stays transparent, as it is in the original. Anything else in
+//! the file is ignored, so whole scripts can be read as they are.
+//!
+//! `*.pl` files in the sprite directory are loaded alongside `*.sprite`
+//! files (see [`crate::assets`]). Their creatures are registered as
+//! `perl_shark`, `perl_castle` and so on, next to the built-in creatures
+//! of the same name rather than in their place.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::assets::{AssetError, CreatureDef, Frame, Heading};
+use crate::entity::{Animation, Sprite};
+
+/// File extension of Perl sprite definitions
+pub const EXTENSION: &str = "pl";
+
+/// Put before image names to name the creatures made from them
+pub const NAME_PREFIX: &str = "perl_";
+
+/// An image variable and its masks
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerlSprite {
+    /// Variable name without `_image`; repeated names get `_2`, `_3`, ...
+    pub name: String,
+    pub frames: Vec<Frame>,
+}
+
+impl PerlSprite {
+    pub fn sprites(&self) -> Vec<Sprite> {
+        self.frames
+            .iter()
+            .map(|frame| Sprite::from_ascii_art(&frame.art, frame.mask.as_deref()))
+            .collect()
+    }
+
+    /// Every frame in turn, looping
+    pub fn animation(&self, frame_duration: Duration) -> Animation {
+        Animation::new(self.sprites(), frame_duration, true)
+    }
+
+    /// A creature with the default settings, named after the image with
+    /// [`NAME_PREFIX`]
+    ///
+    /// An even number of images is read the way the original lays them
+    /// out: the first half facing right, the second half facing left. A
+    /// single image, or an odd number, stays in place like the castle.
+    pub fn to_creature(&self) -> CreatureDef {
+        let mut def = CreatureDef::new(&format!("{NAME_PREFIX}{}", self.name));
+        if self.frames.len().is_multiple_of(2) {
+            let (right, left) = self.frames.split_at(self.frames.len() / 2);
+            def.frames = right.to_vec();
+            def.left_frames = left.to_vec();
+        } else {
+            def.frames = self.frames.clone();
+            def.heading = Heading::Right;
+            def.speed = 0.0;
+        }
+        def
+    }
+}
+
+/// A `name_image` or `name_mask` assignment
+struct Assignment {
+    name: String,
+    mask: bool,
+    strings: Vec<String>,
+}
+
+/// Find the image and mask assignments in Perl `source`
+///
+/// Errors carry the line number and an empty path for the caller to fill
+/// in; the other assignments are still read.
+pub fn parse(source: &str) -> (Vec<PerlSprite>, Vec<AssetError>) {
+    let mut scanner = Scanner { source, pos: 0 };
+    let mut assignments = Vec::new();
+    let mut errors = Vec::new();
+
+    while let Some(offset) = source[scanner.pos..].find(['@', '$']) {
+        let start = scanner.pos + offset;
+        scanner.pos = start + 1;
+        match scanner.assignment(&source[start..=start]) {
+            Ok(Some(assignment)) => assignments.push(assignment),
+            Ok(None) => {}
+            Err(message) => errors.push(AssetError {
+                path: PathBuf::new(),
+                line: source[..start].matches('\n').count() + 1,
+                message,
+            }),
+        }
+    }
+
+    // The nth image of a name goes with the nth mask of that name
+    let mut masks: HashMap<&str, Vec<&[String]>> = HashMap::new();
+    for assignment in assignments.iter().filter(|a| a.mask) {
+        masks
+            .entry(&assignment.name)
+            .or_default()
+            .push(&assignment.strings);
+    }
+
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut sprites = Vec::new();
+    for image in assignments.iter().filter(|a| !a.mask) {
+        let count = seen.entry(&image.name).or_default();
+        *count += 1;
+        let mask = masks
+            .get(image.name.as_str())
+            .and_then(|masks| masks.get(*count - 1))
+            .copied()
+            .unwrap_or_default();
+
+        // Images are often assembled at run time from empty placeholders
+        if image.strings.iter().all(|art| art.trim().is_empty()) {
+            continue;
+        }
+
+        let frames = image
+            .strings
+            .iter()
+            .enumerate()
+            .map(|(index, art)| Frame {
+                art: art.clone(),
+                // One mask can cover several frames, e.g. one per direction
+                mask: (!mask.is_empty())
+                    .then(|| mask[index * mask.len() / image.strings.len()].clone()),
+            })
+            .collect();
+        let name = match *count {
+            1 => image.name.clone(),
+            n => format!("{}_{n}", image.name),
+        };
+        sprites.push(PerlSprite { name, frames });
+    }
+
+    (sprites, errors)
+}
+
+struct Scanner<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn rest(&self) -> &str {
+        &self.source[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += c.len_utf8();
+        }
+        found
+    }
+
+    /// Skip whitespace and `#` comments
+    fn skip_space(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            let comment = trimmed
+                .starts_with('#')
+                .then(|| trimmed.find('\n').unwrap_or(trimmed.len()));
+            self.pos += rest.len() - trimmed.len();
+            match comment {
+                Some(len) => self.pos += len,
+                None => return,
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> &str {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let start = self.pos;
+        self.pos += len;
+        &self.source[start..self.pos]
+    }
+
+    /// The assignment following a sigil, if it is an image or mask made of
+    /// string literals
+    fn assignment(&mut self, sigil: &str) -> Result<Option<Assignment>, String> {
+        let variable = self.identifier().to_string();
+        let (name, mask) = match (
+            variable.strip_suffix("_image"),
+            variable.strip_suffix("_mask"),
+        ) {
+            (Some(name), _) => (name.to_string(), false),
+            (_, Some(name)) => (name.to_string(), true),
+            _ => return Ok(None),
+        };
+        if name.is_empty() {
+            return Ok(None);
+        }
+
+        self.skip_space();
+        if !self.eat('=') || matches!(self.peek(), Some('=' | '~')) {
+            return Ok(None);
+        }
+        self.skip_space();
+
+        let strings = if sigil == "@" {
+            if !self.eat('(') {
+                return Ok(None);
+            }
+            let mut strings = Vec::new();
+            loop {
+                self.skip_space();
+                if self.eat(')') {
+                    break strings;
+                }
+                match self.literal(&variable)? {
+                    Some(string) => strings.push(string),
+                    None => return Ok(None),
+                }
+                self.skip_space();
+                self.eat(',');
+            }
+        } else {
+            match self.literal(&variable)? {
+                Some(string) => vec![string],
+                None => return Ok(None),
+            }
+        };
+
+        Ok(Some(Assignment {
+            name,
+            mask,
+            strings,
+        }))
+    }
+
+    /// A `q{...}` or `'...'` string, without the newlines that open and
+    /// close it; `None` for anything else
+    fn literal(&mut self, variable: &str) -> Result<Option<String>, String> {
+        let start = self.pos;
+        let open = match self.bump() {
+            Some('\'') => '\'',
+            Some('q') => {
+                if self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                    self.pos = start;
+                    return Ok(None);
+                }
+                if self.peek().is_some_and(char::is_whitespace) {
+                    self.skip_space();
+                }
+                match self.bump() {
+                    Some(c) => c,
+                    None => return Err(format!("unterminated string in `{variable}`")),
+                }
+            }
+            _ => {
+                self.pos = start;
+                return Ok(None);
+            }
+        };
+        let close = match open {
+            '{' => '}',
+            '(' => ')',
+            '[' => ']',
+            '<' => '>',
+            c => c,
+        };
+
+        let mut string = String::new();
+        let mut depth = 0;
+        loop {
+            let Some(c) = self.bump() else {
+                return Err(format!("unterminated string in `{variable}`"));
+            };
+            match c {
+                // Only the delimiters and the backslash itself are escaped
+                '\\' => match self.peek() {
+                    Some(next) if next == '\\' || next == open || next == close => {
+                        string.push(next);
+                        self.bump();
+                    }
+                    _ => string.push('\\'),
+                },
+                c if c == close && depth == 0 => break,
+                c if c == close => {
+                    depth -= 1;
+                    string.push(c);
+                }
+                c if c == open => {
+                    depth += 1;
+                    string.push(c);
+                }
+                c => string.push(c),
+            }
+        }
+
+        let string = string.strip_prefix('\n').unwrap_or(&string);
+        let string = string.strip_suffix('\n').unwrap_or(string);
+        Ok(Some(string.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{Category, SpawnRegistry};
+
+    const SHARK: &str = r#"
+sub add_shark {
+	# Two directions
+	my @shark_image = (
+q#
+ __
+( '>
+#,q{
+ __\}
+<' )
+}
+	);
+	my @shark_mask = ( 'cc', q{
+ cc
+WWcc
+} );
+	my $dir = int(rand(2));
+	my @seaweed_image = ('','');
+	my $castle_image = q{
+|?|
+};
+	@shark_image = @_;
+}
+"#;
+
+    #[test]
+    fn test_parse_images_and_masks() {
+        let (sprites, errors) = parse(SHARK);
+        assert!(errors.is_empty());
+
+        let names: Vec<_> = sprites.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["shark", "castle"]);
+
+        let shark = &sprites[0];
+        assert_eq!(shark.frames.len(), 2);
+        assert_eq!(shark.frames[0].art, " __\n( '>");
+        assert_eq!(shark.frames[1].art, " __}\n<' )");
+        assert_eq!(shark.frames[0].mask.as_deref(), Some("cc"));
+        assert_eq!(shark.frames[1].mask.as_deref(), Some(" cc\nWWcc"));
+        assert_eq!(sprites[1].frames[0].mask, None);
+    }
+
+    #[test]
+    fn test_question_marks_are_transparent() {
+        let (sprites, _) = parse(SHARK);
+        let castle = &sprites[1].sprites()[0];
+        assert_eq!(castle.get_bounding_box(), (3, 1));
+        assert!(castle.is_transparent_at(1, 0));
+    }
+
+    #[test]
+    fn test_to_creature_splits_directions() {
+        let (sprites, _) = parse(SHARK);
+        let shark = sprites[0].to_creature();
+        assert_eq!(shark.name, "perl_shark");
+        assert_eq!(shark.frames.len(), 1);
+        assert_eq!(shark.left_frames.len(), 1);
+
+        let castle = sprites[1].to_creature();
+        assert_eq!(castle.speed, 0.0);
+        assert!(castle.left_frames.is_empty());
+    }
+
+    #[test]
+    fn test_built_in_creatures_survive_perl_sprites() {
+        let (sprites, _) = parse(SHARK);
+        let mut registry = SpawnRegistry::new();
+        let defs = sprites.iter().map(PerlSprite::to_creature).collect();
+        crate::assets::register_all(&mut registry, defs);
+
+        assert_eq!(registry.get("shark").unwrap().category, Category::Large);
+        assert_eq!(
+            registry.get("castle").unwrap().category,
+            Category::Decoration
+        );
+        assert_eq!(
+            registry.get("perl_shark").unwrap().category,
+            Category::Ambient
+        );
+        assert!(registry.get("perl_castle").is_some());
+    }
+
+    #[test]
+    fn test_shared_masks_and_repeated_names() {
+        let source = "my @m_image = ('a','b','c','d'); my @m_mask = ('r','g');\n\
+                      my @m_image = ('e'); my @m_mask = ('y');";
+        let (sprites, _) = parse(source);
+        let masks: Vec<_> = sprites[0]
+            .frames
+            .iter()
+            .map(|f| f.mask.as_deref().unwrap())
+            .collect();
+        assert_eq!(masks, vec!["r", "r", "g", "g"]);
+        assert_eq!(sprites[1].name, "m_2");
+        assert_eq!(sprites[1].frames[0].mask.as_deref(), Some("y"));
+    }
+
+    #[test]
+    fn test_unterminated_string() {
+        let (sprites, errors) = parse("my $a_image = 'x';\n\nmy @b_image = (q{\n oops");
+        assert_eq!(sprites.len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 3);
+    }
+}
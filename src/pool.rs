@@ -0,0 +1,135 @@
+//! Reusing short-lived entities instead of allocating new ones
+//!
+//! Bubbles, splashes and droplets come and go many times a second. A kind
+//! implementing [`Recycle`] and spawned with
+//! [`EntityManager::spawn_recycled`] isn't dropped when it dies: the
+//! manager keeps up to [`CAPACITY`] dead ones in its [`EntityPool`] and
+//! restarts one in place for the next spawn, keeping its box and sprites.
+//! Once the pool has warmed up a steady tank spawns them without touching
+//! the heap.
+//!
+//! [`EntityManager::spawn_recycled`]: crate::entity::EntityManager::spawn_recycled
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+use crate::entity::{Entity, EntityId};
+use crate::rng::RngHandle;
+
+/// Dead entities kept per type
+pub const CAPACITY: usize = 64;
+
+/// An entity that can be restarted instead of built anew
+pub trait Recycle: Entity + Sized {
+    /// Where and how a new one starts (position, velocity, ...)
+    type Start;
+
+    /// Build a new one, drawing anything random from `rng`
+    fn create(id: EntityId, start: Self::Start, rng: &mut RngHandle) -> Self;
+
+    /// Start over as a new entity, as [`Recycle::create`] would build it,
+    /// keeping the allocations the old one made
+    fn restart(&mut self, id: EntityId, start: Self::Start, rng: &mut RngHandle);
+}
+
+/// Dead entities waiting to be restarted, by type
+#[derive(Default)]
+pub struct EntityPool {
+    free: HashMap<TypeId, Vec<Box<dyn Entity>>>,
+    created: usize,
+    restarted: usize,
+}
+
+impl EntityPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A new `E` as `id`, restarted from a dead one if there is any
+    pub fn get<E: Recycle>(
+        &mut self,
+        id: EntityId,
+        start: E::Start,
+        rng: &mut RngHandle,
+    ) -> Box<dyn Entity> {
+        let free = self.free.entry(TypeId::of::<E>()).or_default();
+        let recycled = free.pop().and_then(|entity| {
+            let entity: Box<dyn Any> = entity;
+            entity.downcast::<E>().ok()
+        });
+        match recycled {
+            Some(mut entity) => {
+                entity.restart(id, start, rng);
+                self.restarted += 1;
+                entity
+            }
+            None => {
+                self.created += 1;
+                Box::new(E::create(id, start, rng))
+            }
+        }
+    }
+
+    /// Keep a dead entity for reuse if its type has been got from the pool
+    /// and there is room; otherwise it is dropped
+    pub fn put(&mut self, entity: Box<dyn Entity>) {
+        let type_id = (entity.as_ref() as &dyn Any).type_id();
+        if let Some(free) = self.free.get_mut(&type_id) {
+            if free.len() < CAPACITY {
+                free.push(entity);
+            }
+        }
+    }
+
+    /// Dead entities waiting, of every type
+    pub fn len(&self) -> usize {
+        self.free.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Entities built from scratch and restarted, so far
+    pub fn counts(&self) -> (usize, usize) {
+        (self.created, self.restarted)
+    }
+}
+
+impl std::fmt::Debug for EntityPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EntityPool")
+            .field("free", &self.len())
+            .field("created", &self.created)
+            .field("restarted", &self.restarted)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Bubble, Splash};
+    use crate::entity::Position;
+
+    #[test]
+    fn test_dead_entities_are_restarted() {
+        let mut pool = EntityPool::new();
+        let mut rng = RngHandle::default();
+        let first = pool.get::<Bubble>(1, Position::new(3.0, 20.0, 5), &mut rng);
+        let address = first.as_ref() as *const dyn Entity as *const ();
+        pool.put(first);
+        assert_eq!(pool.len(), 1);
+
+        let second = pool.get::<Bubble>(2, Position::new(9.0, 12.0, 5), &mut rng);
+        assert_eq!(second.as_ref() as *const dyn Entity as *const (), address);
+        assert_eq!(second.id(), 2);
+        assert_eq!(second.position(), Position::new(9.0, 12.0, 5));
+        assert!(second.is_alive());
+        assert_eq!(pool.counts(), (1, 1));
+
+        // Types never got from the pool aren't kept
+        pool.put(Box::new(Splash::new(3, Position::new(0.0, 0.0, 0))));
+        assert_eq!(pool.len(), 0);
+    }
+}
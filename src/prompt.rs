@@ -0,0 +1,120 @@
+//! The `:` command line
+//!
+//! Typing `:` opens a prompt in place of the status line, as in vim. Enter
+//! runs the line as a control command (see [`control`](crate::control):
+//! `:spawn whale`, `:set speed 0.5`, `:theme night`, `:seed 42`, `:q`) and
+//! shows the reply as a toast; Esc, or backspacing past the `:`, closes it.
+
+use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+
+/// What a key did to the prompt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptAction {
+    /// Still typing
+    Editing,
+    /// Enter was pressed on this line
+    Submit(String),
+    /// The prompt was closed without running anything
+    Cancel,
+}
+
+/// A command line being typed
+#[derive(Debug, Clone, Default)]
+pub struct Prompt {
+    input: String,
+}
+
+impl Prompt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// What has been typed so far
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Edit the line with `key`
+    pub fn handle_key(&mut self, key: KeyEvent) -> PromptAction {
+        match key.code {
+            KeyCode::Enter => PromptAction::Submit(std::mem::take(&mut self.input)),
+            KeyCode::Esc => PromptAction::Cancel,
+            KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => PromptAction::Cancel,
+            KeyCode::Backspace if self.input.is_empty() => PromptAction::Cancel,
+            KeyCode::Backspace => {
+                self.input.pop();
+                PromptAction::Editing
+            }
+            KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.push(ch);
+                PromptAction::Editing
+            }
+            _ => PromptAction::Editing,
+        }
+    }
+
+    /// Draw the line across `row` of `area` in `style`, with a cursor
+    /// after the text
+    pub fn render(&self, buf: &mut Buffer, area: Rect, row: u16, style: Style) {
+        if row >= area.height || area.is_empty() {
+            return;
+        }
+        let y = area.y + row;
+        let line = format!(":{}", self.input);
+        // Keep the end of a long line in view
+        let visible = area.width.saturating_sub(1) as usize;
+        let skip = line.chars().count().saturating_sub(visible);
+        let mut x = area.x;
+        for ch in line.chars().skip(skip) {
+            buf[(x, y)].set_char(ch).set_style(style);
+            x += 1;
+        }
+        for x in x..area.right() {
+            buf[(x, y)].set_char(' ').set_style(style);
+        }
+        if let Some(cursor) = buf.cell_mut((x, y)) {
+            cursor.set_style(style.add_modifier(Modifier::REVERSED));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_prompt_edits_and_submits_a_line() {
+        let mut prompt = Prompt::new();
+        for ch in "spawn whalee".chars() {
+            assert_eq!(
+                prompt.handle_key(key(KeyCode::Char(ch))),
+                PromptAction::Editing
+            );
+        }
+        prompt.handle_key(key(KeyCode::Backspace));
+        assert_eq!(prompt.input(), "spawn whale");
+
+        let area = Rect::new(0, 0, 8, 2);
+        let mut buf = Buffer::empty(area);
+        prompt.render(&mut buf, area, 1, Style::default());
+        let row: String = (0..8).map(|x| buf[(x, 1)].symbol()).collect();
+        assert_eq!(row, "n whale ");
+        assert!(buf[(7, 1)].modifier.contains(Modifier::REVERSED));
+
+        assert_eq!(
+            prompt.handle_key(key(KeyCode::Enter)),
+            PromptAction::Submit("spawn whale".to_string())
+        );
+        assert_eq!(
+            prompt.handle_key(key(KeyCode::Backspace)),
+            PromptAction::Cancel
+        );
+    }
+}
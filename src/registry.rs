@@ -0,0 +1,376 @@
+//! Named spawners the tank draws from
+//!
+//! The original keeps its large creatures in a hardcoded `@random_objects`
+//! array. Here every spawner is registered with a name, a weight and a
+//! [`Category`], and the spawning functions pick from the registry of the
+//! [`EntityManager`] they fill:
+//!
+//! - **Large** spawners replace a large creature when one dies; one is
+//!   picked at random, weighted. Entries that give the size of their
+//!   creature are left out when it can't fit on the screen, and made rarer
+//!   the more of the screen's height it would fill.
+//! - **Ambient** spawners run when the ambient clock fires; one is picked
+//!   at random, weighted.
+//! - **Decoration** spawners all run once, in registration order, when the
+//!   tank is filled. Ones that give their size are single pieces, run once
+//!   per segment of an ultrawide screen instead.
+//!
+//! The default registry holds the built-in creatures. Embedders can add
+//! their own, or replace or remove built-in ones by name:
+//!
+//! ```
+//! use asciiquarium_rs::registry::Category;
+//! use asciiquarium_rs::{spawning, EntityManager};
+//!
+//! let mut manager = EntityManager::new();
+//! // Whales twice as often, and no ships
+//! manager
+//!     .registry_mut()
+//!     .register("whale", 2.0, Category::Large, spawning::add_whale);
+//! manager.registry_mut().unregister("ship");
+//! ```
+//!
+//! [`EntityManager`]: crate::entity::EntityManager
+
+use std::fmt;
+use std::rc::Rc;
+
+use rand::Rng;
+use ratatui::layout::Rect;
+
+use crate::entity::EntityManager;
+use crate::rng::RngHandle;
+use crate::spawning;
+
+/// Function adding entities to the tank
+pub type Spawner = Rc<dyn Fn(&mut EntityManager, Rect)>;
+
+/// When a spawner runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// Small happenings fired by the ambient clock
+    Ambient,
+    /// Ships, whales and other creatures that take a large creature slot
+    Large,
+    /// Scenery placed once when the tank is filled
+    Decoration,
+}
+
+/// A registered spawner
+#[derive(Clone)]
+pub struct SpawnerEntry {
+    pub name: &'static str,
+    /// Relative chance of being picked within its category; zero disables
+    /// the entry
+    pub weight: f32,
+    pub category: Category,
+    pub spawn: Spawner,
+    /// Columns and rows the creature takes, if known
+    pub size: Option<(u16, u16)>,
+}
+
+impl SpawnerEntry {
+    /// The entry's weight on a screen of `bounds`
+    ///
+    /// Creatures wider or taller than the screen are never picked. Ones
+    /// filling more than half its height get rarer towards zero as they
+    /// approach the full height, so short screens favour small creatures.
+    pub fn weight_for(&self, bounds: Rect) -> f32 {
+        let Some((width, height)) = self.size else {
+            return self.weight;
+        };
+        if width > bounds.width || height > bounds.height {
+            return 0.0;
+        }
+        let share = height as f32 / bounds.height as f32;
+        self.weight * ((1.0 - share) * 2.0).min(1.0)
+    }
+}
+
+impl fmt::Debug for SpawnerEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpawnerEntry")
+            .field("name", &self.name)
+            .field("weight", &self.weight)
+            .field("category", &self.category)
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Spawners by name, in registration order
+#[derive(Debug, Clone)]
+pub struct SpawnRegistry {
+    entries: Vec<SpawnerEntry>,
+}
+
+impl Default for SpawnRegistry {
+    /// The built-in creatures and scenery
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(
+            "water_surface",
+            1.0,
+            Category::Decoration,
+            spawning::add_environment,
+        );
+        registry.register("sand", 1.0, Category::Decoration, spawning::add_sand);
+        registry.register("castle", 1.0, Category::Decoration, spawning::add_castle);
+        registry.register(
+            "seaweed",
+            1.0,
+            Category::Decoration,
+            spawning::add_all_seaweed,
+        );
+
+        // Original random objects array, all equally likely, with the
+        // largest frame of each
+        registry
+            .register("ship", 1.0, Category::Large, spawning::add_ship)
+            .size = Some((27, 7));
+        registry
+            .register("whale", 1.0, Category::Large, spawning::add_whale)
+            .size = Some((20, 7));
+        registry
+            .register(
+                "sea_monster",
+                1.0,
+                Category::Large,
+                spawning::add_sea_monster,
+            )
+            .size = Some((66, 6));
+        registry
+            .register("big_fish", 1.0, Category::Large, spawning::add_big_fish)
+            .size = Some((41, 14));
+        registry
+            .register("shark", 1.0, Category::Large, spawning::add_shark)
+            .size = Some((53, 11));
+
+        registry.register(
+            "bubble_burst",
+            1.0,
+            Category::Ambient,
+            spawning::add_bubble_burst,
+        );
+        registry.register("school", 1.0, Category::Ambient, spawning::add_school);
+        registry.register("glint", 1.0, Category::Ambient, spawning::add_glint);
+        registry.register("anchor", 1.0, Category::Ambient, spawning::add_anchor);
+        registry
+    }
+}
+
+impl SpawnRegistry {
+    /// A registry with the built-in spawners
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry without any spawners
+    pub fn empty() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add a spawner, replacing any spawner registered under `name` (it
+    /// keeps its place in the order); the new entry is returned for
+    /// setting its size
+    pub fn register(
+        &mut self,
+        name: &'static str,
+        weight: f32,
+        category: Category,
+        spawn: impl Fn(&mut EntityManager, Rect) + 'static,
+    ) -> &mut SpawnerEntry {
+        let entry = SpawnerEntry {
+            name,
+            weight: weight.max(0.0),
+            category,
+            spawn: Rc::new(spawn),
+            size: None,
+        };
+        let index = match self.entries.iter().position(|entry| entry.name == name) {
+            Some(index) => {
+                self.entries[index] = entry;
+                index
+            }
+            None => {
+                self.entries.push(entry);
+                self.entries.len() - 1
+            }
+        };
+        &mut self.entries[index]
+    }
+
+    /// Change the weight of the spawner registered under `name`; false if
+    /// there is none
+    pub fn set_weight(&mut self, name: &str, weight: f32) -> bool {
+        match self.entries.iter_mut().find(|entry| entry.name == name) {
+            Some(entry) => {
+                entry.weight = weight.max(0.0);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the spawner registered under `name`, if there is one
+    pub fn unregister(&mut self, name: &str) -> Option<SpawnerEntry> {
+        let index = self.entries.iter().position(|entry| entry.name == name)?;
+        Some(self.entries.remove(index))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SpawnerEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Enabled spawners of `category`, in registration order
+    pub fn entries(&self, category: Category) -> impl Iterator<Item = &SpawnerEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.category == category && entry.weight > 0.0)
+    }
+
+    /// Pick a spawner of `category` at random by weight, among those
+    /// `allow` accepts
+    pub fn choose(
+        &self,
+        category: Category,
+        allow: impl Fn(&SpawnerEntry) -> bool,
+        rng: &mut RngHandle,
+    ) -> Option<SpawnerEntry> {
+        self.choose_weighted(
+            category,
+            |entry| {
+                if allow(entry) {
+                    entry.weight
+                } else {
+                    0.0
+                }
+            },
+            rng,
+        )
+    }
+
+    /// Pick a spawner of `category` at random, each with the chance `weight`
+    /// gives it (zero leaves it out)
+    pub fn choose_weighted(
+        &self,
+        category: Category,
+        weight: impl Fn(&SpawnerEntry) -> f32,
+        rng: &mut RngHandle,
+    ) -> Option<SpawnerEntry> {
+        let candidates: Vec<(&SpawnerEntry, f32)> = self
+            .entries(category)
+            .map(|entry| (entry, weight(entry)))
+            .filter(|&(_, weight)| weight > 0.0)
+            .collect();
+        let total: f32 = candidates.iter().map(|&(_, weight)| weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0.0..total);
+        for &(entry, weight) in &candidates {
+            if roll < weight {
+                return Some(entry.clone());
+            }
+            roll -= weight;
+        }
+        candidates.last().map(|&(entry, _)| entry.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_nothing(_: &mut EntityManager, _: Rect) {}
+
+    #[test]
+    fn test_register_replaces_by_name() {
+        let mut registry = SpawnRegistry::empty();
+        registry.register("a", 1.0, Category::Large, spawn_nothing);
+        registry.register("b", 1.0, Category::Large, spawn_nothing);
+        registry.register("a", 3.0, Category::Large, spawn_nothing);
+
+        let names: Vec<_> = registry.entries(Category::Large).map(|e| e.name).collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert_eq!(registry.get("a").unwrap().weight, 3.0);
+
+        assert!(registry.unregister("a").is_some());
+        assert!(registry.unregister("a").is_none());
+    }
+
+    #[test]
+    fn test_choose_respects_weights_and_filter() {
+        let mut registry = SpawnRegistry::empty();
+        registry.register("never", 0.0, Category::Large, spawn_nothing);
+        registry.register("always", 1.0, Category::Large, spawn_nothing);
+        registry.register("filtered", 5.0, Category::Large, spawn_nothing);
+        registry.register("elsewhere", 5.0, Category::Ambient, spawn_nothing);
+
+        let mut rng = RngHandle::default();
+        for _ in 0..50 {
+            let entry = registry
+                .choose(Category::Large, |entry| entry.name != "filtered", &mut rng)
+                .unwrap();
+            assert_eq!(entry.name, "always");
+        }
+        assert!(registry
+            .choose(Category::Decoration, |_| true, &mut rng)
+            .is_none());
+    }
+
+    #[test]
+    fn test_weights_follow_screen_size() {
+        let registry = SpawnRegistry::default();
+        let weight = |name, width, height| {
+            registry
+                .get(name)
+                .unwrap()
+                .weight_for(Rect::new(0, 0, width, height))
+        };
+
+        // Everything fits a classic 80x24 terminal; the big fish fills
+        // more than half of it and comes a little less often
+        assert_eq!(weight("sea_monster", 80, 24), 1.0);
+        assert_eq!(weight("whale", 80, 24), 1.0);
+        assert!(weight("big_fish", 80, 24) > 0.8 && weight("big_fish", 80, 24) < 1.0);
+
+        // Narrow: no room for the sea monster or the shark
+        assert_eq!(weight("sea_monster", 60, 40), 0.0);
+        assert_eq!(weight("shark", 50, 40), 0.0);
+        assert_eq!(weight("whale", 50, 40), 1.0);
+
+        // Short: the tall creatures make way for small ones
+        assert_eq!(weight("big_fish", 120, 12), 0.0);
+        assert!(weight("shark", 120, 14) < weight("whale", 120, 14));
+        assert_eq!(weight("sea_monster", 120, 12), 1.0);
+
+        // Creatures of unknown size keep their weight
+        let mut registry = SpawnRegistry::empty();
+        registry.register("blob", 2.0, Category::Large, spawn_nothing);
+        let blob = registry.get("blob").unwrap();
+        assert_eq!(blob.weight_for(Rect::new(0, 0, 10, 5)), 2.0);
+    }
+
+    #[test]
+    fn test_set_weight() {
+        let mut registry = SpawnRegistry::default();
+        assert!(registry.set_weight("ship", 0.0));
+        assert!(!registry.set_weight("kraken", 3.0));
+        let large: Vec<_> = registry.entries(Category::Large).map(|e| e.name).collect();
+        assert!(!large.contains(&"ship"));
+    }
+
+    #[test]
+    fn test_default_has_original_random_objects() {
+        let registry = SpawnRegistry::default();
+        let large: Vec<_> = registry.entries(Category::Large).map(|e| e.name).collect();
+        assert_eq!(
+            large,
+            vec!["ship", "whale", "sea_monster", "big_fish", "shark"]
+        );
+    }
+}
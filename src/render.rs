@@ -0,0 +1,521 @@
+//! Rendering options shared by every entity
+//!
+//! [`RenderOptions`] is handed down from the app through
+//! [`EntityManager::render_all`] to each [`Entity::render`], so visual
+//! effects can be switched on without entities knowing about them.
+//!
+//! Depth dimming darkens entities the further back they are, giving a
+//! sense of murky distance: fish at the front are drawn at full
+//! brightness, the castle at the back at little more than half. On
+//! truecolor terminals the colours themselves are darkened; elsewhere the
+//! terminal's dim attribute is used for the back half of the tank.
+//!
+//! The water can also be given a vertical background gradient, light at
+//! the surface and fading to near-black at the bottom. Terminals without
+//! truecolor get a solid blue background instead.
+//!
+//! Half-block mode doubles the vertical resolution of the water gradient
+//! and of particles such as spout droplets by drawing them with the upper
+//! and lower half-block characters; sprites are still drawn over the top
+//! as plain characters.
+//!
+//! Night mode is a pass over the finished buffer: every cell is darkened
+//! except those showing something that glows, and a band of moonlight
+//! shimmers on the water surface.
+//!
+//! Reflections are another pass: whatever swims in the first few rows
+//! under the waterline tints the surface rows above it, mirrored about the
+//! waterline and dimmed.
+//!
+//! [`EntityManager::render_all`]: crate::entity::EntityManager::render_all
+//! [`Entity::render`]: crate::entity::Entity::render
+
+use crate::depth;
+use crate::palette;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use std::collections::HashSet;
+
+/// Brightness kept by the deepest layer when dimming
+const DEEPEST_BRIGHTNESS: f32 = 0.55;
+
+/// Brightness kept by unlit cells at night
+const NIGHT_BRIGHTNESS: f32 = 0.35;
+
+/// Colour of moonlight on the surface
+const MOONLIGHT: Color = Color::Rgb(210, 220, 240);
+
+/// Moonlight band centre, as a fraction of the screen width
+const MOON_COLUMN: f32 = 0.7;
+
+/// Rows under the waterline that show up in the surface
+const REFLECTION_ROWS: u16 = 3;
+
+/// Brightness of a reflection relative to what casts it
+const REFLECTION_BRIGHTNESS: f32 = 0.45;
+
+/// Glyph filling the upper half of a cell
+const UPPER_HALF: char = '▀';
+
+/// Glyph filling the lower half of a cell
+const LOWER_HALF: char = '▄';
+
+/// Background used for the water when truecolor isn't available
+const FALLBACK_WATER: Color = Color::Blue;
+
+/// Visual options applied while rendering entities
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderOptions {
+    /// Draw entities further back in darker colours
+    pub depth_dimming: bool,
+    /// Background gradient for the water (`None` keeps the terminal's own)
+    pub water_gradient: Option<WaterGradient>,
+    /// Darken the tank and light the surface with moonlight
+    pub night_mode: bool,
+    /// Draw the water gradient and particles with half blocks
+    pub half_block: bool,
+    /// Mirror what swims just under the waterline onto the surface
+    pub reflections: bool,
+}
+
+/// Vertical background gradient from the surface to the bottom of the tank
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaterGradient {
+    /// Colour of the top surface line
+    pub surface: (u8, u8, u8),
+    /// Colour of the bottom row
+    pub floor: (u8, u8, u8),
+}
+
+impl Default for WaterGradient {
+    fn default() -> Self {
+        Self {
+            surface: (30, 100, 160),
+            floor: (2, 6, 14),
+        }
+    }
+}
+
+impl WaterGradient {
+    /// Parse `RRGGBB,RRGGBB` (surface then floor, `#` optional)
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (surface, floor) = spec.split_once(',')?;
+        Some(Self {
+            surface: parse_hex(surface)?,
+            floor: parse_hex(floor)?,
+        })
+    }
+
+    /// Colour at `row`, for water spanning rows `top..=bottom`
+    pub fn color_at(&self, row: u16, top: u16, bottom: u16) -> Color {
+        let span = bottom.saturating_sub(top).max(1) as f32;
+        let t = (row.saturating_sub(top) as f32 / span).clamp(0.0, 1.0);
+        let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * t).round() as u8;
+        Color::Rgb(
+            mix(self.surface.0, self.floor.0),
+            mix(self.surface.1, self.floor.1),
+            mix(self.surface.2, self.floor.2),
+        )
+    }
+}
+
+/// A ready-made look for the water, switched to at run time (`:theme`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    /// The terminal's own background, as in the original
+    Day,
+    /// A blue gradient from the surface to the floor
+    Ocean,
+    /// Darkened, with the surface lit by moonlight
+    Night,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Day, Theme::Ocean, Theme::Night];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Day => "day",
+            Theme::Ocean => "ocean",
+            Theme::Night => "night",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+
+    /// Set the water and lighting in `options` for this theme
+    pub fn apply(self, options: &mut RenderOptions) {
+        options.night_mode = self == Theme::Night;
+        options.water_gradient = (self == Theme::Ocean).then(WaterGradient::default);
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<(u8, u8, u8)> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).ok();
+    Some((channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+impl RenderOptions {
+    /// `color` as drawn on an entity at `depth`: darkened when depth
+    /// dimming is on and the terminal can show the darker shade
+    pub fn dimmed(&self, color: Color, depth: u8) -> Color {
+        if self.depth_dimming && palette::truecolor_supported() {
+            palette::scale(color, depth_brightness(depth))
+        } else {
+            color
+        }
+    }
+
+    /// Style for a cell of colour `color` on an entity at `depth`
+    pub fn style_for(&self, color: Color, bold: bool, depth: u8) -> Style {
+        let mut style = Style::default();
+        style = if bold {
+            style.add_modifier(Modifier::BOLD)
+        } else {
+            style.remove_modifier(Modifier::BOLD)
+        };
+
+        if !self.depth_dimming {
+            return style.fg(color).remove_modifier(Modifier::DIM);
+        }
+
+        let brightness = depth_brightness(depth);
+        if palette::truecolor_supported() {
+            style.fg(self.dimmed(color, depth))
+        } else if brightness < 1.0 - (1.0 - DEEPEST_BRIGHTNESS) / 2.0 {
+            style.fg(color).add_modifier(Modifier::DIM)
+        } else {
+            style.fg(color).remove_modifier(Modifier::DIM)
+        }
+    }
+
+    /// Glyph and style for an empty water cell on `row` when the water
+    /// spans rows `top..=bottom`
+    ///
+    /// In half-block mode on truecolor terminals each cell shows two steps
+    /// of the gradient: the upper half as the glyph, the lower half as the
+    /// background. `None` where [`Self::water_background`] is `None`.
+    pub fn water_cell(&self, row: u16, top: u16, bottom: u16) -> Option<(char, Style)> {
+        let background = self.water_background(row, top, bottom)?;
+        match self.water_gradient {
+            Some(gradient) if self.half_block && palette::truecolor_supported() => {
+                let (top, bottom) = (top * 2, bottom * 2 + 1);
+                let upper = gradient.color_at(row * 2, top, bottom);
+                let lower = gradient.color_at(row * 2 + 1, top, bottom);
+                Some((UPPER_HALF, Style::default().fg(upper).bg(lower)))
+            }
+            _ => Some((' ', Style::default().bg(background))),
+        }
+    }
+
+    /// Background colour for `row` when the water spans rows `top..=bottom`
+    ///
+    /// `None` above the water or when no gradient is configured.
+    pub fn water_background(&self, row: u16, top: u16, bottom: u16) -> Option<Color> {
+        let gradient = self.water_gradient?;
+        if row < top {
+            None
+        } else if palette::truecolor_supported() {
+            Some(gradient.color_at(row, top, bottom))
+        } else {
+            Some(FALLBACK_WATER)
+        }
+    }
+}
+
+/// Draw a particle as a half block in the upper or lower half of its cell,
+/// whichever `y` falls in, leaving the other half showing the water
+pub fn draw_half_block(buffer: &mut Buffer, screen_bounds: Rect, x: f32, y: f32, color: Color) {
+    if x < 0.0 || y < 0.0 || x >= screen_bounds.width as f32 || y >= screen_bounds.height as f32 {
+        return;
+    }
+    let Some(cell) = buffer.cell_mut((x as u16, y as u16)) else {
+        return;
+    };
+
+    // An empty water cell is an upper half block over the lower colour
+    let (upper_water, lower_water) = if cell.symbol() == UPPER_HALF.to_string() {
+        (cell.fg, cell.bg)
+    } else {
+        (cell.bg, cell.bg)
+    };
+
+    if y.fract() < 0.5 {
+        cell.set_char(UPPER_HALF).set_fg(color).set_bg(lower_water);
+    } else {
+        cell.set_char(LOWER_HALF).set_fg(color).set_bg(upper_water);
+    }
+}
+
+/// Apply night lighting to a rendered buffer
+///
+/// `glowing` cells keep their colours. The moonlight band covers the
+/// surface lines (`surface`) and ripples along them over `time_secs`.
+pub fn night_pass(
+    buffer: &mut Buffer,
+    area: Rect,
+    surface: std::ops::Range<u16>,
+    glowing: &HashSet<(u16, u16)>,
+    time_secs: f32,
+) {
+    let truecolor = palette::truecolor_supported();
+    let moon_x = area.width as f32 * MOON_COLUMN;
+    let moon_half_width = (area.width as f32 / 12.0).max(4.0);
+
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            if glowing.contains(&(x, y)) {
+                continue;
+            }
+            let Some(cell) = buffer.cell_mut((x, y)) else {
+                continue;
+            };
+
+            if truecolor {
+                cell.fg = palette::scale(cell.fg, NIGHT_BRIGHTNESS);
+                cell.bg = palette::scale(cell.bg, NIGHT_BRIGHTNESS);
+            } else {
+                cell.modifier.insert(Modifier::DIM);
+            }
+
+            let in_band = (x as f32 - moon_x).abs() <= moon_half_width;
+            if surface.contains(&y) && in_band && cell.symbol() != " " {
+                // Ripple: bright where a wave travelling along the surface peaks
+                let wave = (x as f32 * 0.9 - time_secs * 4.0 + y as f32 * 2.0).sin();
+                if wave > 0.2 {
+                    cell.fg = MOONLIGHT;
+                    cell.modifier.remove(Modifier::DIM);
+                    cell.modifier.insert(Modifier::BOLD);
+                }
+            }
+        }
+    }
+}
+
+/// Tint the surface rows (`surface`) with a dimmed, mirrored copy of the
+/// rows just under them
+///
+/// Only cells showing the surface's own waves take a reflection, so a
+/// ship sailing on top keeps its colours. Cells of plain water under the
+/// waterline cast nothing.
+pub fn reflection_pass(buffer: &mut Buffer, area: Rect, surface: std::ops::Range<u16>) {
+    let truecolor = palette::truecolor_supported();
+
+    for offset in 0..REFLECTION_ROWS {
+        let below = surface.end + offset;
+        let Some(above) = surface.end.checked_sub(offset + 1) else {
+            break;
+        };
+        if above < surface.start || below >= area.bottom() {
+            break;
+        }
+
+        for x in area.left()..area.right() {
+            let Some(source) = buffer.cell((x, below)) else {
+                continue;
+            };
+            let symbol = source.symbol();
+            if symbol == " " || symbol == UPPER_HALF.to_string() || source.fg == Color::Reset {
+                continue;
+            }
+            let color = source.fg;
+
+            let Some(cell) = buffer.cell_mut((x, above)) else {
+                continue;
+            };
+            if !matches!(cell.symbol(), "~" | "^") {
+                continue;
+            }
+            if truecolor {
+                cell.fg = palette::scale(color, REFLECTION_BRIGHTNESS);
+            } else {
+                cell.fg = color;
+                cell.modifier.insert(Modifier::DIM);
+            }
+        }
+    }
+}
+
+/// Brightness (0.0-1.0) for an entity at `depth`: full in front of the
+/// fish, fading linearly to `DEEPEST_BRIGHTNESS` at the hiding layer
+pub fn depth_brightness(depth: u8) -> f32 {
+    let front = depth::FISH_START as f32;
+    let back = depth::HIDING as f32;
+    let t = ((depth as f32 - front) / (back - front)).clamp(0.0, 1.0);
+    1.0 - t * (1.0 - DEEPEST_BRIGHTNESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depth_brightness_falls_off() {
+        assert_eq!(depth_brightness(depth::SHARK), 1.0);
+        assert_eq!(depth_brightness(depth::FISH_START), 1.0);
+        assert!(depth_brightness(depth::FISH_END) < 1.0);
+        assert!(depth_brightness(depth::CASTLE) < depth_brightness(depth::FISH_END));
+        assert_eq!(depth_brightness(depth::HIDING), DEEPEST_BRIGHTNESS);
+    }
+
+    #[test]
+    fn test_reflection_mirrors_rows_under_the_waterline() {
+        let area = Rect::new(0, 0, 3, 8);
+        let mut buffer = Buffer::empty(area);
+        for y in 1..4 {
+            for x in 0..3 {
+                buffer[(x, y)].set_char('~').set_fg(Color::Cyan);
+            }
+        }
+        // A fish just under the waterline (row 4) and one further down
+        buffer[(0, 4)].set_char('>').set_fg(Color::Red);
+        buffer[(1, 5)].set_char('>').set_fg(Color::Green);
+        buffer[(2, 7)].set_char('>').set_fg(Color::Yellow);
+        // A ship on the surface keeps its colours
+        buffer[(1, 2)].set_char('|').set_fg(Color::White);
+
+        reflection_pass(&mut buffer, area, 1..4);
+
+        let tinted = |color| {
+            if palette::truecolor_supported() {
+                palette::scale(color, REFLECTION_BRIGHTNESS)
+            } else {
+                color
+            }
+        };
+        assert_eq!(buffer[(0, 3)].fg, tinted(Color::Red));
+        assert_eq!(buffer[(1, 2)].fg, Color::White);
+        assert_eq!(buffer[(2, 1)].fg, Color::Cyan);
+        assert_eq!(buffer[(0, 2)].fg, Color::Cyan);
+    }
+
+    #[test]
+    fn test_gradient_runs_surface_to_floor() {
+        let gradient = WaterGradient::default();
+        assert_eq!(gradient.color_at(5, 5, 24), Color::Rgb(30, 100, 160));
+        assert_eq!(gradient.color_at(24, 5, 24), Color::Rgb(2, 6, 14));
+        assert_eq!(gradient.color_at(30, 5, 24), Color::Rgb(2, 6, 14));
+    }
+
+    #[test]
+    fn test_parse_gradient() {
+        let gradient = WaterGradient::parse("#336699,000000").unwrap();
+        assert_eq!(gradient.surface, (0x33, 0x66, 0x99));
+        assert_eq!(gradient.floor, (0, 0, 0));
+        assert_eq!(WaterGradient::parse("336699"), None);
+        assert_eq!(WaterGradient::parse("3366zz,000000"), None);
+    }
+
+    #[test]
+    fn test_water_background_only_below_surface() {
+        let options = RenderOptions {
+            water_gradient: Some(WaterGradient::default()),
+            ..Default::default()
+        };
+        assert_eq!(options.water_background(4, 5, 24), None);
+        assert!(options.water_background(5, 5, 24).is_some());
+        assert_eq!(RenderOptions::default().water_background(10, 5, 24), None);
+    }
+
+    #[test]
+    fn test_half_block_water_has_two_steps_per_cell() {
+        let options = RenderOptions {
+            water_gradient: Some(WaterGradient::default()),
+            half_block: true,
+            ..Default::default()
+        };
+        let (glyph, style) = options.water_cell(5, 5, 24).unwrap();
+        if palette::truecolor_supported() {
+            assert_eq!(glyph, UPPER_HALF);
+            assert_eq!(style.fg, Some(Color::Rgb(30, 100, 160)));
+            assert_ne!(style.fg, style.bg);
+        } else {
+            assert_eq!(glyph, ' ');
+            assert_eq!(style.bg, Some(FALLBACK_WATER));
+        }
+        assert_eq!(options.water_cell(4, 5, 24), None);
+    }
+
+    #[test]
+    fn test_half_block_particle_keeps_water_behind() {
+        let area = Rect::new(0, 0, 2, 2);
+        let mut buffer = Buffer::empty(area);
+        buffer[(0, 0)]
+            .set_char(UPPER_HALF)
+            .set_fg(Color::Blue)
+            .set_bg(Color::Black);
+
+        draw_half_block(&mut buffer, area, 0.0, 0.7, Color::Cyan);
+        assert_eq!(buffer[(0, 0)].symbol(), "▄");
+        assert_eq!(buffer[(0, 0)].fg, Color::Cyan);
+        assert_eq!(buffer[(0, 0)].bg, Color::Blue);
+
+        draw_half_block(&mut buffer, area, 1.0, 1.2, Color::Cyan);
+        assert_eq!(buffer[(1, 1)].symbol(), "▀");
+
+        // Off-screen particles are skipped
+        draw_half_block(&mut buffer, area, -1.0, 0.0, Color::Cyan);
+    }
+
+    #[test]
+    fn test_night_pass_spares_glowing_cells() {
+        let area = Rect::new(0, 0, 4, 2);
+        let mut buffer = Buffer::empty(area);
+        for x in 0..4 {
+            buffer[(x, 1)].set_char('#').set_fg(Color::Red);
+        }
+        let glowing = HashSet::from([(3, 1)]);
+
+        night_pass(&mut buffer, area, 0..1, &glowing, 0.0);
+
+        assert_eq!(buffer[(3, 1)].fg, Color::Red);
+        assert_eq!(buffer[(3, 1)].modifier, Modifier::empty());
+        assert_ne!(buffer[(0, 1)], buffer[(3, 1)]);
+    }
+
+    #[test]
+    fn test_moonlight_only_on_surface() {
+        let area = Rect::new(0, 0, 40, 3);
+        let mut buffer = Buffer::empty(area);
+        for x in 0..40 {
+            for y in 0..3 {
+                buffer[(x, y)].set_char('~').set_fg(Color::Cyan);
+            }
+        }
+
+        night_pass(&mut buffer, area, 0..1, &HashSet::new(), 0.0);
+
+        let lit = |y: u16| (0..40).filter(|&x| buffer[(x, y)].fg == MOONLIGHT).count();
+        assert!(lit(0) > 0);
+        assert_eq!(lit(2), 0);
+    }
+
+    #[test]
+    fn test_no_dimming_by_default() {
+        let options = RenderOptions::default();
+        let style = options.style_for(Color::Red, false, depth::CASTLE);
+        assert_eq!(style.fg, Some(Color::Red));
+        assert!(!style.add_modifier.contains(Modifier::DIM));
+    }
+
+    #[test]
+    fn test_dimming_only_affects_background() {
+        let options = RenderOptions {
+            depth_dimming: true,
+            ..Default::default()
+        };
+        let front = options.style_for(Color::Red, true, depth::SHARK);
+        let back = options.style_for(Color::Red, true, depth::CASTLE);
+
+        assert!(front.add_modifier.contains(Modifier::BOLD));
+        assert!(!front.add_modifier.contains(Modifier::DIM));
+        assert_ne!(front, back);
+    }
+}
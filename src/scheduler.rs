@@ -0,0 +1,287 @@
+//! Keeping the tank's populations topped up
+//!
+//! The tank keeps three populations at a steady size: the fish, the
+//! seaweed and the large creatures (ships, whales, sharks, ...). Each has a
+//! [`SpawnPolicy`] saying how many there should be, how long a replacement
+//! waits after one dies and how soon after one another replacements may
+//! arrive. The [`EntityManager`] owns the [`SpawnScheduler`]: whenever a
+//! member of a population dies it books a replacement, and it runs the
+//! population's spawner once the booking is due and the population is
+//! below its target.
+//!
+//! The defaults replace everything at once, as the original does; the
+//! filling and resizing functions in [`spawning`] set the
+//! targets for the screen size.
+//!
+//! ```
+//! use std::time::Duration;
+//! use asciiquarium_rs::scheduler::Population;
+//! use asciiquarium_rs::EntityManager;
+//!
+//! let mut manager = EntityManager::new();
+//! // A new large creature a few seconds after the last one leaves
+//! manager.scheduler_mut().policy_mut(Population::Large).respawn_delay = Duration::from_secs(5);
+//! ```
+//!
+//! [`EntityManager`]: crate::entity::EntityManager
+
+use std::time::Duration;
+
+use ratatui::layout::Rect;
+
+use crate::entity::EntityManager;
+use crate::spawning;
+
+/// A group of entities kept at a target size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Population {
+    /// The fish the tank is filled with (not bred fish or passing schools)
+    Fish,
+    Seaweed,
+    /// Creatures taking a large creature slot
+    Large,
+}
+
+impl Population {
+    pub const ALL: [Population; 3] = [Population::Fish, Population::Seaweed, Population::Large];
+
+    /// The function adding one member
+    pub fn spawner(self) -> fn(&mut EntityManager, Rect) {
+        match self {
+            Population::Fish => spawning::add_fish,
+            Population::Seaweed => spawning::add_seaweed,
+            Population::Large => spawning::random_object,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// What follows an entity's death, see [`Entity::respawn_policy`] and
+/// [`EntityManager::set_respawn_policy`]
+///
+/// [`Entity::respawn_policy`]: crate::entity::Entity::respawn_policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RespawnPolicy {
+    /// Nothing follows
+    None,
+    /// A replacement is booked in the entity's population, if it has one;
+    /// a large creature's slot is refilled from the registry, whatever
+    /// kind of creature held it
+    RespawnSame,
+    /// A large creature slot is booked, whatever the entity was
+    RespawnRandomLarge,
+    /// A splat is left where the entity died, followed by a replacement
+    /// as for [`RespawnPolicy::RespawnSame`] if `respawn` is set
+    SpawnSplat { respawn: bool },
+}
+
+impl RespawnPolicy {
+    /// Parse a `--respawn` policy name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "none" => Some(Self::None),
+            "same" => Some(Self::RespawnSame),
+            "random_large" => Some(Self::RespawnRandomLarge),
+            "splat" => Some(Self::SpawnSplat { respawn: false }),
+            "splat_respawn" => Some(Self::SpawnSplat { respawn: true }),
+            _ => None,
+        }
+    }
+}
+
+/// How a population is kept topped up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpawnPolicy {
+    /// Members there should be; replacements stop once this many are alive
+    pub target: usize,
+    /// Time between a member dying and its replacement arriving
+    pub respawn_delay: Duration,
+    /// Least time between two replacements
+    pub cooldown: Duration,
+    /// Time between newcomers arriving on their own while the population
+    /// is below its target, on top of replacements (none by default)
+    pub interval: Option<Duration>,
+}
+
+impl SpawnPolicy {
+    /// Up to `target` members, replaced at once
+    pub fn new(target: usize) -> Self {
+        Self {
+            target,
+            respawn_delay: Duration::ZERO,
+            cooldown: Duration::ZERO,
+            interval: None,
+        }
+    }
+}
+
+/// Spawn policies and the replacements booked under them
+#[derive(Debug, Clone)]
+pub struct SpawnScheduler {
+    policies: [SpawnPolicy; 3],
+    /// Booked replacements and the time left until each is due
+    booked: Vec<(Population, Duration)>,
+    /// Time left until each population may spawn again
+    cooldowns: [Duration; 3],
+    /// Time since each population's last newcomer, see
+    /// [`SpawnPolicy::interval`]
+    since_arrival: [Duration; 3],
+}
+
+impl Default for SpawnScheduler {
+    /// No fish or seaweed until the tank is filled, one large creature
+    fn default() -> Self {
+        Self {
+            policies: [
+                SpawnPolicy::new(0),
+                SpawnPolicy::new(0),
+                SpawnPolicy::new(1),
+            ],
+            booked: Vec::new(),
+            cooldowns: [Duration::ZERO; 3],
+            since_arrival: [Duration::ZERO; 3],
+        }
+    }
+}
+
+impl SpawnScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn policy(&self, population: Population) -> &SpawnPolicy {
+        &self.policies[population.index()]
+    }
+
+    pub fn policy_mut(&mut self, population: Population) -> &mut SpawnPolicy {
+        &mut self.policies[population.index()]
+    }
+
+    pub fn target(&self, population: Population) -> usize {
+        self.policy(population).target
+    }
+
+    pub fn set_target(&mut self, population: Population, target: usize) {
+        self.policy_mut(population).target = target;
+    }
+
+    /// The same policies with nothing booked (for a rebuilt tank)
+    pub fn fresh(&self) -> Self {
+        Self {
+            policies: self.policies,
+            ..Self::default()
+        }
+    }
+
+    /// Book a replacement for a member of `population` that just died
+    pub fn book(&mut self, population: Population) {
+        let delay = self.policy(population).respawn_delay;
+        self.booked.push((population, delay));
+    }
+
+    /// Replacements booked but not yet spawned
+    pub fn booked(&self, population: Population) -> usize {
+        self.booked.iter().filter(|(p, _)| *p == population).count()
+    }
+
+    /// Advance the clocks by `delta_time` and take the replacements that
+    /// are due, oldest booking first, then any newcomers whose interval
+    /// has passed. A population in its cooldown keeps its due bookings for
+    /// later.
+    pub fn advance(&mut self, delta_time: Duration) -> Vec<Population> {
+        for cooldown in &mut self.cooldowns {
+            *cooldown = cooldown.saturating_sub(delta_time);
+        }
+        for (_, remaining) in &mut self.booked {
+            *remaining = remaining.saturating_sub(delta_time);
+        }
+
+        let mut due = Vec::new();
+        let policies = self.policies;
+        let cooldowns = &mut self.cooldowns;
+        self.booked.retain(|&(population, remaining)| {
+            let cooldown = &mut cooldowns[population.index()];
+            if remaining > Duration::ZERO || *cooldown > Duration::ZERO {
+                return true;
+            }
+            *cooldown = policies[population.index()].cooldown;
+            due.push(population);
+            false
+        });
+
+        for population in Population::ALL {
+            let Some(interval) = self.policy(population).interval else {
+                continue;
+            };
+            let since = &mut self.since_arrival[population.index()];
+            *since += delta_time;
+            if *since >= interval {
+                *since = Duration::ZERO;
+                due.push(population);
+            }
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_respawn_delay() {
+        let mut scheduler = SpawnScheduler::new();
+        scheduler.policy_mut(Population::Fish).respawn_delay = Duration::from_secs(2);
+        scheduler.book(Population::Fish);
+        scheduler.book(Population::Seaweed);
+
+        assert_eq!(scheduler.advance(Duration::ZERO), vec![Population::Seaweed]);
+        assert!(scheduler.advance(Duration::from_secs(1)).is_empty());
+        assert_eq!(scheduler.booked(Population::Fish), 1);
+        assert_eq!(
+            scheduler.advance(Duration::from_secs(1)),
+            vec![Population::Fish]
+        );
+    }
+
+    #[test]
+    fn test_cooldown_spaces_replacements() {
+        let mut scheduler = SpawnScheduler::new();
+        scheduler.policy_mut(Population::Large).cooldown = Duration::from_secs(3);
+        scheduler.book(Population::Large);
+        scheduler.book(Population::Large);
+
+        assert_eq!(scheduler.advance(Duration::ZERO), vec![Population::Large]);
+        assert!(scheduler.advance(Duration::from_secs(2)).is_empty());
+        assert_eq!(
+            scheduler.advance(Duration::from_secs(1)),
+            vec![Population::Large]
+        );
+        assert_eq!(scheduler.booked(Population::Large), 0);
+    }
+
+    #[test]
+    fn test_interval_brings_newcomers() {
+        let mut scheduler = SpawnScheduler::new();
+        scheduler.policy_mut(Population::Large).interval = Some(Duration::from_secs(10));
+        assert!(scheduler.advance(Duration::from_secs(6)).is_empty());
+        assert_eq!(
+            scheduler.advance(Duration::from_secs(4)),
+            vec![Population::Large]
+        );
+        assert!(scheduler.advance(Duration::from_secs(9)).is_empty());
+    }
+
+    #[test]
+    fn test_fresh_keeps_policies_only() {
+        let mut scheduler = SpawnScheduler::new();
+        scheduler.set_target(Population::Fish, 12);
+        scheduler.book(Population::Fish);
+        let fresh = scheduler.fresh();
+        assert_eq!(fresh.target(Population::Fish), 12);
+        assert_eq!(fresh.booked(Population::Fish), 0);
+    }
+}
@@ -0,0 +1,90 @@
+//! Running as a screensaver
+//!
+//! `--screensaver` (on by itself when `XSCREENSAVER_WINDOW` is set) makes
+//! the aquarium behave the way screensaver frameworks expect:
+//!
+//! - any key closes it
+//! - it closes when its standard input, if not a terminal, reaches end of
+//!   file, so a parent holding a pipe open can stop it by closing it
+//!
+//! The tank fills whatever terminal the framework runs it in, so the
+//! window size comes from the terminal, or from `COLUMNS`/`LINES` without
+//! one. `asciiquarium screensaver-config` prints entries for xscreensaver,
+//! desktop files and swayidle that start it that way.
+//!
+//! In every mode, SIGTERM, SIGHUP and SIGINT end the app through its
+//! normal exit path so the terminal is restored.
+
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+/// Environment variable xscreensaver sets for the programs it runs
+pub const XSCREENSAVER_WINDOW: &str = "XSCREENSAVER_WINDOW";
+
+/// Whether we look to have been started by xscreensaver
+pub fn detected() -> bool {
+    std::env::var_os(XSCREENSAVER_WINDOW).is_some()
+}
+
+/// Set `stop` once standard input reaches end of file (or fails)
+pub fn stop_on_stdin_eof(stop: Arc<AtomicBool>) {
+    thread::spawn(move || {
+        let mut stdin = io::stdin();
+        let mut buf = [0; 256];
+        while matches!(stdin.read(&mut buf), Ok(read) if read > 0) {}
+        stop.store(true, Ordering::Relaxed);
+    });
+}
+
+/// Set `stop` on SIGTERM, SIGHUP or SIGINT instead of dying on the spot
+#[cfg(unix)]
+pub fn stop_on_signals(stop: &Arc<AtomicBool>) -> io::Result<()> {
+    use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+
+    for signal in [SIGTERM, SIGHUP, SIGINT] {
+        signal_hook::flag::register(signal, Arc::clone(stop))?;
+    }
+    Ok(())
+}
+
+/// Configuration snippets that run `program` as a screensaver
+pub fn config(program: &str) -> String {
+    format!(
+        "\
+# xscreensaver: add to the programs: list in ~/.xscreensaver
+  \"Asciiquarium\"  xterm -into $XSCREENSAVER_WINDOW -bg black -fg white \\
+                    -e {program} --screensaver \\n\\
+
+# Desktop file, e.g. ~/.local/share/applications/screensavers/asciiquarium.desktop
+[Desktop Entry]
+Type=Application
+Name=Asciiquarium
+Comment=An aquarium animation in ASCII art
+TryExec=xterm
+Exec=xterm -fullscreen -bg black -fg white -e {program} --screensaver
+Categories=Screensaver;
+NoDisplay=true
+
+# swayidle (Wayland): start after 5 minutes, stop on activity
+swayidle -w \\
+    timeout 300 'foot --fullscreen --app-id=asciiquarium {program} --screensaver &' \\
+    resume 'pkill -TERM -f -- \"{program} --screensaver\"'
+"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_names_the_program() {
+        let config = config("/usr/bin/asciiquarium");
+        assert!(config.contains("-e /usr/bin/asciiquarium --screensaver \\n\\\n"));
+        assert!(config.contains("\nExec=xterm -fullscreen"));
+        assert!(config.contains("Categories=Screensaver;"));
+        assert!(config.contains("swayidle -w"));
+    }
+}
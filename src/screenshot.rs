@@ -0,0 +1,66 @@
+//! Saving the frame on screen
+//!
+//! F12 (or Ctrl+S) writes the last frame drawn to two files in the working
+//! directory, named after the local time: `.txt` with just the characters
+//! and `.ans` with the ANSI colours, for `cat` or a terminal art viewer.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use ratatui::buffer::Buffer;
+
+use crate::clock;
+use crate::simulation::{buffer_to_ansi, buffer_to_text};
+
+/// Write `frame` to `dir`, returning the plain text file's path
+///
+/// Files are never overwritten: a second screenshot in the same second
+/// gets a `-2` (`-3`, ...) after the time.
+pub fn save(frame: &Buffer, dir: &Path) -> io::Result<PathBuf> {
+    let stamp = clock::local_timestamp().unwrap_or_else(|| "frame".to_string());
+    for n in 1.. {
+        let stem = match n {
+            1 => format!("asciiquarium-{stamp}"),
+            n => format!("asciiquarium-{stamp}-{n}"),
+        };
+        let text = dir.join(format!("{stem}.txt"));
+        let ansi = dir.join(format!("{stem}.ans"));
+        if ansi.exists() {
+            continue;
+        }
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&text) {
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            result => result?,
+        };
+        writeln!(file, "{}", buffer_to_text(frame))?;
+        std::fs::write(&ansi, buffer_to_ansi(frame) + "\n")?;
+        return Ok(text);
+    }
+    unreachable!("ran out of screenshot names")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::{Color, Style};
+
+    #[test]
+    fn test_save_writes_text_and_ansi_without_overwriting() {
+        let dir =
+            std::env::temp_dir().join(format!("asciiquarium-screenshot-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut frame = Buffer::empty(Rect::new(0, 0, 4, 2));
+        frame.set_string(0, 0, "><>", Style::default().fg(Color::Yellow));
+
+        let first = save(&frame, &dir).unwrap();
+        let second = save(&frame, &dir).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), "><> \n    \n");
+        let ansi = std::fs::read_to_string(first.with_extension("ans")).unwrap();
+        assert!(ansi.contains("\x1b[") && ansi.contains("><>"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
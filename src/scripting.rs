@@ -0,0 +1,562 @@
+//! Rhai scripts driving entities (the `scripting` feature)
+//!
+//! Every `*.rhai` file in `~/.config/asciiquarium-rs/scripts/` (or the
+//! directory given with `--scripts`) is a [Rhai](https://rhai.rs) script.
+//! The file name says which entities it drives: the `update` function of
+//! `fish.rhai` runs every step for each live fish, that of `shark.rhai` for
+//! each shark, and so on for every [`Entity::entity_type`]. Any script may
+//! also define `on_event`, which hears each of the tank's
+//! [`AquariumEvent`]s as the map [`AquariumEvent::to_json`] describes.
+//!
+//! ```text
+//! // fish.rhai: fish dart away from sharks
+//! fn update(id, dt) {
+//!     for other in nearby(id, 12.0) {
+//!         if entity_type(other) == "shark" {
+//!             let v = velocity(id);
+//!             set_velocity(id, v.dx * 2.0, v.dy);
+//!         }
+//!     }
+//! }
+//!
+//! // A school follows every fish the shark catches
+//! fn on_event(event) {
+//!     if event.event == "killed" && event.type == "fish" {
+//!         spawn_entity("school");
+//!     }
+//! }
+//! ```
+//!
+//! Scripts act on the tank only through these functions:
+//!
+//! - `position(id)` and `velocity(id)`: maps with `x`/`y` and `dx`/`dy`,
+//!   or `()` once the entity is gone
+//! - `set_position(id, x, y)` and `set_velocity(id, dx, dy)`
+//! - `entity_type(id)`: the entity's type name
+//! - `kill(id)`: the entity dies on the next update, as if eaten
+//! - `spawn_entity(name)`: runs the [`registry`](crate::registry) spawner
+//!   called `name` (`spawn` itself is a reserved word in Rhai)
+//! - `nearby(id, radius)`: other live entities whose centres lie within
+//!   `radius` columns of its centre, nearest first; rows count double, as
+//!   cells are about twice as tall as they are wide
+//!
+//! Changes are made once every script has run for the step. A script
+//! that fails, or runs too long, is stopped; events caused by scripts are
+//! not passed back to them.
+//!
+//! [`Entity::entity_type`]: crate::entity::Entity::entity_type
+
+use crate::entity::{EntityId, EntityManager, Position, Velocity};
+use crate::hooks::AquariumEvent;
+use ratatui::layout::Rect;
+use rhai::{Array, CallFnOptions, Dynamic, Engine, Map, Scope, AST, FLOAT, INT};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// File extension of script files
+pub const EXTENSION: &str = "rhai";
+
+/// Operations one call into a script may take before it is stopped, so a
+/// runaway loop can't freeze the tank
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// A script that couldn't be loaded or stopped running
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// An entity as scripts see it during a step
+struct Seen {
+    entity_type: &'static str,
+    position: Position,
+    velocity: Velocity,
+    size: (f32, f32),
+    alive: bool,
+}
+
+impl Seen {
+    fn centre(&self) -> (f32, f32) {
+        (
+            self.position.x + self.size.0 / 2.0,
+            self.position.y + self.size.1 / 2.0,
+        )
+    }
+}
+
+/// A change a script asked for, made after every script has run
+enum Request {
+    Move(EntityId, Position),
+    Steer(EntityId, Velocity),
+    Kill(EntityId),
+    Spawn { script: usize, name: String },
+}
+
+/// The tank as the script functions see it, and what they asked for
+#[derive(Default)]
+struct Tank {
+    entities: BTreeMap<EntityId, Seen>,
+    requests: Vec<Request>,
+    /// Index of the script running now
+    running: usize,
+}
+
+impl Tank {
+    fn seen(&self, id: INT) -> Option<&Seen> {
+        self.entities.get(&(id as EntityId))
+    }
+
+    fn seen_mut(&mut self, id: INT) -> Option<&mut Seen> {
+        self.entities.get_mut(&(id as EntityId))
+    }
+}
+
+/// One loaded script
+struct Script {
+    path: PathBuf,
+    entity_type: String,
+    ast: AST,
+    update: bool,
+    on_event: bool,
+    stopped: bool,
+}
+
+/// Scripts loaded for a tank, with the events they have yet to hear about
+pub struct Scripts {
+    engine: Engine,
+    scripts: Vec<Script>,
+    tank: Rc<RefCell<Tank>>,
+    events: Rc<RefCell<Vec<AquariumEvent>>>,
+}
+
+impl Default for Scripts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scripts {
+    pub fn new() -> Self {
+        let tank = Rc::new(RefCell::new(Tank::default()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        // The terminal belongs to the tank
+        engine.on_print(|_| {});
+        engine.on_debug(|_, _, _| {});
+        register_api(&mut engine, &tank);
+        Self {
+            engine,
+            scripts: Vec::new(),
+            tank,
+            events: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Compile `source` as the script at `path`, driving the entities its
+    /// file name is called after
+    pub fn add(&mut self, path: &Path, source: &str) -> Result<(), ScriptError> {
+        let error = |message: String| ScriptError {
+            path: path.to_path_buf(),
+            message,
+        };
+        let ast = self
+            .engine
+            .compile(source)
+            .map_err(|err| error(err.to_string()))?;
+        self.engine
+            .run_ast(&ast)
+            .map_err(|err| error(err.to_string()))?;
+
+        let defines = |name: &str, arity: usize| {
+            ast.iter_functions()
+                .any(|f| f.name == name && f.params.len() == arity)
+        };
+        let script = Script {
+            path: path.to_path_buf(),
+            entity_type: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            update: defines("update", 2),
+            on_event: defines("on_event", 1),
+            ast,
+            stopped: false,
+        };
+        self.scripts.push(script);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Have the scripts hear `manager`'s events from now on
+    pub fn listen(&self, manager: &mut EntityManager) {
+        let events = Rc::clone(&self.events);
+        manager.on_event(move |event| events.borrow_mut().push(*event));
+    }
+
+    /// Deliver the events heard since the last step, run `update` for
+    /// every entity each script drives, then make the changes they asked
+    /// for; returns the scripts stopped in this step
+    pub fn run(
+        &mut self,
+        manager: &mut EntityManager,
+        delta_time: Duration,
+        bounds: Rect,
+    ) -> Vec<ScriptError> {
+        self.look_at(manager);
+        let events = std::mem::take(&mut *self.events.borrow_mut());
+        let dt = delta_time.as_secs_f64() as FLOAT;
+        let mut errors = Vec::new();
+
+        for (index, script) in self.scripts.iter_mut().enumerate() {
+            if script.stopped {
+                continue;
+            }
+            self.tank.borrow_mut().running = index;
+            let result = run_script(&self.engine, script, &events, &self.tank, dt);
+            if let Err(message) = result {
+                script.stopped = true;
+                errors.push(ScriptError {
+                    path: script.path.clone(),
+                    message,
+                });
+            }
+        }
+
+        let requests = std::mem::take(&mut self.tank.borrow_mut().requests);
+        let heard = self.events.borrow().len();
+        for request in requests {
+            match request {
+                Request::Move(id, position) => {
+                    if let Some(entity) = manager.get_entity_mut(id) {
+                        entity.set_position(position);
+                    }
+                }
+                Request::Steer(id, velocity) => {
+                    if let Some(entity) = manager.get_entity_mut(id) {
+                        entity.set_velocity(velocity);
+                    }
+                }
+                Request::Kill(id) => {
+                    if let Some(entity) = manager.get_entity_mut(id) {
+                        entity.kill();
+                    }
+                }
+                Request::Spawn { script, name } => match manager.registry().get(&name).cloned() {
+                    Some(entry) => (entry.spawn)(manager, bounds),
+                    None => errors.push(ScriptError {
+                        path: self.scripts[script].path.clone(),
+                        message: format!("no spawner called {name}"),
+                    }),
+                },
+            }
+        }
+        self.events.borrow_mut().truncate(heard);
+        errors
+    }
+
+    /// Take in the tank as it is before the scripts run
+    fn look_at(&self, manager: &EntityManager) {
+        let mut tank = self.tank.borrow_mut();
+        tank.entities = manager
+            .entities()
+            .map(|entity| {
+                let (width, height) = entity.get_current_sprite().get_bounding_box();
+                let seen = Seen {
+                    entity_type: entity.entity_type(),
+                    position: entity.position(),
+                    velocity: entity.velocity(),
+                    size: (width as f32, height as f32),
+                    alive: entity.is_alive(),
+                };
+                (entity.id(), seen)
+            })
+            .collect();
+    }
+}
+
+/// Deliver `events` to one script and update the entities it drives
+fn run_script(
+    engine: &Engine,
+    script: &Script,
+    events: &[AquariumEvent],
+    tank: &RefCell<Tank>,
+    dt: FLOAT,
+) -> Result<(), String> {
+    let mut scope = Scope::new();
+    let mut call = |name: &str, args: Vec<Dynamic>| {
+        let options = CallFnOptions::new().eval_ast(false);
+        engine
+            .call_fn_with_options::<Dynamic>(options, &mut scope, &script.ast, name, args)
+            .map(drop)
+            .map_err(|err| err.to_string())
+    };
+
+    if script.on_event {
+        for event in events {
+            let event = engine
+                .parse_json(event.to_json(), true)
+                .map_err(|err| err.to_string())?;
+            call("on_event", vec![event.into()])?;
+        }
+    }
+
+    if script.update {
+        let ids: Vec<EntityId> = tank
+            .borrow()
+            .entities
+            .iter()
+            .filter(|(_, seen)| seen.alive && seen.entity_type == script.entity_type)
+            .map(|(&id, _)| id)
+            .collect();
+        for id in ids {
+            // Killed by an earlier update this step
+            if !tank.borrow().entities[&id].alive {
+                continue;
+            }
+            call("update", vec![(id as INT).into(), dt.into()])?;
+        }
+    }
+    Ok(())
+}
+
+/// A number given by a script, whole or not
+fn number(value: &Dynamic) -> Option<f32> {
+    value
+        .as_float()
+        .ok()
+        .or_else(|| value.as_int().ok().map(|int| int as FLOAT))
+        .map(|float| float as f32)
+}
+
+fn pair(a: (&str, f32), b: (&str, f32)) -> Dynamic {
+    let mut map = Map::new();
+    map.insert(a.0.into(), (a.1 as FLOAT).into());
+    map.insert(b.0.into(), (b.1 as FLOAT).into());
+    map.into()
+}
+
+/// Register the functions scripts call, all working on `tank`
+fn register_api(engine: &mut Engine, tank: &Rc<RefCell<Tank>>) {
+    let seen = Rc::clone(tank);
+    engine.register_fn("position", move |id: INT| match seen.borrow().seen(id) {
+        Some(seen) => pair(("x", seen.position.x), ("y", seen.position.y)),
+        None => Dynamic::UNIT,
+    });
+
+    let seen = Rc::clone(tank);
+    engine.register_fn("velocity", move |id: INT| match seen.borrow().seen(id) {
+        Some(seen) => pair(("dx", seen.velocity.dx), ("dy", seen.velocity.dy)),
+        None => Dynamic::UNIT,
+    });
+
+    let seen = Rc::clone(tank);
+    engine.register_fn("entity_type", move |id: INT| match seen.borrow().seen(id) {
+        Some(seen) => Dynamic::from(seen.entity_type.to_string()),
+        None => Dynamic::UNIT,
+    });
+
+    let seen = Rc::clone(tank);
+    engine.register_fn("set_position", move |id: INT, x: Dynamic, y: Dynamic| {
+        let mut tank = seen.borrow_mut();
+        let (Some(x), Some(y)) = (number(&x), number(&y)) else {
+            return;
+        };
+        if let Some(seen) = tank.seen_mut(id) {
+            seen.position.x = x;
+            seen.position.y = y;
+            let request = Request::Move(id as EntityId, seen.position);
+            tank.requests.push(request);
+        }
+    });
+
+    let seen = Rc::clone(tank);
+    engine.register_fn("set_velocity", move |id: INT, dx: Dynamic, dy: Dynamic| {
+        let mut tank = seen.borrow_mut();
+        let (Some(dx), Some(dy)) = (number(&dx), number(&dy)) else {
+            return;
+        };
+        if let Some(seen) = tank.seen_mut(id) {
+            seen.velocity = Velocity::new(dx, dy);
+            let request = Request::Steer(id as EntityId, seen.velocity);
+            tank.requests.push(request);
+        }
+    });
+
+    let seen = Rc::clone(tank);
+    engine.register_fn("kill", move |id: INT| {
+        let mut tank = seen.borrow_mut();
+        if let Some(seen) = tank.seen_mut(id) {
+            seen.alive = false;
+            tank.requests.push(Request::Kill(id as EntityId));
+        }
+    });
+
+    let seen = Rc::clone(tank);
+    engine.register_fn("spawn_entity", move |name: &str| {
+        let mut tank = seen.borrow_mut();
+        let request = Request::Spawn {
+            script: tank.running,
+            name: name.to_string(),
+        };
+        tank.requests.push(request);
+    });
+
+    let seen = Rc::clone(tank);
+    engine.register_fn("nearby", move |id: INT, radius: Dynamic| -> Array {
+        let tank = seen.borrow();
+        let (Some(origin), Some(radius)) = (tank.seen(id).map(Seen::centre), number(&radius))
+        else {
+            return Array::new();
+        };
+        let mut found: Vec<(f32, EntityId)> = tank
+            .entities
+            .iter()
+            .filter(|&(&other, seen)| other != id as EntityId && seen.alive)
+            .filter_map(|(&other, seen)| {
+                let (x, y) = seen.centre();
+                let distance = (x - origin.0).hypot((y - origin.1) * 2.0);
+                (distance <= radius).then_some((distance, other))
+            })
+            .collect();
+        found.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        found
+            .into_iter()
+            .map(|(_, id)| Dynamic::from(id as INT))
+            .collect()
+    });
+}
+
+/// `~/.config/asciiquarium-rs/scripts`, honouring `XDG_CONFIG_HOME`
+pub fn default_dir() -> Option<PathBuf> {
+    Some(crate::assets::config_dir()?.join("scripts"))
+}
+
+/// Load every script in `dir`, in file name order
+///
+/// A missing directory simply has no scripts. Files that can't be read or
+/// compiled are reported and skipped.
+pub fn load_dir(dir: &Path) -> (Scripts, Vec<ScriptError>) {
+    let mut scripts = Scripts::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (scripts, Vec::new());
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == EXTENSION))
+        .collect();
+    paths.sort();
+
+    let mut errors = Vec::new();
+    for path in paths {
+        let loaded = std::fs::read_to_string(&path)
+            .map_err(|err| ScriptError {
+                path: path.clone(),
+                message: err.to_string(),
+            })
+            .and_then(|source| scripts.add(&path, &source));
+        if let Err(error) = loaded {
+            errors.push(error);
+        }
+    }
+    (scripts, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Bubble;
+    use crate::entity::EntityKind;
+    use crate::rng::RngHandle;
+
+    const STEP: Duration = Duration::from_millis(16);
+
+    fn bubble(manager: &mut EntityManager, x: f32, y: f32) -> EntityId {
+        manager.spawn(|id| Bubble::new(id, Position::new(x, y, 5), &mut RngHandle::default()))
+    }
+
+    #[test]
+    fn test_update_drives_the_named_entities() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut manager = EntityManager::new();
+        let first = bubble(&mut manager, 10.0, 20.0);
+        let second = bubble(&mut manager, 13.0, 20.0);
+        let lone = bubble(&mut manager, 60.0, 20.0);
+        let mut scripts = Scripts::new();
+        let source = r#"
+            fn update(id, dt) {
+                let near = nearby(id, 5.0);
+                for other in near { kill(other); }
+                if near.is_empty() { set_velocity(id, 0, -1.5); }
+                let p = position(id);
+                set_position(id, p.x, 3);
+            }
+        "#;
+        scripts.add(Path::new("bubble.rhai"), source).unwrap();
+
+        assert!(scripts.run(&mut manager, STEP, bounds).is_empty());
+        assert_eq!(manager.get_entity(first).unwrap().position().y, 3.0);
+        // Killed before its own turn came
+        assert!(!manager.get_entity(second).unwrap().is_alive());
+        let lone = manager.get_entity(lone).unwrap();
+        assert_eq!(lone.velocity().dy, -1.5);
+        assert_eq!(lone.position().y, 3.0);
+    }
+
+    #[test]
+    fn test_events_reach_scripts_without_feedback() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut manager = EntityManager::new();
+        let mut scripts = Scripts::new();
+        let source = r#"
+            fn on_event(event) {
+                if event.event == "spawned" && event.type == "bubble" {
+                    spawn_entity("glint");
+                }
+            }
+        "#;
+        scripts.add(Path::new("tank.rhai"), source).unwrap();
+        scripts.listen(&mut manager);
+        bubble(&mut manager, 10.0, 20.0);
+
+        scripts.run(&mut manager, STEP, bounds);
+        scripts.run(&mut manager, STEP, bounds);
+        assert_eq!(manager.get_entities_of_kind(EntityKind::Glint).len(), 1);
+    }
+
+    #[test]
+    fn test_failing_script_is_stopped() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut manager = EntityManager::new();
+        bubble(&mut manager, 10.0, 20.0);
+        let mut scripts = Scripts::new();
+        scripts
+            .add(Path::new("bubble.rhai"), "fn update(id, dt) { loop {} }")
+            .unwrap();
+
+        let errors = scripts.run(&mut manager, STEP, bounds);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, Path::new("bubble.rhai"));
+        assert!(scripts.run(&mut manager, STEP, bounds).is_empty());
+
+        let error = scripts
+            .add(Path::new("broken.rhai"), "fn update(")
+            .unwrap_err();
+        assert_eq!(error.path, Path::new("broken.rhai"));
+    }
+}
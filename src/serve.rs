@@ -0,0 +1,442 @@
+//! Aquarium sessions for remote clients
+//!
+//! A server gives each client that connects its own [`Session`]: a tank
+//! sized to the client's terminal, stepped on the session's own clock and
+//! drawn as ANSI text into whatever the connection writes to. Like stream
+//! mode, only rows that changed are sent, each at an explicit cursor
+//! address, but with colours kept. The transport only has to pass on the
+//! client's window size and keystrokes and copy the output.
+//!
+//! With the `ssh` feature, `serve --ssh :2222` puts a [`Session`] behind
+//! every SSH shell: anyone may log in, and each gets a tank sized to their
+//! PTY that follows it as the window changes. The host key is made on the
+//! first run and kept in the config directory.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crate::entity::EntityManager;
+use crate::simulation::Simulation;
+use crate::spawning;
+use crate::timestep::FixedTimestep;
+
+/// One client's tank and what they have been sent
+pub struct Session {
+    simulation: Simulation,
+    timestep: FixedTimestep,
+    /// Rows as last sent, to send only the ones that change
+    sent: Vec<String>,
+}
+
+impl Session {
+    /// A filled tank for a `width` x `height` terminal
+    pub fn new(width: u16, height: u16) -> Self {
+        Self::with_entity_manager(EntityManager::new(), width, height)
+    }
+
+    /// A tank filled into `entity_manager` (seeded, or with mode flags
+    /// already set)
+    pub fn with_entity_manager(entity_manager: EntityManager, width: u16, height: u16) -> Self {
+        let mut simulation = Simulation::empty(entity_manager, width, height);
+        let bounds = simulation.world().bounds();
+        spawning::initialize_aquarium(simulation.entity_manager_mut(), bounds);
+        Self {
+            simulation,
+            timestep: FixedTimestep::new(),
+            sent: Vec::new(),
+        }
+    }
+
+    pub fn simulation(&self) -> &Simulation {
+        &self.simulation
+    }
+
+    /// The client's window changed size; the next frame is sent in full
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.simulation.resize(width, height);
+        self.sent.clear();
+    }
+
+    /// Move the tank on by `elapsed` of wall-clock time
+    pub fn advance(&mut self, elapsed: Duration) {
+        let steps = self.timestep.advance(elapsed);
+        self.simulation.run(steps as u64);
+    }
+
+    /// Whether the session goes on after the client typed `input` ('q',
+    /// Ctrl-C and Ctrl-D end it)
+    pub fn handle_input(&self, input: &[u8]) -> bool {
+        !input
+            .iter()
+            .any(|byte| matches!(byte, b'q' | b'Q' | 0x03 | 0x04))
+    }
+
+    /// Take over the client's screen: alternate screen, hidden cursor
+    pub fn start<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        self.sent.clear();
+        out.write_all(b"\x1b[?1049h\x1b[?25l\x1b[2J")?;
+        out.flush()
+    }
+
+    /// Send the rows of the current frame that changed since the last one
+    pub fn draw<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        let frame = self.simulation.to_ansi();
+        let rows: Vec<String> = frame.lines().map(str::to_string).collect();
+        for (y, row) in rows.iter().enumerate() {
+            if self.sent.get(y) != Some(row) {
+                write!(out, "\x1b[{};1H{row}", y + 1)?;
+            }
+        }
+        out.flush()?;
+        self.sent = rows;
+        Ok(())
+    }
+
+    /// Give the client's screen back
+    pub fn finish<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        out.write_all(b"\x1b[0m\x1b[?25h\x1b[?1049l")?;
+        out.flush()
+    }
+}
+
+/// Where `serve --ssh` listens: `:port` on every interface, a bare port on
+/// localhost, anything else as given
+pub fn listen_address(address: &str) -> String {
+    if let Some(port) = address.strip_prefix(':') {
+        format!("0.0.0.0:{port}")
+    } else if address.bytes().all(|byte| byte.is_ascii_digit()) {
+        format!("127.0.0.1:{address}")
+    } else {
+        address.to_string()
+    }
+}
+
+#[cfg(feature = "ssh")]
+pub use ssh::serve_ssh;
+
+#[cfg(feature = "ssh")]
+mod ssh {
+    use std::collections::HashMap;
+    use std::io::{self, Write};
+    use std::net::SocketAddr;
+    use std::sync::{mpsc, Arc};
+    use std::time::{Duration, Instant};
+
+    use russh::keys::ssh_key::LineEnding;
+    use russh::keys::{Algorithm, PrivateKey};
+    use russh::server::{Auth, Config, Handle, Handler, Msg, Server};
+    use russh::{Channel, ChannelId, Pty};
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+    use super::Session;
+    use crate::cli::Options;
+    use crate::{assets, App};
+
+    /// Frames sent to each client per second
+    const FPS: u32 = 30;
+
+    /// Size for clients that don't say
+    const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+    /// What the connection passes on to a client's tank
+    enum Event {
+        Resize(u16, u16),
+        Input(Vec<u8>),
+    }
+
+    /// Serve a tank, set up from `options`, to every SSH shell opened on
+    /// `address` (see [`super::listen_address`]); runs until the listener
+    /// fails
+    pub fn serve_ssh(address: &str, options: Options) -> io::Result<()> {
+        let config = Config {
+            keys: vec![host_key()?],
+            auth_rejection_time: Duration::from_secs(1),
+            auth_rejection_time_initial: Some(Duration::ZERO),
+            inactivity_timeout: Some(Duration::from_secs(3600)),
+            nodelay: true,
+            ..Default::default()
+        };
+        let mut server = Aquariums {
+            options: Arc::new(options),
+        };
+        let address = super::listen_address(address);
+        tokio::runtime::Runtime::new()?
+            .block_on(server.run_on_address(Arc::new(config), address.as_str()))
+    }
+
+    /// The server's key: made on the first run and kept in the config
+    /// directory, so clients see the same host each time
+    fn host_key() -> io::Result<PrivateKey> {
+        let path = assets::config_dir().map(|dir| dir.join("ssh_host_ed25519_key"));
+        if let Some(path) = path.as_ref().filter(|path| path.exists()) {
+            return russh::keys::load_secret_key(path, None).map_err(io::Error::other);
+        }
+        let key = PrivateKey::random(&mut rand::rngs::OsRng, Algorithm::Ed25519)
+            .map_err(io::Error::other)?;
+        if let Some(path) = path {
+            let text = key.to_openssh(LineEnding::LF).map_err(io::Error::other)?;
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let mut file = std::fs::OpenOptions::new();
+            file.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut file, 0o600);
+            file.open(&path)?.write_all(text.as_bytes())?;
+        }
+        Ok(key)
+    }
+
+    /// Hands each connection its own [`Client`]
+    struct Aquariums {
+        options: Arc<Options>,
+    }
+
+    impl Server for Aquariums {
+        type Handler = Client;
+
+        fn new_client(&mut self, _: Option<SocketAddr>) -> Client {
+            Client {
+                options: Arc::clone(&self.options),
+                sizes: HashMap::new(),
+                tanks: HashMap::new(),
+            }
+        }
+    }
+
+    /// One connection: its channels' terminal sizes until their shells
+    /// start, then the tanks running behind them
+    struct Client {
+        options: Arc<Options>,
+        sizes: HashMap<ChannelId, (u16, u16)>,
+        tanks: HashMap<ChannelId, mpsc::Sender<Event>>,
+    }
+
+    impl Client {
+        /// Start a tank for `channel` on its own thread (the simulation stays
+        /// on one thread), with its frames copied out over `handle`
+        fn start(&mut self, channel: ChannelId, handle: Handle) {
+            let (width, height) = self.sizes.remove(&channel).unwrap_or(DEFAULT_SIZE);
+            let (events, inbox) = mpsc::channel();
+            let (sender, mut outbox) = unbounded_channel::<Vec<u8>>();
+            let options = Arc::clone(&self.options);
+            std::thread::spawn(move || {
+                let entity_manager = App::with_options(&options).entity_manager;
+                let session = Session::with_entity_manager(entity_manager, width, height);
+                let out = Output {
+                    sender,
+                    buffer: Vec::new(),
+                };
+                let _ = run(session, &inbox, out);
+            });
+            // Once the tank is done, so is the shell
+            tokio::spawn(async move {
+                while let Some(data) = outbox.recv().await {
+                    if handle.data(channel, data.into()).await.is_err() {
+                        return;
+                    }
+                }
+                let _ = handle.exit_status_request(channel, 0).await;
+                let _ = handle.eof(channel).await;
+                let _ = handle.close(channel).await;
+            });
+            self.tanks.insert(channel, events);
+        }
+    }
+
+    impl Handler for Client {
+        type Error = russh::Error;
+
+        async fn auth_none(&mut self, _: &str) -> Result<Auth, Self::Error> {
+            Ok(Auth::Accept)
+        }
+
+        async fn auth_password(&mut self, _: &str, _: &str) -> Result<Auth, Self::Error> {
+            Ok(Auth::Accept)
+        }
+
+        async fn auth_publickey(
+            &mut self,
+            _: &str,
+            _: &russh::keys::PublicKey,
+        ) -> Result<Auth, Self::Error> {
+            Ok(Auth::Accept)
+        }
+
+        async fn channel_open_session(
+            &mut self,
+            channel: Channel<Msg>,
+            _: &mut russh::server::Session,
+        ) -> Result<bool, Self::Error> {
+            self.sizes.insert(channel.id(), DEFAULT_SIZE);
+            Ok(true)
+        }
+
+        async fn pty_request(
+            &mut self,
+            channel: ChannelId,
+            _: &str,
+            columns: u32,
+            rows: u32,
+            _: u32,
+            _: u32,
+            _: &[(Pty, u32)],
+            session: &mut russh::server::Session,
+        ) -> Result<(), Self::Error> {
+            self.sizes.insert(channel, terminal_size(columns, rows));
+            session.channel_success(channel)
+        }
+
+        async fn shell_request(
+            &mut self,
+            channel: ChannelId,
+            session: &mut russh::server::Session,
+        ) -> Result<(), Self::Error> {
+            self.start(channel, session.handle());
+            session.channel_success(channel)
+        }
+
+        async fn window_change_request(
+            &mut self,
+            channel: ChannelId,
+            columns: u32,
+            rows: u32,
+            _: u32,
+            _: u32,
+            _: &mut russh::server::Session,
+        ) -> Result<(), Self::Error> {
+            let (width, height) = terminal_size(columns, rows);
+            match self.tanks.get(&channel) {
+                Some(tank) => {
+                    let _ = tank.send(Event::Resize(width, height));
+                }
+                None => {
+                    self.sizes.insert(channel, (width, height));
+                }
+            }
+            Ok(())
+        }
+
+        async fn data(
+            &mut self,
+            channel: ChannelId,
+            data: &[u8],
+            _: &mut russh::server::Session,
+        ) -> Result<(), Self::Error> {
+            if let Some(tank) = self.tanks.get(&channel) {
+                let _ = tank.send(Event::Input(data.to_vec()));
+            }
+            Ok(())
+        }
+
+        async fn channel_close(
+            &mut self,
+            channel: ChannelId,
+            _: &mut russh::server::Session,
+        ) -> Result<(), Self::Error> {
+            // Dropping its inbox stops the tank
+            self.tanks.remove(&channel);
+            self.sizes.remove(&channel);
+            Ok(())
+        }
+    }
+
+    /// A PTY size in cells; clients that send nothing get the default
+    fn terminal_size(columns: u32, rows: u32) -> (u16, u16) {
+        if columns == 0 || rows == 0 {
+            return DEFAULT_SIZE;
+        }
+        let clamp = |cells: u32| u16::try_from(cells).unwrap_or(u16::MAX);
+        (clamp(columns), clamp(rows))
+    }
+
+    /// Step and draw one client's tank until they quit or go away
+    fn run(mut session: Session, inbox: &mpsc::Receiver<Event>, mut out: Output) -> io::Result<()> {
+        let frame = Duration::from_secs(1) / FPS;
+        session.start(&mut out)?;
+        let mut last = Instant::now();
+        loop {
+            match inbox.recv_timeout(frame) {
+                Ok(Event::Resize(width, height)) => session.resize(width, height),
+                Ok(Event::Input(input)) => {
+                    if !session.handle_input(&input) {
+                        break;
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+            let now = Instant::now();
+            session.advance(now - last);
+            last = now;
+            session.draw(&mut out)?;
+        }
+        session.finish(&mut out)
+    }
+
+    /// A client's bytes, handed to the connection a frame at a time
+    struct Output {
+        sender: UnboundedSender<Vec<u8>>,
+        buffer: Vec<u8>,
+    }
+
+    impl Write for Output {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.buffer.extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            if self.buffer.is_empty() {
+                return Ok(());
+            }
+            self.sender
+                .send(std::mem::take(&mut self.buffer))
+                .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Castle;
+
+    #[test]
+    fn test_session_sends_changed_rows() {
+        let mut manager = EntityManager::new();
+        manager.spawn(|id| Castle::new_at_position(id, 1.0, 0.0));
+        let mut session = Session {
+            simulation: Simulation::empty(manager, 40, 14),
+            timestep: FixedTimestep::new(),
+            sent: Vec::new(),
+        };
+
+        let mut out = Vec::new();
+        session.draw(&mut out).unwrap();
+        let first = String::from_utf8(out).unwrap();
+        assert!(first.contains("\x1b[1;1H") && first.contains("\x1b[14;1H"));
+
+        // A still castle sends nothing more
+        let mut out = Vec::new();
+        session.advance(Duration::from_millis(100));
+        session.draw(&mut out).unwrap();
+        assert!(out.is_empty());
+
+        session.resize(50, 14);
+        let mut out = Vec::new();
+        session.draw(&mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().contains("\x1b[14;1H"));
+        assert_eq!(session.simulation().world().bounds().width, 50);
+
+        assert!(session.handle_input(b"hello"));
+        assert!(!session.handle_input(b"\x03"));
+    }
+
+    #[test]
+    fn test_listen_address() {
+        assert_eq!(listen_address(":2222"), "0.0.0.0:2222");
+        assert_eq!(listen_address("2222"), "127.0.0.1:2222");
+        assert_eq!(listen_address("[::1]:22"), "[::1]:22");
+    }
+}
@@ -0,0 +1,359 @@
+//! Headless simulation
+//!
+//! A [`Simulation`] is a tank with no terminal and no clock of its own: it
+//! moves on only when stepped, one [`timestep::STEP`] at a time, and
+//! renders into an in-memory [`Buffer`] or a plain text grid on request.
+//! Tests, benchmarks and other frontends can drive the same engine the
+//! binary does.
+//!
+//! ```
+//! use asciiquarium_rs::simulation::Simulation;
+//!
+//! let mut simulation = Simulation::new(80, 24);
+//! simulation.run(60);
+//! assert_eq!(simulation.ticks(), 60);
+//!
+//! let frame = simulation.to_text();
+//! assert_eq!(frame.lines().count(), 24);
+//! ```
+//!
+//! With a seeded [`EntityManager`] the same steps always give the same
+//! frame, so [`to_text`](Simulation::to_text) and
+//! [`to_color_mask`](Simulation::to_color_mask) can be kept as golden
+//! files; `asciiquarium dump --seed N --ticks N` writes them from the
+//! command line.
+
+use std::time::Duration;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier};
+
+use crate::entity::EntityManager;
+use crate::spawning;
+use crate::timestep;
+use crate::ui::AquariumWidget;
+use crate::world::WorldContext;
+
+/// A tank stepped and drawn on demand
+pub struct Simulation {
+    entity_manager: EntityManager,
+    world: WorldContext,
+    widget: AquariumWidget,
+    ticks: u64,
+}
+
+impl Simulation {
+    /// A filled tank of `width` x `height` cells
+    pub fn new(width: u16, height: u16) -> Self {
+        let mut simulation = Self::empty(EntityManager::new(), width, height);
+        spawning::initialize_aquarium(&mut simulation.entity_manager, simulation.world.bounds());
+        simulation
+    }
+
+    /// A tank using `entity_manager` as it is, without filling it (for
+    /// hand-placed scenes)
+    pub fn empty(entity_manager: EntityManager, width: u16, height: u16) -> Self {
+        Self {
+            entity_manager,
+            world: WorldContext::new(Rect::new(0, 0, width, height)),
+            widget: AquariumWidget::new(),
+            ticks: 0,
+        }
+    }
+
+    /// Draw frames with `widget` (render options, debug overlay)
+    pub fn with_widget(self, widget: AquariumWidget) -> Self {
+        Self { widget, ..self }
+    }
+
+    pub fn entity_manager(&self) -> &EntityManager {
+        &self.entity_manager
+    }
+
+    pub fn entity_manager_mut(&mut self) -> &mut EntityManager {
+        &mut self.entity_manager
+    }
+
+    pub fn world(&self) -> &WorldContext {
+        &self.world
+    }
+
+    /// Steps taken so far
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Simulated time so far
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.ticks * 1_000_000_000 / timestep::TICK_RATE as u64)
+    }
+
+    /// Fit the tank to `width` x `height`, keeping its creatures
+    pub fn resize(&mut self, width: u16, height: u16) {
+        let bounds = Rect::new(0, 0, width, height);
+        spawning::resize_aquarium(&mut self.entity_manager, self.world.bounds(), bounds);
+        self.world = WorldContext::new(bounds);
+    }
+
+    /// Advance by one step
+    pub fn step(&mut self) {
+        self.entity_manager.update_all(timestep::STEP, &self.world);
+        self.ticks += 1;
+    }
+
+    /// Advance by `ticks` steps
+    pub fn run(&mut self, ticks: u64) {
+        for _ in 0..ticks {
+            self.step();
+        }
+    }
+
+    /// The current frame, the size of the world
+    pub fn render(&self) -> Buffer {
+        let area = self.world.bounds();
+        let mut buffer = Buffer::empty(area);
+        self.widget.time(self.elapsed().as_secs_f32()).render_tank(
+            &self.entity_manager,
+            None,
+            area,
+            &mut buffer,
+        );
+        buffer
+    }
+
+    /// The current frame as text, one line per row
+    pub fn to_text(&self) -> String {
+        buffer_to_text(&self.render())
+    }
+
+    /// The current frame with ANSI colours, one line per row
+    pub fn to_ansi(&self) -> String {
+        buffer_to_ansi(&self.render())
+    }
+
+    /// The foreground colours of the current frame, lined up with
+    /// [`to_text`](Self::to_text) (see [`buffer_to_color_mask`])
+    pub fn to_color_mask(&self) -> String {
+        buffer_to_color_mask(&self.render())
+    }
+}
+
+/// The symbols of `buffer`, one line per row, without styles
+///
+/// The second half of a wide glyph is skipped so each line reads as it
+/// would on screen.
+pub fn buffer_to_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::with_capacity(area.area() as usize + area.height as usize);
+    for y in area.top()..area.bottom() {
+        if y > area.top() {
+            text.push('\n');
+        }
+        let mut skip = 0;
+        for x in area.left()..area.right() {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let symbol = buffer[(x, y)].symbol();
+            skip = unicode_width::UnicodeWidthStr::width(symbol).saturating_sub(1);
+            text.push_str(symbol);
+        }
+    }
+    text
+}
+
+/// The foreground colours of `buffer` as a colour mask, one character
+/// per character of [`buffer_to_text`]
+///
+/// Letters follow the sprite masks: `r g y b m c w k` for the basic
+/// colours (`w` is grey), capitals for their bright versions (`W` is
+/// white, `K` dark grey). The terminal's default colour is a space, an
+/// indexed colour `%` and an RGB one `#`.
+pub fn buffer_to_color_mask(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut mask = String::with_capacity(area.area() as usize + area.height as usize);
+    for y in area.top()..area.bottom() {
+        if y > area.top() {
+            mask.push('\n');
+        }
+        let mut skip = 0;
+        for x in area.left()..area.right() {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let cell = &buffer[(x, y)];
+            skip = unicode_width::UnicodeWidthStr::width(cell.symbol()).saturating_sub(1);
+            mask.push(mask_char(cell.fg));
+        }
+    }
+    mask
+}
+
+/// Colour mask character for `color`
+fn mask_char(color: Color) -> char {
+    match color {
+        Color::Reset => ' ',
+        Color::Black => 'k',
+        Color::Red => 'r',
+        Color::Green => 'g',
+        Color::Yellow => 'y',
+        Color::Blue => 'b',
+        Color::Magenta => 'm',
+        Color::Cyan => 'c',
+        Color::Gray => 'w',
+        Color::DarkGray => 'K',
+        Color::LightRed => 'R',
+        Color::LightGreen => 'G',
+        Color::LightYellow => 'Y',
+        Color::LightBlue => 'B',
+        Color::LightMagenta => 'M',
+        Color::LightCyan => 'C',
+        Color::White => 'W',
+        Color::Indexed(_) => '%',
+        Color::Rgb(..) => '#',
+    }
+}
+
+/// The cells of `buffer` with ANSI colours and attributes, one line per
+/// row
+///
+/// Styles are written only where they change and reset at the end of
+/// every line, so the text can be printed, piped or cut into lines without
+/// colours bleeding past it.
+pub fn buffer_to_ansi(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        if y > area.top() {
+            text.push('\n');
+        }
+        let mut style = None;
+        let mut skip = 0;
+        for x in area.left()..area.right() {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let cell = &buffer[(x, y)];
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(cell_style) {
+                text.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                style = Some(cell_style);
+            }
+            let symbol = cell.symbol();
+            skip = unicode_width::UnicodeWidthStr::width(symbol).saturating_sub(1);
+            text.push_str(symbol);
+        }
+        text.push_str("\x1b[0m");
+    }
+    text
+}
+
+/// Select Graphic Rendition sequence for a whole cell style, starting from
+/// a reset
+pub(crate) fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(sgr_color(fg, 30));
+    codes.extend(sgr_color(bg, 40));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// SGR parameters for `color`, `base` being 30 for the foreground and 40
+/// for the background; nothing for the terminal's default
+fn sgr_color(color: Color, base: u8) -> Option<String> {
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base.to_string(),
+        Color::Red => (base + 1).to_string(),
+        Color::Green => (base + 2).to_string(),
+        Color::Yellow => (base + 3).to_string(),
+        Color::Blue => (base + 4).to_string(),
+        Color::Magenta => (base + 5).to_string(),
+        Color::Cyan => (base + 6).to_string(),
+        Color::Gray => (base + 7).to_string(),
+        Color::DarkGray => (base + 60).to_string(),
+        Color::LightRed => (base + 61).to_string(),
+        Color::LightGreen => (base + 62).to_string(),
+        Color::LightYellow => (base + 63).to_string(),
+        Color::LightBlue => (base + 64).to_string(),
+        Color::LightMagenta => (base + 65).to_string(),
+        Color::LightCyan => (base + 66).to_string(),
+        Color::White => (base + 67).to_string(),
+        Color::Indexed(index) => format!("{};5;{index}", base + 8),
+        Color::Rgb(r, g, b) => format!("{};2;{r};{g};{b}", base + 8),
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Castle;
+
+    #[test]
+    fn test_golden_castle_frame() {
+        let mut manager = EntityManager::new();
+        manager.spawn(|id| Castle::new_at_position(id, 1.0, 0.0));
+        let mut simulation = Simulation::empty(manager, 34, 13);
+        simulation.run(3);
+
+        let frame = simulation.to_text();
+        let lines: Vec<&str> = frame.lines().collect();
+        assert_eq!(lines.len(), 13);
+        assert_eq!(lines[0], "                T~~               ");
+        assert_eq!(lines[5], " [ ]_[ ]_[ ]/ _   _ \\[ ]_[ ]_[ ]  ");
+        assert_eq!(lines[11], "  |_______|__|_|_|_|__|_______|   ");
+        assert_eq!(lines[12].trim(), "");
+    }
+
+    #[test]
+    fn test_color_mask_lines_up_with_text() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer[(0, 0)].set_symbol("🐟").set_fg(Color::Yellow);
+        buffer[(2, 0)].set_char('>').set_fg(Color::LightRed);
+        buffer[(3, 1)].set_char('o').set_fg(Color::Rgb(1, 2, 3));
+
+        assert_eq!(buffer_to_text(&buffer), "🐟> \n   o");
+        assert_eq!(buffer_to_color_mask(&buffer), "yR \n   #");
+    }
+
+    #[test]
+    fn test_ansi_frame() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 2));
+        buffer[(0, 0)].set_char('>').set_fg(Color::Red);
+        buffer[(1, 0)].set_char('<').set_fg(Color::Red);
+        buffer[(2, 1)]
+            .set_char('o')
+            .set_fg(Color::Rgb(1, 2, 3))
+            .set_bg(Color::Indexed(17))
+            .set_style(Modifier::BOLD);
+
+        let text = buffer_to_ansi(&buffer);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "\x1b[0;31m><\x1b[0m \x1b[0m");
+        assert_eq!(lines[1], "\x1b[0m  \x1b[0;1;38;2;1;2;3;48;5;17mo\x1b[0m");
+    }
+
+    #[test]
+    fn test_steps_count_simulated_time() {
+        let mut simulation = Simulation::new(80, 24);
+        assert!(simulation.entity_manager().entity_count() > 0);
+        simulation.run(timestep::TICK_RATE as u64);
+        assert_eq!(simulation.ticks(), 60);
+        assert_eq!(simulation.elapsed(), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,218 @@
+//! Sound effects for aquarium events
+//!
+//! `--sound bell` rings the terminal bell when something worth hearing
+//! happens; any other value is a shell command to run instead, with the
+//! sound's name in `ASCIIQUARIUM_SOUND`, so samples can be played through
+//! whatever player the system has:
+//!
+//! ```text
+//! asciiquarium --sound 'paplay ~/sounds/$ASCIIQUARIUM_SOUND.wav'
+//! ```
+//!
+//! `--sound-events` picks which [`Sound`]s are played (`shark`, `spout`,
+//! `bubble` or `all`); bubbles pop too often to be on by default. Each
+//! sound plays at most once per [`MIN_GAP`], so a burst of bubbles or a
+//! spout's spray of droplets makes a single noise.
+
+use std::io::{self, Write};
+use std::process::{Child, Command};
+use std::time::Duration;
+
+use crate::clock::Instant;
+use crate::collision::ContactKind;
+use crate::hooks::AquariumEvent;
+
+/// Shortest time between two plays of the same sound
+pub const MIN_GAP: Duration = Duration::from_millis(250);
+
+/// Something that makes a noise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sound {
+    /// A shark bit a fish
+    SharkAttack,
+    /// A whale's spout came down in droplets
+    WhaleSpout,
+    /// A bubble popped at the surface
+    BubblePop,
+}
+
+impl Sound {
+    pub const ALL: [Sound; 3] = [Self::SharkAttack, Self::WhaleSpout, Self::BubblePop];
+
+    /// Played when `--sound-events` isn't given
+    pub const DEFAULT: [Sound; 2] = [Self::SharkAttack, Self::WhaleSpout];
+
+    /// Name passed to sound commands
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::SharkAttack => "shark_attack",
+            Self::WhaleSpout => "whale_spout",
+            Self::BubblePop => "bubble_pop",
+        }
+    }
+
+    /// Parse a `--sound-events` list (`shark,spout`, `all`, `none`),
+    /// skipping names it doesn't know
+    pub fn parse_list(list: &str) -> Vec<Self> {
+        let mut sounds = Vec::new();
+        for name in list.split(',').map(|name| name.trim().to_ascii_lowercase()) {
+            match name.as_str() {
+                "all" => sounds.extend(Self::ALL),
+                "shark" | "shark_attack" => sounds.push(Self::SharkAttack),
+                "spout" | "whale" | "whale_spout" => sounds.push(Self::WhaleSpout),
+                "bubble" | "bubbles" | "bubble_pop" => sounds.push(Self::BubblePop),
+                _ => {}
+            }
+        }
+        sounds
+    }
+}
+
+/// How sounds are made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Player {
+    /// The terminal bell
+    Bell,
+    /// A shell command, run with `ASCIIQUARIUM_SOUND` set
+    Command(String),
+}
+
+impl Player {
+    /// Parse a `--sound` value
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "bell" => Self::Bell,
+            command => Self::Command(command.to_string()),
+        }
+    }
+}
+
+/// Turns aquarium events into sounds and plays them
+#[derive(Debug)]
+pub struct SoundEffects {
+    player: Player,
+    enabled: Vec<Sound>,
+    last_played: Vec<(Sound, Instant)>,
+    /// Commands still playing, reaped as they finish
+    playing: Vec<Child>,
+}
+
+impl SoundEffects {
+    /// Play the `enabled` sounds with `player`
+    pub fn new(player: Player, enabled: &[Sound]) -> Self {
+        Self {
+            player,
+            enabled: enabled.to_vec(),
+            last_played: Vec::new(),
+            playing: Vec::new(),
+        }
+    }
+
+    /// Play the sound for `event`, if it has one
+    pub fn record(&mut self, event: &AquariumEvent) {
+        if let Some(sound) = self.sound_for(event, Instant::now()) {
+            self.play(sound);
+        }
+    }
+
+    /// The sound `event` at `now` should make, if it is enabled and its
+    /// last play was long enough ago
+    pub fn sound_for(&mut self, event: &AquariumEvent, now: Instant) -> Option<Sound> {
+        let sound = match *event {
+            AquariumEvent::Spawned { entity_type, .. } => {
+                (entity_type == "droplet").then_some(Sound::WhaleSpout)
+            }
+            AquariumEvent::Collision {
+                kind: ContactKind::Bite,
+                ..
+            } => Some(Sound::SharkAttack),
+            AquariumEvent::BubblePopped { .. } => Some(Sound::BubblePop),
+            _ => None,
+        }
+        .filter(|sound| self.enabled.contains(sound))?;
+
+        match self
+            .last_played
+            .iter_mut()
+            .find(|(played, _)| *played == sound)
+        {
+            Some((_, last)) if now.duration_since(*last) < MIN_GAP => return None,
+            Some((_, last)) => *last = now,
+            None => self.last_played.push((sound, now)),
+        }
+        Some(sound)
+    }
+
+    /// Make `sound` now; failures are ignored, as a missing player
+    /// shouldn't stop the tank
+    pub fn play(&mut self, sound: Sound) {
+        self.playing
+            .retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+        match &self.player {
+            Player::Bell => {
+                let mut stdout = io::stdout();
+                let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+            }
+            Player::Command(command) => {
+                let child = Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .env("ASCIIQUARIUM_SOUND", sound.name())
+                    .spawn();
+                if let Ok(child) = child {
+                    self.playing.push(child);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::Position;
+
+    #[test]
+    fn test_parse_sound_list() {
+        assert_eq!(
+            Sound::parse_list("Shark, bubbles,kraken"),
+            vec![Sound::SharkAttack, Sound::BubblePop]
+        );
+        assert_eq!(Sound::parse_list("all"), Sound::ALL);
+        assert!(Sound::parse_list("none").is_empty());
+        assert_eq!(Player::parse("bell"), Player::Bell);
+    }
+
+    #[test]
+    fn test_events_make_enabled_sounds_once_per_gap() {
+        let mut effects = SoundEffects::new(Player::Bell, &Sound::DEFAULT);
+        let now = Instant::now();
+        let droplet = AquariumEvent::Spawned {
+            id: 9,
+            entity_type: "droplet",
+        };
+        assert_eq!(effects.sound_for(&droplet, now), Some(Sound::WhaleSpout));
+        assert_eq!(effects.sound_for(&droplet, now), None);
+        assert_eq!(
+            effects.sound_for(&droplet, now + MIN_GAP),
+            Some(Sound::WhaleSpout)
+        );
+
+        // Bubbles aren't on by default
+        let pop = AquariumEvent::BubblePopped {
+            position: Position::new(1.0, 1.0, 0),
+        };
+        assert_eq!(effects.sound_for(&pop, now), None);
+
+        let contact = |kind| AquariumEvent::Collision {
+            first: 3,
+            second: 4,
+            kind,
+        };
+        assert_eq!(effects.sound_for(&contact(ContactKind::Fed), now), None);
+        assert_eq!(
+            effects.sound_for(&contact(ContactKind::Bite), now),
+            Some(Sound::SharkAttack)
+        );
+    }
+}
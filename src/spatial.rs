@@ -0,0 +1,104 @@
+//! Grid index of where entities are
+//!
+//! The tank is cut into buckets of [`CELL_WIDTH`] x [`CELL_HEIGHT`] cells,
+//! each listing the entities whose bounding box reaches into it, so region
+//! and neighbourhood queries look at a few buckets instead of every
+//! entity. The [`EntityManager`] builds the index when first asked after
+//! anything moved.
+//!
+//! Entities are indexed by their on-screen cells, clamped at the top-left
+//! edge the way rendering and collision checks clamp them; queries return
+//! candidates for the caller to test exactly.
+//!
+//! [`EntityManager`]: crate::entity::EntityManager
+
+use std::collections::HashMap;
+
+use crate::entity::{EntityId, Position};
+
+/// Columns per bucket
+pub const CELL_WIDTH: u16 = 16;
+
+/// Rows per bucket
+pub const CELL_HEIGHT: u16 = 8;
+
+/// Entities by the buckets their bounding boxes cover
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    buckets: HashMap<(u16, u16), Vec<EntityId>>,
+}
+
+impl SpatialIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an entity with a `size` bounding box at `position`
+    pub fn insert(&mut self, id: EntityId, position: Position, size: (u16, u16)) {
+        let (x, y) = position.to_screen_coords();
+        let right = x.saturating_add(size.0.max(1) - 1);
+        let bottom = y.saturating_add(size.1.max(1) - 1);
+        for row in y / CELL_HEIGHT..=bottom / CELL_HEIGHT {
+            for column in x / CELL_WIDTH..=right / CELL_WIDTH {
+                self.buckets.entry((column, row)).or_default().push(id);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Entities that may reach into the cells from (`left`, `top`) to
+    /// (`right`, `bottom`) inclusive, each once, in id order
+    pub fn candidates(&self, left: f32, top: f32, right: f32, bottom: f32) -> Vec<EntityId> {
+        let (left, top) = Position::new(left, top, 0).to_screen_coords();
+        let (right, bottom) = Position::new(right, bottom, 0).to_screen_coords();
+        let mut found = Vec::new();
+        // One extra bucket each way absorbs rounding at bucket edges
+        for row in (top / CELL_HEIGHT).saturating_sub(1)..=(bottom / CELL_HEIGHT).saturating_add(1)
+        {
+            for column in
+                (left / CELL_WIDTH).saturating_sub(1)..=(right / CELL_WIDTH).saturating_add(1)
+            {
+                if let Some(ids) = self.buckets.get(&(column, row)) {
+                    found.extend_from_slice(ids);
+                }
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    /// Largest column and row of any bucket, as cells (None if empty)
+    pub fn extent(&self) -> Option<(u16, u16)> {
+        let column = self.buckets.keys().map(|&(column, _)| column).max()?;
+        let row = self.buckets.keys().map(|&(_, row)| row).max()?;
+        Some((
+            (column + 1).saturating_mul(CELL_WIDTH),
+            (row + 1).saturating_mul(CELL_HEIGHT),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_cover_boxes() {
+        let mut index = SpatialIndex::new();
+        index.insert(1, Position::new(0.0, 0.0, 0), (3, 1));
+        // Spans two buckets across
+        index.insert(2, Position::new(30.0, 2.0, 0), (10, 2));
+        index.insert(3, Position::new(100.0, 30.0, 0), (2, 2));
+        // Off the left edge, clamped into the first bucket
+        index.insert(4, Position::new(-20.0, 0.0, 0), (5, 1));
+
+        assert_eq!(index.candidates(0.0, 0.0, 2.0, 0.0), vec![1, 2, 4]);
+        assert_eq!(index.candidates(60.0, 4.0, 61.0, 4.0), vec![2]);
+        assert_eq!(index.candidates(100.0, 30.0, 100.0, 30.0), vec![3]);
+        assert_eq!(index.extent(), Some((112, 32)));
+    }
+}
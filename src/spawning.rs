@@ -4,183 +4,473 @@
 //! original Perl implementation's approach of using function references instead
 //! of complex manager classes.
 
+use crate::backgrounds::Background;
 use crate::entities::*;
-use crate::entity::{Entity, EntityManager};
+use crate::entity::{Direction, Entity, EntityId, EntityKind, EntityManager, Position, Velocity};
+use crate::registry::Category;
+use crate::scheduler::Population;
 use rand::Rng;
 use ratatui::layout::Rect;
+use std::cmp::Reverse;
+use std::time::Duration;
 
-/// Add a fish (death callback for fish)
+/// Most fish the density keys can ask for, as a multiple of the original
+/// count
+pub const MAX_FISH_DENSITY: f32 = 4.0;
+
+/// How much the density keys change the fish density by
+pub const FISH_DENSITY_STEP: f32 = 0.25;
+
+/// Random placements tried before settling for an overlapping one
+const PLACEMENT_ATTEMPTS: usize = 10;
+
+/// Add a fish (the fish population's spawner)
 pub fn add_fish(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    let fish_id = entity_manager.get_next_id();
     let classic_mode = entity_manager.classic_mode();
-    let fish = Fish::new_random(fish_id, screen_bounds, classic_mode);
-    entity_manager.add_entity(Box::new(fish));
+    let waterline = entity_manager.environment().waterline() as u16;
+    let mut rng = entity_manager.rng();
+    entity_manager.spawn_clear(PLACEMENT_ATTEMPTS, |id| {
+        Fish::new_random_below(id, screen_bounds, classic_mode, waterline, &mut rng)
+    });
 }
 
-/// Add seaweed (death callback for seaweed)
+/// Add seaweed (the seaweed population's spawner)
 pub fn add_seaweed(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    let seaweed_id = entity_manager.get_next_id();
-    let seaweed = Seaweed::new_random(seaweed_id, screen_bounds);
-    entity_manager.add_entity(Box::new(seaweed));
+    let mut rng = entity_manager.rng();
+    entity_manager.spawn_clear(PLACEMENT_ATTEMPTS, |id| {
+        Seaweed::new_random(id, screen_bounds, &mut rng)
+    });
+}
+
+/// Terminals at least this wide get extra castles and large creatures
+pub const ULTRAWIDE_WIDTH: u16 = 300;
+
+/// Columns of screen per castle / large creature slot on ultrawide screens
+const ULTRAWIDE_SEGMENT_WIDTH: u16 = 150;
+
+/// Time between large creatures arriving on an ultrawide tank of
+/// [`ULTRAWIDE_REFERENCE_AREA`] cells; bigger tanks get them sooner
+const ULTRAWIDE_SPAWN_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Cells (columns times rows) of the tank [`ULTRAWIDE_SPAWN_INTERVAL`] is
+/// for
+const ULTRAWIDE_REFERENCE_AREA: f32 = ULTRAWIDE_WIDTH as f32 * 40.0;
+
+/// Stretches of the screen laid out alike: the whole screen normally, and
+/// equal segments side by side on ultrawide screens, each with its own
+/// castle, scenery and large creature slot
+pub fn segments(screen_bounds: Rect) -> Vec<Rect> {
+    if screen_bounds.width < ULTRAWIDE_WIDTH {
+        return vec![screen_bounds];
+    }
+    let count = (screen_bounds.width / ULTRAWIDE_SEGMENT_WIDTH) as u32;
+    let edge = |i: u32| (screen_bounds.width as u32 * i / count) as u16;
+    (0..count)
+        .map(|i| Rect {
+            x: screen_bounds.x + edge(i),
+            width: edge(i + 1) - edge(i),
+            ..screen_bounds
+        })
+        .collect()
+}
+
+/// Number of large creatures that may be on screen at once
+///
+/// Normal terminals keep the original single-creature rule; ultrawide ones
+/// get one slot per segment so the scene doesn't feel empty.
+pub fn large_creature_limit(screen_bounds: Rect) -> usize {
+    segments(screen_bounds).len()
+}
+
+/// Time between large creatures arriving on their own, if they do
+///
+/// Normal terminals keep the original pace: a new creature only once the
+/// last has left. On ultrawide ones creatures arrive at a rate that grows
+/// with the tank's area, up to [`large_creature_limit`] at once.
+pub fn large_spawn_interval(screen_bounds: Rect) -> Option<Duration> {
+    if screen_bounds.width < ULTRAWIDE_WIDTH {
+        return None;
+    }
+    let area = screen_bounds.area().max(1) as f32;
+    Some(ULTRAWIDE_SPAWN_INTERVAL.mul_f32(ULTRAWIDE_REFERENCE_AREA / area))
+}
+
+/// Set the large creature slots and pace for a screen of this size
+fn pace_large_creatures(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+    entity_manager.set_large_creature_limit(large_creature_limit(screen_bounds));
+    let interval = large_spawn_interval(screen_bounds);
+    let policy = entity_manager.scheduler_mut().policy_mut(Population::Large);
+    policy.interval = interval;
+    // A slot that empties waits for the next arrival too
+    policy.respawn_delay = interval.unwrap_or(Duration::ZERO);
+}
+
+/// X positions of the castles (or other background scenes) for this screen
+///
+/// One castle in the bottom-right corner like the original, and one at
+/// the right edge of each segment on ultrawide screens.
+pub fn castle_positions(screen_bounds: Rect, background: Background) -> Vec<f32> {
+    if background == Background::None {
+        return Vec::new();
+    }
+    segments(screen_bounds)
+        .into_iter()
+        .map(|segment| {
+            (segment.right() - screen_bounds.x).saturating_sub(background.width()) as f32
+        })
+        .collect()
 }
 
-/// Random object spawner - spawns one random large creature (original behavior)
+/// Add a large creature if a slot is free
+///
+/// The original has only one large creature at a time; ultrawide screens
+/// get more slots (see [`large_creature_limit`]).
+fn add_large<E: Entity>(
+    entity_manager: &mut EntityManager,
+    build: impl FnOnce(EntityId) -> E,
+) -> Option<EntityId> {
+    if entity_manager.large_creatures_full() {
+        return None;
+    }
+    Some(entity_manager.spawn_large(build))
+}
+
+/// Random object spawner - spawns one random large creature from the
+/// registry (original behavior)
 pub fn random_object(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    // Only spawn if no large creature exists (original constraint)
-    if entity_manager.has_large_creature() {
+    // Only spawn if a large creature slot is free (original constraint)
+    if entity_manager.large_creatures_full() {
         return;
     }
 
-    let mut rng = rand::thread_rng();
+    // Only one shark at a time
+    let shark_free = entity_manager
+        .get_entities_of_kind(EntityKind::Shark)
+        .is_empty();
+
+    // Weighted pick from the registered large creatures that fit the
+    // screen (the original array, all equally likely, by default)
+    let entry = entity_manager.registry().choose_weighted(
+        Category::Large,
+        |entry| {
+            if entry.name == "shark" && !shark_free {
+                0.0
+            } else {
+                entry.weight_for(screen_bounds)
+            }
+        },
+        &mut entity_manager.rng(),
+    );
+    if let Some(entry) = entry {
+        (entry.spawn)(entity_manager, screen_bounds);
+    }
+}
 
-    // Original random objects array
-    let spawners: &[fn(&mut EntityManager, Rect)] = &[
-        add_ship,
-        add_whale,
-        add_sea_monster,
-        add_big_fish,
-        add_shark,
-    ];
+/// The large creatures that eat fish
+pub const PREDATORS: [EntityKind; 2] = [EntityKind::Shark, EntityKind::BigFish];
 
-    // Random selection like original: int(rand(scalar(@random_objects)))
-    let index = rng.gen_range(0..spawners.len());
-    spawners[index](entity_manager, screen_bounds);
+/// Whisk the predators out of the tank in a puff of smoke and bring on
+/// a large creature that leaves the fish alone; false if there were none
+pub fn rescue_fish(entity_manager: &mut EntityManager, screen_bounds: Rect) -> bool {
+    let centres = entity_manager.remove_large_of_kinds(&PREDATORS);
+    if centres.is_empty() {
+        return false;
+    }
+    for centre in centres {
+        entity_manager.spawn(|id| Puff::new(id, centre));
+    }
+    if !entity_manager.large_creatures_full() {
+        let entry = entity_manager.registry().choose_weighted(
+            Category::Large,
+            |entry| {
+                if PREDATORS.iter().any(|kind| kind.name() == entry.name) {
+                    0.0
+                } else {
+                    entry.weight_for(screen_bounds)
+                }
+            },
+            &mut entity_manager.rng(),
+        );
+        if let Some(entry) = entry {
+            (entry.spawn)(entity_manager, screen_bounds);
+        }
+    }
+    true
 }
 
 /// Add a ship (large creature)
 pub fn add_ship(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    if entity_manager.has_large_creature() {
-        return; // Only one large creature at a time
-    }
-
-    let ship_id = entity_manager.get_next_id();
-    let ship = Ship::new(ship_id, screen_bounds);
-    entity_manager.set_large_creature(ship_id);
-    entity_manager.add_entity(Box::new(ship));
+    let mut rng = entity_manager.rng();
+    add_large(entity_manager, |id| Ship::new(id, screen_bounds, &mut rng));
 }
 
 /// Add a whale (large creature)
 pub fn add_whale(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    if entity_manager.has_large_creature() {
-        return; // Only one large creature at a time
-    }
-
-    let whale_id = entity_manager.get_next_id();
-    let whale = Whale::new(whale_id, screen_bounds);
-    entity_manager.set_large_creature(whale_id);
-    entity_manager.add_entity(Box::new(whale));
+    let mut rng = entity_manager.rng();
+    add_large(entity_manager, |id| Whale::new(id, screen_bounds, &mut rng));
 }
 
 /// Add a sea monster (large creature)
 pub fn add_sea_monster(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    if entity_manager.has_large_creature() {
-        return; // Only one large creature at a time
-    }
-
-    let monster_id = entity_manager.get_next_id();
     let classic_mode = entity_manager.classic_mode();
-    let monster = SeaMonster::new(monster_id, screen_bounds, classic_mode);
-    entity_manager.set_large_creature(monster_id);
-    entity_manager.add_entity(Box::new(monster));
+    let mut rng = entity_manager.rng();
+    add_large(entity_manager, |id| {
+        SeaMonster::new(id, screen_bounds, classic_mode, &mut rng)
+    });
 }
 
 /// Add a shark (large creature) - special case with teeth cleanup
 pub fn add_shark(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    if entity_manager.has_large_creature() {
-        return; // Only one large creature at a time
-    }
-
-    let shark_id = entity_manager.get_next_id();
-    let teeth_id = entity_manager.get_next_id();
-
-    // Create shark
-    let mut shark = Shark::new_random(shark_id, screen_bounds);
+    let aggressiveness = entity_manager.shark_aggressiveness();
+    let waterline = entity_manager.environment().waterline();
+    let mut rng = entity_manager.rng();
+    let Some(shark_id) = add_large(entity_manager, |id| {
+        let mut shark = Shark::new_random(id, screen_bounds, waterline, &mut rng);
+        shark.set_aggressiveness(aggressiveness);
+        shark
+    }) else {
+        return;
+    };
+    let Some(shark) = entity_manager.get_as::<Shark>(shark_id) else {
+        return;
+    };
 
     // Create teeth at shark's teeth position
     let teeth_position = shark.get_teeth_position();
-    let teeth_velocity = Entity::velocity(&shark);
-    let teeth = SharkTeeth::new(teeth_id, teeth_position, teeth_velocity, shark_id);
-
-    // Associate shark with teeth
-    shark.set_teeth_id(teeth_id);
-
-    entity_manager.set_large_creature(shark_id);
-    entity_manager.add_entity(Box::new(shark));
-    entity_manager.add_entity(Box::new(teeth));
+    let teeth_velocity = Entity::velocity(shark);
+    let (dx, dy) = shark.teeth_offset();
+    let teeth_id =
+        entity_manager.spawn(|id| SharkTeeth::new(id, teeth_position, teeth_velocity, shark_id));
+
+    // Associate shark with teeth; they ride along with the jaw while the
+    // shark changes course and leave with it
+    if let Some(shark) = entity_manager.get_as_mut::<Shark>(shark_id) {
+        shark.set_teeth_id(teeth_id);
+    }
+    entity_manager.attach(teeth_id, shark_id, dx, dy);
 }
 
 /// Add a big fish (large creature)
 pub fn add_big_fish(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    if entity_manager.has_large_creature() {
-        return; // Only one large creature at a time
+    let classic_mode = entity_manager.classic_mode();
+    let waterline = entity_manager.environment().waterline();
+    let mut rng = entity_manager.rng();
+    add_large(entity_manager, |id| {
+        BigFish::new(id, screen_bounds, classic_mode, waterline, &mut rng)
+    });
+}
+
+/// Add a fishing hook on its line (large creature)
+pub fn add_hook(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+    let mut rng = entity_manager.rng();
+    let Some(hook_id) = add_large(entity_manager, |id| Hook::new(id, screen_bounds, &mut rng))
+    else {
+        return;
+    };
+    // Long enough to reach past the top of the screen from the lowest point
+    let length = screen_bounds.height;
+    let line_id = entity_manager.spawn(|id| FishingLine::new(id, length));
+    entity_manager.attach(line_id, hook_id, hook::LINE_X, -(length as f32));
+}
+
+/// Add a row of ducks on the surface (large creature)
+pub fn add_ducks(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+    let mut rng = entity_manager.rng();
+    add_large(entity_manager, |id| Ducks::new(id, screen_bounds, &mut rng));
+}
+
+/// A column of bubbles rising from a random spot on the floor
+pub fn add_bubble_burst(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+    let mut rng = entity_manager.rng();
+    let x = rng.gen_range(1..screen_bounds.width.max(2)) as f32;
+    let floor = screen_bounds.height.saturating_sub(1) as f32;
+
+    for i in 0..rng.gen_range(8..=15) {
+        // Stacked upwards so they come out one after another
+        let position = Position::new(
+            x + rng.gen_range(-1.0..=1.0),
+            floor - i as f32 * 1.5,
+            crate::depth::random_fish_depth(&mut rng),
+        );
+        entity_manager.spawn_recycled::<Bubble>(position);
     }
+}
 
-    let fish_id = entity_manager.get_next_id();
-    let classic_mode = entity_manager.classic_mode();
-    let big_fish = BigFish::new(fish_id, screen_bounds, classic_mode);
-    entity_manager.set_large_creature(fish_id);
-    entity_manager.add_entity(Box::new(big_fish));
+/// A small school of one species darting across the tank
+///
+/// The school is a one-off: its fish are not replaced when they leave.
+pub fn add_school(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+    let mut rng = entity_manager.rng();
+    let species = FishSpecies::random(entity_manager.classic_mode(), &mut rng);
+    let (width, height) = species.get_sprites(&mut rng).0.get_bounding_box();
+    let direction = if rng.gen_bool(0.5) {
+        Direction::Right
+    } else {
+        Direction::Left
+    };
+
+    let waterline = entity_manager.environment().waterline() as u16;
+    let lowest = screen_bounds.height.saturating_sub(height + 4);
+    let school_y = rng.gen_range(waterline..lowest.max(waterline + 1)) as f32;
+
+    for _ in 0..rng.gen_range(5..=8) {
+        // Everyone starts at the same edge; slightly different speeds
+        // spread the school out as it crosses
+        let speed = rng.gen_range(2.5..3.5);
+        let (x, dx) = match direction {
+            Direction::Right => (1.0 - width as f32, speed),
+            Direction::Left => (screen_bounds.width as f32 - 2.0, -speed),
+        };
+        let position = Position::new(
+            x,
+            school_y + rng.gen_range(0..=4) as f32,
+            crate::depth::random_fish_depth(&mut rng),
+        );
+
+        entity_manager.spawn(|id| {
+            let velocity = Velocity::new(dx, 0.0);
+            let mut fish = Fish::new(id, position, velocity, direction, species, &mut rng);
+            fish.set_respawns(false);
+            fish
+        });
+    }
 }
 
-/// Shark death callback - cleans up teeth and spawns new random object
-pub fn shark_death(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    // Remove any remaining shark teeth
-    let teeth_ids: Vec<_> = entity_manager
-        .get_entities_by_type("shark_teeth")
+/// A glint of treasure at the foot of a castle (or anywhere on the floor)
+pub fn add_glint(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+    let mut rng = entity_manager.rng();
+    let background = entity_manager.background();
+    let castles: Vec<Position> = entity_manager
+        .get_entities_of_kind(EntityKind::Castle)
         .iter()
-        .map(|e| e.id())
+        .map(|castle| castle.position())
         .collect();
 
-    for teeth_id in teeth_ids {
-        entity_manager.remove_entity(teeth_id);
-    }
+    let (x, y) = if castles.is_empty() {
+        (
+            rng.gen_range(0..screen_bounds.width.max(1)) as f32,
+            screen_bounds.height.saturating_sub(1) as f32,
+        )
+    } else {
+        let castle = castles[rng.gen_range(0..castles.len())];
+        (
+            castle.x + rng.gen_range(4..background.width().saturating_sub(4).max(5)) as f32,
+            castle.y + background.height() as f32 - 1.0,
+        )
+    };
+
+    entity_manager.spawn(|id| Glint::new(id, x, y));
+}
+
+/// An anchor dropped from the surface at a random column
+pub fn add_anchor(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+    let mut rng = entity_manager.rng();
+    let x = rng.gen_range(0..screen_bounds.width.saturating_sub(5).max(1)) as f32;
+    let y = entity_manager.environment().waterline();
 
-    // Spawn new random large creature
-    random_object(entity_manager, screen_bounds);
+    entity_manager.spawn(|id| Anchor::new(id, x, y));
 }
 
-/// Initialize all fish population based on screen size (original formula)
-pub fn add_all_fish(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+/// Three dolphins leaping along the surface one behind another
+pub fn add_dolphins(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+    let mut rng = entity_manager.rng();
+    let direction = if rng.gen_bool(0.5) {
+        Direction::Right
+    } else {
+        Direction::Left
+    };
+    let waterline = entity_manager.environment().waterline();
+
+    for i in 0..3 {
+        let lag = i as f32 * 18.0;
+        entity_manager.spawn(|id| Dolphin::new(id, screen_bounds, direction, waterline, lag));
+    }
+}
+
+/// Number of fish for a screen of this size with the water surface at
+/// `waterline` (original formula)
+pub fn fish_count(screen_bounds: Rect, waterline: f32) -> usize {
     // Original formula: (height - 9) * width / 350
-    let screen_size =
-        (screen_bounds.height.saturating_sub(9)) as usize * screen_bounds.width as usize;
-    let fish_count = screen_size / 350;
+    let water_rows = screen_bounds
+        .height
+        .saturating_sub(waterline.max(0.0) as u16);
+    water_rows as usize * screen_bounds.width as usize / 350
+}
+
+/// Number of fish for a screen of this size at the tank's fish density
+pub fn fish_target(entity_manager: &EntityManager, screen_bounds: Rect) -> usize {
+    let usual = fish_count(screen_bounds, entity_manager.environment().waterline());
+    (usual as f32 * entity_manager.fish_density()).round() as usize
+}
 
-    for _ in 0..fish_count {
+/// Add or retire fish until the tank holds its [`fish_target`]
+pub fn fit_fish(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+    let target = fish_target(entity_manager, screen_bounds);
+    fit_population(entity_manager, Population::Fish, target, screen_bounds);
+}
+
+/// Number of seaweed for a screen of this width (original formula)
+pub fn seaweed_count(screen_bounds: Rect) -> usize {
+    // Original formula: width / 15
+    (screen_bounds.width as usize / 15).max(1)
+}
+
+/// Initialize all fish population based on screen size (original formula,
+/// scaled by the fish density)
+pub fn add_all_fish(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+    let target = fish_target(entity_manager, screen_bounds);
+    entity_manager
+        .scheduler_mut()
+        .set_target(Population::Fish, target);
+    for _ in 0..target {
         add_fish(entity_manager, screen_bounds);
     }
 }
 
 /// Initialize all seaweed population based on screen width (original formula)
 pub fn add_all_seaweed(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    // Original formula: width / 15
-    let seaweed_count = (screen_bounds.width as usize / 15).max(1);
-
-    for _ in 0..seaweed_count {
+    let target = seaweed_count(screen_bounds);
+    entity_manager
+        .scheduler_mut()
+        .set_target(Population::Seaweed, target);
+    for _ in 0..target {
         add_seaweed(entity_manager, screen_bounds);
     }
 }
 
 /// Initialize water surface
 pub fn add_environment(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    let start_id = entity_manager.get_next_id();
-
-    // Create 4 water surface layers
-    for layer_index in 0..4 {
-        let layer = WaterSurface::new(
-            start_id + layer_index as u64,
-            layer_index,
-            screen_bounds.width,
-        );
-        entity_manager.add_entity(Box::new(layer));
+    let scroll = entity_manager.water_scroll();
+
+    for layer_index in 0..water_surface::LAYERS {
+        entity_manager.spawn(|id| {
+            WaterSurface::new(id, layer_index, screen_bounds.width).with_scroll(scroll)
+        });
     }
 }
 
-/// Initialize castle
+/// Initialize the sandy floor
+pub fn add_sand(entity_manager: &mut EntityManager, screen_bounds: Rect) {
+    let mut rng = entity_manager.rng();
+    entity_manager.spawn(|id| Sand::new(id, screen_bounds, &mut rng));
+}
+
+/// Initialize castle (several on ultrawide screens), or whichever
+/// background scene was chosen instead
 pub fn add_castle(entity_manager: &mut EntityManager, screen_bounds: Rect) {
-    let castle_id = entity_manager.get_next_id();
-    let castle = Castle::new(castle_id, screen_bounds);
-    entity_manager.add_entity(Box::new(castle));
+    let background = entity_manager.background();
+    let y = screen_bounds.height.saturating_sub(background.height()) as f32;
+
+    for x in castle_positions(screen_bounds, background) {
+        add_scene_at(entity_manager, background, x, y);
+    }
+}
+
+/// Add one copy of the background scene with its top-left corner at (x, y)
+fn add_scene_at(entity_manager: &mut EntityManager, background: Background, x: f32, y: f32) {
+    if let Some(sprite) = background.sprite() {
+        entity_manager.spawn(|id| Castle::new_with_sprite(id, x, y, sprite));
+    }
 }
 
 /// Complete initialization sequence (matching original Perl main loop)
@@ -191,10 +481,437 @@ pub fn initialize_aquarium(entity_manager: &mut EntityManager, screen_bounds: Re
     // add_all_seaweed($anim);
     // add_all_fish($anim);
     // random_object(undef, $anim);
-
-    add_environment(entity_manager, screen_bounds);
-    add_castle(entity_manager, screen_bounds);
-    add_all_seaweed(entity_manager, screen_bounds);
+    // The scenery comes from the registry (water surface, sand, castle
+    // and seaweed by default)
+
+    // Scenery filling the tank itself (the built-ins) is added once;
+    // single pieces of a known size, once in each segment so they spread
+    // across ultrawide screens
+    let decorations: Vec<_> = entity_manager
+        .registry()
+        .entries(Category::Decoration)
+        .map(|entry| (entry.spawn.clone(), entry.size.is_some()))
+        .collect();
+    let segments = segments(screen_bounds);
+    for (spawn, piece) in decorations {
+        if piece {
+            for &segment in &segments {
+                spawn(entity_manager, segment);
+            }
+        } else {
+            spawn(entity_manager, screen_bounds);
+        }
+    }
     add_all_fish(entity_manager, screen_bounds);
-    random_object(entity_manager, screen_bounds);
+
+    // Wide screens get one large creature per slot to begin with; the
+    // scheduler brings the next ones at the pace for the screen
+    pace_large_creatures(entity_manager, screen_bounds);
+    for _ in 0..large_creature_limit(screen_bounds) {
+        random_object(entity_manager, screen_bounds);
+    }
+}
+
+/// Fit a filled tank to new screen bounds, keeping its creatures
+///
+/// Every entity moves itself into place through its `on_resize` hook. The
+/// castles are then laid out again, and fish and seaweed are topped up or
+/// thinned out to the numbers a fresh tank of the new size would get.
+pub fn resize_aquarium(entity_manager: &mut EntityManager, old_bounds: Rect, new_bounds: Rect) {
+    entity_manager.resize_all(old_bounds, new_bounds);
+
+    // Castles keep their left-to-right order
+    let mut castles: Vec<(f32, EntityId)> = entity_manager
+        .get_entities_of_kind(EntityKind::Castle)
+        .iter()
+        .map(|castle| (castle.position().x, castle.id()))
+        .collect();
+    castles.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let background = entity_manager.background();
+    let positions = castle_positions(new_bounds, background);
+    let y = new_bounds.height.saturating_sub(background.height()) as f32;
+    for (index, &(_, id)) in castles.iter().enumerate() {
+        match (positions.get(index), entity_manager.get_entity_mut(id)) {
+            (Some(&x), Some(castle)) => castle.set_position(Position::new(x, y, castle.depth())),
+            _ => entity_manager.remove_entity(id),
+        }
+    }
+    for &x in positions.iter().skip(castles.len()) {
+        add_scene_at(entity_manager, background, x, y);
+    }
+
+    fit_population(
+        entity_manager,
+        Population::Seaweed,
+        seaweed_count(new_bounds),
+        new_bounds,
+    );
+    fit_fish(entity_manager, new_bounds);
+
+    // Wider screens have room for more large creatures, narrower ones
+    // lose the newest
+    pace_large_creatures(entity_manager, new_bounds);
+    entity_manager.trim_large_creatures(new_bounds);
+    for _ in 0..large_creature_limit(new_bounds) {
+        random_object(entity_manager, new_bounds);
+    }
+}
+
+/// Set the target of `population` and add or remove members until it is
+/// met. Members off the new screen are removed first, then the newest.
+fn fit_population(
+    entity_manager: &mut EntityManager,
+    population: Population,
+    target: usize,
+    screen_bounds: Rect,
+) {
+    entity_manager
+        .scheduler_mut()
+        .set_target(population, target);
+    let mut members: Vec<(bool, Reverse<Option<u64>>, EntityId)> = entity_manager
+        .entities()
+        .filter(|entity| entity.population() == Some(population))
+        .map(|entity| {
+            let x = entity.position().x;
+            let width = entity.get_current_sprite().get_bounding_box().0 as f32;
+            let visible = x + width > 0.0 && x < screen_bounds.width as f32;
+            let id = entity.id();
+            (visible, Reverse(entity_manager.spawn_sequence(id)), id)
+        })
+        .collect();
+
+    if members.len() > target {
+        members.sort_unstable();
+        let excess = members.len() - target;
+        for (.., id) in members.into_iter().take(excess) {
+            entity_manager.remove_entity(id);
+        }
+    } else {
+        for _ in members.len()..target {
+            (population.spawner())(entity_manager, screen_bounds);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::WATERLINE_Y;
+    use crate::rng::RngHandle;
+
+    #[test]
+    fn test_normal_width_layout() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        assert_eq!(large_creature_limit(bounds), 1);
+        assert_eq!(castle_positions(bounds, Background::Castle), vec![48.0]);
+    }
+
+    #[test]
+    fn test_ultrawide_layout() {
+        let bounds = Rect::new(0, 0, 450, 50);
+        assert_eq!(large_creature_limit(bounds), 3);
+        assert_eq!(
+            castle_positions(bounds, Background::Castle),
+            vec![118.0, 268.0, 418.0]
+        );
+        assert_eq!(segments(bounds)[1], Rect::new(150, 0, 150, 50));
+    }
+
+    #[test]
+    fn test_large_spawn_rate_grows_with_area() {
+        assert_eq!(large_spawn_interval(Rect::new(0, 0, 200, 60)), None);
+        let reference = large_spawn_interval(Rect::new(0, 0, 300, 40)).unwrap();
+        assert_eq!(reference, ULTRAWIDE_SPAWN_INTERVAL);
+        let double = large_spawn_interval(Rect::new(0, 0, 300, 80)).unwrap();
+        assert_eq!(double, reference / 2);
+
+        let bounds = Rect::new(0, 0, 450, 50);
+        let mut entity_manager = EntityManager::new();
+        initialize_aquarium(&mut entity_manager, bounds);
+        let policy = entity_manager.scheduler().policy(Population::Large);
+        assert_eq!(policy.interval, large_spawn_interval(bounds));
+    }
+
+    #[test]
+    fn test_decorations_spread_across_segments() {
+        use crate::registry::Category;
+
+        let bounds = Rect::new(0, 0, 450, 50);
+        let mut entity_manager = EntityManager::new();
+        entity_manager
+            .registry_mut()
+            .register("rock", 1.0, Category::Decoration, |manager, area| {
+                let x = area.x as f32;
+                manager.spawn(|id| Glint::new(id, x, 40.0));
+            })
+            .size = Some((1, 1));
+        initialize_aquarium(&mut entity_manager, bounds);
+
+        let mut rocks: Vec<f32> = entity_manager
+            .get_entities_of_kind(EntityKind::Glint)
+            .iter()
+            .map(|rock| rock.position().x)
+            .collect();
+        rocks.sort_by(f32::total_cmp);
+        assert_eq!(rocks, vec![0.0, 150.0, 300.0]);
+    }
+
+    #[test]
+    fn test_background_scene_placement() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let reef = Background::Reef;
+        assert_eq!(
+            castle_positions(bounds, reef),
+            vec![(80 - reef.width()) as f32]
+        );
+        assert!(castle_positions(bounds, Background::None).is_empty());
+
+        let mut entity_manager = EntityManager::new();
+        entity_manager.set_background(Background::None);
+        initialize_aquarium(&mut entity_manager, bounds);
+        assert!(entity_manager
+            .get_entities_of_kind(EntityKind::Castle)
+            .is_empty());
+
+        let mut entity_manager = EntityManager::new();
+        entity_manager.set_background(Background::Atlantis);
+        initialize_aquarium(&mut entity_manager, bounds);
+        let castles = entity_manager.get_entities_of_kind(EntityKind::Castle);
+        assert_eq!(castles.len(), 1);
+        assert_eq!(
+            castles[0].position().y,
+            (24 - Background::Atlantis.height()) as f32
+        );
+    }
+
+    #[test]
+    fn test_resize_keeps_creatures() {
+        let small = Rect::new(0, 0, 80, 24);
+        let wide = Rect::new(0, 0, 320, 40);
+        let mut entity_manager = EntityManager::new();
+        initialize_aquarium(&mut entity_manager, small);
+        let fish_before: Vec<EntityId> = entity_manager
+            .get_entities_of_kind(EntityKind::Fish)
+            .iter()
+            .map(|fish| fish.id())
+            .collect();
+
+        resize_aquarium(&mut entity_manager, small, wide);
+        entity_manager.check_invariants().unwrap();
+
+        assert_eq!(
+            entity_manager.get_entities_of_kind(EntityKind::Fish).len(),
+            fish_count(wide, WATERLINE_Y)
+        );
+        for id in fish_before {
+            assert!(entity_manager.get_entity_mut(id).is_some());
+        }
+        let mut castles: Vec<f32> = entity_manager
+            .get_entities_of_kind(EntityKind::Castle)
+            .iter()
+            .map(|castle| castle.position().x)
+            .collect();
+        castles.sort_by(f32::total_cmp);
+        assert_eq!(castles, castle_positions(wide, Background::Castle));
+        for seaweed in entity_manager.get_entities_of_kind(EntityKind::Seaweed) {
+            let height = seaweed.get_current_sprite().get_bounding_box().1;
+            assert_eq!(seaweed.position().y, (40 - height) as f32);
+        }
+        let sand = entity_manager.get_entities_of_kind(EntityKind::Sand);
+        assert_eq!(sand[0].get_current_sprite().get_bounding_box().0, 320);
+    }
+
+    #[test]
+    fn test_resize_thins_out_population() {
+        let wide = Rect::new(0, 0, 320, 40);
+        let small = Rect::new(0, 0, 80, 24);
+        let mut entity_manager = EntityManager::new();
+        initialize_aquarium(&mut entity_manager, wide);
+
+        resize_aquarium(&mut entity_manager, wide, small);
+        entity_manager.check_invariants().unwrap();
+
+        assert_eq!(
+            entity_manager.get_entities_of_kind(EntityKind::Fish).len(),
+            fish_count(small, WATERLINE_Y)
+        );
+        assert_eq!(
+            entity_manager
+                .get_entities_of_kind(EntityKind::Seaweed)
+                .len(),
+            seaweed_count(small)
+        );
+        assert_eq!(
+            entity_manager
+                .get_entities_of_kind(EntityKind::Castle)
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_fish_density_adds_and_retires_fish() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut entity_manager = EntityManager::new();
+        initialize_aquarium(&mut entity_manager, bounds);
+        let fish = |manager: &EntityManager| manager.get_entities_of_kind(EntityKind::Fish).len();
+
+        entity_manager.set_fish_density(2.0);
+        fit_fish(&mut entity_manager, bounds);
+        assert_eq!(fish(&entity_manager), fish_count(bounds, WATERLINE_Y) * 2);
+
+        entity_manager.set_fish_density(0.5);
+        fit_fish(&mut entity_manager, bounds);
+        assert_eq!(fish(&entity_manager), fish_target(&entity_manager, bounds));
+        entity_manager.check_invariants().unwrap();
+
+        entity_manager.set_fish_density(100.0);
+        assert_eq!(entity_manager.fish_density(), MAX_FISH_DENSITY);
+    }
+
+    #[test]
+    fn test_fit_population_retires_the_newest() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut entity_manager = EntityManager::new();
+        let seaweed = |manager: &mut EntityManager, x| {
+            manager.spawn(|id| Seaweed::new(id, x, 15.0, 4, &mut RngHandle::default()))
+        };
+        let ids: Vec<_> = (0..3)
+            .map(|n| seaweed(&mut entity_manager, 10.0 + n as f32 * 10.0))
+            .collect();
+        entity_manager.remove_entity(ids[0]);
+        entity_manager.remove_entity(ids[2]);
+        let older = seaweed(&mut entity_manager, 50.0);
+        let newest = seaweed(&mut entity_manager, 60.0);
+        assert!(newest < older);
+
+        fit_population(&mut entity_manager, Population::Seaweed, 2, bounds);
+        assert!(entity_manager.get_entity(newest).is_none());
+        assert!(entity_manager.get_entity(older).is_some());
+        assert!(entity_manager.get_entity(ids[1]).is_some());
+    }
+
+    #[test]
+    fn test_initialize_ultrawide_aquarium() {
+        let bounds = Rect::new(0, 0, 320, 40);
+        let mut entity_manager = EntityManager::new();
+        initialize_aquarium(&mut entity_manager, bounds);
+
+        assert_eq!(
+            entity_manager
+                .get_entities_of_kind(EntityKind::Castle)
+                .len(),
+            2
+        );
+        assert_eq!(entity_manager.large_creature_limit(), 2);
+        assert!(entity_manager.large_creatures_full());
+        assert!(entity_manager.get_entities_of_kind(EntityKind::Shark).len() <= 1);
+    }
+
+    #[test]
+    fn test_seaweed_spawns_in_free_columns() {
+        let bounds = Rect::new(0, 0, 200, 30);
+        let mut entity_manager = EntityManager::new();
+        add_sand(&mut entity_manager, bounds);
+        add_castle(&mut entity_manager, bounds);
+        for _ in 0..5 {
+            add_seaweed(&mut entity_manager, bounds);
+        }
+
+        for seaweed in entity_manager.get_entities_of_kind(EntityKind::Seaweed) {
+            let size = seaweed.get_current_sprite().get_bounding_box();
+            assert_eq!(
+                entity_manager.occupants(seaweed.position(), size),
+                vec![seaweed.id()]
+            );
+        }
+    }
+
+    #[test]
+    fn test_school_is_not_replaced() {
+        let bounds = Rect::new(0, 0, 120, 40);
+        let mut entity_manager = EntityManager::new();
+        add_school(&mut entity_manager, bounds);
+
+        let school = entity_manager.get_entities_of_kind(EntityKind::Fish);
+        assert!((5..=8).contains(&school.len()));
+        assert!(school.iter().all(|fish| fish.population().is_none()));
+    }
+
+    #[test]
+    fn test_shark_teeth_get_their_own_id() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        let mut entity_manager = EntityManager::new();
+        add_shark(&mut entity_manager, bounds);
+
+        let shark = entity_manager.get_entities_of_kind(EntityKind::Shark)[0];
+        let teeth_id = entity_manager.children_of(shark.id())[0];
+        assert_ne!(teeth_id, shark.id());
+        assert_eq!(
+            entity_manager.get_entities_of_kind(EntityKind::SharkTeeth)[0].id(),
+            teeth_id
+        );
+    }
+
+    #[test]
+    fn test_rescue_replaces_the_shark_with_a_harmless_creature() {
+        let bounds = Rect::new(0, 0, 200, 40);
+        let mut entity_manager = EntityManager::new();
+        assert!(!rescue_fish(&mut entity_manager, bounds));
+
+        add_shark(&mut entity_manager, bounds);
+        assert!(rescue_fish(&mut entity_manager, bounds));
+        entity_manager.check_invariants().unwrap();
+        for kind in PREDATORS.into_iter().chain([EntityKind::SharkTeeth]) {
+            assert!(entity_manager.get_entities_of_kind(kind).is_empty());
+        }
+        assert_eq!(
+            entity_manager.get_entities_of_kind(EntityKind::Puff).len(),
+            1
+        );
+        assert_eq!(entity_manager.population_size(Population::Large), 1);
+    }
+
+    #[test]
+    fn test_narrow_screens_skip_wide_creatures() {
+        let narrow = Rect::new(0, 0, 50, 30);
+        for _ in 0..50 {
+            let mut entity_manager = EntityManager::new();
+            random_object(&mut entity_manager, narrow);
+            let creature = entity_manager.entities().next().unwrap();
+            assert!(
+                matches!(
+                    creature.kind(),
+                    EntityKind::Ship | EntityKind::Whale | EntityKind::BigFish
+                ),
+                "{} on a 50 column screen",
+                creature.entity_type()
+            );
+        }
+    }
+
+    #[test]
+    fn test_builtin_masks_fit() {
+        let bounds = Rect::new(0, 0, 200, 60);
+        let mut entity_manager = EntityManager::new();
+        initialize_aquarium(&mut entity_manager, bounds);
+        // Both facings and every variant turn up in a few rounds
+        for _ in 0..20 {
+            add_all_fish(&mut entity_manager, bounds);
+            add_ship(&mut entity_manager, bounds);
+            add_whale(&mut entity_manager, bounds);
+            add_sea_monster(&mut entity_manager, bounds);
+            add_shark(&mut entity_manager, bounds);
+            add_big_fish(&mut entity_manager, bounds);
+            add_bubble_burst(&mut entity_manager, bounds);
+            add_glint(&mut entity_manager, bounds);
+            add_anchor(&mut entity_manager, bounds);
+        }
+
+        for entity in entity_manager.entities() {
+            if let Err(err) = entity.get_current_sprite().check_mask() {
+                panic!("{}: {}", entity.entity_type(), err);
+            }
+        }
+    }
 }
@@ -0,0 +1,163 @@
+//! Per-session statistics
+//!
+//! The app subscribes a [`SessionStats`] to the aquarium's event hooks and
+//! counts what happens while it runs. Counts start from zero each session
+//! and survive resizes and redraws, like any other hook.
+
+use crate::clock::Instant;
+use crate::collision::ContactKind;
+use crate::hooks::AquariumEvent;
+use std::time::Duration;
+
+/// Entity types that count as large creatures
+pub const LARGE_CREATURES: [&str; 5] = ["shark", "whale", "ship", "sea_monster", "big_fish"];
+
+/// Counts gathered over one run of the app
+#[derive(Debug, Clone)]
+pub struct SessionStats {
+    started_at: Instant,
+    large_creatures_seen: [u32; LARGE_CREATURES.len()],
+    fish_eaten: u32,
+    fish_caught: u32,
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            large_creatures_seen: [0; LARGE_CREATURES.len()],
+            fish_eaten: 0,
+            fish_caught: 0,
+        }
+    }
+
+    /// Update the counts from an aquarium event
+    pub fn record(&mut self, event: &AquariumEvent) {
+        match event {
+            AquariumEvent::Spawned { entity_type, .. } => {
+                if let Some(index) = LARGE_CREATURES.iter().position(|kind| kind == entity_type) {
+                    self.large_creatures_seen[index] += 1;
+                }
+            }
+            AquariumEvent::Collision { kind, .. } => match kind {
+                ContactKind::Bite => self.fish_eaten += 1,
+                ContactKind::Hooked => self.fish_caught += 1,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// How long this session has been running
+    pub fn elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// Large creatures seen so far, of all kinds
+    pub fn large_creatures_seen(&self) -> u32 {
+        self.large_creatures_seen.iter().sum()
+    }
+
+    /// Fish bitten by a shark so far
+    pub fn fish_eaten(&self) -> u32 {
+        self.fish_eaten
+    }
+
+    /// Fish taken by the fishhook so far
+    pub fn fish_caught(&self) -> u32 {
+        self.fish_caught
+    }
+
+    /// Large creatures seen so far, per entity type
+    pub fn large_creatures_by_kind(&self) -> impl Iterator<Item = (&'static str, u32)> + '_ {
+        LARGE_CREATURES
+            .iter()
+            .copied()
+            .zip(self.large_creatures_seen.iter().copied())
+    }
+
+    /// Lines for the stats overlay
+    pub fn summary(&self) -> Vec<String> {
+        let secs = self.elapsed().as_secs();
+        let mut lines = vec![
+            format!(
+                "Session: {}:{:02}:{:02}",
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60
+            ),
+            format!("Fish eaten by sharks: {}", self.fish_eaten),
+            format!("Fish caught on the hook: {}", self.fish_caught),
+            format!("Large creatures seen: {}", self.large_creatures_seen()),
+        ];
+        lines.extend(
+            self.large_creatures_by_kind()
+                .map(|(kind, count)| format!("  {kind}: {count}")),
+        );
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_large_creature_spawns() {
+        let mut stats = SessionStats::new();
+        stats.record(&AquariumEvent::Spawned {
+            id: 1,
+            entity_type: "shark",
+        });
+        stats.record(&AquariumEvent::Spawned {
+            id: 2,
+            entity_type: "shark_teeth",
+        });
+        stats.record(&AquariumEvent::Spawned {
+            id: 3,
+            entity_type: "whale",
+        });
+        stats.record(&AquariumEvent::Killed {
+            id: 1,
+            entity_type: "shark",
+        });
+
+        assert_eq!(stats.large_creatures_seen(), 2);
+        let by_kind: Vec<_> = stats.large_creatures_by_kind().collect();
+        assert_eq!(by_kind[0], ("shark", 1));
+        assert_eq!(by_kind[1], ("whale", 1));
+    }
+
+    #[test]
+    fn test_counts_fish_eaten_and_caught() {
+        let mut stats = SessionStats::new();
+        for kind in [
+            ContactKind::Bite,
+            ContactKind::Hooked,
+            ContactKind::Bite,
+            ContactKind::Fed,
+        ] {
+            stats.record(&AquariumEvent::Collision {
+                first: 1,
+                second: 2,
+                kind,
+            });
+        }
+        assert_eq!((stats.fish_eaten(), stats.fish_caught()), (2, 1));
+        assert!(stats.summary()[1].ends_with(": 2"));
+    }
+
+    #[test]
+    fn test_summary_lists_every_kind() {
+        let stats = SessionStats::new();
+        let summary = stats.summary();
+        assert_eq!(summary.len(), 4 + LARGE_CREATURES.len());
+        assert!(summary[0].starts_with("Session: 0:00:0"));
+    }
+}
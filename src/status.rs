@@ -0,0 +1,151 @@
+//! Status line layout
+//!
+//! The status line is built from a template such as
+//! `"{fish} fish | {entities} entities | {fps} fps"`: each `{name}` is
+//! replaced with the current value of that field, and anything else is
+//! kept as written. The default template reproduces the original line.
+//! Its colours and whether it sits on the top or bottom row are
+//! configurable too.
+
+use ratatui::style::{Color, Style};
+
+/// Template giving the classic status line
+pub const DEFAULT_FORMAT: &str = "{paused}{speed}Fish: {fish} | Bubbles: {bubbles} | Water: {water} | {first_fish} | Current: {current} | Total: {entities} | Seen: {seen} | Eaten: {eaten} | Caught: {caught} | {keys}";
+
+/// Key help shown by `{keys}`
+pub const KEYS: &str =
+    "q=quit r=redraw p=pause s=stats n=night f=fps F=feed +/-=speed [/]=fish arrows/hjkl=pan c=follow tab=inspect F3=debug F12=screenshot S/W/M/B/H/D=summon X=rescue";
+
+/// Row the status line is drawn on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusPosition {
+    Top,
+    #[default]
+    Bottom,
+}
+
+impl StatusPosition {
+    /// Parse a `--status-position` value
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "top" => Some(Self::Top),
+            "bottom" => Some(Self::Bottom),
+            _ => None,
+        }
+    }
+}
+
+/// How the status line looks
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusBar {
+    /// Template with `{name}` placeholders
+    pub format: String,
+    pub fg: Color,
+    pub bg: Color,
+    pub position: StatusPosition,
+}
+
+impl Default for StatusBar {
+    fn default() -> Self {
+        Self {
+            format: DEFAULT_FORMAT.to_string(),
+            fg: Color::White,
+            bg: Color::Black,
+            position: StatusPosition::Bottom,
+        }
+    }
+}
+
+impl StatusBar {
+    pub fn style(&self) -> Style {
+        Style::default().fg(self.fg).bg(self.bg)
+    }
+
+    /// Row of `height` rows the line is drawn on
+    pub fn row(&self, height: u16) -> u16 {
+        match self.position {
+            StatusPosition::Top => 0,
+            StatusPosition::Bottom => height.saturating_sub(1),
+        }
+    }
+
+    /// The line for the current values, looked up by placeholder name
+    pub fn line(&self, value: impl Fn(&str) -> Option<String>) -> String {
+        expand(&self.format, value)
+    }
+}
+
+/// Replace each `{name}` in `template` that `value` knows about; unknown
+/// placeholders and unmatched braces are left alone
+pub fn expand(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut line = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        line.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}').map(|end| (&after[..end], end)) {
+            Some((name, end)) if !name.contains('{') => {
+                match value(name) {
+                    Some(text) => line.push_str(&text),
+                    None => line.push_str(&rest[start..start + end + 2]),
+                }
+                rest = &after[end + 1..];
+            }
+            _ => {
+                line.push('{');
+                rest = after;
+            }
+        }
+    }
+    line.push_str(rest);
+    line
+}
+
+/// Parse a colour name (`white`, `light-blue`), index or `#rrggbb`
+pub fn parse_color(spec: &str) -> Option<Color> {
+    spec.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(name: &str) -> Option<String> {
+        match name {
+            "fish" => Some("12".to_string()),
+            "fps" => Some("30".to_string()),
+            "paused" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(
+            expand("{fish} fish | {fps} fps", values),
+            "12 fish | 30 fps"
+        );
+        assert_eq!(expand("{paused}{fish}", values), "12");
+        assert_eq!(expand("{bogus} {fish}", values), "{bogus} 12");
+        assert_eq!(expand("a { b {fish", values), "a { b {fish");
+        assert_eq!(expand("{{fish}}", values), "{12}");
+    }
+
+    #[test]
+    fn test_row() {
+        let mut bar = StatusBar::default();
+        assert_eq!(bar.row(24), 23);
+        bar.position = StatusPosition::Top;
+        assert_eq!(bar.row(24), 0);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(StatusPosition::parse("TOP"), Some(StatusPosition::Top));
+        assert_eq!(StatusPosition::parse("middle"), None);
+        assert_eq!(parse_color("#ff8000"), Some(Color::Rgb(255, 128, 0)));
+        assert_eq!(parse_color("blue"), Some(Color::Blue));
+        assert_eq!(parse_color("sparkly"), None);
+    }
+}
@@ -0,0 +1,476 @@
+//! Slot storage for the tank's entities
+//!
+//! Entities live in a vector of slots. An [`EntityId`] names a slot and the
+//! generation the slot was in when the entity was put there: the low 32
+//! bits hold the slot number plus one, the high 32 the generation. A freed
+//! slot is reused under the next generation, so the id of a removed entity
+//! never finds whatever took its place. A new store hands out 1, 2, 3, ...
+//! and ids stay unique for its whole life, but later ids aren't
+//! necessarily larger: compare [`EntityStore::sequence`] to tell older
+//! entities from newer ones.
+//!
+//! The store also keeps the render order, back to front: by depth, then
+//! [`Entity::z_hint`], then oldest first. The draw list is kept sorted as
+//! entities come, go or change depth or hint, each change moving only the
+//! entry concerned, so drawing a frame never sorts.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::ops::Index;
+use std::time::Duration;
+
+use crate::entity::{Entity, EntityId};
+
+/// One place for an entity, with what it was last stacked by
+#[derive(Default)]
+struct Slot {
+    /// Generation of the slot's current or next occupant
+    generation: u32,
+    entity: Option<Box<dyn Entity>>,
+    depth: u8,
+    z_hint: i32,
+    /// When the occupant was added, counting all additions
+    sequence: u64,
+    /// Store time when the occupant was added
+    born: Duration,
+}
+
+/// Entities by id, with their render order
+#[derive(Default)]
+pub struct EntityStore {
+    slots: Vec<Slot>,
+    /// Empty slots, the most recently freed last
+    free: Vec<u32>,
+    len: usize,
+    added: u64,
+    /// Time passed, as told by [`EntityStore::advance`]
+    now: Duration,
+    /// Draw list, back to front
+    order: Vec<StackKey>,
+}
+
+/// Where an entity is drawn: deepest first, then by stacking hint, then
+/// oldest first
+type StackKey = (Reverse<u8>, i32, u64, EntityId);
+
+impl Slot {
+    fn stack_key(&self, id: EntityId) -> StackKey {
+        (Reverse(self.depth), self.z_hint, self.sequence, id)
+    }
+}
+
+fn make_id(index: u32, generation: u32) -> EntityId {
+    (generation as u64) << 32 | (index as u64 + 1)
+}
+
+/// Slot number and generation of `id` (None for id 0, which is never used)
+fn split_id(id: EntityId) -> Option<(usize, u32)> {
+    let index = (id as u32).checked_sub(1)?;
+    Some((index as usize, (id >> 32) as u32))
+}
+
+impl EntityStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id the next inserted entity gets
+    pub fn next_id(&self) -> EntityId {
+        match self.free.last() {
+            Some(&index) => make_id(index, self.slots[index as usize].generation),
+            None => make_id(self.slots.len() as u32, 0),
+        }
+    }
+
+    /// Add an entity under [`EntityStore::next_id`]
+    pub fn insert(&mut self, entity: Box<dyn Entity>) -> EntityId {
+        let id = self.next_id();
+        let index = match self.free.pop() {
+            Some(index) => index as usize,
+            None => {
+                self.slots.push(Slot::default());
+                self.slots.len() - 1
+            }
+        };
+        let slot = &mut self.slots[index];
+        slot.depth = entity.depth();
+        slot.z_hint = entity.z_hint();
+        slot.sequence = self.added;
+        slot.born = self.now;
+        slot.entity = Some(entity);
+        let key = slot.stack_key(id);
+        self.added += 1;
+        self.len += 1;
+        self.stack(key);
+        id
+    }
+
+    /// Take out the entity `id`, freeing its slot for the next generation
+    pub fn remove(&mut self, id: EntityId) -> Option<Box<dyn Entity>> {
+        let index = self.find(id)?;
+        let key = self.slots[index].stack_key(id);
+        self.unstack(key);
+        let slot = &mut self.slots[index];
+        let entity = slot.entity.take();
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(index as u32);
+        self.len -= 1;
+        entity
+    }
+
+    /// Put `key` in its place in the draw list
+    fn stack(&mut self, key: StackKey) {
+        let at = self.order.partition_point(|other| *other < key);
+        self.order.insert(at, key);
+    }
+
+    /// Take `key` out of the draw list
+    fn unstack(&mut self, key: StackKey) {
+        if let Ok(at) = self.order.binary_search(&key) {
+            self.order.remove(at);
+        }
+    }
+
+    /// Slot holding `id`, if it is still there
+    fn find(&self, id: EntityId) -> Option<usize> {
+        let (index, generation) = split_id(id)?;
+        let slot = self.slots.get(index)?;
+        (slot.generation == generation && slot.entity.is_some()).then_some(index)
+    }
+
+    /// How many entities were added before `id`, over the store's life
+    pub fn sequence(&self, id: EntityId) -> Option<u64> {
+        let index = self.find(id)?;
+        Some(self.slots[index].sequence)
+    }
+
+    /// Move the store's clock on by `elapsed`, ageing every entity
+    pub fn advance(&mut self, elapsed: Duration) {
+        self.now += elapsed;
+    }
+
+    /// How much store time has passed since `id` was added
+    pub fn age(&self, id: EntityId) -> Option<Duration> {
+        let index = self.find(id)?;
+        Some(self.now.saturating_sub(self.slots[index].born))
+    }
+
+    pub fn contains(&self, id: EntityId) -> bool {
+        self.find(id).is_some()
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&dyn Entity> {
+        let index = self.find(id)?;
+        self.slots[index].entity.as_deref()
+    }
+
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut dyn Entity> {
+        let index = self.find(id)?;
+        self.slots[index].entity.as_deref_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Every entity with its id, in slot order
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &dyn Entity)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let entity = slot.entity.as_deref()?;
+            Some((make_id(index as u32, slot.generation), entity))
+        })
+    }
+
+    /// Every entity with its id, in slot order
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (EntityId, &mut dyn Entity)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let id = make_id(index as u32, slot.generation);
+                Some((id, slot.entity.as_deref_mut()?))
+            })
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &dyn Entity> {
+        self.iter().map(|(_, entity)| entity)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut dyn Entity> {
+        self.iter_mut().map(|(_, entity)| entity)
+    }
+
+    /// Pick up depth and stacking hint changes since the last call; true
+    /// if any entity moved
+    pub fn restack(&mut self) -> bool {
+        let mut moved = Vec::new();
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            let Some(entity) = &slot.entity else {
+                continue;
+            };
+            let (depth, z_hint) = (entity.depth(), entity.z_hint());
+            if (depth, z_hint) != (slot.depth, slot.z_hint) {
+                let id = make_id(index as u32, slot.generation);
+                let old = slot.stack_key(id);
+                slot.depth = depth;
+                slot.z_hint = z_hint;
+                moved.push((old, slot.stack_key(id)));
+            }
+        }
+        for &(old, new) in &moved {
+            self.unstack(old);
+            self.stack(new);
+        }
+        !moved.is_empty()
+    }
+
+    /// Ids back to front, in the order they are drawn
+    pub fn render_order(&self) -> impl Iterator<Item = EntityId> + '_ {
+        self.order.iter().map(|&(.., id)| id)
+    }
+
+    /// Ids at `depth`, bottom of the stack first
+    pub fn layer(&self, depth: u8) -> Vec<EntityId> {
+        let start = self.order.partition_point(|&(Reverse(d), ..)| d > depth);
+        self.order[start..]
+            .iter()
+            .take_while(|&&(Reverse(d), ..)| d == depth)
+            .map(|&(.., id)| id)
+            .collect()
+    }
+
+    /// Number of entities at each depth, front to back
+    pub fn layer_counts(&self) -> Vec<(u8, usize)> {
+        let mut counts = BTreeMap::new();
+        for slot in self.slots.iter().filter(|slot| slot.entity.is_some()) {
+            *counts.entry(slot.depth).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
+    }
+
+    /// Verify the slots agree with each other and with the entities in them
+    pub fn check(&self) -> Result<(), String> {
+        let occupied = self.iter().count();
+        if occupied != self.len {
+            return Err(format!(
+                "{occupied} entities stored but {} counted",
+                self.len
+            ));
+        }
+        for &index in &self.free {
+            if self.slots[index as usize].entity.is_some() {
+                return Err(format!("slot {index} is both free and occupied"));
+            }
+        }
+        for (id, entity) in self.iter() {
+            if entity.id() != id {
+                return Err(format!("entity {} is stored as {id}", entity.id()));
+            }
+        }
+        if self.order.len() != self.len || !self.render_order().all(|id| self.contains(id)) {
+            return Err("render order is out of date".to_string());
+        }
+        if !self.order.is_sorted() {
+            return Err("render order is out of order".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Index<EntityId> for EntityStore {
+    type Output = dyn Entity;
+
+    fn index(&self, id: EntityId) -> &Self::Output {
+        self.get(id)
+            .unwrap_or_else(|| panic!("no entity {id} in the store"))
+    }
+}
+
+impl std::fmt::Debug for EntityStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EntityStore")
+            .field("len", &self.len)
+            .field("slots", &self.slots.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Glint;
+    use crate::entity::{EntityKind, Position, Sprite, Velocity};
+    use ratatui::layout::Rect;
+
+    /// A glint whose stacking hint can be changed
+    struct Hinted {
+        glint: Glint,
+        z_hint: i32,
+    }
+
+    impl Entity for Hinted {
+        fn id(&self) -> EntityId {
+            self.glint.id()
+        }
+
+        fn position(&self) -> Position {
+            self.glint.position()
+        }
+
+        fn set_position(&mut self, position: Position) {
+            self.glint.set_position(position);
+        }
+
+        fn velocity(&self) -> Velocity {
+            self.glint.velocity()
+        }
+
+        fn set_velocity(&mut self, velocity: Velocity) {
+            self.glint.set_velocity(velocity);
+        }
+
+        fn depth(&self) -> u8 {
+            self.glint.depth()
+        }
+
+        fn get_current_sprite(&self) -> &Sprite {
+            self.glint.get_current_sprite()
+        }
+
+        fn update(&mut self, delta_time: Duration, world_bounds: Rect) {
+            self.glint.update(delta_time, world_bounds);
+        }
+
+        fn is_alive(&self) -> bool {
+            self.glint.is_alive()
+        }
+
+        fn kill(&mut self) {
+            self.glint.kill();
+        }
+
+        fn kind(&self) -> EntityKind {
+            self.glint.kind()
+        }
+
+        fn z_hint(&self) -> i32 {
+            self.z_hint
+        }
+    }
+
+    #[test]
+    fn test_stale_ids_miss_reused_slots() {
+        let mut store = EntityStore::new();
+        let first = store.insert(Box::new(Glint::new(1, 0.0, 0.0)));
+        let second = store.insert(Box::new(Glint::new(2, 5.0, 0.0)));
+        assert_eq!((first, second), (1, 2));
+
+        store.remove(first);
+        let third = store.next_id();
+        store.insert(Box::new(Glint::new(third, 9.0, 0.0)));
+        assert_ne!(third, first);
+        assert!(store.get(first).is_none());
+        assert!(store.remove(first).is_none());
+        assert_eq!(store[third].position().x, 9.0);
+        assert_eq!(store.len(), 2);
+        store.check().unwrap();
+    }
+
+    #[test]
+    fn test_sequence_follows_insertion_not_ids() {
+        let mut store = EntityStore::new();
+        let ids: Vec<_> = (0..3)
+            .map(|n| store.insert(Box::new(Glint::new(store.next_id(), n as f32, 0.0))))
+            .collect();
+        store.remove(ids[0]);
+        store.remove(ids[2]);
+        let older = store.insert(Box::new(Glint::new(store.next_id(), 0.0, 0.0)));
+        let newer = store.insert(Box::new(Glint::new(store.next_id(), 0.0, 0.0)));
+        // The newer entity took the lower slot
+        assert!(newer < older);
+        assert!(store.sequence(newer) > store.sequence(older));
+        assert_eq!(store.sequence(ids[1]), Some(1));
+        assert_eq!(store.sequence(ids[0]), None);
+    }
+
+    #[test]
+    fn test_age_counts_from_insertion() {
+        let mut store = EntityStore::new();
+        let old = store.insert(Box::new(Glint::new(store.next_id(), 0.0, 0.0)));
+        store.advance(Duration::from_secs(5));
+        let young = store.insert(Box::new(Glint::new(store.next_id(), 0.0, 0.0)));
+        store.advance(Duration::from_secs(2));
+        assert_eq!(store.age(old), Some(Duration::from_secs(7)));
+        assert_eq!(store.age(young), Some(Duration::from_secs(2)));
+        store.remove(old);
+        assert_eq!(store.age(old), None);
+    }
+
+    #[test]
+    fn test_render_order() {
+        use crate::entity::Position;
+
+        let mut store = EntityStore::new();
+        let back = store.insert(Box::new(Glint::new(1, 0.0, 0.0)));
+        let front = store.insert(Box::new(Glint::new(2, 0.0, 0.0)));
+        let newer = store.insert(Box::new(Glint::new(3, 0.0, 0.0)));
+        let depth = store[back].depth();
+        let mut position = store[back].position();
+        position.depth = depth + 1;
+        store.get_mut(back).unwrap().set_position(position);
+        assert!(store.restack());
+        assert_eq!(
+            store.render_order().collect::<Vec<_>>(),
+            [back, front, newer]
+        );
+
+        // Deeper layers are drawn first, whatever their age
+        let mut position = Position {
+            depth: depth + 2,
+            ..position
+        };
+        store.get_mut(front).unwrap().set_position(position);
+        store.restack();
+        assert_eq!(
+            store.render_order().collect::<Vec<_>>(),
+            [front, back, newer]
+        );
+        position.depth = depth;
+        store.get_mut(front).unwrap().set_position(position);
+        store.restack();
+        assert_eq!(store.layer(depth), [front, newer]);
+        assert_eq!(store.layer_counts(), vec![(depth, 2), (depth + 1, 1)]);
+
+        // Newcomers go on top of their layer and leavers drop out
+        store.remove(newer);
+        let newest = store.insert(Box::new(Glint::new(store.next_id(), 0.0, 0.0)));
+        assert_eq!(
+            store.render_order().collect::<Vec<_>>(),
+            [back, front, newest]
+        );
+        store.check().unwrap();
+    }
+
+    #[test]
+    fn test_restack_on_hint_change() {
+        let mut store = EntityStore::new();
+        let hinted = store.insert(Box::new(Hinted {
+            glint: Glint::new(1, 0.0, 0.0),
+            z_hint: 0,
+        }));
+        let newer = store.insert(Box::new(Glint::new(2, 0.0, 0.0)));
+        assert!(!store.restack());
+        assert_eq!(store.render_order().collect::<Vec<_>>(), [hinted, newer]);
+
+        // Same depth, higher hint: now drawn over the newer glint
+        let entity = store.get_mut(hinted).unwrap() as &mut dyn std::any::Any;
+        entity.downcast_mut::<Hinted>().unwrap().z_hint = 1;
+        assert!(store.restack());
+        assert_eq!(store.render_order().collect::<Vec<_>>(), [newer, hinted]);
+        store.check().unwrap();
+    }
+}
@@ -0,0 +1,201 @@
+//! Stream output for non-TTY destinations
+//!
+//! Dumb terminals, serial consoles and pipes don't cope well with the
+//! alternate screen and full-screen clears that ratatui uses. In stream mode
+//! the aquarium is rendered into an off-screen buffer and only lines that
+//! changed since the previous frame are written, each prefixed with an
+//! explicit cursor address. Nothing is ever followed by a newline, so the
+//! terminal never scrolls and scrollback stays clean.
+
+use crate::simulation::sgr;
+use ratatui::buffer::Buffer;
+use std::io::{self, Write};
+use unicode_width::UnicodeWidthStr;
+
+/// Default size used when the terminal size can't be queried
+pub const FALLBACK_SIZE: (u16, u16) = (80, 24);
+
+/// Writes changed lines of successive frames using cursor addressing
+#[derive(Debug, Default)]
+pub struct StreamRenderer {
+    previous: Vec<String>,
+    /// Whether lines carry their cells' colours as ANSI sequences
+    colors: bool,
+}
+
+impl StreamRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A renderer that keeps colours, for the `dumb` backend
+    pub fn with_colors() -> Self {
+        Self {
+            colors: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn has_colors(&self) -> bool {
+        self.colors
+    }
+
+    /// Write the rows of `buffer` that differ from the previous frame
+    pub fn render_changes<W: Write>(&mut self, buffer: &Buffer, out: &mut W) -> io::Result<()> {
+        let area = buffer.area;
+        let mut lines = Vec::with_capacity(area.height as usize);
+
+        for y in 0..area.height {
+            // Leave the bottom-right cell alone: writing it makes terminals
+            // with automatic margins scroll the whole screen up a line
+            let width = if y + 1 == area.height {
+                area.width.saturating_sub(1)
+            } else {
+                area.width
+            };
+
+            // A wide character covers the cell after it, so skip that cell
+            // to keep the rest of the line in its columns
+            let mut line = String::new();
+            let mut hidden = 0;
+            let mut style = None;
+            for x in 0..width {
+                let cell = &buffer[(area.x + x, area.y + y)];
+                if hidden > 0 {
+                    hidden -= 1;
+                    continue;
+                }
+                if self.colors && style != Some((cell.fg, cell.bg, cell.modifier)) {
+                    line.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                    style = Some((cell.fg, cell.bg, cell.modifier));
+                }
+                line.push_str(cell.symbol());
+                hidden = cell.symbol().width().saturating_sub(1);
+            }
+            if style.is_some() {
+                line.push_str("\x1b[0m");
+            }
+            lines.push(line);
+        }
+
+        for (y, line) in lines.iter().enumerate() {
+            if self.previous.get(y) != Some(line) {
+                // CUP is 1-based: ESC [ row ; col H
+                write!(out, "\x1b[{};1H{}", y + 1, line)?;
+            }
+        }
+        out.flush()?;
+
+        self.previous = lines;
+        Ok(())
+    }
+
+    /// Forget the previous frame so the next one is written in full
+    pub fn invalidate(&mut self) {
+        self.previous.clear();
+    }
+}
+
+/// Best-effort terminal size for stream mode
+///
+/// Uses the terminal if it can be queried, then `COLUMNS`/`LINES`, and
+/// finally [`FALLBACK_SIZE`].
+pub fn stream_size() -> (u16, u16) {
+    if let Ok(size) = ratatui::crossterm::terminal::size() {
+        if size.0 > 0 && size.1 > 0 {
+            return size;
+        }
+    }
+
+    let from_env = |name: &str| std::env::var(name).ok().and_then(|v| v.parse().ok());
+    match (from_env("COLUMNS"), from_env("LINES")) {
+        (Some(width), Some(height)) => (width, height),
+        _ => FALLBACK_SIZE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+
+    fn render(renderer: &mut StreamRenderer, buffer: &Buffer) -> String {
+        let mut out = Vec::new();
+        renderer.render_changes(buffer, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_first_frame_addresses_every_line() {
+        let mut renderer = StreamRenderer::new();
+        let buffer = Buffer::empty(Rect::new(0, 0, 4, 3));
+
+        let output = render(&mut renderer, &buffer);
+        assert!(output.contains("\x1b[1;1H"));
+        assert!(output.contains("\x1b[2;1H"));
+        assert!(output.contains("\x1b[3;1H"));
+        assert!(!output.contains('\n'));
+        assert!(!output.contains("\x1b[2J"));
+    }
+
+    #[test]
+    fn test_only_changed_lines_written() {
+        let mut renderer = StreamRenderer::new();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 3));
+        render(&mut renderer, &buffer);
+
+        buffer[(1, 1)].set_char('>');
+        let output = render(&mut renderer, &buffer);
+        assert_eq!(output, "\x1b[2;1H >  ");
+
+        // No changes, no output
+        assert_eq!(render(&mut renderer, &buffer), "");
+    }
+
+    #[test]
+    fn test_wide_characters_keep_columns() {
+        let mut renderer = StreamRenderer::new();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer[(0, 0)].set_symbol("🐟");
+        buffer[(1, 0)].set_char('x');
+        buffer[(2, 0)].set_char('>');
+
+        let output = render(&mut renderer, &buffer);
+        assert!(output.starts_with("\x1b[1;1H🐟> "));
+    }
+
+    #[test]
+    fn test_bottom_right_cell_never_written() {
+        let mut renderer = StreamRenderer::new();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer[(3, 1)].set_char('X');
+
+        let output = render(&mut renderer, &buffer);
+        assert!(!output.contains('X'));
+    }
+
+    #[test]
+    fn test_colors_kept_when_asked_for() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer[(1, 0)]
+            .set_char('>')
+            .set_fg(ratatui::style::Color::Red);
+
+        let plain = render(&mut StreamRenderer::new(), &buffer);
+        assert!(!plain.contains("\x1b[0"));
+        let colored = render(&mut StreamRenderer::with_colors(), &buffer);
+        assert!(colored.starts_with("\x1b[1;1H\x1b[0m \x1b[0;31m>\x1b[0m  \x1b[0m"));
+    }
+
+    #[test]
+    fn test_invalidate_rewrites_everything() {
+        let mut renderer = StreamRenderer::new();
+        let buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        render(&mut renderer, &buffer);
+
+        renderer.invalidate();
+        let output = render(&mut renderer, &buffer);
+        assert!(output.contains("\x1b[1;1H"));
+        assert!(output.contains("\x1b[2;1H"));
+    }
+}
@@ -0,0 +1,111 @@
+//! Chaos-monkey stress test mode (hidden `--stress` flag)
+//!
+//! Every tick the stress test randomly spawns bursts of entities, resizes the
+//! world, toggles classic/ecology mode and redraws, and the app checks the
+//! entity manager's invariants afterwards. Users can run it to reproduce
+//! performance problems or crashes; maintainers use it to harden the engine.
+
+use crate::entities::Bubble;
+use crate::entity::{EntityManager, Position};
+use crate::spawning;
+use crate::world::WorldContext;
+use rand::Rng;
+use ratatui::layout::Rect;
+
+/// Stop spawning bursts beyond this many entities
+const MAX_ENTITIES: usize = 2000;
+
+/// Chance per tick of each kind of disruption
+const BURST_CHANCE: f64 = 0.3;
+const RESIZE_CHANCE: f64 = 0.02;
+const TOGGLE_CHANCE: f64 = 0.01;
+const REDRAW_CHANCE: f64 = 0.01;
+
+/// Drives random disruptions of the simulation, drawn from the tank's own
+/// generator
+#[derive(Debug, Default)]
+pub struct StressTest {
+    ticks: u64,
+}
+
+impl StressTest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of ticks run so far
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Apply this tick's random disruptions
+    pub fn step(&mut self, entity_manager: &mut EntityManager, world: &mut WorldContext) {
+        self.ticks += 1;
+        let mut rng = entity_manager.rng();
+
+        if rng.gen_bool(RESIZE_CHANCE) {
+            let bounds = Rect::new(0, 0, rng.gen_range(20..=400), rng.gen_range(10..=120));
+            *world = WorldContext::new(bounds);
+            Self::reinitialize(entity_manager, world);
+        }
+
+        if rng.gen_bool(TOGGLE_CHANCE) {
+            entity_manager.set_classic_mode(!entity_manager.classic_mode());
+        }
+        if rng.gen_bool(TOGGLE_CHANCE) {
+            entity_manager.set_ecology_mode(!entity_manager.ecology_mode());
+        }
+        if rng.gen_bool(REDRAW_CHANCE) {
+            Self::reinitialize(entity_manager, world);
+        }
+
+        if rng.gen_bool(BURST_CHANCE) && entity_manager.entity_count() < MAX_ENTITIES {
+            Self::spawn_burst(entity_manager, world.bounds());
+        }
+    }
+
+    /// Throw in a burst of fish and bubbles
+    fn spawn_burst(entity_manager: &mut EntityManager, bounds: Rect) {
+        let mut rng = entity_manager.rng();
+        let count = rng.gen_range(20..=50);
+        for _ in 0..count {
+            if rng.gen_bool(0.5) {
+                spawning::add_fish(entity_manager, bounds);
+            } else {
+                let position = Position::new(
+                    rng.gen_range(0.0..bounds.width.max(1) as f32),
+                    rng.gen_range(0.0..bounds.height.max(1) as f32),
+                    crate::depth::random_fish_depth(&mut rng),
+                );
+                entity_manager.spawn_recycled::<Bubble>(position);
+            }
+        }
+    }
+
+    fn reinitialize(entity_manager: &mut EntityManager, world: &WorldContext) {
+        *entity_manager = entity_manager.fresh();
+        spawning::initialize_aquarium(entity_manager, world.bounds());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stress_keeps_invariants() {
+        let mut stress = StressTest::new();
+        let mut world = WorldContext::new(Rect::new(0, 0, 120, 40));
+        let mut entity_manager = EntityManager::new();
+        spawning::initialize_aquarium(&mut entity_manager, world.bounds());
+
+        for _ in 0..300 {
+            stress.step(&mut entity_manager, &mut world);
+            entity_manager.update_all(Duration::from_millis(33), &world);
+            entity_manager.check_invariants().unwrap();
+        }
+
+        assert_eq!(stress.ticks(), 300);
+    }
+}
@@ -0,0 +1,290 @@
+//! A one- to three-row aquarium for status lines and thin bars
+//!
+//! `--strip` swaps the tank for a strip no taller than [`MAX_ROWS`]: a
+//! line of waves on top, tiny fish (`><>`, `<><`) swimming under it and
+//! now and then a shark fin cutting along the surface, eating the fish it
+//! passes over. With a single row the fish swim in the waves themselves.
+//! The full-size creatures wouldn't fit, so the strip keeps its own small
+//! set with its own spawn rules instead of using the entity manager.
+//!
+//! Run it in a short tmux pane (`tmux split-window -l 1 'asciiquarium
+//! --strip'`) or a thin terminal window; `asciiquarium snapshot --strip`
+//! prints a single frame.
+
+use std::time::Duration;
+
+use rand::Rng;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::Widget;
+
+use crate::palette;
+use crate::rng::RngHandle;
+use crate::timestep;
+
+/// Most rows a strip uses; taller areas only get their top rows drawn
+pub const MAX_ROWS: u16 = 3;
+
+/// Columns of strip per fish
+const COLUMNS_PER_FISH: u16 = 16;
+
+/// Chance per tick of a missing fish swimming in
+const FISH_CHANCE: f64 = 1.0 / 40.0;
+
+/// Chance per tick of a fin appearing (about every half minute)
+const FIN_CHANCE: f64 = 1.0 / 1800.0;
+
+/// Fish colours, before the palette is applied
+const FISH_COLORS: [Color; 6] = [
+    Color::Yellow,
+    Color::Green,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Red,
+    Color::LightBlue,
+];
+
+/// Surface pattern, scrolled as the waves move
+const WAVES: &[u8] = b"~~-~~~-~";
+
+/// A tiny fish; `speed` is in cells per tick, negative to the left
+#[derive(Debug, Clone, PartialEq)]
+struct StripFish {
+    x: f32,
+    row: u16,
+    speed: f32,
+    color: Color,
+}
+
+impl StripFish {
+    fn art(&self) -> &'static str {
+        if self.speed >= 0.0 {
+            "><>"
+        } else {
+            "<><"
+        }
+    }
+}
+
+/// A shark fin moving along the surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Fin {
+    x: f32,
+    speed: f32,
+}
+
+impl Fin {
+    fn art(&self) -> &'static str {
+        if self.speed >= 0.0 {
+            "/|"
+        } else {
+            "|\\"
+        }
+    }
+}
+
+/// The strip's fish, fin and waves
+pub struct Strip {
+    width: u16,
+    rows: u16,
+    fish: Vec<StripFish>,
+    fin: Option<Fin>,
+    /// How far the waves have scrolled
+    waves: f32,
+    rng: RngHandle,
+}
+
+impl Strip {
+    /// A strip `width` columns wide and `rows` (at most [`MAX_ROWS`]) tall,
+    /// with its fish already spread across it, drawing from `rng`
+    pub fn new(width: u16, rows: u16, rng: RngHandle) -> Self {
+        let mut strip = Self {
+            width,
+            rows: rows.clamp(1, MAX_ROWS),
+            fish: Vec::new(),
+            fin: None,
+            waves: 0.0,
+            rng,
+        };
+        for _ in 0..strip.fish_wanted() {
+            let x = strip.rng.gen_range(0.0..width.max(1) as f32);
+            let fish = strip.new_fish(x);
+            strip.fish.push(fish);
+        }
+        strip
+    }
+
+    /// Rows in use
+    pub fn rows(&self) -> u16 {
+        self.rows
+    }
+
+    /// Fish in the strip
+    pub fn fish_count(&self) -> usize {
+        self.fish.len()
+    }
+
+    /// Fit a new size, keeping the fish that still fit
+    pub fn resize(&mut self, width: u16, rows: u16) {
+        self.width = width;
+        self.rows = rows.clamp(1, MAX_ROWS);
+        let (first, last) = self.fish_rows();
+        for fish in &mut self.fish {
+            fish.row = fish.row.clamp(first, last);
+        }
+        let wanted = self.fish_wanted();
+        self.fish.truncate(wanted);
+    }
+
+    /// Move everything on by `delta_time`, eating, removing and adding
+    /// fish and fins as they come and go
+    pub fn update(&mut self, delta_time: Duration) {
+        let ticks = timestep::ticks(delta_time);
+        self.waves += 0.05 * ticks;
+        for fish in &mut self.fish {
+            fish.x += fish.speed * ticks;
+        }
+        if let Some(fin) = &mut self.fin {
+            fin.x += fin.speed * ticks;
+        }
+
+        // The shark under the fin takes any fish it passes over
+        if let Some(fin) = self.fin {
+            self.fish
+                .retain(|fish| fish.x + 3.0 <= fin.x || fish.x >= fin.x + 2.0);
+        }
+
+        let width = self.width as f32;
+        let on_screen = |x: f32, speed: f32| if speed >= 0.0 { x < width } else { x > -3.0 };
+        self.fish.retain(|fish| on_screen(fish.x, fish.speed));
+        self.fin = self.fin.filter(|fin| on_screen(fin.x, fin.speed));
+
+        if self.fish.len() < self.fish_wanted() && self.rng.gen_bool(FISH_CHANCE) {
+            let x = if self.rng.gen_bool(0.5) { -3.0 } else { width };
+            let fish = self.new_fish(x);
+            self.fish.push(fish);
+        }
+        if self.fin.is_none() && self.rng.gen_bool(FIN_CHANCE) {
+            let speed = self.rng.gen_range(0.3..0.6);
+            self.fin = Some(if self.rng.gen_bool(0.5) {
+                Fin { x: -2.0, speed }
+            } else {
+                Fin {
+                    x: width,
+                    speed: -speed,
+                }
+            });
+        }
+    }
+
+    /// How many fish the strip keeps topped up to
+    fn fish_wanted(&self) -> usize {
+        (self.width / COLUMNS_PER_FISH).max(1) as usize
+    }
+
+    /// First and last row fish swim in: under the waves if there is room
+    fn fish_rows(&self) -> (u16, u16) {
+        (u16::from(self.rows > 1), self.rows - 1)
+    }
+
+    /// A fish at `x`, heading into the strip
+    fn new_fish(&mut self, x: f32) -> StripFish {
+        let (first, last) = self.fish_rows();
+        let speed = self.rng.gen_range(0.05..0.25);
+        let heading_right = x < self.width as f32 / 2.0;
+        StripFish {
+            x,
+            row: self.rng.gen_range(first..=last),
+            speed: if heading_right { speed } else { -speed },
+            color: FISH_COLORS[self.rng.gen_range(0..FISH_COLORS.len())],
+        }
+    }
+}
+
+impl Widget for &Strip {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let palette = palette::current();
+        let rows = area.height.min(self.rows);
+        let put = |buf: &mut Buffer, x: f32, row: u16, art: &str, color: Color| {
+            for (i, ch) in art.chars().enumerate() {
+                let column = x.round() as i32 + i as i32;
+                if (0..area.width as i32).contains(&column) && row < rows {
+                    buf[(area.x + column as u16, area.y + row)]
+                        .set_char(ch)
+                        .set_style(Style::default().fg(palette.remap(color)));
+                }
+            }
+        };
+
+        let shift = self.waves as usize;
+        for x in 0..area.width {
+            let wave = WAVES[(x as usize + shift) % WAVES.len()] as char;
+            let color = if wave == '-' {
+                Color::Blue
+            } else {
+                Color::Cyan
+            };
+            put(buf, x as f32, 0, &wave.to_string(), color);
+        }
+        for fish in &self.fish {
+            put(buf, fish.x, fish.row, fish.art(), fish.color);
+        }
+        if let Some(fin) = self.fin {
+            put(buf, fin.x, 0, fin.art(), Color::White);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width)
+            .map(|x| buf[(x, y)].symbol().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_fish_swim_under_the_waves() {
+        let mut strip = Strip::new(40, 5, RngHandle::default());
+        assert_eq!(strip.rows(), MAX_ROWS);
+        assert_eq!(strip.fish_count(), 2);
+        strip.fish = vec![StripFish {
+            x: 4.0,
+            row: 2,
+            speed: 0.1,
+            color: Color::Yellow,
+        }];
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 4));
+        strip.render(buf.area, &mut buf);
+        assert!(row(&buf, 0).starts_with("~~-~~~-~"));
+        assert_eq!(&row(&buf, 2)[4..7], "><>");
+        assert_eq!(row(&buf, 3).trim(), "");
+
+        // On a single row the fish swim in the waves
+        strip.resize(40, 1);
+        assert_eq!(strip.fish[0].row, 0);
+    }
+
+    #[test]
+    fn test_fin_eats_fish_it_passes() {
+        let mut strip = Strip::new(16, 2, RngHandle::default());
+        strip.fish = vec![StripFish {
+            x: 10.0,
+            row: 1,
+            speed: 0.0,
+            color: Color::Green,
+        }];
+        strip.fin = Some(Fin { x: 7.0, speed: 1.0 });
+        // A replacement may swim in, but never a still one
+        let still = |strip: &Strip| strip.fish.iter().any(|fish| fish.speed == 0.0);
+
+        strip.update(timestep::STEP);
+        assert!(still(&strip));
+        strip.update(timestep::STEP * 2);
+        assert!(!still(&strip));
+    }
+}
@@ -0,0 +1,237 @@
+//! The tank as an ambient system monitor
+//!
+//! With `--sysmon` the aquarium's liveliness follows the machine it runs
+//! on, sampled every [`SAMPLE_INTERVAL`]:
+//!
+//! - the fish population tracks CPU usage, from a lone fish on an idle
+//!   machine to twice the usual number at full load; extra fish swim in
+//!   at once, surplus ones leave as they reach the edge
+//! - bubbles rise from the floor with network traffic, more the busier
+//!   it is
+//! - a storm blows while the load average is above [`STORM_LOAD`] per CPU
+//!
+//! The figures are read from `/proc`, so this is Linux only, and needs the
+//! `sysmon` feature (on by default).
+
+use std::fs;
+use std::time::Duration;
+
+use rand::Rng;
+use ratatui::layout::Rect;
+
+use crate::clock::Instant;
+use crate::entities::Bubble;
+use crate::entity::{EntityManager, Position};
+use crate::scheduler::Population;
+use crate::spawning;
+
+/// How often the host is sampled
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Load average per CPU above which a storm blows
+pub const STORM_LOAD: f32 = 1.0;
+
+/// Most fish added in one sample, so a spike fills the tank over a few
+/// seconds rather than in one frame
+const MAX_NEW_FISH: usize = 4;
+
+/// Network traffic (bytes per second) that gets the first bubble; each
+/// tenfold increase adds another
+const BUBBLE_TRAFFIC: f64 = 10_000.0;
+
+/// Most bubbles per sample
+const MAX_BUBBLES: usize = 8;
+
+/// The host's state over one sample interval
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HostSample {
+    /// Share of CPU time spent busy, 0.0-1.0
+    pub cpu: f32,
+    /// Bytes sent and received per second, loopback excluded
+    pub network: f64,
+    /// One-minute load average per CPU
+    pub load: f32,
+}
+
+impl HostSample {
+    /// Fish the tank should hold for a screen of this size with the water
+    /// surface at `waterline`
+    pub fn fish_target(&self, screen_bounds: Rect, waterline: f32) -> usize {
+        let usual = spawning::fish_count(screen_bounds, waterline) as f32;
+        ((usual * 2.0 * self.cpu.clamp(0.0, 1.0)).round() as usize).max(1)
+    }
+
+    /// Bubbles to release this sample
+    pub fn bubbles(&self) -> usize {
+        if self.network < BUBBLE_TRAFFIC {
+            return 0;
+        }
+        let tens = (self.network / BUBBLE_TRAFFIC).log10().floor() as usize;
+        (tens + 1).min(MAX_BUBBLES)
+    }
+
+    /// Whether the load calls for a storm
+    pub fn is_stormy(&self) -> bool {
+        self.load > STORM_LOAD
+    }
+
+    /// Bring the tank in line with this sample
+    pub fn apply(&self, manager: &mut EntityManager, screen_bounds: Rect) {
+        let target = self.fish_target(screen_bounds, manager.environment().waterline());
+        manager.scheduler_mut().set_target(Population::Fish, target);
+        let missing = target.saturating_sub(manager.population_size(Population::Fish));
+        for _ in 0..missing.min(MAX_NEW_FISH) {
+            spawning::add_fish(manager, screen_bounds);
+        }
+
+        let mut rng = manager.rng();
+        let floor = screen_bounds.height.saturating_sub(1) as f32;
+        for _ in 0..self.bubbles() {
+            let x = rng.gen_range(1..screen_bounds.width.max(2)) as f32;
+            let position = Position::new(x, floor, crate::depth::random_fish_depth(&mut rng));
+            manager.spawn_recycled::<Bubble>(position);
+        }
+
+        manager.environment_mut().set_storm(self.is_stormy());
+    }
+}
+
+/// Counters read from `/proc`, kept to turn the next read into rates
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Counters {
+    cpu_busy: u64,
+    cpu_total: u64,
+    network: u64,
+}
+
+/// Samples the host by reading `/proc`
+#[derive(Debug, Default)]
+pub struct HostMonitor {
+    last: Option<(Counters, Instant)>,
+}
+
+impl HostMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The host since the last sample, once [`SAMPLE_INTERVAL`] has
+    /// passed; `None` in between, on the first call or if `/proc` can't be
+    /// read
+    pub fn poll(&mut self, now: Instant) -> Option<HostSample> {
+        if let Some((_, at)) = self.last {
+            if now.duration_since(at) < SAMPLE_INTERVAL {
+                return None;
+            }
+        }
+        let stat = fs::read_to_string("/proc/stat").ok()?;
+        let net = fs::read_to_string("/proc/net/dev").ok()?;
+        let loadavg = fs::read_to_string("/proc/loadavg").ok()?;
+        let (cpu_busy, cpu_total) = parse_cpu(&stat)?;
+        let counters = Counters {
+            cpu_busy,
+            cpu_total,
+            network: parse_network(&net),
+        };
+        let cpus = std::thread::available_parallelism().map_or(1, |cpus| cpus.get());
+        let load = parse_load(&loadavg)? / cpus as f32;
+
+        let (last, at) = self.last.replace((counters, now))?;
+        let total = counters.cpu_total.saturating_sub(last.cpu_total);
+        let busy = counters.cpu_busy.saturating_sub(last.cpu_busy);
+        let seconds = now.duration_since(at).as_secs_f64().max(f64::EPSILON);
+        Some(HostSample {
+            cpu: if total == 0 {
+                0.0
+            } else {
+                busy as f32 / total as f32
+            },
+            network: counters.network.saturating_sub(last.network) as f64 / seconds,
+            load,
+        })
+    }
+}
+
+/// Busy and total jiffies from the `cpu` line of `/proc/stat`
+fn parse_cpu(stat: &str) -> Option<(u64, u64)> {
+    let line = stat.lines().find(|line| line.starts_with("cpu "))?;
+    let times: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|field| field.parse().ok())
+        .collect();
+    // user nice system idle iowait ...; idle and iowait are the idle time
+    let idle = times.get(3)? + times.get(4).unwrap_or(&0);
+    let total: u64 = times.iter().take(8).sum();
+    Some((total - idle, total))
+}
+
+/// Bytes received and sent by all interfaces but loopback, from
+/// `/proc/net/dev`
+fn parse_network(dev: &str) -> u64 {
+    dev.lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(interface, _)| interface.trim() != "lo")
+        .map(|(_, fields)| {
+            let fields: Vec<u64> = fields
+                .split_whitespace()
+                .filter_map(|field| field.parse().ok())
+                .collect();
+            fields.first().unwrap_or(&0) + fields.get(8).unwrap_or(&0)
+        })
+        .sum()
+}
+
+/// The one-minute load average from `/proc/loadavg`
+fn parse_load(loadavg: &str) -> Option<f32> {
+    loadavg.split_whitespace().next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::WATERLINE_Y;
+
+    #[test]
+    fn test_parse_proc_files() {
+        let stat = "cpu  100 0 50 800 50 0 0 0 0 0\ncpu0 50 0 25 400 25 0 0 0 0 0\n";
+        assert_eq!(parse_cpu(stat), Some((150, 1000)));
+
+        let dev = "Inter-|   Receive\n face |bytes    packets\n\
+                   lo: 999 1 0 0 0 0 0 0 999 1 0 0 0 0 0 0\n\
+                   eth0: 1000 10 0 0 0 0 0 0 500 5 0 0 0 0 0 0\n";
+        assert_eq!(parse_network(dev), 1500);
+        assert_eq!(parse_load("2.50 1.00 0.50 3/400 1234\n"), Some(2.5));
+    }
+
+    #[test]
+    fn test_sample_sets_the_tank() {
+        let bounds = Rect::new(0, 0, 100, 44);
+        let mut manager = EntityManager::new();
+        let busy = HostSample {
+            cpu: 1.0,
+            network: 2_000_000.0,
+            load: 1.5,
+        };
+        assert_eq!(
+            busy.fish_target(bounds, WATERLINE_Y),
+            2 * spawning::fish_count(bounds, WATERLINE_Y)
+        );
+        assert_eq!(busy.bubbles(), 3);
+
+        busy.apply(&mut manager, bounds);
+        assert_eq!(manager.population_size(Population::Fish), MAX_NEW_FISH);
+        assert_eq!(manager.entity_count(), MAX_NEW_FISH + 3);
+        assert!(manager.environment().is_storm());
+
+        let idle = HostSample {
+            cpu: 0.0,
+            network: 0.0,
+            load: 0.1,
+        };
+        assert_eq!(idle.fish_target(bounds, WATERLINE_Y), 1);
+        idle.apply(&mut manager, bounds);
+        assert_eq!(manager.scheduler().target(Population::Fish), 1);
+        assert!(!manager.environment().is_storm());
+    }
+}
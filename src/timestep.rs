@@ -0,0 +1,113 @@
+//! Fixed-rate simulation clock
+//!
+//! The tank is simulated in fixed steps of [`STEP`] no matter how often it
+//! is drawn: the wall-clock time between frames is banked in a
+//! [`FixedTimestep`] and spent one whole step at a time, so a tank drawn at
+//! 10 fps ends up exactly where one drawn at 60 fps does, and tests can
+//! advance it tick by tick.
+//!
+//! Speeds are given in cells per tick, the unit the original's per-frame
+//! speeds were written in; [`ticks`] converts a time span into ticks.
+
+use std::time::Duration;
+
+/// Simulation ticks per second
+pub const TICK_RATE: u32 = 60;
+
+/// Time simulated by one tick
+pub const STEP: Duration = Duration::from_nanos(1_000_000_000 / TICK_RATE as u64);
+
+/// Most ticks run for one frame at the full frame rate; time beyond that
+/// (a suspended terminal, a debugger) is dropped rather than fast-forwarded
+pub const MAX_TICKS_PER_FRAME: u32 = 10;
+
+/// Fastest the simulation may run, as a multiple of real time
+pub const MAX_TIME_SCALE: f32 = 4.0;
+
+/// How much the speed keys change the time scale by
+pub const TIME_SCALE_STEP: f32 = 0.25;
+
+/// Number of ticks (possibly fractional) in `delta_time`
+pub fn ticks(delta_time: Duration) -> f32 {
+    delta_time.as_secs_f32() * TICK_RATE as f32
+}
+
+/// Accumulates frame time and hands it out in whole steps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedTimestep {
+    accumulated: Duration,
+    /// Most steps handed out at once
+    max_ticks: u32,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self {
+            accumulated: Duration::ZERO,
+            max_ticks: MAX_TICKS_PER_FRAME,
+        }
+    }
+}
+
+impl FixedTimestep {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expect frames `interval` apart, letting a slow frame rate (as when
+    /// idle) catch up on up to two intervals rather than
+    /// [`MAX_TICKS_PER_FRAME`]
+    pub fn set_frame_interval(&mut self, interval: Duration) {
+        let steps = (interval.as_nanos() / STEP.as_nanos()) as u32;
+        self.max_ticks = MAX_TICKS_PER_FRAME.max(steps.saturating_mul(2));
+    }
+
+    /// Bank `elapsed` and return how many steps are due
+    pub fn advance(&mut self, elapsed: Duration) -> u32 {
+        self.accumulated += elapsed;
+        let due = (self.accumulated.as_nanos() / STEP.as_nanos()) as u32;
+        if due > self.max_ticks {
+            self.accumulated = Duration::ZERO;
+            return self.max_ticks;
+        }
+        self.accumulated -= STEP * due;
+        due
+    }
+
+    /// Drop banked time (e.g. when resuming from pause)
+    pub fn reset(&mut self) {
+        self.accumulated = Duration::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_advance_banks_remainder() {
+        let mut clock = FixedTimestep::new();
+        assert_eq!(clock.advance(STEP / 2), 0);
+        assert_eq!(clock.advance(STEP / 2), 1);
+        assert_eq!(clock.advance(STEP * 3 + STEP / 4), 3);
+        assert_eq!(clock.advance(STEP), 1);
+    }
+
+    #[test]
+    fn test_advance_caps_long_frames() {
+        let mut clock = FixedTimestep::new();
+        assert_eq!(clock.advance(Duration::from_secs(5)), MAX_TICKS_PER_FRAME);
+        assert_eq!(clock.advance(Duration::ZERO), 0);
+
+        // Idling at 2 Hz still keeps up with the wall clock
+        clock.set_frame_interval(Duration::from_millis(500));
+        assert_eq!(clock.advance(Duration::from_millis(500)), TICK_RATE / 2);
+        assert_eq!(clock.advance(Duration::from_secs(5)), TICK_RATE);
+    }
+
+    #[test]
+    fn test_ticks() {
+        assert_eq!(ticks(STEP).round(), 1.0);
+        assert_eq!(ticks(Duration::from_secs(1)), TICK_RATE as f32);
+    }
+}
@@ -0,0 +1,159 @@
+//! Optional title splash shown before the tank fills
+//!
+//! `--title` covers the screen with the title art for [`HOLD`] (or until a
+//! key is pressed), then dissolves it cell by cell over [`DISSOLVE`] to
+//! reveal the aquarium underneath. The tank is filled behind the cover and
+//! stays still until the dissolve starts.
+
+use crate::clock::Instant;
+use std::time::Duration;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+};
+
+/// How long the title is shown before it starts to dissolve
+pub const HOLD: Duration = Duration::from_secs(2);
+
+/// How long the dissolve into the aquarium takes
+pub const DISSOLVE: Duration = Duration::from_secs(1);
+
+const ART: &str = r"   _            _  _                         _
+  /_\   ___ __ (_)(_) __ _  _  _  __ _  _ _ (_) _  _  _ __
+ / _ \ (_-</ _|| || |/ _` || || |/ _` || '_|| || || || '  \
+/_/ \_\/__/\__||_||_|\__, | \_,_|\__,_||_|  |_| \_,_||_|_|_|
+                        |_|";
+
+/// State of the title splash
+#[derive(Debug, Clone)]
+pub struct TitleScreen {
+    shown_at: Instant,
+    dissolve_at: Instant,
+}
+
+impl TitleScreen {
+    /// Show the title from `now`
+    pub fn new(now: Instant) -> Self {
+        Self {
+            shown_at: now,
+            dissolve_at: now + HOLD,
+        }
+    }
+
+    /// Start dissolving at `now` if the title is still held
+    pub fn skip(&mut self, now: Instant) {
+        self.dissolve_at = self.dissolve_at.min(now);
+    }
+
+    /// Whether the title still covers the whole tank
+    pub fn is_holding(&self, now: Instant) -> bool {
+        now < self.dissolve_at
+    }
+
+    /// Whether the dissolve has finished
+    pub fn is_done(&self, now: Instant) -> bool {
+        self.progress(now) >= 1.0
+    }
+
+    /// How far the dissolve has got, from 0.0 (fully covered) to 1.0
+    fn progress(&self, now: Instant) -> f32 {
+        now.saturating_duration_since(self.dissolve_at)
+            .as_secs_f32()
+            / DISSOLVE.as_secs_f32()
+    }
+
+    /// Draw the cover and the title art centred in `area`. Cells whose
+    /// dissolve threshold has passed are left alone, so the aquarium
+    /// already drawn into `buf` shows through.
+    pub fn render(&self, buf: &mut Buffer, area: Rect, now: Instant) {
+        let progress = self.progress(now);
+        if progress >= 1.0 {
+            return;
+        }
+
+        let lines: Vec<&str> = ART.lines().collect();
+        let art_width = lines.iter().map(|line| line.len()).max().unwrap_or(0) as u16;
+        let art_left = area.x + area.width.saturating_sub(art_width) / 2;
+        let art_top = area.y + area.height.saturating_sub(lines.len() as u16) / 2;
+        let art_style = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD);
+        // Fade in: the title is dim for its first half second
+        let art_style = if now.duration_since(self.shown_at) < HOLD / 4 {
+            art_style.add_modifier(Modifier::DIM)
+        } else {
+            art_style
+        };
+
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                if dissolve_threshold(x, y) < progress {
+                    continue;
+                }
+                let glyph = y
+                    .checked_sub(art_top)
+                    .and_then(|row| lines.get(row as usize))
+                    .and_then(|line| line.chars().nth(x.checked_sub(art_left)? as usize))
+                    .unwrap_or(' ');
+                if let Some(cell) = buf.cell_mut((x, y)) {
+                    cell.reset();
+                    cell.set_char(glyph);
+                    if glyph != ' ' {
+                        cell.set_style(art_style);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pseudo-random point in [0, 1) at which a cell drops out of the cover
+fn dissolve_threshold(x: u16, y: u16) -> f32 {
+    let mut hash = (x as u32).wrapping_mul(0x9E37_79B9) ^ (y as u32).wrapping_mul(0x85EB_CA6B);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x2C1B_3C6D);
+    hash ^= hash >> 12;
+    (hash % 1024) as f32 / 1024.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols(buf: &Buffer) -> String {
+        buf.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn test_title_covers_then_dissolves() {
+        let start = Instant::now();
+        let title = TitleScreen::new(start);
+        let area = Rect::new(0, 0, 80, 24);
+        let mut buf = Buffer::filled(area, ratatui::buffer::Cell::new("~"));
+
+        title.render(&mut buf, area, start + HOLD / 2);
+        assert!(title.is_holding(start + HOLD / 2));
+        assert!(!symbols(&buf).contains('~'));
+        assert!(symbols(&buf).contains("/_/ \\_\\"));
+
+        // Halfway through the dissolve some of the tank shows through
+        let mut buf = Buffer::filled(area, ratatui::buffer::Cell::new("~"));
+        title.render(&mut buf, area, start + HOLD + DISSOLVE / 2);
+        let shown = symbols(&buf).matches('~').count();
+        assert!(shown > 0 && shown < 80 * 24);
+
+        assert!(title.is_done(start + HOLD + DISSOLVE));
+    }
+
+    #[test]
+    fn test_skip_starts_dissolve() {
+        let start = Instant::now();
+        let mut title = TitleScreen::new(start);
+
+        title.skip(start + Duration::from_millis(100));
+        assert!(!title.is_holding(start + Duration::from_millis(100)));
+        assert!(title.is_done(start + Duration::from_millis(100) + DISSOLVE));
+    }
+}
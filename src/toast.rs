@@ -0,0 +1,239 @@
+//! Brief notices over the tank
+//!
+//! Keys and control commands that change a setting (speed, fish density,
+//! theme) confirm the new value with a toast, and the arrival of a large
+//! creature or an ambient happening is announced the same way. Toasts
+//! queue up in [`Toasts`], stacked away from the status line with the
+//! newest nearest to it, and fade out over their last [`FADE_TIME`] of
+//! [`TOAST_TIME`].
+
+use crate::clock::Instant;
+use crate::hooks::AquariumEvent;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Color, style::Style};
+
+/// How long a toast stays up
+pub const TOAST_TIME: Duration = Duration::from_millis(1500);
+
+/// How long a toast takes to fade out, at the end of its time
+pub const FADE_TIME: Duration = Duration::from_millis(500);
+
+/// Most toasts shown at once; older ones make way for new ones
+pub const MAX_TOASTS: usize = 3;
+
+/// A line of text shown for a moment
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    text: String,
+    /// The setting the toast reports, so a newer value replaces it
+    key: Option<&'static str>,
+    until: Instant,
+}
+
+impl Toast {
+    /// Show `text` from `now`
+    pub fn new(text: impl Into<String>, now: Instant) -> Self {
+        Self {
+            text: format!(" {} ", text.into()),
+            key: None,
+            until: now + TOAST_TIME,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        self.text.trim()
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now >= self.until
+    }
+
+    /// Black on white, dimming through grey as the toast fades out
+    fn style(&self, now: Instant) -> Style {
+        let left = self.until.saturating_duration_since(now);
+        let background = if left > FADE_TIME {
+            Color::White
+        } else if left > FADE_TIME / 2 {
+            Color::Gray
+        } else {
+            Color::DarkGray
+        };
+        Style::default().fg(Color::Black).bg(background)
+    }
+
+    /// Draw the toast centred on `row` of `area`
+    pub fn render(&self, buf: &mut Buffer, area: Rect, row: u16, now: Instant) {
+        if self.is_expired(now) || row >= area.height {
+            return;
+        }
+        let width = (self.text.chars().count() as u16).min(area.width);
+        let x = area.x + (area.width - width) / 2;
+        let style = self.style(now);
+        for (offset, ch) in self.text.chars().take(width as usize).enumerate() {
+            if let Some(cell) = buf.cell_mut((x + offset as u16, area.y + row)) {
+                cell.set_char(ch);
+                cell.set_style(style);
+            }
+        }
+    }
+}
+
+/// The toasts currently up, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct Toasts {
+    queue: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show `text` from `now`
+    pub fn push(&mut self, text: impl Into<String>, now: Instant) {
+        self.add(Toast::new(text, now));
+    }
+
+    /// Show the new value of setting `key`, in place of any toast still up
+    /// for its old one
+    pub fn set(&mut self, key: &'static str, text: impl Into<String>, now: Instant) {
+        self.queue.retain(|toast| toast.key != Some(key));
+        self.add(Toast {
+            key: Some(key),
+            ..Toast::new(text, now)
+        });
+    }
+
+    fn add(&mut self, toast: Toast) {
+        self.queue.push_back(toast);
+        while self.queue.len() > MAX_TOASTS {
+            self.queue.pop_front();
+        }
+    }
+
+    /// Drop the toasts that are done
+    pub fn expire(&mut self, now: Instant) {
+        self.queue.retain(|toast| !toast.is_expired(now));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// The toasts up, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.queue.iter()
+    }
+
+    /// Draw the newest toast on `row` of `area` and older ones further
+    /// from it: above it, or below if `downwards`
+    pub fn render(&self, buf: &mut Buffer, area: Rect, row: u16, downwards: bool, now: Instant) {
+        for (age, toast) in self.queue.iter().rev().enumerate() {
+            let row = if downwards {
+                row.checked_add(age as u16)
+            } else {
+                row.checked_sub(age as u16)
+            };
+            if let Some(row) = row {
+                toast.render(buf, area, row, now);
+            }
+        }
+    }
+}
+
+/// What to tell the viewer about `event`, if anything: large creatures
+/// arriving and the more noticeable ambient happenings
+pub fn announce(event: &AquariumEvent) -> Option<String> {
+    let text = match event {
+        AquariumEvent::LargeCreatureEntered { entity_type, .. } => match *entity_type {
+            "shark" => "Shark incoming!".to_string(),
+            "ship" => "Ship ahoy!".to_string(),
+            "whale" => "Thar she blows!".to_string(),
+            "ducks" => "Ducks overhead".to_string(),
+            name => {
+                let name = name.replace('_', " ");
+                let mut chars = name.chars();
+                let first = chars.next()?.to_uppercase();
+                format!("{first}{} incoming!", chars.as_str())
+            }
+        },
+        AquariumEvent::Ambient { name } => match *name {
+            "school" => "A school swims by".to_string(),
+            "anchor" => "Anchor dropped".to_string(),
+            "bubble_burst" => "Bubbles!".to_string(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect()
+    }
+
+    #[test]
+    fn test_toast_is_centred_and_fades_until_it_expires() {
+        let area = Rect::new(0, 0, 16, 4);
+        let now = Instant::now();
+        let toast = Toast::new("speed x2", now);
+        assert_eq!(toast.text(), "speed x2");
+
+        let mut buf = Buffer::empty(area);
+        toast.render(&mut buf, area, 2, now);
+        assert_eq!(row(&buf, 2), "    speed x2    ");
+        assert_eq!(buf[(4, 2)].bg, Color::White);
+
+        let fading = now + TOAST_TIME - FADE_TIME / 4;
+        toast.render(&mut buf, area, 2, fading);
+        assert_eq!(buf[(4, 2)].bg, Color::DarkGray);
+
+        let mut buf = Buffer::empty(area);
+        toast.render(&mut buf, area, 2, now + TOAST_TIME);
+        assert_eq!(buf, Buffer::empty(area));
+    }
+
+    #[test]
+    fn test_toasts_stack_and_replace_settings() {
+        let area = Rect::new(0, 0, 16, 4);
+        let now = Instant::now();
+        let mut toasts = Toasts::new();
+        toasts.set("speed", "speed x2", now);
+        toasts.push("Shark incoming!", now);
+        toasts.set("speed", "speed x3", now);
+        let texts: Vec<_> = toasts.iter().map(Toast::text).collect();
+        assert_eq!(texts, ["Shark incoming!", "speed x3"]);
+
+        let mut buf = Buffer::empty(area);
+        toasts.render(&mut buf, area, 3, false, now);
+        assert_eq!(row(&buf, 3), "    speed x3    ");
+        assert_eq!(row(&buf, 2), " Shark incoming!");
+
+        for n in 0..MAX_TOASTS {
+            toasts.push(format!("toast {n}"), now);
+        }
+        assert_eq!(toasts.iter().count(), MAX_TOASTS);
+        toasts.expire(now + TOAST_TIME);
+        assert!(toasts.is_empty());
+    }
+
+    #[test]
+    fn test_announcements() {
+        let entered = |entity_type| AquariumEvent::LargeCreatureEntered { id: 1, entity_type };
+        assert_eq!(
+            announce(&entered("shark")).as_deref(),
+            Some("Shark incoming!")
+        );
+        assert_eq!(
+            announce(&entered("sea_monster")).as_deref(),
+            Some("Sea monster incoming!")
+        );
+        assert_eq!(announce(&AquariumEvent::Ambient { name: "glint" }), None);
+    }
+}
@@ -1,77 +1,585 @@
+use std::collections::HashSet;
+
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::{Color, Style},
-    widgets::Widget,
+    widgets::{StatefulWidget, Widget},
 };
 
+#[cfg(not(target_arch = "wasm32"))]
 use crate::app::App;
+use crate::clock::Instant;
+use crate::dirty::TankCache;
+use crate::entities::water_surface;
+use crate::entity::{Entity, EntityId, EntityManager};
+use crate::render::{self, RenderOptions};
+use crate::spawning;
+use crate::timestep::{self, FixedTimestep};
+use crate::world::WorldContext;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{inspector, status};
+
+/// Tank state for [`AquariumWidget`]: the entities and the world they
+/// swim in
+///
+/// The tank fills itself to fit the first area it is rendered into, and
+/// is fitted to the new size (keeping its creatures) when that changes.
+/// Call [`Aquarium::tick`] from the host application's own tick to move
+/// things along.
+pub struct Aquarium {
+    entity_manager: EntityManager,
+    world: WorldContext,
+    last_update: Instant,
+    timestep: FixedTimestep,
+    size: Option<(u16, u16)>,
+    cache: TankCache,
+}
+
+impl Aquarium {
+    pub fn new() -> Self {
+        Self::with_entity_manager(EntityManager::new())
+    }
+
+    /// A tank using `entity_manager`'s settings (classic mode, event
+    /// callbacks and so on)
+    pub fn with_entity_manager(entity_manager: EntityManager) -> Self {
+        Self {
+            entity_manager,
+            world: WorldContext::new(Rect::default()),
+            last_update: Instant::now(),
+            timestep: FixedTimestep::new(),
+            size: None,
+            cache: TankCache::new(),
+        }
+    }
+
+    pub fn entity_manager(&self) -> &EntityManager {
+        &self.entity_manager
+    }
+
+    pub fn entity_manager_mut(&mut self) -> &mut EntityManager {
+        &mut self.entity_manager
+    }
+
+    /// Advance the simulation by the time since the last tick, in whole
+    /// steps of [`timestep::STEP`]
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        let steps = self.timestep.advance(now.duration_since(self.last_update));
+        self.last_update = now;
+
+        if self.size.is_some() {
+            for _ in 0..steps {
+                self.entity_manager.update_all(timestep::STEP, &self.world);
+            }
+        }
+    }
+
+    /// Fill the tank to `size`, or fit the existing tank to it
+    fn fit(&mut self, size: (u16, u16)) {
+        if self.size == Some(size) {
+            return;
+        }
+        let bounds = Rect::new(0, 0, size.0, size.1);
+        if self.size.is_some() {
+            spawning::resize_aquarium(&mut self.entity_manager, self.world.bounds(), bounds);
+        } else {
+            spawning::initialize_aquarium(&mut self.entity_manager, bounds);
+        }
+        self.world = WorldContext::new(bounds);
+        self.size = Some(size);
+    }
+}
+
+impl Default for Aquarium {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The tank itself: water, entities, night lighting and the optional debug
+/// overlay, drawn into any [`Rect`]
+///
+/// Render it with an [`Aquarium`] as state, or render an `&Aquarium`
+/// directly with the default look. The binary adds its status bar and
+/// overlays on top.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AquariumWidget {
+    options: RenderOptions,
+    time_secs: f32,
+    debug: bool,
+    camera: Option<WorldContext>,
+    highlight: Option<EntityId>,
+}
+
+impl AquariumWidget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Visual options passed to every entity
+    pub fn render_options(self, options: RenderOptions) -> Self {
+        Self { options, ..self }
+    }
+
+    /// Seconds since the tank was started; drives the moonlight ripple
+    pub fn time(self, time_secs: f32) -> Self {
+        Self { time_secs, ..self }
+    }
+
+    /// Outline entity bounding boxes and collisions
+    pub fn debug(self, debug: bool) -> Self {
+        Self { debug, ..self }
+    }
+
+    /// Outline one entity, such as the one being inspected
+    pub fn highlight(self, highlight: Option<EntityId>) -> Self {
+        Self { highlight, ..self }
+    }
+
+    /// Look at a world larger than the area through its viewport
+    pub fn camera(self, world: WorldContext) -> Self {
+        Self {
+            camera: world.has_camera().then_some(world),
+            ..self
+        }
+    }
+
+    /// Draw the tank held by `entity_manager` into `area`
+    ///
+    /// Entities are positioned from the top-left corner of the screen, so
+    /// the tank is drawn into a scratch buffer at the origin and then
+    /// copied into place. With a `cache`, only the parts of the tank that
+    /// changed since the last frame are drawn again. With a camera the
+    /// whole world is drawn and the part under the viewport copied.
+    pub(crate) fn render_tank(
+        self,
+        entity_manager: &EntityManager,
+        cache: Option<&mut TankCache>,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let (local, origin) = match self.camera {
+            Some(world) => {
+                let (bounds, viewport) = (world.bounds(), world.viewport());
+                (
+                    Rect::new(0, 0, bounds.width, bounds.height),
+                    (viewport.x, viewport.y),
+                )
+            }
+            None => (Rect::new(0, 0, area.width, area.height), (0, 0)),
+        };
+        let mut tank = match cache {
+            Some(cache) => cache
+                .update(
+                    entity_manager,
+                    self.options,
+                    (local.width, local.height),
+                    |layer| self.draw_static(entity_manager, local, layer),
+                    |region| entity_manager.render_moving(region, local, &self.options),
+                )
+                .clone(),
+            None => {
+                let mut tank = Buffer::empty(local);
+                self.draw_scene(entity_manager, local, &mut tank);
+                tank
+            }
+        };
+
+        let waterline = entity_manager.environment().waterline().max(0.0) as u16;
+        let surface_top = waterline.saturating_sub(water_surface::LAYERS as u16);
+        if self.options.reflections {
+            render::reflection_pass(&mut tank, local, surface_top..waterline);
+        }
+
+        if self.options.night_mode {
+            let glowing = entity_manager.glowing_cells(local);
+            render::night_pass(
+                &mut tank,
+                local,
+                surface_top..waterline,
+                &glowing,
+                self.time_secs,
+            );
+        }
+
+        if self.debug {
+            render_debug(entity_manager, local, &mut tank);
+        }
+        if let Some(entity) = self.highlight.and_then(|id| entity_manager.get_entity(id)) {
+            outline(&mut tank, local, entity, Color::Yellow);
+        }
 
-impl Widget for &App {
-    /// Renders the aquarium with all entities
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        // Clear the screen with default/transparent background
-        // Let entities handle their own background colors
         for y in 0..area.height {
             for x in 0..area.width {
-                if x < buf.area.width && y < buf.area.height {
-                    let cell = buf.cell_mut((x, y)).unwrap();
-                    cell.set_char(' ');
-                    cell.set_style(Style::default()); // Transparent background everywhere
+                let target = (area.x + x, area.y + y);
+                let source = tank.cell((origin.0 + x, origin.1 + y));
+                if let (Some(cell), Some(source)) = (buf.cell_mut(target), source) {
+                    *cell = source.clone();
                 }
             }
         }
+    }
+
+    /// Draw the water and the entities into `tank`
+    fn draw_scene(self, entity_manager: &EntityManager, screen: Rect, tank: &mut Buffer) {
+        self.draw_static(entity_manager, screen, tank);
+        entity_manager.render_moving(tank, screen, &self.options);
+    }
+
+    /// Draw the water and the static layer into `tank`
+    fn draw_static(self, entity_manager: &EntityManager, screen: Rect, tank: &mut Buffer) {
+        // Clear the tank with default/transparent background, or the water
+        // gradient from the top surface line down when one is configured.
+        // Entities only set foreground colours, so the water shows through.
+        let waterline = entity_manager.environment().waterline().max(0.0) as u16;
+        let surface_top = waterline.saturating_sub(water_surface::LAYERS as u16);
+        let floor = screen.height.saturating_sub(1);
+        let area = tank.area;
+        for y in area.top()..area.bottom() {
+            let (glyph, style) = self
+                .options
+                .water_cell(y, surface_top, floor)
+                .unwrap_or((' ', Style::default()));
+            for x in area.left()..area.right() {
+                let cell = tank.cell_mut((x, y)).unwrap();
+                cell.set_char(glyph);
+                cell.set_style(style);
+            }
+        }
+
+        entity_manager.render_static_layer(tank, screen, &self.options);
+    }
+}
+
+impl StatefulWidget for AquariumWidget {
+    type State = Aquarium;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Aquarium) {
+        state.fit((area.width, area.height));
+        self.render_tank(&state.entity_manager, Some(&mut state.cache), area, buf);
+    }
+}
+
+impl Widget for &Aquarium {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        AquariumWidget::new().render_tank(&self.entity_manager, None, area, buf);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Widget for &App {
+    /// Renders the aquarium with all entities
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // The strip is too thin for a status bar or overlays
+        if let Some(strip) = &self.strip {
+            strip.render(area, buf);
+            return;
+        }
 
-        // Water surface is now rendered through the entity system
+        AquariumWidget::new()
+            .render_options(self.render_options)
+            // The session clock drives the moonlight ripple
+            .time(self.stats.borrow().elapsed().as_secs_f32())
+            .debug(self.show_debug)
+            .camera(self.world)
+            .highlight(self.selected)
+            .render_tank(
+                self.entity_manager(),
+                Some(&mut self.tank_cache.borrow_mut()),
+                area,
+                buf,
+            );
 
-        // Render all entities through the entity manager
-        self.entity_manager().render_all(buf, area);
+        if let Some((column, row)) = self.feed_cursor {
+            render_crosshair(buf, area, column, row);
+        }
 
         // Render status information
         self.render_status(area, buf);
+
+        if self.show_stats {
+            self.render_stats(area, buf);
+        }
+
+        if let Some(text) = self
+            .selected
+            .and_then(|id| inspector::describe(self.entity_manager(), id))
+        {
+            // Across the edge away from the status line
+            let row = match self.status_bar.row(area.height) {
+                0 => area.height.saturating_sub(1),
+                _ => 0,
+            };
+            inspector::render(&text, buf, area, row);
+        }
+
+        // Stacked from beside the status line into the tank
+        let (row, downwards) = match self.status_bar.row(area.height) {
+            0 => (1, true),
+            row => (row - 1, false),
+        };
+        self.toasts
+            .borrow()
+            .render(buf, area, row, downwards, Instant::now());
+
+        if let Some(tooltip) = &self.tooltip {
+            tooltip.render(buf, area, Instant::now());
+        }
+
+        if let Some(title) = &self.title {
+            title.render(buf, area, Instant::now());
+        }
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl App {
-    /// Render status information
+    /// Render status information from the status bar's template
     fn render_status(&self, area: Rect, buf: &mut Buffer) {
-        let fish_count = self.entity_manager().get_entities_by_type("fish").len();
-        let bubble_count = self.entity_manager().get_entities_by_type("bubble").len();
-        let water_count = self
-            .entity_manager()
-            .get_entities_by_type("water_surface")
-            .len();
-        let total_entities = self.entity_manager().entity_count();
-
-        // Get debug info about first fish position
-        let fish_debug =
-            if let Some(first_fish) = self.entity_manager().get_entities_by_type("fish").first() {
-                let pos = first_fish.position();
-                format!("Fish1@({:.1},{:.1})", pos.x, pos.y)
-            } else {
-                "NoFish".to_string()
+        use crate::entity::EntityKind;
+
+        // The command line takes the status line's place while it is open
+        if let Some(prompt) = &self.prompt {
+            let row = self.status_bar.row(area.height);
+            prompt.render(buf, area, row, self.status_bar.style());
+            return;
+        }
+
+        let manager = self.entity_manager();
+        let count = |kind| manager.get_entities_of_kind(kind).len().to_string();
+
+        let status_line = self.status_bar.line(|name| {
+            let value = match name {
+                "fish" => count(EntityKind::Fish),
+                "bubbles" => count(EntityKind::Bubble),
+                "water" => count(EntityKind::WaterSurface),
+                "entities" => manager.entity_count().to_string(),
+                "seen" => self.stats.borrow().large_creatures_seen().to_string(),
+                "eaten" => self.stats.borrow().fish_eaten().to_string(),
+                "caught" => self.stats.borrow().fish_caught().to_string(),
+                "fps" => format!("{:.0}", self.perf.fps()),
+                "keys" => status::KEYS.to_string(),
+                "paused" => if self.paused { "PAUSED | " } else { "" }.to_string(),
+                // Only shown while running at other than real time
+                "speed" => match manager.time_scale() {
+                    1.0 => String::new(),
+                    scale => format!("SPEED x{scale} | "),
+                },
+                "current" => {
+                    let current = manager.environment().current();
+                    format!("{:+.2},{:+.2}", current.dx, current.dy)
+                }
+                // Debug info about the first fish's position
+                "first_fish" => match manager.get_entities_of_kind(EntityKind::Fish).first() {
+                    Some(first_fish) => {
+                        let pos = first_fish.position();
+                        format!("Fish1@({:.1},{:.1})", pos.x, pos.y)
+                    }
+                    None => "NoFish".to_string(),
+                },
+                _ => return None,
             };
+            Some(value)
+        });
 
-        let status_line = if self.paused {
-            format!(
-                "PAUSED | Fish: {} | Bubbles: {} | Water: {} | {} | Total: {} | q=quit r=redraw p=pause",
-                fish_count, bubble_count, water_count, fish_debug, total_entities
-            )
+        // The readout goes first so narrow terminals still show it
+        let status_line = if self.show_perf {
+            let layers = manager.layer_counts();
+            format!("{} | {}", self.perf.summary(&layers), status_line)
         } else {
-            format!(
-                "Fish: {} | Bubbles: {} | Water: {} | {} | Total: {} | q=quit r=redraw p=pause",
-                fish_count, bubble_count, water_count, fish_debug, total_entities
-            )
+            status_line
         };
 
-        // Render status at the bottom
-        let status_y = area.height.saturating_sub(1);
+        let status_y = area.y + self.status_bar.row(area.height);
+        let style = self.status_bar.style();
         for (x, ch) in status_line.chars().enumerate().take(area.width as usize) {
-            if (x as u16) < buf.area.width && status_y < buf.area.height {
-                let cell = buf.cell_mut((x as u16, status_y)).unwrap();
+            if let Some(cell) = buf.cell_mut((area.x + x as u16, status_y)) {
                 cell.set_char(ch);
-                cell.set_style(Style::default().fg(Color::White).bg(Color::Black));
+                cell.set_style(style);
             }
         }
     }
+
+    /// Render the session stats in a box in the top-left corner
+    fn render_stats(&self, area: Rect, buf: &mut Buffer) {
+        let lines = self.stats.borrow().summary();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0) + 2;
+        let style = Style::default().fg(Color::White).bg(Color::Black);
+
+        for (y, line) in lines.iter().enumerate() {
+            let row = format!(" {line:<width$}", width = width - 1);
+            for (x, ch) in row.chars().enumerate() {
+                let (x, y) = (area.x + 1 + x as u16, area.y + 1 + y as u16);
+                if x < buf.area.right() && y < buf.area.bottom() {
+                    let cell = buf.cell_mut((x, y)).unwrap();
+                    cell.set_char(ch);
+                    cell.set_style(style);
+                }
+            }
+        }
+    }
+}
+
+/// Outline every entity's bounding box with a label showing its id,
+/// type, depth and velocity. Entities currently in contact are drawn in
+/// red. Outlines sit one cell outside the sprite and only cover blank
+/// cells, so the sprites themselves stay readable.
+fn render_debug(manager: &EntityManager, area: Rect, buf: &mut Buffer) {
+    let colliding: HashSet<_> = manager
+        .check_collisions()
+        .into_iter()
+        .flat_map(|contact| [contact.source, contact.target])
+        .collect();
+
+    let mut entities: Vec<_> = manager.entities().collect();
+    entities.sort_by_key(|entity| entity.id());
+
+    for entity in entities {
+        let color = if colliding.contains(&entity.id()) {
+            Color::Red
+        } else {
+            Color::DarkGray
+        };
+        let (left, top) = outline(buf, area, entity, color);
+
+        let velocity = entity.velocity();
+        let label = format!(
+            "#{} {} d:{} v:{:+.1},{:+.1}",
+            entity.id(),
+            entity.entity_type(),
+            entity.depth(),
+            velocity.dx,
+            velocity.dy
+        );
+        for (offset, ch) in label.chars().enumerate() {
+            put(buf, area, left + 1 + offset as i32, top, ch, Color::Yellow);
+        }
+    }
+}
+
+/// Draw the feed mode crosshair centred on `column`, `row` of `area`
+#[cfg(not(target_arch = "wasm32"))]
+fn render_crosshair(buf: &mut Buffer, area: Rect, column: u16, row: u16) {
+    let (x, y) = ((area.x + column) as i32, (area.y + row) as i32);
+    let arms = [
+        (0, 0, '+'),
+        (-2, 0, '-'),
+        (-1, 0, '-'),
+        (1, 0, '-'),
+        (2, 0, '-'),
+        (0, -1, '|'),
+        (0, 1, '|'),
+    ];
+    for (dx, dy, ch) in arms {
+        put(buf, area, x + dx, y + dy, ch, Color::LightRed);
+    }
+}
+
+/// Box `entity` in on blank cells one cell outside its sprite, returning
+/// the box's top-left corner
+fn outline(buf: &mut Buffer, area: Rect, entity: &dyn Entity, color: Color) -> (i32, i32) {
+    let position = entity.position();
+    let (width, height) = entity.get_current_sprite().get_bounding_box();
+    let left = position.x.floor() as i32 - 1;
+    let top = position.y.floor() as i32 - 1;
+    let right = left + width as i32 + 1;
+    let bottom = top + height as i32 + 1;
+
+    for x in left..=right {
+        let glyph = match x {
+            _ if x == left => ('┌', '└'),
+            _ if x == right => ('┐', '┘'),
+            _ => ('─', '─'),
+        };
+        put_blank(buf, area, x, top, glyph.0, color);
+        put_blank(buf, area, x, bottom, glyph.1, color);
+    }
+    for y in top + 1..bottom {
+        put_blank(buf, area, left, y, '│', color);
+        put_blank(buf, area, right, y, '│', color);
+    }
+    (left, top)
+}
+
+/// Draw `ch` at (`x`, `y`) if it lies within `area`, keeping the cell's
+/// background so the water stays visible
+fn put(buf: &mut Buffer, area: Rect, x: i32, y: i32, ch: char, color: Color) {
+    let (Ok(x), Ok(y)) = (u16::try_from(x), u16::try_from(y)) else {
+        return;
+    };
+    if x < area.right() && y < area.bottom() {
+        if let Some(cell) = buf.cell_mut((x, y)) {
+            cell.set_char(ch);
+            cell.set_fg(color);
+        }
+    }
+}
+
+/// Like [`put`], but leaves cells that already show something alone
+fn put_blank(buf: &mut Buffer, area: Rect, x: i32, y: i32, ch: char, color: Color) {
+    let (Ok(cx), Ok(cy)) = (u16::try_from(x), u16::try_from(y)) else {
+        return;
+    };
+    if buf
+        .cell((cx, cy))
+        .is_some_and(|cell| matches!(cell.symbol(), " " | "▀"))
+    {
+        put(buf, area, x, y, ch, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::EntityKind;
+    use ratatui::buffer::Cell;
+
+    #[test]
+    fn test_widget_renders_inside_its_pane() {
+        let mut buf = Buffer::filled(Rect::new(0, 0, 120, 40), Cell::new("#"));
+        let pane = Rect::new(20, 10, 80, 24);
+        let mut aquarium = Aquarium::new();
+
+        AquariumWidget::new().render(pane, &mut buf, &mut aquarium);
+
+        assert_eq!(
+            aquarium
+                .entity_manager()
+                .get_entities_of_kind(EntityKind::Castle)
+                .len(),
+            1
+        );
+        assert_eq!(buf[(19, 10)].symbol(), "#");
+        assert_eq!(buf[(100, 33)].symbol(), "#");
+        // Layer 0 of the water surface is the top line of the tank (a
+        // ship may be sailing over part of it)
+        let surface = (20..100).filter(|&x| buf[(x, 15)].symbol() == "~");
+        assert!(surface.count() > 40);
+    }
+
+    #[test]
+    fn test_aquarium_fits_new_size() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 400, 40));
+        let mut aquarium = Aquarium::new();
+
+        AquariumWidget::new().render(Rect::new(0, 0, 80, 24), &mut buf, &mut aquarium);
+        AquariumWidget::new().render(Rect::new(0, 0, 320, 40), &mut buf, &mut aquarium);
+
+        assert_eq!(
+            aquarium
+                .entity_manager()
+                .get_entities_of_kind(EntityKind::Castle)
+                .len(),
+            2
+        );
+        assert_eq!(
+            aquarium
+                .entity_manager()
+                .get_entities_of_kind(EntityKind::Sand)
+                .len(),
+            1
+        );
+    }
 }
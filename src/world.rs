@@ -0,0 +1,154 @@
+//! World extents versus the visible viewport
+//!
+//! Entities live in world space. Normally the world is exactly the terminal,
+//! but with a camera the viewport is only a window onto a larger world.
+//! Simulation (movement, off-screen death, spawning) always uses the world
+//! bounds so panning the camera never kills or spawns anything; only
+//! rendering cares about the viewport.
+
+use crate::entity::Position;
+use ratatui::layout::Rect;
+
+/// Shared description of the world passed to the simulation each frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorldContext {
+    bounds: Rect,
+    viewport: Rect,
+}
+
+impl WorldContext {
+    /// A world that is exactly the size of the screen (no camera)
+    pub fn new(bounds: Rect) -> Self {
+        Self {
+            bounds,
+            viewport: bounds,
+        }
+    }
+
+    /// A world with a camera looking at `viewport` (in world coordinates)
+    pub fn with_viewport(bounds: Rect, viewport: Rect) -> Self {
+        Self { bounds, viewport }
+    }
+
+    /// Extents of the simulated world
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    /// Part of the world currently visible on screen
+    pub fn viewport(&self) -> Rect {
+        self.viewport
+    }
+
+    /// Whether the visible area differs from the world (a camera exists)
+    pub fn has_camera(&self) -> bool {
+        self.viewport != self.bounds
+    }
+
+    /// Move the camera, keeping the world as it is
+    pub fn set_viewport(&mut self, viewport: Rect) {
+        self.viewport = viewport;
+    }
+
+    /// Move the camera by `dx`, `dy` cells, stopping at the world's edges
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        let max_x = self.bounds.width.saturating_sub(self.viewport.width) as i32;
+        let max_y = self.bounds.height.saturating_sub(self.viewport.height) as i32;
+        self.viewport.x = (self.viewport.x as i32 + dx).clamp(0, max_x) as u16;
+        self.viewport.y = (self.viewport.y as i32 + dy).clamp(0, max_y) as u16;
+    }
+
+    /// Move the camera so the world point `x`, `y` is as near the middle of
+    /// the screen as the world's edges allow
+    pub fn center_on(&mut self, x: f32, y: f32) {
+        let dx = x as i32 - (self.viewport.x + self.viewport.width / 2) as i32;
+        let dy = y as i32 - (self.viewport.y + self.viewport.height / 2) as i32;
+        self.pan(dx, dy);
+    }
+
+    /// Convert a world position into screen coordinates (may be negative)
+    pub fn to_screen(&self, position: Position) -> (i32, i32) {
+        (
+            position.x as i32 - self.viewport.x as i32,
+            position.y as i32 - self.viewport.y as i32,
+        )
+    }
+}
+
+/// Whether a sprite of `size` at `position` lies entirely outside `bounds`
+///
+/// Matches Term::Animation's die_offscreen: only fully hidden entities count.
+pub fn is_outside(bounds: Rect, position: Position, size: (u16, u16)) -> bool {
+    let left = bounds.x as f32;
+    let top = bounds.y as f32;
+    let right = bounds.x as f32 + bounds.width as f32;
+    let bottom = bounds.y as f32 + bounds.height as f32;
+
+    position.x + size.0 as f32 <= left
+        || position.x >= right
+        || position.y + size.1 as f32 <= top
+        || position.y >= bottom
+}
+
+/// Whether two sprite bounding boxes share at least one cell
+pub fn overlaps(a: Position, a_size: (u16, u16), b: Position, b_size: (u16, u16)) -> bool {
+    a.x < b.x + b_size.0 as f32
+        && b.x < a.x + a_size.0 as f32
+        && a.y < b.y + b_size.1 as f32
+        && b.y < a.y + a_size.1 as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_world_without_camera() {
+        let world = WorldContext::new(Rect::new(0, 0, 80, 24));
+        assert!(!world.has_camera());
+        assert_eq!(world.viewport(), world.bounds());
+        assert_eq!(world.to_screen(Position::new(10.0, 5.0, 0)), (10, 5));
+    }
+
+    #[test]
+    fn test_world_with_camera() {
+        let world =
+            WorldContext::with_viewport(Rect::new(0, 0, 240, 24), Rect::new(100, 0, 80, 24));
+        assert!(world.has_camera());
+        assert_eq!(world.to_screen(Position::new(110.0, 5.0, 0)), (10, 5));
+        assert_eq!(world.to_screen(Position::new(20.0, 5.0, 0)), (-80, 5));
+    }
+
+    #[test]
+    fn test_camera_stays_inside_world() {
+        let mut world =
+            WorldContext::with_viewport(Rect::new(0, 0, 300, 40), Rect::new(0, 0, 80, 24));
+        world.pan(-5, 3);
+        assert_eq!(world.viewport(), Rect::new(0, 3, 80, 24));
+        world.pan(500, 500);
+        assert_eq!(world.viewport(), Rect::new(220, 16, 80, 24));
+
+        world.center_on(150.0, 20.0);
+        assert_eq!(world.viewport(), Rect::new(110, 8, 80, 24));
+        world.center_on(10.0, 0.0);
+        assert_eq!(world.viewport(), Rect::new(0, 0, 80, 24));
+    }
+
+    #[test]
+    fn test_is_outside() {
+        let bounds = Rect::new(0, 0, 80, 24);
+        assert!(!is_outside(bounds, Position::new(-3.0, 10.0, 0), (5, 3)));
+        assert!(is_outside(bounds, Position::new(-5.0, 10.0, 0), (5, 3)));
+        assert!(is_outside(bounds, Position::new(80.0, 10.0, 0), (5, 3)));
+        assert!(!is_outside(bounds, Position::new(79.0, 10.0, 0), (5, 3)));
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let a = Position::new(10.0, 10.0, 0);
+        assert!(overlaps(a, (5, 3), Position::new(14.0, 12.0, 0), (2, 2)));
+        // Touching edges don't count
+        assert!(!overlaps(a, (5, 3), Position::new(15.0, 10.0, 0), (2, 2)));
+        assert!(!overlaps(a, (5, 3), Position::new(10.0, 13.0, 0), (2, 2)));
+    }
+}
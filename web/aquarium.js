@@ -0,0 +1,71 @@
+// Runs asciiquarium_web.wasm in an xterm.js terminal.
+//
+// Query parameters: cols and rows for the tank size (80x24 by default) and
+// seed to repeat a tank exactly.
+
+const STEP_MS = 1000 / 60;
+// Most steps taken for one animation frame, as in the terminal build
+const MAX_STEPS_PER_FRAME = 10;
+
+const params = new URLSearchParams(location.search);
+const cols = Number(params.get("cols")) || 80;
+const rows = Number(params.get("rows")) || 24;
+const seed = Number(params.get("seed")) >>> 0;
+
+let memory;
+const imports = {
+  env: {
+    aquarium_now: () => performance.now(),
+    aquarium_random_fill: (ptr, len) => {
+      // getRandomValues takes at most 64 KiB at a time
+      for (let start = 0; start < len; start += 65536) {
+        const end = Math.min(len, start + 65536);
+        crypto.getRandomValues(new Uint8Array(memory.buffer, ptr + start, end - start));
+      }
+    },
+  },
+};
+
+const { instance } = await WebAssembly.instantiateStreaming(
+  fetch("asciiquarium_web.wasm"),
+  imports,
+);
+const wasm = instance.exports;
+memory = wasm.memory;
+
+const term = new Terminal({ cols, rows, disableStdin: true, cursorBlink: false });
+term.open(document.getElementById("aquarium"));
+term.write("\x1b[?25l");
+
+const tank = wasm.aquarium_new(cols, rows, seed);
+const decoder = new TextDecoder();
+
+function draw() {
+  const ptr = wasm.aquarium_frame(tank);
+  const len = wasm.aquarium_frame_len(tank);
+  const text = decoder.decode(new Uint8Array(memory.buffer, ptr, len));
+  term.write("\x1b[H" + text.replaceAll("\n", "\r\n"));
+}
+
+let last = performance.now();
+let pending = 0;
+function animate(now) {
+  pending += now - last;
+  last = now;
+  let steps = Math.floor(pending / STEP_MS);
+  if (steps > MAX_STEPS_PER_FRAME) {
+    // Back from a hidden tab: carry on rather than catch up
+    steps = MAX_STEPS_PER_FRAME;
+    pending = 0;
+  } else {
+    pending -= steps * STEP_MS;
+  }
+  if (steps > 0) {
+    wasm.aquarium_step(tank, steps);
+    draw();
+  }
+  requestAnimationFrame(animate);
+}
+
+draw();
+requestAnimationFrame(animate);
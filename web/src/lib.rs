@@ -0,0 +1,111 @@
+//! Asciiquarium in the browser
+//!
+//! The simulation core of the terminal build compiled to
+//! `wasm32-unknown-unknown`, with a few plain C-ABI exports for the page
+//! to drive it (no wasm-bindgen needed). The page makes a tank, steps it
+//! from its animation loop and writes each frame, an ANSI string in the
+//! module's memory, to a web terminal; `index.html` and `aquarium.js` do
+//! this with xterm.js.
+//!
+//! The module imports two functions from `env`:
+//!
+//! - `aquarium_now() -> f64`: milliseconds from a fixed point, for
+//!   [`asciiquarium_rs::clock`]
+//! - `aquarium_random_fill(ptr, len)`: fill `len` bytes of memory at `ptr`
+//!   with random bytes, to seed tanks made without a seed
+//!
+//! Build with `cargo build --release --target wasm32-unknown-unknown` and
+//! serve this directory with the `.wasm` file copied next to `index.html`.
+//! On other targets the crate is empty.
+
+#[cfg(target_arch = "wasm32")]
+mod exports {
+    use asciiquarium_rs::entity::EntityManager;
+    use asciiquarium_rs::simulation::Simulation;
+    use asciiquarium_rs::spawning;
+
+    extern "C" {
+        fn aquarium_random_fill(ptr: *mut u8, len: usize);
+    }
+
+    fn fill_random(buf: &mut [u8]) -> Result<(), getrandom::Error> {
+        // SAFETY: the page writes exactly `len` bytes at `ptr`
+        unsafe { aquarium_random_fill(buf.as_mut_ptr(), buf.len()) };
+        Ok(())
+    }
+
+    getrandom::register_custom_getrandom!(fill_random);
+
+    /// A tank and the text of its last frame
+    pub struct Tank {
+        simulation: Simulation,
+        frame: String,
+    }
+
+    /// Make a filled `width` x `height` tank; a `seed` of 0 picks one at
+    /// random
+    #[no_mangle]
+    pub extern "C" fn aquarium_new(width: u32, height: u32, seed: u32) -> *mut Tank {
+        let mut entity_manager = EntityManager::new();
+        if seed != 0 {
+            entity_manager.seed(seed as u64);
+        }
+        let (width, height) = (width.min(u16::MAX as u32), height.min(u16::MAX as u32));
+        let mut simulation = Simulation::empty(entity_manager, width as u16, height as u16);
+        let bounds = simulation.world().bounds();
+        spawning::initialize_aquarium(simulation.entity_manager_mut(), bounds);
+        Box::into_raw(Box::new(Tank {
+            simulation,
+            frame: String::new(),
+        }))
+    }
+
+    /// Drop a tank made by [`aquarium_new`]
+    ///
+    /// # Safety
+    ///
+    /// `tank` must come from [`aquarium_new`] and not be used again.
+    #[no_mangle]
+    pub unsafe extern "C" fn aquarium_free(tank: *mut Tank) {
+        if !tank.is_null() {
+            drop(Box::from_raw(tank));
+        }
+    }
+
+    /// Advance by `ticks` simulation steps of 1/60 s
+    ///
+    /// # Safety
+    ///
+    /// `tank` must be a live tank from [`aquarium_new`].
+    #[no_mangle]
+    pub unsafe extern "C" fn aquarium_step(tank: *mut Tank, ticks: u32) {
+        let tank = &mut *tank;
+        tank.simulation.run(ticks as u64);
+    }
+
+    /// Draw the current frame, returning where its UTF-8 text starts; see
+    /// [`aquarium_frame_len`] for its length
+    ///
+    /// The text stays put until the next call or until the tank is freed.
+    ///
+    /// # Safety
+    ///
+    /// `tank` must be a live tank from [`aquarium_new`].
+    #[no_mangle]
+    pub unsafe extern "C" fn aquarium_frame(tank: *mut Tank) -> *const u8 {
+        let tank = &mut *tank;
+        tank.frame = tank.simulation.to_ansi();
+        tank.frame.as_ptr()
+    }
+
+    /// Length in bytes of the text from the last [`aquarium_frame`]
+    ///
+    /// # Safety
+    ///
+    /// `tank` must be a live tank from [`aquarium_new`].
+    #[no_mangle]
+    pub unsafe extern "C" fn aquarium_frame_len(tank: *const Tank) -> usize {
+        let tank = &*tank;
+        tank.frame.len()
+    }
+}